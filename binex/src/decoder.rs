@@ -8,6 +8,10 @@ use flate2::read::GzDecoder;
 
 use crate::prelude::{ClosedSourceElement, Error, Message, StreamElement};
 
+/// Largest framing around a record: SYNC byte, 4 byte MID and MLEN,
+/// 16 byte checksum, 4 byte reversed length and terminating SYNC byte.
+const MAX_FRAMING_SIZE: usize = 30;
+
 /// Abstraction for Plain or Compressed [R]
 enum Reader<R: Read> {
     Plain(R),
@@ -48,9 +52,9 @@ pub struct Decoder<'a, R: Read> {
     rd_ptr: usize,
     /// Reached EOS
     eos: bool,
-    /// Internal buffer. Buffer is sized to fully contain
-    /// the "worst case" open source [Message].
-    buf: [u8; 4096],
+    /// Internal buffer. Buffer grows to fully contain
+    /// the largest [Message] encountered so far.
+    buf: Vec<u8>,
     /// [R]
     reader: Reader<R>,
     /// Reference to past [ClosedSourceElement] (if any)
@@ -85,10 +89,8 @@ impl<'a, R: Read> Decoder<'a, R> {
     ///                 // and user should react accordingly,
     ///                 break;
     ///             },
-    ///             Error::ReversedStream | Error::LittleEndianStream => {
-    ///                 // this library is currently limited:
-    ///                 //  - reversed streams are not supported yet
-    ///                 //  - little endian streams are not supported yet
+    ///             Error::CorrupctBadCRC | Error::CorruptReversedTermination => {
+    ///                 // corrupt message: it is simply discarded
     ///             },
     ///             _ => {},
     ///         },
@@ -104,7 +106,7 @@ impl<'a, R: Read> Decoder<'a, R> {
             eos: false,
             rd_ptr: 0,
             wr_ptr: 0,
-            buf: [0; 4096],
+            buf: vec![0; 4096],
             past_element: None,
             reader: reader.into(),
         }
@@ -138,10 +140,8 @@ impl<'a, R: Read> Decoder<'a, R> {
     ///                 // and user should react accordingly,
     ///                 break;
     ///             },
-    ///             Error::ReversedStream | Error::LittleEndianStream => {
-    ///                 // this library is currently limited:
-    ///                 //  - reversed streams are not supported yet
-    ///                 //  - little endian streams are not supported yet
+    ///             Error::CorrupctBadCRC | Error::CorruptReversedTermination => {
+    ///                 // corrupt message: it is simply discarded
     ///             },
     ///             _ => {},
     ///         },
@@ -157,7 +157,7 @@ impl<'a, R: Read> Decoder<'a, R> {
             eos: false,
             rd_ptr: 0,
             wr_ptr: 0,
-            buf: [0; 4096],
+            buf: vec![0; 4096],
             past_element: None,
             reader: GzDecoder::new(reader).into(),
         }
@@ -206,7 +206,7 @@ impl<'a, R: Read> Iterator for Decoder<'a, R> {
                     Error::NonSupportedMesssage(mlen) => {
                         self.rd_ptr += mlen;
 
-                        if self.rd_ptr > 4096 {
+                        if self.rd_ptr > self.buf.len() {
                             self.rd_ptr = 0;
                             self.wr_ptr = 0;
                         }
@@ -217,31 +217,28 @@ impl<'a, R: Read> Iterator for Decoder<'a, R> {
                         }
                     },
                     Error::IncompleteMessage(mlen) => {
-                        // decoded partial valid frame
-                        if self.rd_ptr + mlen > 4096 {
-                            // frame would not fit in buffer:
-                            // abort: we do not support that scenario.
-                            // This should never happen anyway: internal buffer should be sized correctly.
-                            self.buf = [0; 4096];
-                            self.wr_ptr = 0;
-                            self.rd_ptr = 0;
-                            return Some(Err(Error::TooLargeInternalLimitation));
-                        } else {
-                            // preserved content (shift left)
-                            // and permit the refill that will conclude this message
-                            self.buf.copy_within(self.rd_ptr.., 0);
+                        // decoded partial valid frame:
+                        // preserved content (shift left)
+                        // and permit the refill that will conclude this message
+                        self.buf.copy_within(self.rd_ptr.., 0);
 
-                            self.wr_ptr -= self.rd_ptr;
-                            self.rd_ptr = 0;
-                            return Some(Err(Error::IncompleteMessage(mlen)));
+                        self.wr_ptr -= self.rd_ptr;
+                        self.rd_ptr = 0;
+
+                        if mlen + MAX_FRAMING_SIZE > self.buf.len() {
+                            // large records (for example 0x7f-05 observations
+                            // of many satellites) exceed 4kB
+                            self.buf.resize(mlen + MAX_FRAMING_SIZE, 0);
                         }
+
+                        return Some(Err(Error::IncompleteMessage(mlen)));
                     },
                     Error::ClosedSourceMessage(closed_source) => {
                         // determine whether
                         // - this element is self sustained (ie., fully described by this meta)
                         // - the followup of previous elements
                         // - or the last element of a serie
-                        if self.rd_ptr + closed_source.size < 4096 {
+                        if self.rd_ptr + closed_source.size < self.buf.len() {
                             // content is fully wrapped in buffer: expose as is
                             // self.past_element = Some(ClosedSourceElement {
                             //     provider: meta.provider,
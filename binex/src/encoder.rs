@@ -0,0 +1,158 @@
+use std::io::{Error as IoError, Write};
+
+#[cfg(feature = "flate2")]
+use flate2::{write::GzEncoder, Compression};
+
+use crate::prelude::{Error, Message};
+
+/// Abstraction for Plain or Compressed [W]
+enum Writer<W: Write> {
+    Plain(W),
+    #[cfg(feature = "flate2")]
+    Compressed(GzEncoder<W>),
+}
+
+impl<W: Write> From<W> for Writer<W> {
+    fn from(w: W) -> Writer<W> {
+        Self::Plain(w)
+    }
+}
+
+#[cfg(feature = "flate2")]
+#[cfg_attr(docsrs, doc(cfg(feature = "flate2")))]
+impl<W: Write> From<GzEncoder<W>> for Writer<W> {
+    fn from(w: GzEncoder<W>) -> Writer<W> {
+        Self::Compressed(w)
+    }
+}
+
+impl<W: Write> Write for Writer<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, IoError> {
+        match self {
+            Self::Plain(w) => w.write(buf),
+            #[cfg(feature = "flate2")]
+            Self::Compressed(w) => w.write(buf),
+        }
+    }
+    fn flush(&mut self) -> Result<(), IoError> {
+        match self {
+            Self::Plain(w) => w.flush(),
+            #[cfg(feature = "flate2")]
+            Self::Compressed(w) => w.flush(),
+        }
+    }
+}
+
+/// BINEX Stream Encoder. Use this structure to encode a serie
+/// of [Message]s into any [Write]able interface.
+/// It is the counterpart of our [Decoder](crate::prelude::Decoder).
+pub struct Encoder<W: Write> {
+    /// Internal buffer. Buffer grows to fully contain
+    /// the largest [Message] encoded so far.
+    buf: Vec<u8>,
+    /// [W]
+    writer: Writer<W>,
+}
+
+impl<W: Write> Encoder<W> {
+    /// Creates a new BINEX [Encoder] from [W] writable interface.
+    /// ```
+    /// use binex::prelude::{
+    ///     Decoder, Encoder, Epoch, Message, Meta, MonumentGeoRecord, Record, StreamElement,
+    /// };
+    ///
+    /// let meta = Meta {
+    ///     reversed: false,
+    ///     big_endian: true,
+    ///     enhanced_crc: false,
+    /// };
+    ///
+    /// let geo = MonumentGeoRecord::new_igs(
+    ///     Epoch::from_gpst_seconds(60.0),
+    ///     "Receiver model",
+    ///     "Antenna model",
+    ///     "Geodetic marker",
+    ///     "Marker number",
+    ///     "Site location",
+    ///     "Site name",
+    /// );
+    ///
+    /// let msg = Message::new(meta, Record::new_monument_geo(geo));
+    ///
+    /// // encode into memory
+    /// let mut encoder = Encoder::new(Vec::<u8>::new());
+    ///
+    /// let size = encoder.encode(&msg)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(size, msg.encoding_size());
+    ///
+    /// // and decode it back
+    /// let bytes = encoder.into_inner()
+    ///     .unwrap();
+    ///
+    /// let mut decoder = Decoder::new(bytes.as_slice());
+    ///
+    /// match decoder.next() {
+    ///     Some(Ok(StreamElement::OpenSource(decoded))) => {
+    ///         assert_eq!(decoded, msg);
+    ///     },
+    ///     _ => panic!("decoding failed"),
+    /// }
+    /// ```
+    pub fn new(writer: W) -> Self {
+        Self {
+            buf: Vec::with_capacity(4096),
+            writer: writer.into(),
+        }
+    }
+
+    #[cfg(feature = "flate2")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "flate2")))]
+    /// Creates a new Compressed BINEX stream [Encoder] from [W] writable
+    /// interface. Encoded [Message]s are Gzip compressed. The Gzip trailer
+    /// is written once [Self::into_inner] is invoked or this [Encoder] is dropped.
+    pub fn new_gzip(writer: W) -> Self {
+        Self {
+            buf: Vec::with_capacity(4096),
+            writer: GzEncoder::new(writer, Compression::default()).into(),
+        }
+    }
+
+    /// Encodes this [Message] into the stream.
+    /// Returns the number of bytes encoded, which is equal to [Message::encoding_size].
+    pub fn encode(&mut self, msg: &Message) -> Result<usize, Error> {
+        let size = msg.encoding_size();
+
+        if size > self.buf.len() {
+            // large records (for example 0x7f-05 observations
+            // of many satellites) exceed 4kB
+            self.buf.resize(size, 0);
+        }
+
+        let size = msg.encode(&mut self.buf, size)?;
+
+        self.writer
+            .write_all(&self.buf[..size])
+            .map_err(|_| Error::IoError)?;
+
+        Ok(size)
+    }
+
+    /// Flushes the underlying [W] writable interface.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush().map_err(|_| Error::IoError)
+    }
+
+    /// Terminates this stream and returns the underlying [W] writable interface.
+    pub fn into_inner(self) -> Result<W, Error> {
+        match self.writer {
+            Writer::Plain(mut w) => {
+                w.flush().map_err(|_| Error::IoError)?;
+                Ok(w)
+            },
+            #[cfg(feature = "flate2")]
+            Writer::Compressed(w) => w.finish().map_err(|_| Error::IoError),
+        }
+    }
+}
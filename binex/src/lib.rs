@@ -5,6 +5,7 @@
 use thiserror::Error;
 
 mod decoder;
+mod encoder;
 mod message;
 mod stream;
 
@@ -13,10 +14,13 @@ pub(crate) mod utils;
 pub mod prelude {
     pub use crate::{
         decoder::Decoder,
+        encoder::Encoder,
         message::{
-            EphemerisFrame, GALEphemeris, GLOEphemeris, GPSEphemeris, GPSRaw, Message, Meta,
-            MonumentGeoMetadata, MonumentGeoRecord, PositionEcef3d, PositionGeo3d, Record,
-            SBASEphemeris, Solutions, TemporalSolution, Velocity3d, VelocityNED3d,
+            EphemerisFrame, GALEphemeris, GLOEphemeris, GNSSObservations, GPSEphemeris, GPSRaw,
+            Message, Meta, MonumentGeoMetadata, MonumentGeoRecord, ObservationFrame,
+            ObservationRecord, ObservationSystem, PositionEcef3d, PositionGeo3d, ReceiverClock,
+            Record, SBASEphemeris, SVObservations, SignalObservation, Solutions, SystemTimeOffset,
+            SystemTimes, TemporalSolution, UndecodedRecord, Velocity3d, VelocityNED3d,
         },
         stream::{ClosedSourceElement, Provider, StreamElement},
         ClosedSourceMeta, Error,
@@ -56,12 +60,6 @@ pub enum Error {
     /// Missing SYNC byte
     NoSyncByte,
     // InvalidStartofStream,
-    /// Library limitation: reversed streams are not supported
-    ReversedStream,
-    /// Library limitation: little endian streams are not verified yet
-    LittleEndianStream,
-    /// Library limitation: enhanced CRC is not supported yet
-    EnhancedCrc,
     /// Found an unsupported timescale that we cannot interprate.
     NonSupportedTimescale,
     /// Found unknown message ID
@@ -72,12 +70,17 @@ pub enum Error {
     MissingCRC,
     /// Message corrupt: received CRC does not match expected CRC
     CorrupctBadCRC,
+    /// Reversible message corrupt: reversed length or
+    /// terminating SYNC byte does not match
+    CorruptReversedTermination,
     /// Incomplete message: need more data to complete
     IncompleteMessage(usize),
     /// Library limitation: not all open source Messages supported yet
     NonSupportedMesssage(usize),
     /// Library limtation: not all subrecords supported yet
     NonSupportedSubRecord,
+    /// 0x7f-05 subrecords describe 1 up to 64 satellites
+    InvalidSatelliteCount,
     /// Library limtation: should never happen, because this library
     /// will be designed to parse all open source [Message]s.
     /// This may happen as either we're still in development (bad internal design)
@@ -130,7 +130,10 @@ impl Checksum {
             };
             val_u32 as u128
         } else {
-            unimplemented!("md5");
+            // MD5 digest is always stored as is
+            let mut digest = [0; 16];
+            digest.copy_from_slice(&slice[..16]);
+            u128::from_le_bytes(digest)
         }
     }
     /// Calculates expected Checksum for this msg
@@ -273,4 +276,21 @@ mod test {
 
         assert_eq!(Checksum::XOR16.calc(&buf, buf.len()), 0x5376);
     }
+
+    #[test]
+    fn test_md5() {
+        let buf = [0x61, 0x62, 0x63];
+        let md5 = Checksum::MD5.calc(&buf, buf.len());
+
+        let digest = [
+            0x90, 0x01, 0x50, 0x98, 0x3c, 0xd2, 0x4f, 0xb0, 0xd6, 0x96, 0x3f, 0x7d, 0x28, 0xe1,
+            0x7f, 0x72,
+        ];
+
+        assert_eq!(md5.to_le_bytes(), digest);
+
+        for big_endian in [true, false] {
+            assert_eq!(Checksum::MD5.decode(&digest, 16, big_endian), md5);
+        }
+    }
 }
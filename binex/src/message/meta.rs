@@ -28,6 +28,15 @@ impl Meta {
     const REV_SYNC_LE_ENHANCED_CRC: u8 = 0xd8;
     // Reversed stream +BE +enhanced
     const REV_SYNC_BE_ENHANCED_CRC: u8 = 0xf8;
+
+    // Reversed stream termination +LE +standard
+    const REV_TERM_LE_STANDARD_CRC: u8 = 0xb4;
+    // Reversed stream termination +BE +standard
+    const REV_TERM_BE_STANDARD_CRC: u8 = 0xb0;
+    // Reversed stream termination +LE +enhanced
+    const REV_TERM_LE_ENHANCED_CRC: u8 = 0xe4;
+    // Reversed stream termination +BE +enhanced
+    const REV_TERM_BE_ENHANCED_CRC: u8 = 0xe0;
 }
 
 impl Meta {
@@ -63,6 +72,24 @@ impl Meta {
             }
         }
     }
+    /// Generates correct Sync byte to terminate this reversible [Meta] stream.
+    /// Only reversible messages are terminated.
+    pub(crate) fn terminating_sync_byte(&self) -> u8 {
+        if self.big_endian {
+            if self.enhanced_crc {
+                Self::REV_TERM_BE_ENHANCED_CRC
+            } else {
+                Self::REV_TERM_BE_STANDARD_CRC
+            }
+        } else {
+            if self.enhanced_crc {
+                Self::REV_TERM_LE_ENHANCED_CRC
+            } else {
+                Self::REV_TERM_LE_STANDARD_CRC
+            }
+        }
+    }
+
     /// Locate SYNC byte in provided buffer
     /// ## Returns
     /// - [Meta]
@@ -179,6 +206,23 @@ mod test {
         }
     }
 
+    #[test]
+    fn forge_terminating_sync_byte() {
+        for (big_endian, enhanced_crc, expected) in [
+            (false, false, 0xb4),
+            (true, false, 0xb0),
+            (false, true, 0xe4),
+            (true, true, 0xe0),
+        ] {
+            let meta = Meta {
+                reversed: true,
+                big_endian,
+                enhanced_crc,
+            };
+            assert_eq!(meta.terminating_sync_byte(), expected);
+        }
+    }
+
     #[test]
     fn test_sync_byte_matcher() {
         for i in 0..256 {
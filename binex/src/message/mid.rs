@@ -50,14 +50,38 @@ impl From<MessageID> for u32 {
         match val {
             MessageID::SiteMonumentMarker => 0,
             MessageID::Ephemeris => 1,
-            // MessageID::ObservationTimeTagRxInfo => 0x02,
-            // MessageID::Meteo => 0x03,
-            // MessageID::ReceiverInfo => 0x04,
-            // MessageID::ProcessedSolutions => 0x05,
-            // MessageID::ReceiverInfoPrototyping => 0x7d,
-            // MessageID::MeteoPrototyping => 0x7e,
-            // MessageID::ObservationTimeTagRxPrototyping => 0x7f,
-            _ => 0xffffffff,
+            MessageID::ObservationTimeTagRxInfo => 0x02,
+            MessageID::Meteo => 0x03,
+            MessageID::ReceiverInfo => 0x04,
+            MessageID::ProcessedSolutions => 0x05,
+            MessageID::ReceiverInfoPrototyping => 0x7d,
+            MessageID::MeteoPrototyping => 0x7e,
+            MessageID::ObservationTimeTagRxPrototyping => 0x7f,
+            MessageID::Unknown => 0xffffffff,
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::MessageID;
+    #[test]
+    fn mid_to_from_u32() {
+        for mid in [
+            MessageID::SiteMonumentMarker,
+            MessageID::Ephemeris,
+            MessageID::ObservationTimeTagRxInfo,
+            MessageID::Meteo,
+            MessageID::ReceiverInfo,
+            MessageID::ProcessedSolutions,
+            MessageID::ReceiverInfoPrototyping,
+            MessageID::MeteoPrototyping,
+            MessageID::ObservationTimeTagRxPrototyping,
+        ] {
+            let val: u32 = mid.into();
+            assert_eq!(MessageID::from(val), mid);
+        }
+        assert_eq!(u32::from(MessageID::Unknown), 0xffffffff);
+        assert_eq!(MessageID::from(0x42), MessageID::Unknown);
+    }
+}
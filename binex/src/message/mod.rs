@@ -5,9 +5,11 @@ mod record; // Record: message content
 mod time; // Epoch encoding/decoding // checksum calc.
 
 pub use record::{
-    EphemerisFrame, GALEphemeris, GLOEphemeris, GPSEphemeris, GPSRaw, MonumentGeoMetadata,
-    MonumentGeoRecord, PositionEcef3d, PositionGeo3d, Record, SBASEphemeris, Solutions,
-    SolutionsFrame, TemporalSolution, Velocity3d, VelocityNED3d,
+    EphemerisFrame, GALEphemeris, GLOEphemeris, GNSSObservations, GPSEphemeris, GPSRaw,
+    MonumentGeoMetadata, MonumentGeoRecord, ObservationFrame, ObservationRecord, ObservationSystem,
+    PositionEcef3d, PositionGeo3d, ReceiverClock, Record, SBASEphemeris, SVObservations,
+    SignalObservation, Solutions, SolutionsFrame, SystemTimeOffset, SystemTimes, TemporalSolution,
+    UndecodedRecord, Velocity3d, VelocityNED3d,
 };
 
 pub use meta::Meta;
@@ -45,7 +47,7 @@ impl Message {
     pub fn encoding_size(&self) -> usize {
         let mut total = 1; // SYNC
 
-        let mid = self.record.to_message_id() as u32;
+        let mid: u32 = self.record.to_message_id().into();
         total += Self::bnxi_encoding_size(mid);
 
         let mlen = self.record.encoding_size();
@@ -56,6 +58,13 @@ impl Message {
         let ck = Checksum::from_len(mlen, self.meta.enhanced_crc);
         total += ck.len();
 
+        if self.meta.reversed {
+            // reversed record length (excluding SYNC) + terminating SYNC
+            let rlen = total - 1;
+            total += Self::bnxi_encoding_size(rlen as u32);
+            total += 1;
+        }
+
         total
     }
 
@@ -79,23 +88,6 @@ impl Message {
         let big_endian = meta.big_endian;
         let enhanced_crc = meta.enhanced_crc;
 
-        /////////////////////////////////////
-        // TODO: current library limitations
-        /////////////////////////////////////
-
-        if reversed {
-            // Reversed streams: not understood
-            return Err(Error::ReversedStream);
-        }
-        if enhanced_crc {
-            // Enhanced CRC scheme not implemented
-            return Err(Error::EnhancedCrc);
-        }
-        if !big_endian {
-            // Little endianess not tested
-            return Err(Error::LittleEndianStream);
-        }
-
         // make sure we can parse up to 4 byte MID
         if buf_len - sync_off < 4 {
             return Err(Error::NotEnoughBytes);
@@ -142,6 +134,20 @@ impl Message {
                 let solutions = Solutions::decode(mlen as usize, big_endian, &buf[ptr..])?;
                 Record::new_solutions(solutions)
             },
+            MessageID::ObservationTimeTagRxPrototyping => {
+                let obs = ObservationRecord::decode(mlen as usize, big_endian, &buf[ptr..])?;
+                Record::new_observations(obs)
+            },
+            MessageID::Meteo | MessageID::MeteoPrototyping => {
+                let prototyping = mid == MessageID::MeteoPrototyping;
+                let meteo = UndecodedRecord::decode(mlen, prototyping, &buf[ptr..])?;
+                Record::new_meteo(meteo)
+            },
+            MessageID::ReceiverInfo | MessageID::ReceiverInfoPrototyping => {
+                let prototyping = mid == MessageID::ReceiverInfoPrototyping;
+                let info = UndecodedRecord::decode(mlen, prototyping, &buf[ptr..])?;
+                Record::new_receiver_info(info)
+            },
             MessageID::Unknown => {
                 return Err(Error::UnknownMessage);
             },
@@ -178,10 +184,38 @@ impl Message {
         let expected = checksum.calc(&buf[sync_off + 1..], mlen + mid_1_4 + mlen_1_4);
 
         if expected != ck {
-            Err(Error::CorrupctBadCRC)
-        } else {
-            Ok(Self { meta, record })
+            return Err(Error::CorrupctBadCRC);
         }
+
+        // 6. reversible record termination
+        if reversed {
+            let rlen = (mid_1_4 + mlen_1_4 + mlen + ck_len) as u32;
+            let rlen_1_4 = Self::bnxi_encoding_size(rlen);
+
+            ptr += mlen + ck_len;
+
+            if ptr + rlen_1_4 + 1 > buf_len {
+                // buffer does not contain complete message!
+                return Err(Error::IncompleteMessage(mlen));
+            }
+
+            // reversed length is encoded backwards
+            let mut bnxi = [0; 4];
+            bnxi[..rlen_1_4].copy_from_slice(&buf[ptr..ptr + rlen_1_4]);
+            bnxi[..rlen_1_4].reverse();
+
+            let (decoded, size) = Self::decode_bnxi(&bnxi, big_endian);
+
+            if decoded != rlen || size != rlen_1_4 {
+                return Err(Error::CorruptReversedTermination);
+            }
+
+            if buf[ptr + rlen_1_4] != meta.terminating_sync_byte() {
+                return Err(Error::CorruptReversedTermination);
+            }
+        }
+
+        Ok(Self { meta, record })
     }
 
     /// Tries to encode [Message] into provided buffer.
@@ -199,7 +233,7 @@ impl Message {
 
         // grab meta definitions
         let big_endian = self.meta.big_endian;
-        // let reversed = self.meta.reversed;
+        let reversed = self.meta.reversed;
         let enhanced_crc = self.meta.enhanced_crc;
 
         // Encode SYNC byte
@@ -207,7 +241,7 @@ impl Message {
         let mut ptr = 1;
 
         // Encode MID
-        let mid = self.record.to_message_id() as u32;
+        let mid: u32 = self.record.to_message_id().into();
         let mid_1_4 = Self::encode_bnxi(mid, big_endian, &mut buf[ptr..])?;
         ptr += mid_1_4;
 
@@ -227,6 +261,15 @@ impl Message {
             Record::Solutions(fr) => {
                 ptr += fr.encode(big_endian, &mut buf[ptr..])?;
             },
+            Record::Observations(obs) => {
+                ptr += obs.encode(big_endian, &mut buf[ptr..])?;
+            },
+            Record::Meteo(meteo) => {
+                ptr += meteo.encode(&mut buf[ptr..])?;
+            },
+            Record::ReceiverInfo(info) => {
+                ptr += info.encode(&mut buf[ptr..])?;
+            },
         }
 
         // encode CRC
@@ -256,17 +299,32 @@ impl Message {
                 buf[ptr + i] = crc_bytes[i];
             }
         } else {
-            let crc_bytes = if big_endian {
-                crc_u128.to_be_bytes()
-            } else {
-                crc_u128.to_le_bytes()
-            };
+            // MD5 digest is always stored as is
+            let crc_bytes = crc_u128.to_le_bytes();
             for i in 0..ck_len {
                 buf[ptr + i] = crc_bytes[i];
             }
         }
 
-        Ok(ptr + ck_len)
+        ptr += ck_len;
+
+        // Reversible record termination:
+        // reversed length (excluding SYNC) encoded backwards + terminating SYNC
+        if reversed {
+            let rlen = (mid_1_4 + mlen_1_4 + mlen + ck_len) as u32;
+
+            let mut bnxi = [0; 4];
+            let rlen_1_4 = Self::encode_bnxi(rlen, big_endian, &mut bnxi)?;
+
+            buf[ptr..ptr + rlen_1_4].copy_from_slice(&bnxi[..rlen_1_4]);
+            buf[ptr..ptr + rlen_1_4].reverse();
+            ptr += rlen_1_4;
+
+            buf[ptr] = self.meta.terminating_sync_byte();
+            ptr += 1;
+        }
+
+        Ok(ptr)
     }
 
     /// Number of bytes to encode U32 using the 1-4 BNXI algorithm.
@@ -303,63 +361,71 @@ impl Message {
             return (val32 & 0x7f, 1);
         }
 
+        if !big_endian {
+            return Self::decode_le_bnxi(&buf[..min_size]);
+        }
+
         // multi byte case
         let (val, size) = if buf[1] & Self::BNXI_KEEP_GOING_MASK == 0 {
             let mut val;
 
-            let (byte0, byte1) = if big_endian {
-                (buf[0], buf[1])
-            } else {
-                (buf[1], buf[0])
-            };
-
-            val = (byte0 & Self::BNXI_BYTE_MASK) as u32;
+            val = (buf[0] & Self::BNXI_BYTE_MASK) as u32;
             val <<= 7;
-            val |= byte1 as u32;
+            val |= buf[1] as u32;
 
             (val, 2)
         } else if buf[2] & Self::BNXI_KEEP_GOING_MASK == 0 {
             let mut val;
 
-            let (byte0, byte1, byte2) = if big_endian {
-                (buf[0], buf[1], buf[2])
-            } else {
-                (buf[2], buf[1], buf[0])
-            };
-
-            val = (byte0 & Self::BNXI_BYTE_MASK) as u32;
+            val = (buf[0] & Self::BNXI_BYTE_MASK) as u32;
             val <<= 8;
 
-            val |= (byte1 & Self::BNXI_BYTE_MASK) as u32;
+            val |= (buf[1] & Self::BNXI_BYTE_MASK) as u32;
             val <<= 7;
 
-            val |= byte2 as u32;
+            val |= buf[2] as u32;
             (val, 3)
         } else {
             let mut val;
 
-            let (byte0, byte1, byte2, byte3) = if big_endian {
-                (buf[0], buf[1], buf[2], buf[3])
-            } else {
-                (buf[3], buf[2], buf[1], buf[0])
-            };
-
-            val = (byte0 & Self::BNXI_BYTE_MASK) as u32;
+            val = (buf[0] & Self::BNXI_BYTE_MASK) as u32;
             val <<= 8;
 
-            val |= (byte1 & Self::BNXI_BYTE_MASK) as u32;
+            val |= (buf[1] & Self::BNXI_BYTE_MASK) as u32;
             val <<= 8;
 
-            val |= (byte2 & Self::BNXI_BYTE_MASK) as u32;
+            val |= (buf[2] & Self::BNXI_BYTE_MASK) as u32;
             val <<= 7;
 
-            val |= byte3 as u32;
+            val |= buf[3] as u32;
             (val, 4)
         };
 
         (val, size)
     }
 
+    /// Decodes 1-4 BNXI little endian encoded U32 integer.
+    /// The least significant 7 bit group comes first,
+    /// the 4th byte (if any) contributes 8 bits.
+    fn decode_le_bnxi(buf: &[u8]) -> (u32, usize) {
+        let mut val = 0_u32;
+
+        for (i, byte) in buf.iter().enumerate() {
+            if i == 3 {
+                val |= (*byte as u32) << 21;
+                return (val, 4);
+            }
+
+            val |= ((byte & Self::BNXI_BYTE_MASK) as u32) << (7 * i);
+
+            if byte & Self::BNXI_KEEP_GOING_MASK == 0 {
+                return (val, i + 1);
+            }
+        }
+
+        (val, buf.len())
+    }
+
     /// U32 to BNXI encoder according to [https://www.unavco.org/data/gps-gnss/data-formats/binex/conventions.html/#ubnxi_details].
     /// Encodes into given buffer, returns encoding size.
     /// Will fail if buffer is too small.
@@ -375,6 +441,21 @@ impl Message {
             return Ok(1);
         }
 
+        if !big_endian {
+            // least significant 7 bit group first
+            for (i, byte) in buf.iter_mut().take(size).enumerate() {
+                if i == 3 {
+                    *byte = (val >> 21) as u8;
+                } else {
+                    *byte = ((val >> (7 * i)) as u8) & Self::BNXI_BYTE_MASK;
+                    if i < size - 1 {
+                        *byte |= Self::BNXI_KEEP_GOING_MASK;
+                    }
+                }
+            }
+            return Ok(size);
+        }
+
         // multi byte case
         let mut val32 = (val & 0xffffff80) << 1;
         val32 |= val & 0xff;
@@ -383,41 +464,23 @@ impl Message {
             val32 |= 0x8000;
             val32 &= 0xff7f;
 
-            if big_endian {
-                buf[0] = ((val32 & 0xff00) >> 8) as u8;
-                buf[1] = val32 as u8;
-            } else {
-                buf[1] = ((val32 & 0xff00) >> 8) as u8;
-                buf[0] = val32 as u8;
-            }
+            buf[0] = ((val32 & 0xff00) >> 8) as u8;
+            buf[1] = val32 as u8;
         } else if size == 3 {
             val32 |= 0x808000;
             val32 &= 0xffff7f;
 
-            if big_endian {
-                buf[0] = ((val32 & 0xffff00) >> 16) as u8;
-                buf[1] = ((val32 & 0xff00) >> 8) as u8;
-                buf[2] = val32 as u8;
-            } else {
-                buf[2] = ((val32 & 0xffff00) >> 16) as u8;
-                buf[1] = ((val32 & 0xff00) >> 8) as u8;
-                buf[0] = val32 as u8;
-            }
+            buf[0] = ((val32 & 0xffff00) >> 16) as u8;
+            buf[1] = ((val32 & 0xff00) >> 8) as u8;
+            buf[2] = val32 as u8;
         } else {
             val32 |= 0x80808000;
             val32 &= 0xffffff7f;
 
-            if big_endian {
-                buf[0] = ((val32 & 0xffffff00) >> 24) as u8;
-                buf[1] = ((val32 & 0xffff00) >> 16) as u8;
-                buf[2] = ((val32 & 0xff00) >> 8) as u8;
-                buf[3] = val32 as u8;
-            } else {
-                buf[3] = ((val32 & 0xffffff00) >> 24) as u8;
-                buf[2] = ((val32 & 0xffff00) >> 16) as u8;
-                buf[1] = ((val32 & 0xff00) >> 8) as u8;
-                buf[0] = val32 as u8;
-            }
+            buf[0] = ((val32 & 0xffffff00) >> 24) as u8;
+            buf[1] = ((val32 & 0xffff00) >> 16) as u8;
+            buf[2] = ((val32 & 0xff00) >> 8) as u8;
+            buf[3] = val32 as u8;
         }
 
         Ok(size)
@@ -428,8 +491,10 @@ impl Message {
 mod test {
     use super::Message;
     use crate::message::{
-        EphemerisFrame, GALEphemeris, GPSEphemeris, GPSRaw, Meta, MonumentGeoMetadata,
-        MonumentGeoRecord, PositionEcef3d, Record, Solutions, SolutionsFrame, Velocity3d,
+        EphemerisFrame, GALEphemeris, GNSSObservations, GPSEphemeris, GPSRaw, Meta,
+        MonumentGeoMetadata, MonumentGeoRecord, ObservationRecord, ObservationSystem,
+        PositionEcef3d, Record, SVObservations, SignalObservation, Solutions, SolutionsFrame,
+        Velocity3d,
     };
     use crate::prelude::Epoch;
     use crate::Error;
@@ -562,6 +627,32 @@ mod test {
         }
     }
 
+    #[test]
+    fn little_endian_bnxi() {
+        for (val, bytes) in [
+            (0, vec![0]),
+            (127, vec![0x7f]),
+            (128, vec![0x80, 0x01]),
+            (0x1fa, vec![0xfa, 0x03]),
+            (16383, vec![0xff, 0x7f]),
+            (16384, vec![0x80, 0x80, 0x01]),
+            (0x181fa, vec![0xfa, 0x83, 0x06]),
+            (2097151, vec![0xff, 0xff, 0x7f]),
+            (2097152, vec![0x80, 0x80, 0x80, 0x01]),
+            (0x18181fa, vec![0xfa, 0x83, 0x86, 0x0c]),
+        ] {
+            let mut encoded = [0; 4];
+            let size = Message::encode_bnxi(val, false, &mut encoded).unwrap();
+
+            assert_eq!(size, bytes.len(), "bad size for {}", val);
+            assert_eq!(&encoded[..size], bytes, "bad encoding for {}", val);
+
+            let (decoded, size) = Message::decode_bnxi(&encoded, false);
+            assert_eq!(size, bytes.len());
+            assert_eq!(decoded, val);
+        }
+    }
+
     #[test]
     fn decode_no_sync_byte() {
         let buf = [0, 0, 0, 0, 0];
@@ -690,6 +781,64 @@ mod test {
         assert_eq!(parsed, msg);
     }
 
+    #[test]
+    fn test_observations() {
+        let mut meta = Meta::default();
+
+        meta.reversed = false;
+        meta.big_endian = true;
+        meta.enhanced_crc = false;
+
+        let l1c = SignalObservation::new(0, 21456789.123, 21456789.3699, 45.2);
+        let l2w = SignalObservation::new(10, 21456786.623, 21456786.543, 40.1)
+            .with_doppler(-1233.5)
+            .with_slip_count(7);
+
+        let g12 = SVObservations::new(ObservationSystem::GPS, 12)
+            .with_signal(l1c)
+            .with_signal(l2w);
+
+        let obs = ObservationRecord::new(
+            Epoch::from_gpst_seconds(120.5),
+            GNSSObservations::default().with_sv(g12),
+        );
+
+        // subrecord + tstamp + flags + SV + L1 + L2
+        let obs_len = obs.encoding_size();
+        assert_eq!(obs_len, 1 + 6 + 1 + 2 + 10 + 12);
+
+        let record = Record::new_observations(obs);
+        let msg = Message::new(meta, record);
+
+        // SYNC + MID(1) + MLEN(1) + RLEN + CRC(1)
+        assert_eq!(msg.encoding_size(), 1 + 1 + 1 + obs_len + 1);
+
+        let mut encoded = [0; 64];
+        msg.encode(&mut encoded, 64).unwrap();
+
+        assert_eq!(encoded[0], 0xe2);
+        assert_eq!(encoded[1], 0x7f);
+        assert_eq!(encoded[3], 0x05);
+
+        // parse back
+        let parsed = Message::decode(&encoded).unwrap();
+        let record = parsed.record.as_observations().unwrap();
+
+        assert_eq!(record.epoch, Epoch::from_gpst_seconds(120.5));
+
+        let observations = record.gnss_observations().unwrap();
+        assert_eq!(observations.sv.len(), 1);
+        assert_eq!(observations.sv[0].prn, 12);
+        assert_eq!(observations.sv[0].signals.len(), 2);
+        assert_eq!(observations.sv[0].signals[1].doppler_hz, Some(-1233.5));
+        assert_eq!(observations.sv[0].signals[1].slip_count, Some(7));
+
+        // mirror
+        let mut reencoded = [0; 64];
+        parsed.encode(&mut reencoded, 64).unwrap();
+        assert_eq!(reencoded, encoded);
+    }
+
     #[test]
     fn test_pvt_wgs84() {
         let mut meta = Meta::default();
@@ -765,4 +914,117 @@ mod test {
         // add comment
         // add extra
     }
+
+    #[test]
+    fn test_reversed_pvt_wgs84() {
+        let meta = Meta {
+            reversed: true,
+            big_endian: true,
+            enhanced_crc: false,
+        };
+
+        let mut solutions = Solutions::new(Epoch::from_gpst_seconds(1.100));
+
+        solutions.frames.push(SolutionsFrame::AntennaEcefPosition(
+            PositionEcef3d::new_wgs84(1.0, 2.0, 3.0),
+        ));
+
+        let sol_len = solutions.encoding_size();
+
+        let record = Record::new_solutions(solutions);
+        let msg = Message::new(meta, record);
+
+        // SYNC + MID(1) + MLEN(1) + RLEN + CRC(1) + REV_LEN(1) + SYNC
+        let mlen = 1 + 1 + 1 + sol_len + 1 + 1 + 1;
+        assert_eq!(msg.encoding_size(), mlen);
+
+        let mut encoded = [0; 40];
+        let size = msg.encode(&mut encoded, 40).unwrap();
+        assert_eq!(size, mlen);
+
+        assert_eq!(
+            encoded,
+            [
+                242, 5, 32, 0, 0, 0, 0, 4, 76, 1, 0, 63, 240, 0, 0, 0, 0, 0, 0, 64, 0, 0, 0, 0, 0,
+                0, 0, 64, 8, 0, 0, 0, 0, 0, 0, 171, 35, 176, 0, 0
+            ]
+        );
+
+        // parse back
+        let parsed = Message::decode(&encoded).unwrap();
+        assert_eq!(parsed, msg);
+
+        // corrupt termination
+        let mut corrupt = encoded;
+        corrupt[37] = 0xb4;
+
+        match Message::decode(&corrupt) {
+            Err(Error::CorruptReversedTermination) => {},
+            Err(e) => panic!("returned unexpected error: {:?}", e),
+            _ => panic!("should have failed"),
+        }
+
+        let mut corrupt = encoded;
+        corrupt[36] = 34;
+
+        match Message::decode(&corrupt) {
+            Err(Error::CorruptReversedTermination) => {},
+            Err(e) => panic!("returned unexpected error: {:?}", e),
+            _ => panic!("should have failed"),
+        }
+
+        // incomplete termination
+        match Message::decode(&encoded[..37]) {
+            Err(Error::IncompleteMessage(_)) => {},
+            Err(e) => panic!("returned unexpected error: {:?}", e),
+            _ => panic!("should have failed"),
+        }
+    }
+
+    #[test]
+    fn test_all_sync_bytes() {
+        let gps_eph = EphemerisFrame::GPS(GPSEphemeris {
+            sv_prn: 10,
+            toe: 1000,
+            e: 0.01,
+            sqrt_a: 5153.6,
+            ..Default::default()
+        });
+
+        for reversed in [false, true] {
+            for big_endian in [false, true] {
+                for enhanced_crc in [false, true] {
+                    let meta = Meta {
+                        reversed,
+                        big_endian,
+                        enhanced_crc,
+                    };
+
+                    let record = Record::new_ephemeris_frame(gps_eph.clone());
+                    let msg = Message::new(meta, record);
+
+                    // SYNC + MID(1) + MLEN(2) + RLEN(129) + CRC
+                    let mut expected = 1 + 1 + 2 + 129;
+                    expected += if enhanced_crc { 4 } else { 2 };
+
+                    if reversed {
+                        // REV_LEN(2) + SYNC
+                        expected += 2 + 1;
+                    }
+
+                    assert_eq!(msg.encoding_size(), expected, "{:?}", meta);
+
+                    let mut encoded = [0; 256];
+                    let size = msg.encode(&mut encoded, 256).unwrap();
+                    assert_eq!(size, expected, "{:?}", meta);
+
+                    let parsed = Message::decode(&encoded).unwrap_or_else(|e| {
+                        panic!("{:?}: failed to decode: {:?}", meta, e);
+                    });
+
+                    assert_eq!(parsed, msg);
+                }
+            }
+        }
+    }
 }
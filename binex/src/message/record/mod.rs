@@ -4,17 +4,24 @@ use crate::message::MessageID;
 
 mod ephemeris; // ephemeris frames
 mod monument; // geodetic marker // ephemeris frames
+mod observation; // observation frames
 mod solutions; // solutions frames
+mod undecoded; // meteo & receiver info records
 
 pub use ephemeris::{
     EphemerisFrame, GALEphemeris, GLOEphemeris, GPSEphemeris, GPSRaw, SBASEphemeris,
 };
 
 pub use monument::{MonumentGeoMetadata, MonumentGeoRecord};
+pub use observation::{
+    GNSSObservations, ObservationFrame, ObservationRecord, ObservationSystem, ReceiverClock,
+    SVObservations, SignalObservation, SystemTimeOffset, SystemTimes,
+};
 pub use solutions::{
     PositionEcef3d, PositionGeo3d, Solutions, SolutionsFrame, TemporalSolution, Velocity3d,
     VelocityNED3d,
 };
+pub use undecoded::UndecodedRecord;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Record {
@@ -25,6 +32,12 @@ pub enum Record {
     EphemerisFrame(EphemerisFrame),
     /// Solutions frame
     Solutions(Solutions),
+    /// Time tagged GNSS observations
+    Observations(ObservationRecord),
+    /// Local meteorological and geophysical information, not interpreted
+    Meteo(UndecodedRecord),
+    /// Receiver state information, not interpreted
+    ReceiverInfo(UndecodedRecord),
 }
 
 impl From<MonumentGeoRecord> for Record {
@@ -39,6 +52,18 @@ impl From<EphemerisFrame> for Record {
     }
 }
 
+impl From<Solutions> for Record {
+    fn from(sol: Solutions) -> Self {
+        Self::Solutions(sol)
+    }
+}
+
+impl From<ObservationRecord> for Record {
+    fn from(obs: ObservationRecord) -> Self {
+        Self::Observations(obs)
+    }
+}
+
 impl Default for Record {
     fn default() -> Self {
         Self::MonumentGeo(Default::default())
@@ -58,6 +83,18 @@ impl Record {
     pub fn new_solutions(sol: Solutions) -> Self {
         Self::Solutions(sol)
     }
+    /// Builds new [ObservationRecord]
+    pub fn new_observations(obs: ObservationRecord) -> Self {
+        Self::Observations(obs)
+    }
+    /// Builds new Meteo record from [UndecodedRecord]
+    pub fn new_meteo(meteo: UndecodedRecord) -> Self {
+        Self::Meteo(meteo)
+    }
+    /// Builds new Receiver info record from [UndecodedRecord]
+    pub fn new_receiver_info(info: UndecodedRecord) -> Self {
+        Self::ReceiverInfo(info)
+    }
     /// [MonumentGeoRecord] unwrapping attempt
     pub fn as_monument_geo(&self) -> Option<&MonumentGeoRecord> {
         match self {
@@ -79,12 +116,48 @@ impl Record {
             _ => None,
         }
     }
+    /// [ObservationRecord] unwrapping attempt
+    pub fn as_observations(&self) -> Option<&ObservationRecord> {
+        match self {
+            Self::Observations(obs) => Some(obs),
+            _ => None,
+        }
+    }
+    /// Meteo [UndecodedRecord] unwrapping attempt
+    pub fn as_meteo(&self) -> Option<&UndecodedRecord> {
+        match self {
+            Self::Meteo(meteo) => Some(meteo),
+            _ => None,
+        }
+    }
+    /// Receiver info [UndecodedRecord] unwrapping attempt
+    pub fn as_receiver_info(&self) -> Option<&UndecodedRecord> {
+        match self {
+            Self::ReceiverInfo(info) => Some(info),
+            _ => None,
+        }
+    }
     /// Returns [MessageID] to associate to [Self] in stream header.
     pub(crate) fn to_message_id(&self) -> MessageID {
         match self {
             Self::EphemerisFrame(_) => MessageID::Ephemeris,
             Self::MonumentGeo(_) => MessageID::SiteMonumentMarker,
             Self::Solutions(_) => MessageID::ProcessedSolutions,
+            Self::Observations(_) => MessageID::ObservationTimeTagRxPrototyping,
+            Self::Meteo(meteo) => {
+                if meteo.prototyping {
+                    MessageID::MeteoPrototyping
+                } else {
+                    MessageID::Meteo
+                }
+            },
+            Self::ReceiverInfo(info) => {
+                if info.prototyping {
+                    MessageID::ReceiverInfoPrototyping
+                } else {
+                    MessageID::ReceiverInfo
+                }
+            },
         }
     }
 
@@ -94,6 +167,9 @@ impl Record {
             Self::Solutions(sol) => sol.encoding_size(),
             Self::EphemerisFrame(fr) => fr.encoding_size(),
             Self::MonumentGeo(geo) => geo.encoding_size(),
+            Self::Observations(obs) => obs.encoding_size(),
+            Self::Meteo(meteo) => meteo.encoding_size(),
+            Self::ReceiverInfo(info) => info.encoding_size(),
         }
     }
 }
@@ -2,9 +2,11 @@
 
 use crate::{
     message::{
+        record::{PositionEcef3d, PositionGeo3d},
         time::{decode_gpst_epoch, encode_epoch, TimeResolution},
         Message,
     },
+    utils::Utils,
     Error,
 };
 
@@ -50,6 +52,13 @@ pub struct MonumentGeoRecord {
     pub comments: Vec<String>,
     /// Readable frames that we expose with high level methods
     pub frames: Vec<GeoStringFrame>,
+    /// Antenna ECEF coordinates (if known)
+    pub antenna_ecef3d: Option<PositionEcef3d>,
+    /// Antenna geographic coordinates (if known)
+    pub antenna_geo3d: Option<PositionGeo3d>,
+    /// Antenna (height, east, north) offset
+    /// to reference point in meters (if known)
+    pub antenna_offset3d: Option<(f64, f64, f64)>,
 }
 
 impl Default for MonumentGeoRecord {
@@ -59,6 +68,9 @@ impl Default for MonumentGeoRecord {
             meta: MonumentGeoMetadata::RNX2BIN,
             comments: Default::default(),
             frames: Default::default(),
+            antenna_ecef3d: None,
+            antenna_geo3d: None,
+            antenna_offset3d: None,
         }
     }
 }
@@ -256,6 +268,21 @@ impl MonumentGeoRecord {
             let fid = FieldID::from(fid);
             ptr += size;
 
+            if fid == FieldID::AntennaOffset3D {
+                // 3 real8, no string
+                if mlen < ptr + 24 {
+                    break;
+                }
+
+                let h = Utils::decode_f64(big_endian, &buf[ptr..ptr + 8])?;
+                let e = Utils::decode_f64(big_endian, &buf[ptr + 8..ptr + 16])?;
+                let n = Utils::decode_f64(big_endian, &buf[ptr + 16..ptr + 24])?;
+
+                ret.antenna_offset3d = Some((h, e, n));
+                ptr += 24;
+                continue;
+            }
+
             if mlen < ptr + 1 {
                 // cant decode 1-4b
                 break;
@@ -264,6 +291,25 @@ impl MonumentGeoRecord {
             // decode strlen
             let (s_len, size) = Message::decode_bnxi(&buf[ptr..], big_endian);
             let s_len = s_len as usize;
+
+            if fid == FieldID::AntennaEcef3D || fid == FieldID::AntennaGeo3D {
+                // ellipsoid description, followed by 3 real8
+                if mlen < ptr + size + s_len + 24 {
+                    break;
+                }
+
+                if fid == FieldID::AntennaEcef3D {
+                    if let Ok(ecef) = PositionEcef3d::decode(big_endian, &buf[ptr..]) {
+                        ret.antenna_ecef3d = Some(ecef);
+                    }
+                } else if let Ok(geo) = PositionGeo3d::decode(big_endian, &buf[ptr..]) {
+                    ret.antenna_geo3d = Some(geo);
+                }
+
+                ptr += size + s_len + 24;
+                continue;
+            }
+
             ptr += size;
 
             // decode str: tolerance to bad utf8
@@ -273,9 +319,6 @@ impl MonumentGeoRecord {
                     FieldID::Comments => {
                         ret.comments.push(s.to_string());
                     },
-                    FieldID::AntennaEcef3D | FieldID::AntennaGeo3D | FieldID::AntennaOffset3D => {
-                        // TODO: unhandled yet !
-                    },
                     FieldID::Unknown => {
                         // bad ID: debug trace ?
                     },
@@ -304,10 +347,6 @@ impl MonumentGeoRecord {
 
     /// Encodes [MonumentGeoRecord] into buffer, returns encoded size (total bytes).
     /// [MonumentGeoRecord] must fit in preallocated buffer.
-    // TODO: missing following fields
-    //  - AntennaECEF3D
-    //  - AntennaGeo3D
-    //  - AntennaOffset3D
     pub(crate) fn encode(&self, big_endian: bool, buf: &mut [u8]) -> Result<usize, Error> {
         let size = self.encoding_size();
         if buf.len() < size {
@@ -355,15 +394,39 @@ impl MonumentGeoRecord {
             ptr += strlen;
         }
 
+        // encode antenna coordinates
+        if let Some(ecef) = &self.antenna_ecef3d {
+            let fid = FieldID::AntennaEcef3D;
+            ptr += Message::encode_bnxi(fid as u32, big_endian, &mut buf[ptr..])?;
+            ptr += ecef.encode(big_endian, &mut buf[ptr..])?;
+        }
+
+        if let Some(geo) = &self.antenna_geo3d {
+            let fid = FieldID::AntennaGeo3D;
+            ptr += Message::encode_bnxi(fid as u32, big_endian, &mut buf[ptr..])?;
+            ptr += geo.encode(big_endian, &mut buf[ptr..])?;
+        }
+
+        if let Some((h, e, n)) = self.antenna_offset3d {
+            let fid = FieldID::AntennaOffset3D;
+            ptr += Message::encode_bnxi(fid as u32, big_endian, &mut buf[ptr..])?;
+
+            for value in [h, e, n] {
+                let bytes = if big_endian {
+                    value.to_be_bytes()
+                } else {
+                    value.to_le_bytes()
+                };
+                buf[ptr..ptr + 8].copy_from_slice(&bytes);
+                ptr += 8;
+            }
+        }
+
         Ok(size)
     }
 
     /// Returns total length (bytewise) required to fully encode [Self].
     /// Use this to fulfill [Self::encode] requirements.
-    // TODO: missing following fields
-    //  - AntennaECEF3D
-    //  - AntennaGeo3D
-    //  - AntennaOffset3D
     pub(crate) fn encoding_size(&self) -> usize {
         let mut size = 6; // tstamp + meta
 
@@ -389,6 +452,17 @@ impl MonumentGeoRecord {
             size += s_len;
         }
 
+        // antenna coordinates: 1-4 FID + payload
+        if let Some(ecef) = &self.antenna_ecef3d {
+            size += 1 + ecef.encoding_size();
+        }
+        if let Some(geo) = &self.antenna_geo3d {
+            size += 1 + geo.encoding_size();
+        }
+        if self.antenna_offset3d.is_some() {
+            size += 1 + 24;
+        }
+
         size
     }
 
@@ -541,6 +615,121 @@ impl MonumentGeoRecord {
         s.push_or_update(FieldID::Extra, extra);
         s
     }
+
+    /// Define Antenna ECEF coordinates, in meters and WGS84 ellipsoid.
+    pub fn with_antenna_ecef_position(&self, x_ecef_m: f64, y_ecef_m: f64, z_ecef_m: f64) -> Self {
+        let mut s = self.clone();
+        s.antenna_ecef3d = Some(PositionEcef3d::new_wgs84(x_ecef_m, y_ecef_m, z_ecef_m));
+        s
+    }
+
+    /// Define Antenna geographic coordinates, in decimal degrees
+    /// and meters, with WGS84 ellipsoid.
+    pub fn with_antenna_geo_position(&self, long_ddeg: f64, lat_ddeg: f64, alt_m: f64) -> Self {
+        let mut s = self.clone();
+        s.antenna_geo3d = Some(PositionGeo3d::new_wgs84(long_ddeg, lat_ddeg, alt_m));
+        s
+    }
+
+    /// Define Antenna (height, east, north) offset to reference point, in meters.
+    pub fn with_antenna_offset(&self, h_m: f64, e_m: f64, n_m: f64) -> Self {
+        let mut s = self.clone();
+        s.antenna_offset3d = Some((h_m, e_m, n_m));
+        s
+    }
+
+    // returns readable frame content, if it exists
+    fn frame_string(&self, fid: FieldID) -> Option<&str> {
+        self.frames
+            .iter()
+            .find(|fr| fr.fid == fid)
+            .map(|fr| fr.string.as_str())
+    }
+
+    /// Returns receiver model, if known
+    pub fn receiver_model(&self) -> Option<&str> {
+        self.frame_string(FieldID::ReceiverType)
+    }
+
+    /// Returns receiver serial number, if known
+    pub fn receiver_serial_number(&self) -> Option<&str> {
+        self.frame_string(FieldID::ReceiverNumber)
+    }
+
+    /// Returns receiver firmware version, if known
+    pub fn receiver_firm_version(&self) -> Option<&str> {
+        self.frame_string(FieldID::ReceiverFirmwareVersion)
+    }
+
+    /// Returns name of observer, if known
+    pub fn observer(&self) -> Option<&str> {
+        self.frame_string(FieldID::ObserverName)
+    }
+
+    /// Returns observer's contact, if known
+    pub fn observer_contact(&self) -> Option<&str> {
+        self.frame_string(FieldID::ObserverContact)
+    }
+
+    /// Returns Geodetic marker name, if known
+    pub fn geodetic_marker_name(&self) -> Option<&str> {
+        self.frame_string(FieldID::MarkerName)
+    }
+
+    /// Returns Geodetic marker number (DOMES), if known
+    pub fn geodetic_marker_number(&self) -> Option<&str> {
+        self.frame_string(FieldID::MarkerNumber)
+    }
+
+    /// Returns location of this geodetic site, if known
+    pub fn site_location(&self) -> Option<&str> {
+        self.frame_string(FieldID::SiteLocation)
+    }
+
+    /// Returns name of this geodetic site, if known
+    pub fn site_name(&self) -> Option<&str> {
+        self.frame_string(FieldID::SiteName)
+    }
+
+    /// Returns Agency (Organization), if known
+    pub fn agency(&self) -> Option<&str> {
+        self.frame_string(FieldID::AgencyName)
+    }
+
+    /// Returns Antenna model, if known
+    pub fn antenna_model(&self) -> Option<&str> {
+        self.frame_string(FieldID::AntennaType)
+    }
+
+    /// Returns Antenna serial number, if known
+    pub fn antenna_serial_number(&self) -> Option<&str> {
+        self.frame_string(FieldID::AntennaNumber)
+    }
+
+    /// Returns readable Geophysical information, if any
+    pub fn geophysical_info(&self) -> Option<&str> {
+        self.frame_string(FieldID::Geophysical)
+    }
+
+    /// Returns readable Climatic information, if any
+    pub fn climatic_info(&self) -> Option<&str> {
+        self.frame_string(FieldID::Climatic)
+    }
+
+    /// Returns readable UserID, if any
+    pub fn user_id(&self) -> Option<&str> {
+        self.frame_string(FieldID::UserID)
+    }
+
+    /// Returns name of this Geodetic project, if any
+    pub fn project_name(&self) -> Option<&str> {
+        self.frame_string(FieldID::ProjectName)
+    }
+
+    /// Returns extra note, if any
+    pub fn extra_info(&self) -> Option<&str> {
+        self.frame_string(FieldID::Extra)
+    }
 }
 
 #[cfg(test)]
@@ -648,4 +837,36 @@ mod test {
             ]
         );
     }
+
+    #[test]
+    fn monument_geo_antenna_coordinates() {
+        let big_endian = true;
+
+        let mut geo = MonumentGeoRecord::default()
+            .with_receiver_model("RCVR")
+            .with_antenna_ecef_position(4696989.6880, 723994.1970, 4239678.3040)
+            .with_antenna_offset(0.1, 0.2, 0.3);
+
+        geo.epoch = Epoch::from_gpst_seconds(60.0 + 0.75);
+
+        // ts + meta + (FID_1_4 +strlen_1_4 +strlen)
+        //  + FID_1_4 + strlen_1_4 + 3*real8
+        //  + FID_1_4 + 3*real8
+        assert_eq!(geo.encoding_size(), 5 + 1 + 1 + 1 + 4 + 1 + 1 + 24 + 1 + 24);
+
+        let mut buf = [0; 63];
+        let size = geo.encode(big_endian, &mut buf).unwrap();
+        assert_eq!(size, 63);
+
+        let decoded = MonumentGeoRecord::decode(size, big_endian, &buf).unwrap();
+        assert_eq!(decoded, geo);
+
+        assert_eq!(decoded.receiver_model(), Some("RCVR"));
+        assert_eq!(decoded.antenna_model(), None);
+
+        let ecef = decoded.antenna_ecef3d.unwrap();
+        assert_eq!(ecef.ellipsoid, "WGS84");
+        assert_eq!(ecef.x_ecef_m, 4696989.6880);
+        assert_eq!(decoded.antenna_offset3d, Some((0.1, 0.2, 0.3)));
+    }
 }
@@ -0,0 +1,1001 @@
+//! 0x7f-05 GNSS observables
+use crate::Error;
+
+/// Reads `size` bytes (at most 8) as a single bitfield word,
+/// with respect of the stream endianness.
+fn read_word(big_endian: bool, buf: &[u8], size: usize) -> Result<u64, Error> {
+    if buf.len() < size {
+        return Err(Error::NotEnoughBytes);
+    }
+    let mut word = 0u64;
+    for i in 0..size {
+        let byte = if big_endian {
+            buf[i]
+        } else {
+            buf[size - 1 - i]
+        };
+        word = (word << 8) | byte as u64;
+    }
+    Ok(word)
+}
+
+/// Writes `size` bytes (at most 8) bitfield word,
+/// with respect of the stream endianness.
+fn write_word(big_endian: bool, word: u64, buf: &mut [u8], size: usize) {
+    for i in 0..size {
+        let byte = (word >> (8 * (size - 1 - i))) as u8;
+        if big_endian {
+            buf[i] = byte;
+        } else {
+            buf[size - 1 - i] = byte;
+        }
+    }
+}
+
+/// Interprets the `bits` LSB of `val` as signed integer
+fn signed(val: u64, bits: u32) -> i64 {
+    let val = (val & ((1 << bits) - 1)) as i64;
+    if val & (1 << (bits - 1)) > 0 {
+        val - (1 << bits)
+    } else {
+        val
+    }
+}
+
+/// Returns `bits` LSB of signed integer, saturated to the field capacity
+fn unsigned(val: i64, bits: u32) -> u64 {
+    let max = (1i64 << (bits - 1)) - 1;
+    let min = -(1i64 << (bits - 1));
+    (val.clamp(min, max) as u64) & ((1 << bits) - 1)
+}
+
+/// Splits SNR [dB.Hz] into 0.4 dB.Hz integer part and 2 bit signed 0.1 dB.Hz remainder
+fn split_snr(snr_dbhz: f64) -> (u8, i64) {
+    let tenth = (snr_dbhz * 10.0).round() as i64;
+    let coarse = (tenth + 2).div_euclid(4);
+    let coarse = coarse.clamp(0, 255);
+    (coarse as u8, (tenth - 4 * coarse).clamp(-2, 1))
+}
+
+/// [ObservationSystem] is the GNSS constellation identifier
+/// used in 0x7f-05 subrecords.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum ObservationSystem {
+    #[default]
+    GPS,
+    Glonass,
+    SBAS,
+    Galileo,
+    BeiDou,
+    QZSS,
+    /// Unknown system, identifier preserved as is
+    Unknown(u8),
+}
+
+impl From<u8> for ObservationSystem {
+    fn from(val: u8) -> Self {
+        match val {
+            0 => Self::GPS,
+            1 => Self::Glonass,
+            2 => Self::SBAS,
+            3 => Self::Galileo,
+            4 => Self::BeiDou,
+            5 => Self::QZSS,
+            val => Self::Unknown(val),
+        }
+    }
+}
+
+impl From<ObservationSystem> for u8 {
+    fn from(val: ObservationSystem) -> u8 {
+        match val {
+            ObservationSystem::GPS => 0,
+            ObservationSystem::Glonass => 1,
+            ObservationSystem::SBAS => 2,
+            ObservationSystem::Galileo => 3,
+            ObservationSystem::BeiDou => 4,
+            ObservationSystem::QZSS => 5,
+            ObservationSystem::Unknown(val) => val,
+        }
+    }
+}
+
+impl ObservationSystem {
+    /// Converts 0x7f-05 signal code to RINEX (V3) signal code,
+    /// that is, frequency band and tracking attribute (like "1C").
+    /// Returns None if this signal code is not defined for this system.
+    pub fn rinex_code(&self, code: u8) -> Option<&'static str> {
+        const GPS: [&str; 27] = [
+            "1C", "1C", "1P", "1W", "1Y", "1M", "1X", "1N", "", "", "2W", "2C", "2D", "2S", "2L",
+            "2X", "2P", "2W", "2Y", "2M", "2N", "", "", "5X", "5I", "5Q", "5X",
+        ];
+        const GLO: [&str; 17] = [
+            "1C", "1C", "1P", "", "", "", "", "", "", "", "2C", "2C", "2P", "3X", "3I", "3Q", "3X",
+        ];
+        const SBAS: [&str; 13] = ["1C", "1C", "", "", "", "", "", "", "", "", "5I", "5Q", "5X"];
+        const GAL: [&str; 24] = [
+            "1C", "1A", "1B", "1C", "1X", "1Z", "5X", "5I", "5Q", "5X", "7X", "7I", "7Q", "7X",
+            "8X", "8I", "8Q", "8X", "6X", "6A", "6B", "6C", "6X", "6Z",
+        ];
+        const BDS: [&str; 16] = [
+            "2X", "2I", "2Q", "2X", "7X", "7I", "7Q", "7X", "6X", "6I", "6Q", "6X", "1X", "1S",
+            "1L", "1X",
+        ];
+        const QZSS: [&str; 31] = [
+            "1C", "1C", "1S", "1L", "1X", "", "", "2X", "2S", "2L", "2X", "", "", "5X", "5I", "5Q",
+            "5X", "", "", "6X", "6S", "6L", "6X", "", "", "", "", "", "", "", "1Z",
+        ];
+        let table: &[&'static str] = match self {
+            Self::GPS => &GPS,
+            Self::Glonass => &GLO,
+            Self::SBAS => &SBAS,
+            Self::Galileo => &GAL,
+            Self::BeiDou => &BDS,
+            Self::QZSS => &QZSS,
+            Self::Unknown(_) => return None,
+        };
+        match table.get(code as usize) {
+            Some(code) if !code.is_empty() => Some(code),
+            _ => None,
+        }
+    }
+}
+
+/// Receiver clock state, when described by the receiver
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct ReceiverClock {
+    /// Clock reset indicator (2 bits)
+    pub reset: u8,
+    /// Receiver clock offset [s], 1 ns resolution
+    pub offset_s: f64,
+}
+
+/// Offset between one constellation time and the receiver reference time system
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct SystemTimeOffset {
+    /// Time system identifier (4 bits)
+    pub system: u8,
+    /// Time offset [s], 1 ns resolution
+    pub offset_s: f64,
+}
+
+/// System times, when described by the receiver
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SystemTimes {
+    /// Reference time system identifier (4 bits)
+    pub reference: u8,
+    /// [SystemTimeOffset]s (up to 15)
+    pub offsets: Vec<SystemTimeOffset>,
+}
+
+/// [SignalObservation] describes observations on one signal
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SignalObservation {
+    /// Signal code, see [ObservationSystem::rinex_code]
+    pub code: u8,
+    /// Cycle slip (loss of lock) indicator
+    pub slip: bool,
+    /// SNR [dB.Hz], 0.1 dB.Hz resolution
+    pub snr_dbhz: f64,
+    /// Pseudo range [m], 1 mm resolution
+    pub pseudo_range_m: f64,
+    /// Carrier phase range [m]: phase [cycles] times wavelength
+    pub phase_range_m: f64,
+    /// Doppler shift [Hz], 1/256 Hz resolution
+    pub doppler_hz: Option<f64>,
+    /// Cycle slip counter
+    pub slip_count: Option<u16>,
+    /// Glonass FDMA channel number
+    pub glo_channel: Option<i8>,
+    /// Flag bytes (as is, without the continuation bit), index in 2 LSB
+    flags: Vec<u8>,
+}
+
+impl SignalObservation {
+    /// Flags byte #0: doppler is present
+    const DOPPLER: u8 = 0x04;
+    /// Flags byte #0: slip counter is present
+    const SLIP_COUNT: u8 = 0x08;
+    /// Flags byte #0: slip counter is 2 byte wide
+    const SLIP_COUNT_U16: u8 = 0x10;
+    /// Flags byte #0: 0.1 mm (instead of 0.02 mm) phase resolution
+    const COARSE_PHASE: u8 = 0x20;
+    /// Flags byte #0: extended range and phase fields
+    const EXTENDED: u8 = 0x40;
+
+    /// Builds new [SignalObservation] from signal code, pseudo range [m],
+    /// carrier phase range [m] and SNR [dB.Hz].
+    pub fn new(code: u8, pseudo_range_m: f64, phase_range_m: f64, snr_dbhz: f64) -> Self {
+        Self {
+            code,
+            snr_dbhz,
+            pseudo_range_m,
+            phase_range_m,
+            ..Default::default()
+        }
+    }
+
+    /// Copies and returns [SignalObservation] with cycle slip indication
+    pub fn with_slip(&self, slip: bool) -> Self {
+        let mut s = self.clone();
+        s.slip = slip;
+        s
+    }
+
+    /// Copies and returns [SignalObservation] with doppler shift [Hz]
+    pub fn with_doppler(&self, doppler_hz: f64) -> Self {
+        let mut s = self.clone();
+        s.doppler_hz = Some(doppler_hz);
+        s.set_flags(0, s.flags(0) | Self::DOPPLER);
+        s
+    }
+
+    /// Copies and returns [SignalObservation] with cycle slip counter
+    pub fn with_slip_count(&self, count: u16) -> Self {
+        let mut s = self.clone();
+        s.slip_count = Some(count);
+        let mut flags = s.flags(0) | Self::SLIP_COUNT;
+        if count > u8::MAX as u16 {
+            flags |= Self::SLIP_COUNT_U16;
+        }
+        s.set_flags(0, flags);
+        s
+    }
+
+    /// Copies and returns [SignalObservation] with Glonass FDMA channel number
+    pub fn with_glonass_channel(&self, channel: i8) -> Self {
+        let mut s = self.clone();
+        s.glo_channel = Some(channel);
+        s.set_flags(2, 0x02 | ((unsigned(channel as i64, 4) as u8) << 2));
+        s
+    }
+
+    /// Returns flags byte #index (null if not described)
+    fn flags(&self, index: u8) -> u8 {
+        self.flags
+            .iter()
+            .rev()
+            .find(|flags| **flags & 0x03 == index)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Updates (or appends) flags byte #index
+    fn set_flags(&mut self, index: u8, value: u8) {
+        let value = (value & 0x7c) | index;
+        if let Some(flags) = self
+            .flags
+            .iter_mut()
+            .rev()
+            .find(|flags| **flags & 0x03 == index)
+        {
+            *flags = value;
+        } else {
+            self.flags.push(value);
+            self.flags.sort();
+        }
+    }
+
+    /// Phase resolution [m]
+    fn phase_resolution(&self) -> f64 {
+        if self.flags(0) & Self::COARSE_PHASE > 0 {
+            0.0001
+        } else {
+            0.00002
+        }
+    }
+
+    /// [SignalObservation] decoding attempt.
+    /// `range0` is the pseudo range of the first signal of this SV,
+    /// which is used as reference by the following signals.
+    fn decode(big_endian: bool, buf: &[u8], range0: Option<f64>) -> Result<(Self, usize), Error> {
+        if buf.is_empty() {
+            return Err(Error::NotEnoughBytes);
+        }
+
+        let mut s = Self {
+            code: buf[0] & 0x1f,
+            slip: buf[0] & 0x20 > 0,
+            ..Default::default()
+        };
+
+        let mut more = buf[0] & 0x80 > 0;
+        let mut ptr = 1;
+
+        while more {
+            if ptr >= buf.len() {
+                return Err(Error::NotEnoughBytes);
+            }
+            s.flags.push(buf[ptr] & 0x7f);
+            more = buf[ptr] & 0x80 > 0;
+            ptr += 1;
+        }
+
+        let flags = s.flags(0);
+        let extended = flags & Self::EXTENDED > 0;
+        let phase_res = s.phase_resolution();
+
+        if ptr >= buf.len() {
+            return Err(Error::NotEnoughBytes);
+        }
+
+        let mut snr_tenth = buf[ptr] as i64 * 4;
+        ptr += 1;
+
+        s.pseudo_range_m = match range0 {
+            None => {
+                let word = read_word(big_endian, &buf[ptr..], 5)?;
+                ptr += 5;
+                snr_tenth += signed(word >> 38, 2);
+                ((word >> 6) & 0xffffffff) as f64 * 0.064 + (word & 0x3f) as f64 * 0.001
+            },
+            Some(range0) => {
+                if extended {
+                    let word = read_word(big_endian, &buf[ptr..], 3)?;
+                    ptr += 3;
+                    snr_tenth += signed(word >> 22, 2);
+                    range0 + signed(word, 20) as f64 * 0.001
+                } else {
+                    let word = read_word(big_endian, &buf[ptr..], 2)?;
+                    ptr += 2;
+                    range0 + signed(word, 16) as f64 * 0.001
+                }
+            },
+        };
+
+        let word = read_word(big_endian, &buf[ptr..], 3)?;
+        ptr += 3;
+
+        s.phase_range_m = if extended {
+            s.pseudo_range_m + signed(word, 24) as f64 * phase_res
+        } else {
+            snr_tenth += signed(word >> 22, 2);
+            s.pseudo_range_m + signed(word, 22) as f64 * phase_res
+        };
+
+        s.snr_dbhz = snr_tenth as f64 * 0.1;
+
+        if flags & Self::DOPPLER > 0 {
+            let word = read_word(big_endian, &buf[ptr..], 3)?;
+            ptr += 3;
+            s.doppler_hz = Some(signed(word, 24) as f64 / 256.0);
+        }
+
+        if flags & Self::SLIP_COUNT > 0 {
+            if flags & Self::SLIP_COUNT_U16 > 0 {
+                let word = read_word(big_endian, &buf[ptr..], 2)?;
+                ptr += 2;
+                s.slip_count = Some(word as u16);
+            } else {
+                if ptr >= buf.len() {
+                    return Err(Error::NotEnoughBytes);
+                }
+                s.slip_count = Some(buf[ptr] as u16);
+                ptr += 1;
+            }
+        }
+
+        if has_flags(&s.flags, 2) {
+            s.glo_channel = Some(signed((s.flags(2) >> 2) as u64, 4) as i8);
+        }
+
+        Ok((s, ptr))
+    }
+
+    /// Encodes [SignalObservation] into buffer, returns encoded size.
+    /// Differential fields are saturated to their capacity.
+    fn encode(
+        &self,
+        big_endian: bool,
+        buf: &mut [u8],
+        range0: Option<f64>,
+    ) -> Result<usize, Error> {
+        let size = self.encoding_size(range0.is_none());
+        if buf.len() < size {
+            return Err(Error::NotEnoughBytes);
+        }
+
+        let flags = self.flags(0);
+        let extended = flags & Self::EXTENDED > 0;
+        let phase_res = self.phase_resolution();
+
+        buf[0] = self.code & 0x1f;
+        if self.slip {
+            buf[0] |= 0x20;
+        }
+        if !self.flags.is_empty() {
+            buf[0] |= 0x80;
+        }
+
+        let mut ptr = 1;
+        for (i, flags) in self.flags.iter().enumerate() {
+            buf[ptr] = flags & 0x7f;
+            if i < self.flags.len() - 1 {
+                buf[ptr] |= 0x80;
+            }
+            ptr += 1;
+        }
+
+        let (snr, snr_frac) = split_snr(self.snr_dbhz);
+        buf[ptr] = snr;
+        ptr += 1;
+
+        let snr_frac = unsigned(snr_frac, 2);
+
+        match range0 {
+            None => {
+                let range_mm = (self.pseudo_range_m * 1.0E3).round().max(0.0) as u64;
+                let range_mm = range_mm.min(0xffffffff * 64 + 63);
+                let word = (snr_frac << 38) | ((range_mm / 64) << 6) | (range_mm % 64);
+                write_word(big_endian, word, &mut buf[ptr..], 5);
+                ptr += 5;
+            },
+            Some(range0) => {
+                let dr_mm = ((self.pseudo_range_m - range0) * 1.0E3).round() as i64;
+                if extended {
+                    let word = (snr_frac << 22) | unsigned(dr_mm, 20);
+                    write_word(big_endian, word, &mut buf[ptr..], 3);
+                    ptr += 3;
+                } else {
+                    write_word(big_endian, unsigned(dr_mm, 16), &mut buf[ptr..], 2);
+                    ptr += 2;
+                }
+            },
+        }
+
+        // phase is described with respect to the pseudo range, as decoded
+        let range_m = self.decoded_range(range0);
+        let dphi = ((self.phase_range_m - range_m) / phase_res).round() as i64;
+
+        let word = if extended {
+            unsigned(dphi, 24)
+        } else if range0.is_none() {
+            // SNR remainder is already described
+            unsigned(dphi, 22)
+        } else {
+            (snr_frac << 22) | unsigned(dphi, 22)
+        };
+
+        write_word(big_endian, word, &mut buf[ptr..], 3);
+        ptr += 3;
+
+        if flags & Self::DOPPLER > 0 {
+            let doppler = (self.doppler_hz.unwrap_or_default() * 256.0).round() as i64;
+            write_word(big_endian, unsigned(doppler, 24), &mut buf[ptr..], 3);
+            ptr += 3;
+        }
+
+        if flags & Self::SLIP_COUNT > 0 {
+            let count = self.slip_count.unwrap_or_default();
+            if flags & Self::SLIP_COUNT_U16 > 0 {
+                write_word(big_endian, count as u64, &mut buf[ptr..], 2);
+                ptr += 2;
+            } else {
+                buf[ptr] = count.min(u8::MAX as u16) as u8;
+                ptr += 1;
+            }
+        }
+
+        Ok(ptr)
+    }
+
+    /// Pseudo range [m] as it will be decoded
+    fn decoded_range(&self, range0: Option<f64>) -> f64 {
+        match range0 {
+            None => {
+                let range_mm = (self.pseudo_range_m * 1.0E3).round().max(0.0) as u64;
+                (range_mm / 64) as f64 * 0.064 + (range_mm % 64) as f64 * 0.001
+            },
+            Some(range0) => {
+                let bits = if self.flags(0) & Self::EXTENDED > 0 {
+                    20
+                } else {
+                    16
+                };
+                let dr_mm = ((self.pseudo_range_m - range0) * 1.0E3).round() as i64;
+                range0 + signed(unsigned(dr_mm, bits), bits) as f64 * 0.001
+            },
+        }
+    }
+
+    /// Returns total length (bytewise) required to fully encode [Self].
+    fn encoding_size(&self, first: bool) -> usize {
+        let flags = self.flags(0);
+        let mut size = 1 + self.flags.len() + 1; // code + flags + snr
+        size += if first {
+            5
+        } else if flags & Self::EXTENDED > 0 {
+            3
+        } else {
+            2
+        };
+        size += 3; // phase
+        if flags & Self::DOPPLER > 0 {
+            size += 3;
+        }
+        if flags & Self::SLIP_COUNT > 0 {
+            if flags & Self::SLIP_COUNT_U16 > 0 {
+                size += 2;
+            } else {
+                size += 1;
+            }
+        }
+        size
+    }
+}
+
+/// True if flags byte #index is described
+fn has_flags(flags: &[u8], index: u8) -> bool {
+    flags.iter().any(|flags| *flags & 0x03 == index)
+}
+
+/// [SVObservations] gathers all [SignalObservation]s of one satellite
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SVObservations {
+    /// [ObservationSystem]
+    pub system: ObservationSystem,
+    /// Satellite PRN number
+    pub prn: u8,
+    /// [SignalObservation]s (up to 7)
+    pub signals: Vec<SignalObservation>,
+}
+
+impl SVObservations {
+    /// Builds new [SVObservations] for this satellite
+    pub fn new(system: ObservationSystem, prn: u8) -> Self {
+        Self {
+            system,
+            prn,
+            signals: Vec::with_capacity(4),
+        }
+    }
+
+    /// Copies and returns [SVObservations] with one more [SignalObservation]
+    pub fn with_signal(&self, signal: SignalObservation) -> Self {
+        let mut s = self.clone();
+        s.signals.push(signal);
+        s
+    }
+
+    fn decode(big_endian: bool, buf: &[u8]) -> Result<(Self, usize), Error> {
+        if buf.len() < 2 {
+            return Err(Error::NotEnoughBytes);
+        }
+
+        let prn = buf[0];
+        let nobs = ((buf[1] >> 4) & 0x07) as usize;
+        let system = ObservationSystem::from(buf[1] & 0x0f);
+
+        let mut ptr = 2;
+        let mut signals = Vec::<SignalObservation>::with_capacity(nobs);
+
+        for i in 0..nobs {
+            let range0 = if i == 0 {
+                None
+            } else {
+                Some(signals[0].pseudo_range_m)
+            };
+            let (signal, size) = SignalObservation::decode(big_endian, &buf[ptr..], range0)?;
+            signals.push(signal);
+            ptr += size;
+        }
+
+        Ok((
+            Self {
+                system,
+                prn,
+                signals,
+            },
+            ptr,
+        ))
+    }
+
+    fn encode(&self, big_endian: bool, buf: &mut [u8]) -> Result<usize, Error> {
+        let size = self.encoding_size();
+        if buf.len() < size {
+            return Err(Error::NotEnoughBytes);
+        }
+
+        let nobs = self.signals.len().min(7);
+
+        buf[0] = self.prn;
+        buf[1] = ((nobs as u8) << 4) | (u8::from(self.system) & 0x0f);
+
+        let mut ptr = 2;
+        let mut range0 = None;
+
+        for signal in self.signals.iter().take(nobs) {
+            ptr += signal.encode(big_endian, &mut buf[ptr..], range0)?;
+            if range0.is_none() {
+                range0 = Some(signal.decoded_range(None));
+            }
+        }
+
+        Ok(ptr)
+    }
+
+    fn encoding_size(&self) -> usize {
+        let mut size = 2;
+        for (i, signal) in self.signals.iter().take(7).enumerate() {
+            size += signal.encoding_size(i == 0);
+        }
+        size
+    }
+}
+
+/// [GNSSObservations] is the content of 0x7f-05 subrecords:
+/// the observations of all tracked satellites at one sampling instant.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GNSSObservations {
+    /// [ReceiverClock] state, when described
+    pub clock: Option<ReceiverClock>,
+    /// [SystemTimes], when described
+    pub system_times: Option<SystemTimes>,
+    /// [SVObservations]: from 1 up to 64 satellites per subrecord
+    pub sv: Vec<SVObservations>,
+}
+
+impl GNSSObservations {
+    /// Receiver clock offset is present
+    const CLOCK_MASK: u8 = 0x80;
+    /// System times are present
+    const SYSTEM_TIMES_MASK: u8 = 0x40;
+
+    /// Copies and returns [GNSSObservations] with [ReceiverClock] state
+    pub fn with_receiver_clock(&self, clock: ReceiverClock) -> Self {
+        let mut s = self.clone();
+        s.clock = Some(clock);
+        s
+    }
+
+    /// Copies and returns [GNSSObservations] with [SystemTimes]
+    pub fn with_system_times(&self, times: SystemTimes) -> Self {
+        let mut s = self.clone();
+        s.system_times = Some(times);
+        s
+    }
+
+    /// Copies and returns [GNSSObservations] with one more satellite
+    pub fn with_sv(&self, sv: SVObservations) -> Self {
+        let mut s = self.clone();
+        s.sv.push(sv);
+        s
+    }
+
+    /// [GNSSObservations] decoding attempt from 0x7f-05 payload
+    pub(crate) fn decode(big_endian: bool, buf: &[u8]) -> Result<Self, Error> {
+        if buf.is_empty() {
+            return Err(Error::NotEnoughBytes);
+        }
+
+        let flags = buf[0];
+        let nsat = (flags & 0x3f) as usize + 1;
+        let mut ptr = 1;
+
+        let clock = if flags & Self::CLOCK_MASK > 0 {
+            let word = read_word(big_endian, &buf[ptr..], 3)?;
+            ptr += 3;
+            Some(ReceiverClock {
+                reset: (word >> 22) as u8,
+                offset_s: signed(word, 22) as f64 * 1.0E-9,
+            })
+        } else {
+            None
+        };
+
+        let system_times = if flags & Self::SYSTEM_TIMES_MASK > 0 {
+            if ptr >= buf.len() {
+                return Err(Error::NotEnoughBytes);
+            }
+            let nsys = (buf[ptr] >> 4) as usize;
+            let reference = buf[ptr] & 0x0f;
+            ptr += 1;
+
+            let mut offsets = Vec::with_capacity(nsys);
+            for _ in 0..nsys {
+                let word = read_word(big_endian, &buf[ptr..], 4)?;
+                ptr += 4;
+                offsets.push(SystemTimeOffset {
+                    system: (word & 0x0f) as u8,
+                    offset_s: signed(word >> 8, 24) as f64 * 1.0E-9,
+                });
+            }
+
+            Some(SystemTimes { reference, offsets })
+        } else {
+            None
+        };
+
+        let mut sv = Vec::with_capacity(nsat);
+        for _ in 0..nsat {
+            let (obs, size) = SVObservations::decode(big_endian, &buf[ptr..])?;
+            sv.push(obs);
+            ptr += size;
+        }
+
+        Ok(Self {
+            clock,
+            system_times,
+            sv,
+        })
+    }
+
+    /// Encodes [GNSSObservations] into buffer, returns encoded size (total bytes).
+    /// Self must describe 1 to 64 satellites.
+    pub(crate) fn encode(&self, big_endian: bool, buf: &mut [u8]) -> Result<usize, Error> {
+        if self.sv.is_empty() || self.sv.len() > 64 {
+            return Err(Error::InvalidSatelliteCount);
+        }
+
+        let size = self.encoding_size();
+        if buf.len() < size {
+            return Err(Error::NotEnoughBytes);
+        }
+
+        buf[0] = (self.sv.len() - 1) as u8;
+
+        let mut ptr = 1;
+
+        if let Some(clock) = &self.clock {
+            buf[0] |= Self::CLOCK_MASK;
+            let offset_ns = (clock.offset_s * 1.0E9).round() as i64;
+            let word = (((clock.reset & 0x03) as u64) << 22) | unsigned(offset_ns, 22);
+            write_word(big_endian, word, &mut buf[ptr..], 3);
+            ptr += 3;
+        }
+
+        if let Some(times) = &self.system_times {
+            buf[0] |= Self::SYSTEM_TIMES_MASK;
+            let nsys = times.offsets.len().min(15);
+            buf[ptr] = ((nsys as u8) << 4) | (times.reference & 0x0f);
+            ptr += 1;
+
+            for offset in times.offsets.iter().take(nsys) {
+                let offset_ns = (offset.offset_s * 1.0E9).round() as i64;
+                let word = (unsigned(offset_ns, 24) << 8) | (offset.system & 0x0f) as u64;
+                write_word(big_endian, word, &mut buf[ptr..], 4);
+                ptr += 4;
+            }
+        }
+
+        for sv in self.sv.iter() {
+            ptr += sv.encode(big_endian, &mut buf[ptr..])?;
+        }
+
+        Ok(ptr)
+    }
+
+    /// Returns total length (bytewise) required to fully encode [Self].
+    pub(crate) fn encoding_size(&self) -> usize {
+        let mut size = 1;
+        if self.clock.is_some() {
+            size += 3;
+        }
+        if let Some(times) = &self.system_times {
+            size += 1 + 4 * times.offsets.len().min(15);
+        }
+        for sv in self.sv.iter() {
+            size += sv.encoding_size();
+        }
+        size
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Hand assembled 0x7f-05 subrecord following the NetR8 layout:
+    // clock offset, G12 (L1C, L2C) and R05 (L1C).
+    // This is not a receiver capture: test_resources does not ship any
+    // BINEX stream yet. Cross check against a real 0x7f-05 capture
+    // once one is available.
+    const NETR8: [u8; 45] = [
+        129, 127, 250, 36, 12, 32, 0, 113, 4, 254, 236, 158, 131, 0, 48, 57, 171, 12, 100, 246, 60,
+        127, 240, 96, 251, 46, 128, 7, 5, 17, 128, 132, 54, 110, 196, 160, 187, 250, 234, 63, 255,
+        156, 7, 208, 0,
+    ];
+
+    #[test]
+    fn bitfields() {
+        assert_eq!(signed(0x3, 2), -1);
+        assert_eq!(signed(0x1, 2), 1);
+        assert_eq!(signed(0x2, 2), -2);
+        assert_eq!(unsigned(-1, 2), 0x3);
+        assert_eq!(unsigned(-3, 2), 0x2);
+        assert_eq!(unsigned(100, 4), 0x7);
+        assert_eq!(split_snr(45.2), (113, 0));
+        assert_eq!(split_snr(40.1), (100, 1));
+        assert_eq!(split_snr(43.9), (110, -1));
+
+        let mut buf = [0; 3];
+        for big_endian in [true, false] {
+            write_word(big_endian, 0x123456, &mut buf, 3);
+            assert_eq!(read_word(big_endian, &buf, 3).unwrap(), 0x123456);
+        }
+        assert_eq!(buf, [0x56, 0x34, 0x12]);
+    }
+
+    #[test]
+    fn signal_codes() {
+        assert_eq!(ObservationSystem::GPS.rinex_code(0), Some("1C"));
+        assert_eq!(ObservationSystem::GPS.rinex_code(11), Some("2C"));
+        assert_eq!(ObservationSystem::GPS.rinex_code(8), None);
+        assert_eq!(ObservationSystem::Galileo.rinex_code(12), Some("7Q"));
+        assert_eq!(ObservationSystem::Glonass.rinex_code(12), Some("2P"));
+        assert_eq!(ObservationSystem::Unknown(9).rinex_code(0), None);
+    }
+
+    #[test]
+    fn gnss_observations() {
+        let big_endian = true;
+        let decoded = GNSSObservations::decode(big_endian, &NETR8).unwrap();
+
+        let clock = decoded.clock.unwrap();
+        assert_eq!(clock.reset, 1);
+        assert!((clock.offset_s + 1.5E-6).abs() < 1.0E-12);
+        assert!(decoded.system_times.is_none());
+
+        assert_eq!(decoded.sv.len(), 2);
+
+        let g12 = &decoded.sv[0];
+        assert_eq!(g12.system, ObservationSystem::GPS);
+        assert_eq!(g12.prn, 12);
+        assert_eq!(g12.signals.len(), 2);
+
+        let l1c = &g12.signals[0];
+        assert_eq!(l1c.code, 0);
+        assert!(!l1c.slip);
+        assert!((l1c.snr_dbhz - 45.2).abs() < 1.0E-9);
+        assert!((l1c.pseudo_range_m - 21456789.123).abs() < 1.0E-6);
+        assert!((l1c.phase_range_m - 21456789.3699).abs() < 1.0E-6);
+        assert!(l1c.doppler_hz.is_none());
+        assert!(l1c.slip_count.is_none());
+        assert!(l1c.glo_channel.is_none());
+
+        let l2c = &g12.signals[1];
+        assert_eq!(l2c.code, 11);
+        assert!(l2c.slip);
+        assert!((l2c.snr_dbhz - 40.1).abs() < 1.0E-9);
+        assert!((l2c.pseudo_range_m - 21456786.623).abs() < 1.0E-6);
+        assert!((l2c.phase_range_m - 21456786.543).abs() < 1.0E-6);
+        assert_eq!(l2c.doppler_hz, Some(-1233.5));
+        assert_eq!(l2c.slip_count, Some(7));
+
+        let r05 = &decoded.sv[1];
+        assert_eq!(r05.system, ObservationSystem::Glonass);
+        assert_eq!(r05.prn, 5);
+
+        let l1c = &r05.signals[0];
+        assert!((l1c.snr_dbhz - 43.9).abs() < 1.0E-9);
+        assert!((l1c.pseudo_range_m - 19876543.210).abs() < 1.0E-6);
+        assert!((l1c.phase_range_m - 19876543.208).abs() < 1.0E-6);
+        assert_eq!(l1c.doppler_hz, Some(2000.0));
+        assert_eq!(l1c.glo_channel, Some(-3));
+
+        // mirror op
+        assert_eq!(decoded.encoding_size(), NETR8.len());
+
+        let mut encoded = [0; 45];
+        let size = decoded.encode(big_endian, &mut encoded).unwrap();
+        assert_eq!(size, NETR8.len());
+        assert_eq!(encoded, NETR8);
+    }
+
+    #[test]
+    fn gnss_observations_builder() {
+        let l1c = SignalObservation::new(0, 21456789.123, 21456789.3699, 45.2);
+
+        let l2c = SignalObservation::new(11, 21456786.623, 21456786.543, 40.1)
+            .with_slip(true)
+            .with_doppler(-1233.5)
+            .with_slip_count(7);
+
+        let g12 = SVObservations::new(ObservationSystem::GPS, 12)
+            .with_signal(l1c)
+            .with_signal(l2c);
+
+        let l1c = SignalObservation::new(0, 19876543.210, 19876543.208, 43.9)
+            .with_doppler(2000.0)
+            .with_glonass_channel(-3);
+
+        let r05 = SVObservations::new(ObservationSystem::Glonass, 5).with_signal(l1c);
+
+        let observations = GNSSObservations::default()
+            .with_receiver_clock(ReceiverClock {
+                reset: 1,
+                offset_s: -1.5E-6,
+            })
+            .with_sv(g12)
+            .with_sv(r05);
+
+        assert_eq!(observations.encoding_size(), NETR8.len());
+
+        for big_endian in [true, false] {
+            let mut encoded = [0; 45];
+            let size = observations.encode(big_endian, &mut encoded).unwrap();
+            assert_eq!(size, NETR8.len());
+
+            if big_endian {
+                assert_eq!(encoded, NETR8);
+            }
+
+            let decoded = GNSSObservations::decode(big_endian, &encoded).unwrap();
+            assert_eq!(decoded.sv.len(), 2);
+            assert_eq!(decoded.sv[1].signals[0].glo_channel, Some(-3));
+
+            let mut reencoded = [0; 45];
+            decoded.encode(big_endian, &mut reencoded).unwrap();
+            assert_eq!(reencoded, encoded);
+        }
+    }
+
+    #[test]
+    fn satellite_count() {
+        let mut buf = [0; 16];
+        assert!(matches!(
+            GNSSObservations::default().encode(true, &mut buf),
+            Err(Error::InvalidSatelliteCount)
+        ));
+
+        let l1c = SignalObservation::new(0, 21456789.123, 21456789.3699, 45.2);
+
+        let mut observations = GNSSObservations::default();
+        for prn in 1..=64 {
+            observations = observations
+                .with_sv(SVObservations::new(ObservationSystem::GPS, prn).with_signal(l1c.clone()));
+        }
+
+        let mut buf = vec![0; observations.encoding_size()];
+        observations.encode(true, &mut buf).unwrap();
+
+        let decoded = GNSSObservations::decode(true, &buf).unwrap();
+        assert_eq!(decoded.sv.len(), 64);
+
+        let observations =
+            observations.with_sv(SVObservations::new(ObservationSystem::GPS, 65).with_signal(l1c));
+
+        let mut buf = vec![0; observations.encoding_size()];
+        assert!(matches!(
+            observations.encode(true, &mut buf),
+            Err(Error::InvalidSatelliteCount)
+        ));
+    }
+
+    #[test]
+    fn system_times() {
+        let observations = GNSSObservations::default()
+            .with_system_times(SystemTimes {
+                reference: 0,
+                offsets: vec![
+                    SystemTimeOffset {
+                        system: 1,
+                        offset_s: 1.0E-6,
+                    },
+                    SystemTimeOffset {
+                        system: 3,
+                        offset_s: -2.5E-8,
+                    },
+                ],
+            })
+            .with_sv(
+                SVObservations::new(ObservationSystem::Galileo, 1)
+                    .with_signal(SignalObservation::new(1, 23456789.0, 23456789.0, 48.0)),
+            );
+
+        // flags + nsys + 2 * 4 + SV
+        assert_eq!(observations.encoding_size(), 1 + 1 + 8 + 2 + 1 + 1 + 5 + 3);
+
+        let mut encoded = [0; 22];
+        observations.encode(true, &mut encoded).unwrap();
+
+        let decoded = GNSSObservations::decode(true, &encoded).unwrap();
+        let times = decoded.system_times.unwrap();
+        assert_eq!(times.offsets.len(), 2);
+        assert_eq!(times.offsets[0].system, 1);
+        assert!((times.offsets[0].offset_s - 1.0E-6).abs() < 1.0E-12);
+        assert_eq!(times.offsets[1].system, 3);
+        assert!((times.offsets[1].offset_s + 2.5E-8).abs() < 1.0E-12);
+        assert_eq!(decoded.sv[0].system, ObservationSystem::Galileo);
+    }
+}
@@ -0,0 +1,236 @@
+//! GNSS observations (0x7f prototyping record).
+//! Only 0x7f-05 subrecords are interpreted, see [ObservationFrame].
+use crate::{
+    message::time::{decode_gpst_epoch, encode_epoch, TimeResolution},
+    Error,
+};
+
+use hifitime::{Epoch, TimeScale};
+
+mod frame;
+
+pub use frame::{
+    GNSSObservations, ObservationSystem, ReceiverClock, SVObservations, SignalObservation,
+    SystemTimeOffset, SystemTimes,
+};
+
+/// [ObservationFrame] describes the content of one 0x7f subrecord
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObservationFrame {
+    /// 0x7f-05: GNSS observables (Trimble NetR8 and later),
+    /// fully interpreted.
+    GNSS(GNSSObservations),
+    /// 0x7f-00 to 0x7f-04 subrecords (older receiver prototypes).
+    /// These are not interpreted by this library: we have neither
+    /// a description of their content nor real captures to validate
+    /// a decoder against. Their payload is preserved as is, so a decoded
+    /// message is re-encoded identically.
+    Undecoded {
+        /// Subrecord ID
+        subrecord: u8,
+        /// Raw payload, following the time tag
+        payload: Vec<u8>,
+    },
+}
+
+impl Default for ObservationFrame {
+    fn default() -> Self {
+        Self::GNSS(Default::default())
+    }
+}
+
+impl ObservationFrame {
+    /// Subrecord ID
+    pub(crate) fn subrecord(&self) -> u8 {
+        match self {
+            Self::GNSS(_) => ObservationRecord::GNSS_SUBRECORD,
+            Self::Undecoded { subrecord, .. } => *subrecord,
+        }
+    }
+    /// Returns total length (bytewise) required to fully encode [Self].
+    pub(crate) fn encoding_size(&self) -> usize {
+        match self {
+            Self::GNSS(obs) => obs.encoding_size(),
+            Self::Undecoded { payload, .. } => payload.len(),
+        }
+    }
+}
+
+/// [ObservationRecord] is the time tagged GNSS observations
+/// record (0x7f), streamed at sampling rate by the receiver.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ObservationRecord {
+    /// Sampling [Epoch], expressed in GPST
+    pub epoch: Epoch,
+    /// [ObservationFrame]
+    pub frame: ObservationFrame,
+}
+
+impl ObservationRecord {
+    /// 1 byte subrecord ID
+    /// 4 byte minutes   }
+    /// 2 byte ms        } epoch
+    const MIN_SIZE: usize = 1 + 4 + 2;
+
+    /// 0x7f-05 subrecord
+    const GNSS_SUBRECORD: u8 = 0x05;
+
+    /// Creates new [ObservationRecord] from [GNSSObservations] sampled at [Epoch].
+    /// ```
+    /// use binex::prelude::{
+    ///     Epoch, Message, Meta, Record,
+    ///     ObservationRecord, GNSSObservations, SVObservations,
+    ///     ObservationSystem, SignalObservation,
+    /// };
+    ///
+    /// let meta = Meta {
+    ///     reversed: false,
+    ///     big_endian: true,
+    ///     enhanced_crc: false,
+    /// };
+    ///
+    /// let t = Epoch::from_gpst_seconds(60.0 + 0.5);
+    ///
+    /// // G12 L1 C/A observations
+    /// let l1ca = SignalObservation::new(0, 21456789.123, 21456789.3699, 45.2)
+    ///     .with_doppler(-1233.5);
+    ///
+    /// let g12 = SVObservations::new(ObservationSystem::GPS, 12)
+    ///     .with_signal(l1ca);
+    ///
+    /// let observations = GNSSObservations::default()
+    ///     .with_sv(g12);
+    ///
+    /// let record = ObservationRecord::new(t, observations);
+    ///
+    /// let msg = Message::new(meta, Record::new_observations(record));
+    ///
+    /// let mut encoded = [0; 64];
+    /// msg.encode(&mut encoded, 64)
+    ///     .unwrap();
+    ///
+    /// let decoded = Message::decode(&encoded)
+    ///     .unwrap();
+    ///
+    /// let record = decoded.record.as_observations()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(record.epoch, t);
+    /// ```
+    pub fn new(epoch: Epoch, observations: GNSSObservations) -> Self {
+        Self {
+            epoch,
+            frame: ObservationFrame::GNSS(observations),
+        }
+    }
+
+    /// Returns [GNSSObservations] if this is a 0x7f-05 subrecord
+    pub fn gnss_observations(&self) -> Option<&GNSSObservations> {
+        match &self.frame {
+            ObservationFrame::GNSS(obs) => Some(obs),
+            _ => None,
+        }
+    }
+
+    /// [ObservationRecord] decoding attempt from buffered content.
+    /// ## Inputs
+    ///    - mlen: message length in bytes
+    ///    - big_endian: endianness
+    ///    - buf: buffered content
+    /// ## Outputs
+    ///    - Result<[ObservationRecord], [Error]>
+    pub(crate) fn decode(mlen: usize, big_endian: bool, buf: &[u8]) -> Result<Self, Error> {
+        if mlen < Self::MIN_SIZE || buf.len() < mlen {
+            return Err(Error::NotEnoughBytes);
+        }
+
+        let subrecord = buf[0];
+
+        // decode timestamp
+        let epoch = decode_gpst_epoch(big_endian, TimeResolution::MilliSecond, &buf[1..])?;
+
+        let payload = &buf[Self::MIN_SIZE..mlen];
+
+        let frame = match subrecord {
+            Self::GNSS_SUBRECORD => {
+                ObservationFrame::GNSS(GNSSObservations::decode(big_endian, payload)?)
+            },
+            0x00..=0x04 => ObservationFrame::Undecoded {
+                subrecord,
+                payload: payload.to_vec(),
+            },
+            _ => return Err(Error::NonSupportedSubRecord),
+        };
+
+        Ok(Self { epoch, frame })
+    }
+
+    /// Encodes [ObservationRecord] into buffer, returns encoded size (total bytes).
+    /// [ObservationRecord] must fit in preallocated buffer.
+    pub(crate) fn encode(&self, big_endian: bool, buf: &mut [u8]) -> Result<usize, Error> {
+        let size = self.encoding_size();
+        if buf.len() < size {
+            return Err(Error::NotEnoughBytes);
+        }
+
+        buf[0] = self.frame.subrecord();
+
+        // encode tstamp
+        let t = self.epoch.to_time_scale(TimeScale::GPST);
+        let mut ptr = 1;
+        ptr += encode_epoch(t, TimeResolution::MilliSecond, big_endian, &mut buf[ptr..])?;
+
+        match &self.frame {
+            ObservationFrame::GNSS(obs) => {
+                ptr += obs.encode(big_endian, &mut buf[ptr..])?;
+            },
+            ObservationFrame::Undecoded { payload, .. } => {
+                buf[ptr..ptr + payload.len()].copy_from_slice(payload);
+                ptr += payload.len();
+            },
+        }
+
+        Ok(ptr)
+    }
+
+    /// Returns total length (bytewise) required to fully encode [Self].
+    /// Use this to fulfill [Self::encode] requirements.
+    pub(crate) fn encoding_size(&self) -> usize {
+        Self::MIN_SIZE + self.frame.encoding_size()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn undecoded_subrecord() {
+        let big_endian = true;
+        let buf = [0x03, 0, 0, 0, 1, 0x2a, 0xf8, 1, 2, 3, 4];
+
+        let decoded = ObservationRecord::decode(buf.len(), big_endian, &buf).unwrap();
+
+        assert_eq!(decoded.epoch, Epoch::from_gpst_seconds(71.0));
+        assert_eq!(
+            decoded.frame,
+            ObservationFrame::Undecoded {
+                subrecord: 3,
+                payload: vec![1, 2, 3, 4],
+            }
+        );
+
+        assert_eq!(decoded.encoding_size(), buf.len());
+
+        let mut encoded = [0; 11];
+        let size = decoded.encode(big_endian, &mut encoded).unwrap();
+        assert_eq!(size, buf.len());
+        assert_eq!(encoded, buf);
+    }
+
+    #[test]
+    fn unknown_subrecord() {
+        let buf = [0x06, 0, 0, 0, 1, 0x2a, 0xf8, 1, 2, 3, 4];
+        assert!(ObservationRecord::decode(buf.len(), true, &buf).is_err());
+    }
+}
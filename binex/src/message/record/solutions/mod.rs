@@ -191,6 +191,15 @@ impl Solutions {
         s
     }
 
+    /// Define antenna position solution (without velocity), in ECEF
+    pub fn with_position_ecef_wgs84(&self, x_ecef_m: f64, y_ecef_m: f64, z_ecef_m: f64) -> Self {
+        let mut s = self.clone();
+        s.frames.push(SolutionsFrame::AntennaEcefPosition(
+            PositionEcef3d::new_wgs84(x_ecef_m, y_ecef_m, z_ecef_m),
+        ));
+        s
+    }
+
     /// Define PVT solution update, in ECEF
     pub fn with_pvt_ecef_wgs84(
         &self,
@@ -0,0 +1,81 @@
+//! Records this library identifies but does not interpret.
+use crate::Error;
+
+/// [UndecodedRecord] is the content of a Meteo (0x03, 0x7e)
+/// or Receiver info (0x04, 0x7d) record.
+/// These records are not interpreted by this library: we have neither
+/// a description of their content nor real captures to validate a decoder against.
+/// Their payload is preserved as is, so a decoded message is re-encoded identically.
+/// ```
+/// use binex::prelude::{Message, Meta, Record, UndecodedRecord};
+///
+/// let meta = Meta {
+///     reversed: false,
+///     big_endian: true,
+///     enhanced_crc: false,
+/// };
+///
+/// let meteo = UndecodedRecord {
+///     prototyping: false,
+///     payload: vec![0x01, 0x02, 0x03, 0x04],
+/// };
+///
+/// let msg = Message::new(meta, Record::new_meteo(meteo));
+///
+/// let mut encoded = [0; 16];
+/// let _ = msg.encode(&mut encoded, msg.encoding_size())
+///     .unwrap();
+///
+/// // definitive MID
+/// assert_eq!(encoded[1], 0x03);
+///
+/// let decoded = Message::decode(&encoded)
+///     .unwrap();
+///
+/// assert_eq!(decoded, msg);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UndecodedRecord {
+    /// True when streamed with the prototyping MID (0x7e, 0x7d),
+    /// false when streamed with the definitive MID (0x03, 0x04).
+    pub prototyping: bool,
+    /// Record content, as streamed
+    pub payload: Vec<u8>,
+}
+
+impl UndecodedRecord {
+    /// [UndecodedRecord] decoding attempt from buffered content.
+    /// ## Inputs
+    ///    - mlen: message length in bytes
+    ///    - prototyping: true when streamed with the prototyping MID
+    ///    - buf: buffered content
+    /// ## Outputs
+    ///    - Result<[UndecodedRecord], [Error]>
+    pub(crate) fn decode(mlen: usize, prototyping: bool, buf: &[u8]) -> Result<Self, Error> {
+        if buf.len() < mlen {
+            return Err(Error::NotEnoughBytes);
+        }
+
+        Ok(Self {
+            prototyping,
+            payload: buf[..mlen].to_vec(),
+        })
+    }
+
+    /// Encodes [UndecodedRecord] into buffer, returns encoded size (total bytes).
+    /// [UndecodedRecord] must fit in preallocated buffer.
+    pub(crate) fn encode(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let size = self.encoding_size();
+        if buf.len() < size {
+            return Err(Error::NotEnoughBytes);
+        }
+
+        buf[..size].copy_from_slice(&self.payload);
+        Ok(size)
+    }
+
+    /// Returns total length (bytewise) required to fully encode [Self].
+    pub(crate) fn encoding_size(&self) -> usize {
+        self.payload.len()
+    }
+}
@@ -0,0 +1,262 @@
+use binex::prelude::{
+    Decoder, Encoder, EphemerisFrame, Epoch, Error, GNSSObservations, GPSEphemeris, Message, Meta,
+    MonumentGeoRecord, ObservationRecord, ObservationSystem, Record, SVObservations,
+    SignalObservation, Solutions, StreamElement, TemporalSolution,
+};
+
+fn messages() -> Vec<Message> {
+    let meta = Meta {
+        reversed: false,
+        big_endian: true,
+        enhanced_crc: false,
+    };
+
+    let t = Epoch::from_gpst_seconds(60.0 + 0.75);
+
+    let geo = MonumentGeoRecord::new_igs(
+        t,
+        "Great receiver",
+        "Fancy antenna",
+        "MARKERNAME",
+        "MARKERNUMBER",
+        "SITE",
+        "SITENAME",
+    )
+    .with_comment("encoded");
+
+    let eph = GPSEphemeris {
+        sv_prn: 9,
+        toe: 2189,
+        toc: 86400,
+        tow: 86100,
+        iode: 12,
+        iodc: 12,
+        e: 0.00125,
+        sqrt_a: 5153.6,
+        m0_rad: 1.25,
+        clock_offset: 1.0E-5,
+        ..Default::default()
+    };
+
+    let pvt = Solutions::new(t).with_pvt_ecef_wgs84(
+        3582105.291,
+        532589.7313,
+        5232754.8054,
+        0.0,
+        0.0,
+        0.0,
+        TemporalSolution {
+            offset_s: 1.0E-6,
+            drift_s_s: None,
+        },
+    );
+
+    vec![
+        Message::new(meta, Record::new_monument_geo(geo)),
+        Message::new(
+            meta,
+            Record::new_ephemeris_frame(EphemerisFrame::new_gps(eph)),
+        ),
+        Message::new(meta, Record::new_solutions(pvt)),
+    ]
+}
+
+fn decode_all(bytes: &[u8], gzip: bool) -> Vec<Message> {
+    let mut decoded = Vec::new();
+
+    let mut decoder = if gzip {
+        Decoder::new_gzip(bytes)
+    } else {
+        Decoder::new(bytes)
+    };
+
+    loop {
+        match decoder.next() {
+            Some(Ok(StreamElement::OpenSource(msg))) => decoded.push(msg),
+            Some(Ok(StreamElement::ClosedSource(_))) => {},
+            Some(Err(Error::IoError)) => panic!("i/o error"),
+            Some(Err(_)) => {},
+            None => break,
+        }
+    }
+
+    decoded
+}
+
+#[test]
+fn encoder_decoder_stream() {
+    let messages = messages();
+
+    let mut encoder = Encoder::new(Vec::<u8>::new());
+
+    let mut total = 0;
+    for msg in messages.iter() {
+        let size = encoder.encode(msg).unwrap();
+        assert_eq!(size, msg.encoding_size());
+        total += size;
+    }
+
+    let bytes = encoder.into_inner().unwrap();
+    assert_eq!(bytes.len(), total);
+
+    assert_eq!(decode_all(&bytes, false), messages);
+}
+
+#[test]
+fn large_observation_record() {
+    let meta = Meta {
+        reversed: false,
+        big_endian: true,
+        enhanced_crc: false,
+    };
+
+    let mut observations = GNSSObservations::default();
+
+    for prn in 1..=64 {
+        let mut sv = SVObservations::new(ObservationSystem::GPS, prn);
+        for code in 0..7 {
+            sv = sv.with_signal(SignalObservation::new(
+                code,
+                21456789.123 + code as f64,
+                21456789.3699 + code as f64,
+                45.25,
+            ));
+        }
+        observations = observations.with_sv(sv);
+    }
+
+    let t = Epoch::from_gpst_seconds(60.0);
+
+    let msg = Message::new(
+        meta,
+        Record::new_observations(ObservationRecord::new(t, observations)),
+    );
+
+    // does not fit in 4kB
+    assert!(msg.encoding_size() > 4096);
+
+    let mut encoder = Encoder::new(Vec::<u8>::new());
+
+    let size = encoder.encode(&msg).unwrap();
+    assert_eq!(size, msg.encoding_size());
+
+    let bytes = encoder.into_inner().unwrap();
+    assert_eq!(bytes.len(), size);
+
+    let decoded = Message::decode(&bytes).unwrap();
+
+    let observations = decoded
+        .record
+        .as_observations()
+        .unwrap()
+        .gnss_observations()
+        .unwrap();
+
+    assert_eq!(observations.sv.len(), 64);
+
+    for sv in observations.sv.iter() {
+        assert_eq!(sv.signals.len(), 7);
+    }
+
+    // mirror op
+    let mut encoder = Encoder::new(Vec::<u8>::new());
+    encoder.encode(&decoded).unwrap();
+    assert_eq!(encoder.into_inner().unwrap(), bytes);
+}
+
+#[test]
+fn large_observation_stream() {
+    let meta = Meta {
+        reversed: false,
+        big_endian: true,
+        enhanced_crc: false,
+    };
+
+    let mut observations = GNSSObservations::default();
+
+    for prn in 1..=64 {
+        let mut sv = SVObservations::new(ObservationSystem::GPS, prn);
+        for code in 0..7 {
+            sv = sv.with_signal(SignalObservation::new(
+                code,
+                21456789.123 + code as f64,
+                21456789.3699 + code as f64,
+                45.25,
+            ));
+        }
+        observations = observations.with_sv(sv);
+    }
+
+    let t = Epoch::from_gpst_seconds(60.0);
+
+    let large = Message::new(
+        meta,
+        Record::new_observations(ObservationRecord::new(t, observations)),
+    );
+
+    // does not fit in the initial 4kB decoding buffer
+    assert!(large.encoding_size() > 4096);
+
+    // surrounded by other messages, so the large record
+    // is not aligned with the decoder internal buffer
+    let mut expected = messages();
+    expected.push(large.clone());
+    expected.push(large);
+    expected.extend(messages());
+
+    let mut encoder = Encoder::new(Vec::<u8>::new());
+
+    for msg in expected.iter() {
+        encoder.encode(msg).unwrap();
+    }
+
+    let bytes = encoder.into_inner().unwrap();
+
+    assert_eq!(decode_all(&bytes, false), expected);
+}
+
+#[test]
+fn reversed_little_endian_stream() {
+    let mut expected = Vec::new();
+
+    for reversed in [false, true] {
+        for big_endian in [false, true] {
+            for enhanced_crc in [false, true] {
+                let meta = Meta {
+                    reversed,
+                    big_endian,
+                    enhanced_crc,
+                };
+                for msg in messages() {
+                    expected.push(Message::new(meta, msg.record));
+                }
+            }
+        }
+    }
+
+    let mut encoder = Encoder::new(Vec::<u8>::new());
+
+    for msg in expected.iter() {
+        encoder.encode(msg).unwrap();
+    }
+
+    let bytes = encoder.into_inner().unwrap();
+
+    assert_eq!(decode_all(&bytes, false), expected);
+}
+
+#[cfg(feature = "flate2")]
+#[test]
+fn gzip_encoder_decoder_stream() {
+    let messages = messages();
+
+    let mut encoder = Encoder::new_gzip(Vec::<u8>::new());
+
+    for msg in messages.iter() {
+        encoder.encode(msg).unwrap();
+    }
+
+    let bytes = encoder.into_inner().unwrap();
+
+    assert_eq!(decode_all(&bytes, true), messages);
+}
@@ -0,0 +1,79 @@
+use binex::prelude::{Message, Meta, Record, UndecodedRecord};
+
+/// Builds a big endian, XOR8 protected frame
+fn frame(mid: u8, payload: &[u8]) -> Vec<u8> {
+    let mut buf = vec![0xE2, mid, payload.len() as u8];
+    buf.extend_from_slice(payload);
+    let crc = buf[1..].iter().fold(0, |crc, b| crc ^ b);
+    buf.push(crc);
+    buf
+}
+
+#[test]
+fn meteo_passthrough() {
+    // arbitrary content: must not be interpreted
+    let payload = [0x4a, 0x7d, 0x00, 0x11, 0x03, 0xe8, 0xff, 0x80, 0x01];
+
+    for (mid, prototyping) in [(0x03, false), (0x7e, true)] {
+        let buf = frame(mid, &payload);
+
+        let decoded = Message::decode(&buf).unwrap();
+
+        assert_eq!(
+            decoded.record,
+            Record::new_meteo(UndecodedRecord {
+                prototyping,
+                payload: payload.to_vec(),
+            })
+        );
+
+        // re-encoded identically, same MID
+        let mut encoded = [0; 32];
+        let size = decoded.encode(&mut encoded, 32).unwrap();
+        assert_eq!(&encoded[..size], &buf[..]);
+    }
+}
+
+#[test]
+fn receiver_info_passthrough() {
+    // arbitrary content: must not be interpreted
+    let payload = [0x4a, 0x7d, 0x00, 0x11, 0x03, 0x01, 0x02, 0x08, b'O', b'K'];
+
+    for (mid, prototyping) in [(0x04, false), (0x7d, true)] {
+        let buf = frame(mid, &payload);
+
+        let decoded = Message::decode(&buf).unwrap();
+
+        assert_eq!(
+            decoded.record,
+            Record::new_receiver_info(UndecodedRecord {
+                prototyping,
+                payload: payload.to_vec(),
+            })
+        );
+
+        // re-encoded identically, same MID
+        let mut encoded = [0; 32];
+        let size = decoded.encode(&mut encoded, 32).unwrap();
+        assert_eq!(&encoded[..size], &buf[..]);
+    }
+}
+
+#[test]
+fn meteo_encoding() {
+    let mut meta = Meta::default();
+    meta.big_endian = true;
+
+    let info = UndecodedRecord {
+        prototyping: true,
+        payload: vec![0x00, 0x01, 0x02],
+    };
+
+    let msg = Message::new(meta, Record::new_meteo(info));
+
+    let mut buf = [0; 16];
+    let size = msg.encode(&mut buf, 16).unwrap();
+
+    assert_eq!(&buf[..size], &frame(0x7e, &[0x00, 0x01, 0x02])[..]);
+    assert_eq!(Message::decode(&buf).unwrap(), msg);
+}
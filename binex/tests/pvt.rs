@@ -59,3 +59,26 @@ fn test_pvt_drift() {
     let parsed = Message::decode(&buf).unwrap();
     assert_eq!(parsed, msg);
 }
+
+#[test]
+fn test_position_ecef() {
+    let mut meta = Meta::default();
+    meta.big_endian = true;
+
+    let msg = Message::new(
+        meta,
+        Record::new_solutions(
+            Solutions::new(Epoch::from_gpst_seconds(60.100)).with_position_ecef_wgs84(
+                3582105.291,
+                532589.7313,
+                5232754.8054,
+            ),
+        ),
+    );
+
+    let mut buf = [0; 1024];
+    msg.encode(&mut buf, 1024).unwrap();
+
+    let parsed = Message::decode(&buf).unwrap();
+    assert_eq!(parsed, msg);
+}
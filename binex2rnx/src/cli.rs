@@ -0,0 +1,55 @@
+use clap::{Arg, ArgAction, ArgMatches, ColorChoice, Command};
+use std::path::{Path, PathBuf};
+
+pub struct Cli {
+    /// arguments passed by user
+    pub matches: ArgMatches,
+}
+
+impl Cli {
+    pub fn new() -> Self {
+        Self {
+            matches: {
+                Command::new("binex2rnx")
+                    .author("Guillaume W. Bres <guillaume.bressaix@gmail.com>")
+                    .version(env!("CARGO_PKG_VERSION"))
+                    .about("BINEX to RINEX conversion tool")
+                    .arg_required_else_help(true)
+                    .color(ColorChoice::Always)
+                    .arg(
+                        Arg::new("filepath")
+                            .short('f')
+                            .long("fp")
+                            .help("Input BINEX file (gzip compressed files are supported)")
+                            .required(true),
+                    )
+                    .arg(
+                        Arg::new("short")
+                            .short('s')
+                            .long("short")
+                            .action(ArgAction::SetTrue)
+                            .help(
+                                "Prefer shortened filename convention.
+Otherwise, we default to modern (V3+) long filenames.",
+                            ),
+                    )
+                    .arg(
+                        Arg::new("workspace")
+                            .short('w')
+                            .long("workspace")
+                            .help("Define custom workspace location"),
+                    )
+                    .get_matches()
+            },
+        }
+    }
+    pub fn input_path(&self) -> PathBuf {
+        Path::new(self.matches.get_one::<String>("filepath").unwrap()).to_path_buf()
+    }
+    pub fn short_filenames(&self) -> bool {
+        self.matches.get_flag("short")
+    }
+    pub fn workspace(&self) -> Option<&String> {
+        self.matches.get_one::<String>("workspace")
+    }
+}
@@ -0,0 +1,133 @@
+//! BINEX site records to RINEX header fields
+use binex::prelude::MonumentGeoRecord;
+
+use rinex::prelude::{GroundPosition, Header};
+
+/// Updates [Header] with the site description of this [MonumentGeoRecord].
+/// Only fields described by the record are modified, so a stream that
+/// spreads site information over several records is fully preserved.
+pub fn apply(header: &mut Header, geo: &MonumentGeoRecord) {
+    for comment in geo.comments.iter() {
+        if !header.comments.contains(comment) {
+            header.comments.push(comment.clone());
+        }
+    }
+
+    if let Some(name) = geo.geodetic_marker_name().or(geo.site_name()) {
+        let marker = header.geodetic_marker.clone().unwrap_or_default();
+        header.geodetic_marker = Some(marker.with_name(name));
+    }
+
+    if let Some(number) = geo.geodetic_marker_number() {
+        let marker = header.geodetic_marker.clone().unwrap_or_default();
+        header.geodetic_marker = Some(marker.with_number(number));
+    }
+
+    if let Some(observer) = geo.observer() {
+        header.observer = observer.to_string();
+    }
+
+    if let Some(agency) = geo.agency() {
+        header.agency = agency.to_string();
+    }
+
+    let model = geo.receiver_model();
+    let sn = geo.receiver_serial_number();
+    let firmware = geo.receiver_firm_version();
+
+    if model.is_some() || sn.is_some() || firmware.is_some() {
+        let mut rcvr = header.rcvr.clone().unwrap_or_default();
+        if let Some(model) = model {
+            rcvr.model = model.to_string();
+        }
+        if let Some(sn) = sn {
+            rcvr.sn = sn.to_string();
+        }
+        if let Some(firmware) = firmware {
+            rcvr.firmware = firmware.to_string();
+        }
+        header.rcvr = Some(rcvr);
+    }
+
+    let model = geo.antenna_model();
+    let sn = geo.antenna_serial_number();
+
+    if model.is_some() || sn.is_some() || geo.antenna_offset3d.is_some() {
+        let mut antenna = header.rcvr_antenna.clone().unwrap_or_default();
+        if let Some(model) = model {
+            antenna = antenna.with_model(model);
+        }
+        if let Some(sn) = sn {
+            antenna = antenna.with_serial_number(sn);
+        }
+        if let Some((h, e, n)) = geo.antenna_offset3d {
+            antenna = antenna
+                .with_height(h)
+                .with_eastern_component(e)
+                .with_northern_component(n);
+        }
+        header.rcvr_antenna = Some(antenna);
+    }
+
+    if let Some(ecef) = &geo.antenna_ecef3d {
+        header.ground_position = Some(GroundPosition::from_ecef_wgs84((
+            ecef.x_ecef_m,
+            ecef.y_ecef_m,
+            ecef.z_ecef_m,
+        )));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use binex::prelude::{Epoch, MonumentGeoMetadata};
+
+    #[test]
+    fn site_description() {
+        let geo = MonumentGeoRecord::new(
+            Epoch::from_gpst_seconds(60.0),
+            MonumentGeoMetadata::RNX2BIN,
+            "SEPT POLARX5",
+            "TRM57971.00",
+            "ESBC",
+            "10118M001",
+        )
+        .with_comment("converted")
+        .with_agency("SDFE")
+        .with_receiver_serial_number("3013312")
+        .with_antenna_offset(0.1, 0.0, 0.0)
+        .with_antenna_ecef_position(3582105.291, 532589.7313, 5232754.8054);
+
+        let mut header = Header::basic_obs();
+        apply(&mut header, &geo);
+
+        let marker = header.geodetic_marker.as_ref().unwrap();
+        assert_eq!(marker.name, "ESBC");
+        assert_eq!(marker.number(), Some("10118M001".to_string()));
+
+        assert_eq!(header.agency, "SDFE");
+        assert_eq!(header.comments, vec!["converted".to_string()]);
+
+        let rcvr = header.rcvr.as_ref().unwrap();
+        assert_eq!(rcvr.model, "SEPT POLARX5");
+        assert_eq!(rcvr.sn, "3013312");
+
+        let antenna = header.rcvr_antenna.as_ref().unwrap();
+        assert_eq!(antenna.model, "TRM57971.00");
+        assert_eq!(antenna.height, Some(0.1));
+
+        assert_eq!(
+            header.ground_position,
+            Some(GroundPosition::from_ecef_wgs84((
+                3582105.291,
+                532589.7313,
+                5232754.8054
+            )))
+        );
+
+        // applying same site description twice does not duplicate comments
+        apply(&mut header, &geo);
+        assert_eq!(header.comments.len(), 1);
+    }
+}
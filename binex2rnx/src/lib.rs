@@ -0,0 +1,186 @@
+//! BINEX to RINEX conversion.
+//! [Converter] collects ephemerides, observations and site description
+//! from a stream of BINEX records, and forms the NAV and OBS RINEX products.
+//! Homepage: <https://github.com/georust/rinex>
+pub mod header;
+pub mod nav;
+pub mod obs;
+
+use std::collections::HashMap;
+
+use binex::prelude::Record as BinexRecord;
+
+use rinex::{
+    navigation, observation,
+    prelude::{Constellation, Header, Observable, Rinex, RinexType},
+    record::Record,
+};
+
+/// RINEX products formed by the [Converter]
+#[derive(Debug, Clone)]
+pub struct Products {
+    /// Navigation RINEX, when ephemerides were collected
+    pub nav: Option<Rinex>,
+    /// Observation RINEX, when observations were collected
+    pub obs: Option<Rinex>,
+}
+
+impl Products {
+    /// Iterates over all formed products
+    pub fn iter(&self) -> impl Iterator<Item = &Rinex> + '_ {
+        self.nav.iter().chain(self.obs.iter())
+    }
+}
+
+/// [Converter] accumulates BINEX [BinexRecord]s and forms the
+/// corresponding RINEX products.
+/// ```
+/// use binex2rnx::Converter;
+///
+/// let converter = Converter::new();
+/// let products = converter.products();
+/// assert_eq!(products.iter().count(), 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Converter {
+    /// Site description, shared by all products
+    site: Header,
+    /// Navigation record
+    nav_rec: navigation::Record,
+    /// Observation record
+    obs_rec: observation::Record,
+    /// Encountered observables
+    codes: HashMap<Constellation, Vec<Observable>>,
+}
+
+impl Default for Converter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Converter {
+    /// Builds a new [Converter], with empty records.
+    pub fn new() -> Self {
+        let mut site = Header::default();
+        site.program = format!("binex2rnx v{}", env!("CARGO_PKG_VERSION"));
+        Self {
+            site,
+            nav_rec: navigation::Record::new(),
+            obs_rec: observation::Record::new(),
+            codes: HashMap::new(),
+        }
+    }
+
+    /// Site description collected so far, shared by all products.
+    pub fn site(&self) -> &Header {
+        &self.site
+    }
+
+    /// Returns true if no ephemeris nor observations have been collected.
+    pub fn is_empty(&self) -> bool {
+        self.nav_rec.is_empty() && self.obs_rec.is_empty()
+    }
+
+    /// Collects one BINEX [BinexRecord].
+    /// Records that have no RINEX equivalent are dropped, and so are
+    /// rebroadcasted ephemerides (same [SV](rinex::prelude::SV), message type and ToC).
+    pub fn append(&mut self, record: &BinexRecord) {
+        match record {
+            BinexRecord::EphemerisFrame(fr) => {
+                if let Some((toc, frame)) = nav::nav_frame(fr) {
+                    let frames = self.nav_rec.entry(toc).or_default();
+                    // ephemerides are rebroadcasted: store each one once
+                    let stored = frame.as_eph().is_some_and(|(msg, sv, _)| {
+                        frames
+                            .iter()
+                            .filter_map(|fr| fr.as_eph())
+                            .any(|(stored_msg, stored_sv, _)| stored_msg == msg && stored_sv == sv)
+                    });
+                    if !stored {
+                        frames.push(frame);
+                    }
+                }
+            },
+            BinexRecord::Observations(obs) => {
+                obs::append(&mut self.obs_rec, &mut self.codes, obs);
+            },
+            BinexRecord::MonumentGeo(geo) => {
+                header::apply(&mut self.site, geo);
+            },
+            BinexRecord::Solutions(_) | BinexRecord::Meteo(_) | BinexRecord::ReceiverInfo(_) => {},
+        }
+    }
+
+    /// Forms the NAV and OBS [Rinex] [Products], when they are not empty.
+    pub fn products(self) -> Products {
+        let nav = if self.nav_rec.is_empty() {
+            None
+        } else {
+            let header = self
+                .site
+                .with_type(RinexType::NavigationData)
+                .with_constellation(Constellation::Mixed);
+
+            Some(Rinex::new(header, Record::NavRecord(self.nav_rec)))
+        };
+
+        let obs = if self.obs_rec.is_empty() {
+            None
+        } else {
+            let first = self.obs_rec.keys().next().map(|(t, _)| *t);
+            let last = self.obs_rec.keys().next_back().map(|(t, _)| *t);
+
+            let header = self
+                .site
+                .with_type(RinexType::ObservationData)
+                .with_constellation(Constellation::Mixed)
+                .with_observation_fields(observation::HeaderFields {
+                    codes: self.codes,
+                    time_of_first_obs: first,
+                    time_of_last_obs: last,
+                    ..Default::default()
+                });
+
+            Some(Rinex::new(header, Record::ObsRecord(self.obs_rec)))
+        };
+
+        Products { nav, obs }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use binex::prelude::{EphemerisFrame, GPSEphemeris};
+
+    #[test]
+    fn ephemeris_rebroadcast() {
+        let eph = GPSEphemeris {
+            sv_prn: 9,
+            toe: 2_000,
+            tow: 7_200,
+            toc: 14_400,
+            iode: 12,
+            sqrt_a: 5153.6,
+            ..Default::default()
+        };
+
+        let mut converter = Converter::new();
+
+        for _ in 0..3 {
+            converter.append(&BinexRecord::EphemerisFrame(EphemerisFrame::GPS(
+                eph.clone(),
+            )));
+        }
+
+        // another vehicle, same ToC
+        let other = GPSEphemeris { sv_prn: 10, ..eph };
+        converter.append(&BinexRecord::EphemerisFrame(EphemerisFrame::GPS(other)));
+
+        assert_eq!(converter.nav_rec.len(), 1);
+
+        let frames = converter.nav_rec.values().next().unwrap();
+        assert_eq!(frames.len(), 2);
+    }
+}
@@ -0,0 +1,159 @@
+//! BINEX to RINEX converter.
+//! Collects ephemerides, observations and site description
+//! from a BINEX stream, and generates NAV and OBS RINEX files.
+//! Homepage: <https://github.com/georust/rinex>
+mod cli;
+
+use cli::Cli;
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use binex::prelude::{Decoder, Error as BinexError, StreamElement};
+
+use binex2rnx::Converter;
+
+use rinex::{prelude::Rinex, prod::ProductionAttributes};
+
+/// Conversion errors
+#[derive(Debug)]
+enum Error {
+    /// I/O error on the input BINEX stream
+    BinexError(BinexError),
+    /// Failed to generate RINEX products
+    RinexError(rinex::Error),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::BinexError(e) => write!(f, "binex error: {:?}", e),
+            Self::RinexError(e) => write!(f, "rinex error: {}", e),
+        }
+    }
+}
+
+impl From<BinexError> for Error {
+    fn from(e: BinexError) -> Self {
+        Self::BinexError(e)
+    }
+}
+
+impl From<rinex::Error> for Error {
+    fn from(e: rinex::Error) -> Self {
+        Self::RinexError(e)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::RinexError(rinex::Error::IoError(e))
+    }
+}
+
+fn workspace(cli: &Cli) -> PathBuf {
+    if let Some(workspace) = cli.workspace() {
+        Path::new(workspace).to_path_buf()
+    } else {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("WORKSPACE")
+    }
+}
+
+fn create_workspace(path: &Path) {
+    std::fs::create_dir_all(path).unwrap_or_else(|_| {
+        panic!(
+            "failed to create workspace \"{}\": permission denied",
+            path.to_string_lossy(),
+        )
+    });
+}
+
+fn input_name(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .expect("failed to determine input file name")
+        .to_str()
+        .expect("failed to determine input file name");
+
+    if stem.ends_with(".bnx") {
+        stem.strip_suffix(".bnx")
+            .expect("failed to determine input file name")
+            .to_string()
+    } else {
+        stem.to_string()
+    }
+}
+
+/// Names output file after the geodetic marker, when it is known
+fn output_name(rinex: &Rinex, short: bool) -> String {
+    let custom = match (&rinex.header.geodetic_marker, rinex.first_epoch()) {
+        (Some(marker), Some(t)) => {
+            let (year, _, _, _, _, _, _) = t.to_gregorian_utc();
+            Some(ProductionAttributes {
+                name: marker.name.to_uppercase().chars().take(4).collect(),
+                year: year as u32,
+                doy: t.day_of_year().round() as u32,
+                details: None,
+                region: None,
+            })
+        },
+        _ => None,
+    };
+    rinex.standard_filename(short, None, custom)
+}
+
+fn generate(rinex: &Rinex, short: bool, workspace: &Path) -> Result<(), rinex::Error> {
+    let output_name = output_name(rinex, short);
+    let outputpath = format!("{}/{}", workspace.to_string_lossy(), output_name);
+    rinex.to_file(&outputpath)?;
+    println!("\"{}\" generated", outputpath);
+    Ok(())
+}
+
+fn main() -> Result<(), Error> {
+    let cli = Cli::new();
+    let short = cli.short_filenames();
+
+    let input_path = cli.input_path();
+    let input_name = input_name(&input_path);
+    println!("converting \"{}\"..", input_name);
+
+    let workspace_path = workspace(&cli).join(&input_name);
+
+    create_workspace(&workspace_path);
+
+    let fd = File::open(&input_path)?;
+
+    let gzip = input_path.extension().is_some_and(|ext| ext == "gz");
+
+    let mut decoder = if gzip {
+        Decoder::new_gzip(fd)
+    } else {
+        Decoder::new(fd)
+    };
+
+    let mut converter = Converter::new();
+
+    loop {
+        match decoder.next() {
+            Some(Ok(StreamElement::OpenSource(msg))) => converter.append(&msg.record),
+            Some(Ok(StreamElement::ClosedSource(_))) => {},
+            Some(Err(BinexError::IoError)) => return Err(BinexError::IoError.into()),
+            Some(Err(_)) => {},
+            None => break,
+        }
+    }
+
+    if converter.is_empty() {
+        println!("no ephemeris nor observations found");
+        return Ok(());
+    }
+
+    for rinex in converter.products().iter() {
+        generate(rinex, short, &workspace_path)?;
+    }
+
+    Ok(())
+}
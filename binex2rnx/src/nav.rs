@@ -0,0 +1,307 @@
+//! BINEX ephemeris frames to RINEX navigation frames
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+use binex::prelude::{EphemerisFrame, GALEphemeris, GLOEphemeris, GPSEphemeris, SBASEphemeris};
+
+use rinex::navigation::{
+    Ephemeris, GalHealth, GeoHealth, GloHealth, Health, NavFrame, NavMsgType, OrbitItem,
+};
+
+use rinex::prelude::{Constellation, Duration, Epoch, TimeScale, SV};
+
+/// Converts one BINEX [EphemerisFrame] to a RINEX [NavFrame],
+/// and returns the [Epoch] (ToC) it should be indexed by.
+/// Raw GPS frames (undecoded subframes) are not supported.
+pub fn nav_frame(frame: &EphemerisFrame) -> Option<(Epoch, NavFrame)> {
+    match frame {
+        EphemerisFrame::GPS(eph) => Some(gps(eph)),
+        EphemerisFrame::GAL(eph) => Some(gal(eph)),
+        EphemerisFrame::GLO(eph) => Some(glo(eph)),
+        EphemerisFrame::SBAS(eph) => Some(sbas(eph)),
+        EphemerisFrame::GPSRaw(_) => None,
+    }
+}
+
+/// Builds [Epoch] from GPS week counter and second of week, in given [TimeScale]
+fn week_epoch(week: u16, seconds: f64, ts: TimeScale) -> Epoch {
+    let t = Epoch::from_duration(
+        Duration::from_days(week as f64 * 7.0) + Duration::from_seconds(seconds),
+        TimeScale::GPST,
+    );
+    t.to_time_scale(ts)
+}
+
+/// GPS ephemeris (0x01-01). BINEX encodes PRN - 1,
+/// the week counter that goes with ToE/ToC, and the uint2
+/// bitfield that holds fit interval (bits 0-7), L2P flag (bit 8)
+/// and L2 codes (bits 9-10).
+fn gps(eph: &GPSEphemeris) -> (Epoch, NavFrame) {
+    let sv = SV::new(Constellation::GPS, eph.sv_prn + 1);
+    let toc = week_epoch(eph.toe, eph.toc as f64, TimeScale::GPST);
+
+    let orbits = HashMap::from([
+        ("iode".to_string(), OrbitItem::F64(eph.iode as f64)),
+        ("crs".to_string(), OrbitItem::F64(eph.crs as f64)),
+        (
+            "deltaN".to_string(),
+            OrbitItem::F64(eph.delta_n_rad_s as f64),
+        ),
+        ("m0".to_string(), OrbitItem::F64(eph.m0_rad)),
+        ("cuc".to_string(), OrbitItem::F64(eph.cuc as f64)),
+        ("e".to_string(), OrbitItem::F64(eph.e)),
+        ("cus".to_string(), OrbitItem::F64(eph.cus as f64)),
+        ("sqrta".to_string(), OrbitItem::F64(eph.sqrt_a)),
+        ("toe".to_string(), OrbitItem::F64(eph.toc as f64)),
+        ("cic".to_string(), OrbitItem::F64(eph.cic as f64)),
+        ("omega0".to_string(), OrbitItem::F64(eph.omega_0_rad)),
+        ("cis".to_string(), OrbitItem::F64(eph.cis as f64)),
+        ("i0".to_string(), OrbitItem::F64(eph.i0_rad)),
+        ("crc".to_string(), OrbitItem::F64(eph.crc as f64)),
+        ("omega".to_string(), OrbitItem::F64(eph.omega_rad)),
+        (
+            "omegaDot".to_string(),
+            OrbitItem::F64(eph.omega_dot_rad_s as f64),
+        ),
+        ("idot".to_string(), OrbitItem::F64(eph.i_dot_rad_s as f64)),
+        (
+            "l2Codes".to_string(),
+            OrbitItem::F64(((eph.uint2 >> 9) & 0x03) as f64),
+        ),
+        ("week".to_string(), OrbitItem::U32(eph.toe as u32)),
+        (
+            "l2pDataFlag".to_string(),
+            OrbitItem::F64(((eph.uint2 >> 8) & 0x01) as f64),
+        ),
+        ("svAccuracy".to_string(), OrbitItem::F64(eph.ura_m as f64)),
+        (
+            "health".to_string(),
+            OrbitItem::Health(Health::from_bits_retain(eph.sv_health as u8)),
+        ),
+        ("tgd".to_string(), OrbitItem::F64(eph.tgd as f64)),
+        ("iodc".to_string(), OrbitItem::F64(eph.iodc as f64)),
+        ("t_tm".to_string(), OrbitItem::F64(eph.tow as f64)),
+        (
+            "fitInt".to_string(),
+            OrbitItem::F64((eph.uint2 & 0xff) as f64),
+        ),
+    ]);
+
+    let ephemeris = Ephemeris {
+        clock_bias: eph.clock_offset as f64,
+        clock_drift: eph.clock_drift as f64,
+        clock_drift_rate: eph.clock_drift_rate as f64,
+        orbits,
+    };
+
+    (toc, NavFrame::Eph(NavMsgType::LNAV, sv, ephemeris))
+}
+
+/// Galileo ephemeris (0x01-04). BINEX encodes PRN - 1,
+/// a week counter aligned to GPS, and angular rates in semi circles.
+/// Data source bit 1 identifies F/NAV frames.
+fn gal(eph: &GALEphemeris) -> (Epoch, NavFrame) {
+    let sv = SV::new(Constellation::Galileo, eph.sv_prn + 1);
+    let toc = week_epoch(eph.toe_week, eph.toe_s as f64, TimeScale::GST);
+
+    let msg = if eph.source & 0x02 > 0 {
+        NavMsgType::FNAV
+    } else {
+        NavMsgType::INAV
+    };
+
+    let orbits = HashMap::from([
+        ("iodnav".to_string(), OrbitItem::F64(eph.iodnav as f64)),
+        ("crs".to_string(), OrbitItem::F64(eph.crs as f64)),
+        (
+            "deltaN".to_string(),
+            OrbitItem::F64(eph.delta_n_semi_circles_s as f64 * PI),
+        ),
+        ("m0".to_string(), OrbitItem::F64(eph.m0_rad)),
+        ("cuc".to_string(), OrbitItem::F64(eph.cuc as f64)),
+        ("e".to_string(), OrbitItem::F64(eph.e)),
+        ("cus".to_string(), OrbitItem::F64(eph.cus as f64)),
+        ("sqrta".to_string(), OrbitItem::F64(eph.sqrt_a)),
+        ("toe".to_string(), OrbitItem::F64(eph.toe_s as f64)),
+        ("cic".to_string(), OrbitItem::F64(eph.cic as f64)),
+        ("omega0".to_string(), OrbitItem::F64(eph.omega_0_rad)),
+        ("cis".to_string(), OrbitItem::F64(eph.cis as f64)),
+        ("i0".to_string(), OrbitItem::F64(eph.i0_rad)),
+        ("crc".to_string(), OrbitItem::F64(eph.crc as f64)),
+        ("omega".to_string(), OrbitItem::F64(eph.omega_rad)),
+        (
+            "omegaDot".to_string(),
+            OrbitItem::F64(eph.omega_dot_semi_circles as f64 * PI),
+        ),
+        (
+            "idot".to_string(),
+            OrbitItem::F64(eph.idot_semi_circles_s as f64 * PI),
+        ),
+        ("dataSrc".to_string(), OrbitItem::F64(eph.source as f64)),
+        ("week".to_string(), OrbitItem::U32(eph.toe_week as u32)),
+        ("sisa".to_string(), OrbitItem::F64(eph.sisa as f64)),
+        (
+            "health".to_string(),
+            OrbitItem::GalHealth(GalHealth::from_bits_retain(eph.sv_health)),
+        ),
+        (
+            "bgdE5aE1".to_string(),
+            OrbitItem::F64(eph.bgd_e5a_e1_s as f64),
+        ),
+        (
+            "bgdE5bE1".to_string(),
+            OrbitItem::F64(eph.bgd_e5b_e1_s as f64),
+        ),
+        ("t_tm".to_string(), OrbitItem::F64(eph.tow as f64)),
+    ]);
+
+    let ephemeris = Ephemeris {
+        clock_bias: eph.clock_offset as f64,
+        clock_drift: eph.clock_drift as f64,
+        clock_drift_rate: eph.clock_drift_rate as f64,
+        orbits,
+    };
+
+    (toc, NavFrame::Eph(msg, sv, ephemeris))
+}
+
+/// Glonass ephemeris (0x01-02). BINEX encodes slot - 1,
+/// the day counter since GPST origin and the time of day in UTC.
+/// The clock offset is already expressed as -TauN.
+fn glo(eph: &GLOEphemeris) -> (Epoch, NavFrame) {
+    let sv = SV::new(Constellation::Glonass, eph.slot + 1);
+
+    let toc = Epoch::from_gregorian_utc_at_midnight(1980, 1, 6)
+        + Duration::from_days(eph.day as f64)
+        + Duration::from_seconds(eph.tod_s as f64);
+
+    let health = if eph.sv_health & 0x01 > 0 {
+        GloHealth::Unhealthy
+    } else {
+        GloHealth::Healthy
+    };
+
+    let orbits = HashMap::from([
+        ("satPosX".to_string(), OrbitItem::F64(eph.x_km)),
+        ("velX".to_string(), OrbitItem::F64(eph.vel_x_km)),
+        ("accelX".to_string(), OrbitItem::F64(eph.acc_x_km)),
+        ("health".to_string(), OrbitItem::GloHealth(health)),
+        ("satPosY".to_string(), OrbitItem::F64(eph.y_km)),
+        ("velY".to_string(), OrbitItem::F64(eph.vel_y_km)),
+        ("accelY".to_string(), OrbitItem::F64(eph.acc_y_km)),
+        ("channel".to_string(), OrbitItem::I8(eph.freq_channel)),
+        ("satPosZ".to_string(), OrbitItem::F64(eph.z_km)),
+        ("velZ".to_string(), OrbitItem::F64(eph.vel_z_km)),
+        ("accelZ".to_string(), OrbitItem::F64(eph.acc_z_km)),
+        ("ageOp".to_string(), OrbitItem::F64(eph.age_op_days as f64)),
+        ("L1L2GrpDelay".to_string(), OrbitItem::F64(eph.l1_l2_gd)),
+    ]);
+
+    let ephemeris = Ephemeris {
+        clock_bias: eph.clock_offset_s,
+        clock_drift: eph.clock_rel_freq_bias,
+        clock_drift_rate: 0.0,
+        orbits,
+    };
+
+    (toc, NavFrame::Eph(NavMsgType::FDMA, sv, ephemeris))
+}
+
+/// SBAS ephemeris (0x01-03). BINEX encodes the actual PRN (120-158),
+/// the GPS week counter and time of week.
+fn sbas(eph: &SBASEphemeris) -> (Epoch, NavFrame) {
+    let sv = SV::new(Constellation::SBAS, eph.sbas_prn.saturating_sub(100));
+    let toc = week_epoch(eph.toe, eph.tow as f64, TimeScale::GPST);
+
+    let orbits = HashMap::from([
+        ("satPosX".to_string(), OrbitItem::F64(eph.x_km)),
+        ("velX".to_string(), OrbitItem::F64(eph.vel_x_km)),
+        ("accelX".to_string(), OrbitItem::F64(eph.acc_x_km)),
+        (
+            "health".to_string(),
+            OrbitItem::GeoHealth(GeoHealth::from_bits_retain(eph.uint1)),
+        ),
+        ("satPosY".to_string(), OrbitItem::F64(eph.y_km)),
+        ("velY".to_string(), OrbitItem::F64(eph.vel_y_km)),
+        ("accelY".to_string(), OrbitItem::F64(eph.acc_y_km)),
+        ("accuracyCode".to_string(), OrbitItem::F64(eph.ura as f64)),
+        ("satPosZ".to_string(), OrbitItem::F64(eph.z_km)),
+        ("velZ".to_string(), OrbitItem::F64(eph.vel_z_km)),
+        ("accelZ".to_string(), OrbitItem::F64(eph.acc_z_km)),
+        ("iodn".to_string(), OrbitItem::F64(eph.iodn as f64)),
+    ]);
+
+    let ephemeris = Ephemeris {
+        clock_bias: eph.clock_offset,
+        clock_drift: eph.clock_drift,
+        clock_drift_rate: 0.0,
+        orbits,
+    };
+
+    (toc, NavFrame::Eph(NavMsgType::SBAS, sv, ephemeris))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn gps_ephemeris() {
+        let eph = GPSEphemeris {
+            sv_prn: 9,
+            toe: 2_000,
+            tow: 7_200,
+            toc: 14_400,
+            iode: 12,
+            sqrt_a: 5153.6,
+            e: 0.01,
+            clock_offset: 1.0E-4,
+            uint2: 0x0304,
+            ..Default::default()
+        };
+
+        let (toc, frame) = nav_frame(&EphemerisFrame::GPS(eph)).unwrap();
+
+        assert_eq!(
+            toc,
+            Epoch::from_gpst_seconds(2_000.0 * 7.0 * 86400.0 + 14_400.0)
+        );
+
+        let (msg, sv, ephemeris) = frame.as_eph().unwrap();
+        assert_eq!(msg, NavMsgType::LNAV);
+        assert_eq!(sv, SV::new(Constellation::GPS, 10));
+
+        assert_eq!(ephemeris.clock_bias, 1.0E-4_f32 as f64);
+        assert_eq!(ephemeris.get_orbit_f64("sqrta"), Some(5153.6));
+        assert_eq!(ephemeris.get_orbit_f64("iode"), Some(12.0));
+        assert_eq!(ephemeris.get_orbit_f64("fitInt"), Some(4.0));
+        assert_eq!(ephemeris.get_orbit_f64("l2pDataFlag"), Some(1.0));
+        assert_eq!(ephemeris.get_orbit_f64("l2Codes"), Some(1.0));
+        assert_eq!(ephemeris.toe(TimeScale::GPST), Some(toc));
+        assert!(ephemeris.is_healthy(sv));
+    }
+
+    #[test]
+    fn glonass_ephemeris() {
+        let eph = GLOEphemeris {
+            slot: 0,
+            day: 365,
+            tod_s: 3_600,
+            x_km: 10_000.0,
+            freq_channel: -4,
+            sv_health: 1,
+            ..Default::default()
+        };
+
+        let (toc, frame) = nav_frame(&EphemerisFrame::GLO(eph)).unwrap();
+
+        assert_eq!(toc, Epoch::from_gregorian_utc(1981, 1, 5, 1, 0, 0, 0));
+
+        let (msg, sv, ephemeris) = frame.as_eph().unwrap();
+        assert_eq!(msg, NavMsgType::FDMA);
+        assert_eq!(sv, SV::new(Constellation::Glonass, 1));
+        assert_eq!(ephemeris.glonass_channel(), Some(-4));
+        assert_eq!(ephemeris.get_orbit_f64("satPosX"), Some(10_000.0));
+        assert!(!ephemeris.is_healthy(sv));
+    }
+}
@@ -0,0 +1,243 @@
+//! BINEX observations to RINEX observation epochs
+use std::collections::{BTreeMap, HashMap};
+
+use binex::prelude::{ObservationRecord, ObservationSystem, SignalObservation};
+
+use rinex::{
+    observation::{LliFlags, ObservationData, Record, SNR},
+    prelude::{Carrier, Constellation, EpochFlag, Observable, SV},
+};
+
+/// Identifies [SV] from BINEX system and PRN. SBAS and QZSS
+/// vehicles may be described by their actual PRN (120+ and 193+).
+pub fn vehicle(system: ObservationSystem, prn: u8) -> Option<SV> {
+    let constellation = match system {
+        ObservationSystem::GPS => Constellation::GPS,
+        ObservationSystem::Glonass => Constellation::Glonass,
+        ObservationSystem::SBAS => Constellation::SBAS,
+        ObservationSystem::Galileo => Constellation::Galileo,
+        ObservationSystem::BeiDou => Constellation::BeiDou,
+        ObservationSystem::QZSS => Constellation::QZSS,
+        ObservationSystem::Unknown(_) => return None,
+    };
+    let prn = match constellation {
+        Constellation::SBAS if prn >= 100 => prn - 100,
+        Constellation::QZSS if prn >= 193 => prn - 192,
+        _ => prn,
+    };
+    Some(SV::new(constellation, prn))
+}
+
+/// Returns [Carrier] wavelength [m] for this signal,
+/// taking Glonass FDMA channel into account, when known.
+fn wavelength(
+    constellation: Constellation,
+    phase: &Observable,
+    glo_channel: Option<i8>,
+) -> Option<f64> {
+    let carrier = Carrier::from_observable(constellation, phase).ok()?;
+    let carrier = match (carrier, glo_channel) {
+        (Carrier::G1(None), Some(k)) => Carrier::G1(Some(k)),
+        (Carrier::G2(None), Some(k)) => Carrier::G2(Some(k)),
+        (carrier, _) => carrier,
+    };
+    Some(carrier.wavelength())
+}
+
+/// Converts one [SignalObservation] to RINEX observations
+fn signal_observations(
+    constellation: Constellation,
+    code: &str,
+    signal: &SignalObservation,
+) -> Vec<(Observable, ObservationData)> {
+    let mut ret = Vec::with_capacity(4);
+
+    let snr = Some(SNR::from(signal.snr_dbhz));
+
+    ret.push((
+        Observable::PseudoRange(format!("C{}", code)),
+        ObservationData::new(signal.pseudo_range_m, None, snr),
+    ));
+
+    let phase = Observable::Phase(format!("L{}", code));
+
+    if let Some(lambda) = wavelength(constellation, &phase, signal.glo_channel) {
+        let lli = if signal.slip {
+            Some(LliFlags::LOCK_LOSS)
+        } else {
+            None
+        };
+        ret.push((
+            phase,
+            ObservationData::new(signal.phase_range_m / lambda, lli, snr),
+        ));
+    }
+
+    if let Some(doppler) = signal.doppler_hz {
+        ret.push((
+            Observable::Doppler(format!("D{}", code)),
+            ObservationData::new(doppler, None, None),
+        ));
+    }
+
+    ret.push((
+        Observable::SSI(format!("S{}", code)),
+        ObservationData::new(signal.snr_dbhz, None, None),
+    ));
+
+    ret
+}
+
+/// Appends one BINEX [ObservationRecord] to the RINEX observation [Record].
+/// Observations of an epoch that spans several records are merged.
+/// Newly encountered [Observable]s are registered in `codes`, which is
+/// to describe the header section. Undecoded subrecords are dropped.
+pub fn append(
+    record: &mut Record,
+    codes: &mut HashMap<Constellation, Vec<Observable>>,
+    obs: &ObservationRecord,
+) {
+    let gnss = match obs.gnss_observations() {
+        Some(gnss) => gnss,
+        None => return,
+    };
+
+    let clock = gnss.clock.map(|clock| clock.offset_s);
+
+    let mut vehicles = BTreeMap::<SV, HashMap<Observable, ObservationData>>::new();
+
+    for sv_obs in gnss.sv.iter() {
+        let sv = match vehicle(sv_obs.system, sv_obs.prn) {
+            Some(sv) => sv,
+            None => continue,
+        };
+
+        let mut observations = HashMap::<Observable, ObservationData>::new();
+
+        for signal in sv_obs.signals.iter() {
+            let code = match sv_obs.system.rinex_code(signal.code) {
+                Some(code) => code,
+                None => continue,
+            };
+
+            for (observable, data) in signal_observations(sv.constellation, code, signal) {
+                let known = codes.entry(sv.constellation).or_default();
+                if !known.contains(&observable) {
+                    known.push(observable.clone());
+                }
+                observations.insert(observable, data);
+            }
+        }
+
+        if !observations.is_empty() {
+            vehicles.insert(sv, observations);
+        }
+    }
+
+    if !vehicles.is_empty() {
+        let (epoch_clock, epoch_vehicles) = record.entry((obs.epoch, EpochFlag::Ok)).or_default();
+
+        if clock.is_some() {
+            *epoch_clock = clock;
+        }
+
+        for (sv, observations) in vehicles {
+            epoch_vehicles.entry(sv).or_default().extend(observations);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use binex::prelude::{Epoch, GNSSObservations, SVObservations};
+
+    #[test]
+    fn gps_observations() {
+        let t = Epoch::from_gpst_seconds(60.5);
+
+        let l1ca = SignalObservation::new(0, 21456789.123, 21456789.369, 45.2)
+            .with_doppler(-1233.5)
+            .with_slip(true);
+
+        let g12 = SVObservations::new(ObservationSystem::GPS, 12).with_signal(l1ca);
+
+        // unknown system is dropped
+        let unknown = SVObservations::new(ObservationSystem::Unknown(9), 1)
+            .with_signal(SignalObservation::new(0, 1.0, 1.0, 45.0));
+
+        let gnss = GNSSObservations::default().with_sv(g12).with_sv(unknown);
+
+        let mut record = Record::new();
+        let mut codes = HashMap::new();
+
+        append(&mut record, &mut codes, &ObservationRecord::new(t, gnss));
+
+        let (clock, vehicles) = record.get(&(t, EpochFlag::Ok)).unwrap();
+        assert!(clock.is_none());
+        assert_eq!(vehicles.len(), 1);
+
+        let g12 = vehicles.get(&SV::new(Constellation::GPS, 12)).unwrap();
+        assert_eq!(g12.len(), 4);
+
+        let c1c = g12
+            .get(&Observable::PseudoRange("C1C".to_string()))
+            .unwrap();
+        assert!((c1c.obs - 21456789.123).abs() < 1.0E-6);
+
+        let l1c = g12.get(&Observable::Phase("L1C".to_string())).unwrap();
+        let lambda = Carrier::L1.wavelength();
+        assert!((l1c.obs - 21456789.369 / lambda).abs() < 1.0E-6);
+        assert_eq!(l1c.lli, Some(LliFlags::LOCK_LOSS));
+
+        let d1c = g12.get(&Observable::Doppler("D1C".to_string())).unwrap();
+        assert_eq!(d1c.obs, -1233.5);
+
+        let gps_codes = codes.get(&Constellation::GPS).unwrap();
+        assert_eq!(gps_codes.len(), 4);
+    }
+
+    #[test]
+    fn split_epoch() {
+        let t = Epoch::from_gpst_seconds(60.5);
+
+        let g12 = SVObservations::new(ObservationSystem::GPS, 12)
+            .with_signal(SignalObservation::new(0, 21456789.123, 21456789.369, 45.2));
+
+        let e11 = SVObservations::new(ObservationSystem::Galileo, 11)
+            .with_signal(SignalObservation::new(1, 23456789.0, 23456789.0, 48.0));
+
+        let mut record = Record::new();
+        let mut codes = HashMap::new();
+
+        // one epoch spread over two frames
+        for sv in [g12, e11] {
+            let gnss = GNSSObservations::default().with_sv(sv);
+            append(&mut record, &mut codes, &ObservationRecord::new(t, gnss));
+        }
+
+        assert_eq!(record.len(), 1);
+
+        let (_, vehicles) = record.get(&(t, EpochFlag::Ok)).unwrap();
+        assert_eq!(vehicles.len(), 2);
+        assert!(vehicles.contains_key(&SV::new(Constellation::GPS, 12)));
+        assert!(vehicles.contains_key(&SV::new(Constellation::Galileo, 11)));
+    }
+
+    #[test]
+    fn vehicles() {
+        assert_eq!(
+            vehicle(ObservationSystem::SBAS, 123),
+            Some(SV::new(Constellation::SBAS, 23))
+        );
+        assert_eq!(
+            vehicle(ObservationSystem::QZSS, 194),
+            Some(SV::new(Constellation::QZSS, 2))
+        );
+        assert_eq!(
+            vehicle(ObservationSystem::Galileo, 11),
+            Some(SV::new(Constellation::Galileo, 11))
+        );
+        assert_eq!(vehicle(ObservationSystem::Unknown(7), 1), None);
+    }
+}
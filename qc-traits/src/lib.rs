@@ -5,6 +5,9 @@
 pub mod merge;
 pub use merge::{Error as MergeError, Merge};
 
+pub mod split;
+pub use split::{Error as SplitError, Split};
+
 #[cfg(feature = "processing")]
 #[cfg_attr(docrs, doc(cfg(feature = "processing")))]
 pub mod processing;
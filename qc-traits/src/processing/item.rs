@@ -55,6 +55,9 @@ pub enum FilterItem {
     ConstellationItem(Vec<Constellation>),
     /// Clock Offset Item
     ClockItem,
+    /// Healthy spacecrafts, as described by Navigation data.
+    /// Described as "healthy".
+    HealthItem,
     /// List of complex items originally described as Strings
     ComplexItem(Vec<String>),
 }
@@ -169,6 +172,11 @@ impl std::str::FromStr for FilterItem {
             } else {
                 Err(ItemError::InvalidDuration)
             }
+        /*
+         * Health
+         */
+        } else if items.len() == 1 && items[0].trim().eq_ignore_ascii_case("healthy") {
+            Ok(Self::HealthItem)
         /*
          * SV
          */
@@ -237,6 +245,7 @@ impl std::fmt::Display for FilterItem {
             Self::SvItem(svs) => {
                 write!(f, "sv: {:?}", svs)
             },
+            Self::HealthItem => f.write_str("healthy"),
             _ => Ok(()),
         }
     }
@@ -270,6 +279,11 @@ mod test {
         let dt = Duration::from_str("1 d").unwrap();
         let target: FilterItem = dt.into();
         assert_eq!(target, FilterItem::DurationItem(dt));
+
+        assert_eq!(
+            FilterItem::from_str(" Healthy").unwrap(),
+            FilterItem::HealthItem
+        );
     }
     #[test]
     fn test_from_elevation() {
@@ -389,6 +389,20 @@ mod test {
         assert_eq!(mask, m2);
     }
     #[test]
+    fn mask_health() {
+        let mask = MaskFilter::from_str("=healthy").unwrap();
+        assert_eq!(
+            mask,
+            MaskFilter {
+                operand: MaskOperand::Equals,
+                item: FilterItem::HealthItem,
+            }
+        );
+        assert_eq!(MaskFilter::from_str("healthy").unwrap(), mask);
+        let opposite = MaskFilter::from_str("!=healthy").unwrap();
+        assert_eq!(!mask, opposite, "MaskFilter::Not()");
+    }
+    #[test]
     fn mask_complex() {
         let mask = MaskFilter::from_str("=L1C,S1C,D1P,C1W").unwrap();
         assert_eq!(
@@ -11,6 +11,8 @@ pub use mask::{Error as MaskError, MaskFilter, MaskOperand, Masking};
 mod decim;
 pub use decim::{Decimate, DecimationFilter, DecimationFilterType, Error as DecimationError};
 
+pub use crate::split::{Error as SplitError, Split};
+
 /// Preprocessing Trait is usually implemented by GNSS data
 /// to preprocess prior further analysis.
 pub trait Preprocessing: Masking + Decimate {
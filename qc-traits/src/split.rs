@@ -0,0 +1,33 @@
+//! Split trait, to form smaller data contexts
+use hifitime::{Duration, Epoch};
+use thiserror::Error;
+
+/// [Split] specific Errors.
+#[derive(Error, Debug, Clone)]
+pub enum Error {
+    /// Dataset is not indexed by [Epoch]
+    #[error("this dataset is not indexed by epoch")]
+    NoEpochIteration,
+    /// Dataset does not contain specified [Epoch]
+    #[error("this dataset does not contain specified epoch")]
+    NonExistingEpoch,
+    /// Null or negative split duration
+    #[error("invalid split duration")]
+    InvalidDuration,
+}
+
+/// Split Trait is implemented to form smaller data contexts,
+/// usually to cut a dataset into several files.
+pub trait Split {
+    /// Splits Self at desired epoch,
+    /// retaining |e(k) < epoch| ("before"), as left component,
+    /// and |e(k) >= epoch| ("inclusive after"), as right component.
+    fn split(&self, epoch: Epoch) -> Result<(Self, Self), Error>
+    where
+        Self: Sized;
+    /// Splits Self into a serie of contexts of equal durations.
+    /// Each context spans |e(k) - e(0)| < dt, where e(0) is its first epoch.
+    fn split_dt(&self, dt: Duration) -> Result<Vec<Self>, Error>
+    where
+        Self: Sized;
+}
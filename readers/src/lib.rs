@@ -0,0 +1,14 @@
+#![doc(html_logo_url = "https://raw.githubusercontent.com/georust/meta/master/logo/logo.png")]
+#![doc = include_str!("../README.md")]
+#![cfg_attr(docrs, feature(doc_cfg))]
+
+#[cfg(feature = "lzw")]
+#[cfg_attr(docrs, doc(cfg(feature = "lzw")))]
+mod lzw;
+
+mod sniffer;
+
+#[cfg(feature = "lzw")]
+pub use lzw::LzwDecoder;
+
+pub use sniffer::SniffedReader;
@@ -0,0 +1,291 @@
+//! Unix `compress` (.Z) LZW decoder.
+//! Streams the decompressed content of a .Z file,
+//! following the historical `compress(1)` implementation.
+use std::io::{BufRead, Error, ErrorKind, Read, Result};
+
+/// .Z magic bytes
+const MAGIC: [u8; 2] = [0x1f, 0x9d];
+
+/// Header flag: max code width mask
+const BIT_MASK: u8 = 0x1f;
+
+/// Header flag: block mode (dictionary may be reset)
+const BLOCK_MODE: u8 = 0x80;
+
+/// Initial code width
+const INIT_BITS: u8 = 9;
+
+/// Maximal code width
+const MAX_BITS: u8 = 16;
+
+/// Clear code (block mode only)
+const CLEAR: u32 = 256;
+
+/// Decompressed bytes buffered at once
+const BUF_SIZE: usize = 4096;
+
+/// [LzwDecoder] decompresses a Unix `compress` (.Z) stream on the fly.
+/// Compressed bytes are grabbed one at a time: wrap unbuffered
+/// interfaces (like [std::fs::File]) in a [std::io::BufReader].
+/// Decompressed bytes are buffered, [LzwDecoder] implements [BufRead].
+#[derive(Debug)]
+pub struct LzwDecoder<R: Read> {
+    /// Compressed stream
+    inner: R,
+    /// True once the .Z header has been consumed
+    header: bool,
+    /// Max code width, from header
+    max_bits: u8,
+    /// Block mode, from header
+    block_mode: bool,
+    /// Current code width
+    n_bits: u8,
+    /// Next free dictionary entry
+    free_ent: u32,
+    /// Previous code
+    prev: Option<u32>,
+    /// First byte of the previous string
+    finchar: u8,
+    /// Dictionary: prefix code
+    prefix: Vec<u16>,
+    /// Dictionary: suffix byte
+    suffix: Vec<u8>,
+    /// Pending bits, LSB first
+    bit_buf: u32,
+    /// Number of pending bits
+    bit_count: u8,
+    /// Codes consumed in current group.
+    /// `compress` emits codes by groups of 8,
+    /// padded when the code width changes.
+    group_codes: u8,
+    /// Decompressed bytes of current code, in reverse order
+    stack: Vec<u8>,
+    /// Decompressed bytes
+    buf: Vec<u8>,
+    /// Read pointer in decompressed bytes
+    pos: usize,
+    /// True once compressed stream is consumed
+    eos: bool,
+}
+
+impl<R: Read> LzwDecoder<R> {
+    /// Builds a new [LzwDecoder] that decompresses provided .Z stream.
+    /// The header is verified on first read.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            header: false,
+            max_bits: MAX_BITS,
+            block_mode: true,
+            n_bits: INIT_BITS,
+            free_ent: 256,
+            prev: None,
+            finchar: 0,
+            prefix: vec![0; 1 << MAX_BITS],
+            suffix: vec![0; 1 << MAX_BITS],
+            bit_buf: 0,
+            bit_count: 0,
+            group_codes: 0,
+            stack: Vec::with_capacity(1 << MAX_BITS),
+            buf: Vec::with_capacity(BUF_SIZE + (1 << MAX_BITS)),
+            pos: 0,
+            eos: false,
+        }
+    }
+    /// Returns inner stream
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+    fn read_byte(&mut self) -> Result<Option<u8>> {
+        let mut byte = [0_u8; 1];
+        loop {
+            match self.inner.read(&mut byte) {
+                Ok(0) => return Ok(None),
+                Ok(_) => return Ok(Some(byte[0])),
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+    fn read_header(&mut self) -> Result<()> {
+        let mut header = [0_u8; 3];
+        self.inner
+            .read_exact(&mut header)
+            .map_err(|e| match e.kind() {
+                ErrorKind::UnexpectedEof => {
+                    Error::new(ErrorKind::InvalidData, "truncated .Z header")
+                },
+                _ => e,
+            })?;
+        if header[..2] != MAGIC {
+            return Err(Error::new(ErrorKind::InvalidData, "not a .Z stream"));
+        }
+        self.max_bits = header[2] & BIT_MASK;
+        if !(INIT_BITS..=MAX_BITS).contains(&self.max_bits) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid .Z code width: {}", self.max_bits),
+            ));
+        }
+        self.block_mode = header[2] & BLOCK_MODE > 0;
+        self.free_ent = if self.block_mode { CLEAR + 1 } else { CLEAR };
+        self.header = true;
+        Ok(())
+    }
+    /// Grabs next code, None on end of stream
+    fn read_code(&mut self) -> Result<Option<u32>> {
+        while self.bit_count < self.n_bits {
+            match self.read_byte()? {
+                Some(byte) => {
+                    self.bit_buf |= (byte as u32) << self.bit_count;
+                    self.bit_count += 8;
+                },
+                None => return Ok(None),
+            }
+        }
+        let code = self.bit_buf & ((1 << self.n_bits) - 1);
+        self.bit_buf >>= self.n_bits;
+        self.bit_count -= self.n_bits;
+        self.group_codes = (self.group_codes + 1) % 8;
+        Ok(Some(code))
+    }
+    /// Discards the padding that ends current group of codes
+    fn skip_group(&mut self) -> Result<()> {
+        while self.group_codes > 0 {
+            if self.read_code()?.is_none() {
+                self.group_codes = 0;
+            }
+        }
+        Ok(())
+    }
+    /// Max code for current width
+    fn max_code(&self) -> u32 {
+        if self.n_bits == self.max_bits {
+            1 << self.max_bits
+        } else {
+            (1 << self.n_bits) - 1
+        }
+    }
+    /// Decodes next code into self.stack.
+    /// Returns false on end of stream.
+    fn decode(&mut self) -> Result<bool> {
+        if self.free_ent > self.max_code() {
+            self.skip_group()?;
+            self.n_bits += 1;
+        }
+        let code = match self.read_code()? {
+            Some(code) => code,
+            None => return Ok(false),
+        };
+        if code == CLEAR && self.block_mode {
+            self.skip_group()?;
+            self.n_bits = INIT_BITS;
+            self.free_ent = CLEAR + 1;
+            self.prev = None;
+            return Ok(true);
+        }
+        let prev = match self.prev {
+            Some(prev) => prev,
+            None => {
+                if code > 255 {
+                    return Err(Error::new(ErrorKind::InvalidData, "corrupt .Z stream"));
+                }
+                self.finchar = code as u8;
+                self.stack.push(self.finchar);
+                self.prev = Some(code);
+                return Ok(true);
+            },
+        };
+        let mut cur = code;
+        if cur >= self.free_ent {
+            // KwKwK case: code about to be defined
+            if cur > self.free_ent {
+                return Err(Error::new(ErrorKind::InvalidData, "corrupt .Z stream"));
+            }
+            self.stack.push(self.finchar);
+            cur = prev;
+        }
+        while cur > 255 {
+            self.stack.push(self.suffix[cur as usize]);
+            cur = self.prefix[cur as usize] as u32;
+        }
+        self.finchar = cur as u8;
+        self.stack.push(self.finchar);
+        if self.free_ent < (1 << self.max_bits) {
+            self.prefix[self.free_ent as usize] = prev as u16;
+            self.suffix[self.free_ent as usize] = self.finchar;
+            self.free_ent += 1;
+        }
+        self.prev = Some(code);
+        Ok(true)
+    }
+}
+
+impl<R: Read> BufRead for LzwDecoder<R> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        if !self.header {
+            self.read_header()?;
+        }
+        if self.pos == self.buf.len() {
+            self.buf.clear();
+            self.pos = 0;
+            while self.buf.len() < BUF_SIZE && !self.eos {
+                if self.decode()? {
+                    self.buf.extend(self.stack.drain(..).rev());
+                } else {
+                    self.eos = true;
+                }
+            }
+        }
+        Ok(&self.buf[self.pos..])
+    }
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.buf.len());
+    }
+}
+
+impl<R: Read> Read for LzwDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let available = self.fill_buf()?;
+        let size = available.len().min(buf.len());
+        buf[..size].copy_from_slice(&available[..size]);
+        self.consume(size);
+        Ok(size)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LzwDecoder;
+    use std::io::Read;
+    #[test]
+    fn lzw_decoding() {
+        // "TOBEORNOTTOBEORTOBEORNOT" compressed with `compress -b 16`
+        let compressed = [
+            0x1f, 0x9d, 0x90, 0x54, 0x9e, 0x08, 0x29, 0xf2, 0x44, 0x8a, 0x93, 0x27, 0x54, 0x02,
+            0x0e, 0x2c, 0xa8, 0x90, 0xa0, 0x41, 0x84,
+        ];
+        let mut decoder = LzwDecoder::new(&compressed[..]);
+        let mut content = String::new();
+        decoder.read_to_string(&mut content).unwrap();
+        assert_eq!(content, "TOBEORNOTTOBEORTOBEORNOT");
+    }
+    #[test]
+    fn lzw_read_lines() {
+        use std::io::BufRead;
+        // "TOBEORNOTTOBEORTOBEORNOT" compressed with `compress -b 16`
+        let compressed = [
+            0x1f, 0x9d, 0x90, 0x54, 0x9e, 0x08, 0x29, 0xf2, 0x44, 0x8a, 0x93, 0x27, 0x54, 0x02,
+            0x0e, 0x2c, 0xa8, 0x90, 0xa0, 0x41, 0x84,
+        ];
+        let decoder = LzwDecoder::new(&compressed[..]);
+        let lines = decoder.lines().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(lines, vec!["TOBEORNOTTOBEORTOBEORNOT".to_string()]);
+    }
+    #[test]
+    fn lzw_invalid_header() {
+        let mut decoder = LzwDecoder::new(&[0x1f, 0x8b, 0x08, 0x00][..]);
+        let mut content = Vec::new();
+        assert!(decoder.read_to_end(&mut content).is_err());
+    }
+}
@@ -0,0 +1,102 @@
+//! Content sniffing reader wrapper, for integrated
+//! .gz and .Z decompression of any data source.
+#[cfg(feature = "flate2")]
+use flate2::read::GzDecoder;
+use std::io::BufRead;
+
+#[cfg(feature = "flate2")]
+use std::io::BufReader;
+
+#[cfg(feature = "lzw")]
+use crate::LzwDecoder;
+
+/// gzip magic bytes
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// .Z (unix compress) magic bytes
+const LZW_MAGIC: [u8; 2] = [0x1f, 0x9d];
+
+/// [SniffedReader] wraps any [BufRead] data source (in memory buffer,
+/// network stream..). Compression is identified by content sniffing,
+/// rather than file extension.
+#[derive(Debug)]
+pub enum SniffedReader<R: BufRead> {
+    /// Readable content
+    Plain(R),
+    /// gzip compressed content
+    #[cfg(feature = "flate2")]
+    Gz(BufReader<GzDecoder<R>>),
+    /// Unix compress (LZW) compressed content
+    #[cfg(feature = "lzw")]
+    Lzw(LzwDecoder<R>),
+}
+
+impl<R: BufRead> SniffedReader<R> {
+    /// Builds a new [SniffedReader], possibly decompressing
+    /// the content, depending on its first bytes.
+    pub fn new(mut reader: R) -> std::io::Result<Self> {
+        let magic = reader.fill_buf()?;
+        if magic.starts_with(&GZIP_MAGIC) {
+            // --> gzip encoded
+            #[cfg(feature = "flate2")]
+            {
+                Ok(Self::Gz(BufReader::new(GzDecoder::new(reader))))
+            }
+            #[cfg(not(feature = "flate2"))]
+            {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::Unsupported,
+                    "gzip decompression requires --flate2 feature",
+                ))
+            }
+        } else if magic.starts_with(&LZW_MAGIC) {
+            // --> unix compress (LZW) encoded
+            #[cfg(feature = "lzw")]
+            {
+                Ok(Self::Lzw(LzwDecoder::new(reader)))
+            }
+            #[cfg(not(feature = "lzw"))]
+            {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::Unsupported,
+                    ".Z decompression requires --lzw feature",
+                ))
+            }
+        } else {
+            Ok(Self::Plain(reader))
+        }
+    }
+}
+
+impl<R: BufRead> std::io::Read for SniffedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, std::io::Error> {
+        match self {
+            Self::Plain(ref mut h) => h.read(buf),
+            #[cfg(feature = "flate2")]
+            Self::Gz(ref mut h) => h.read(buf),
+            #[cfg(feature = "lzw")]
+            Self::Lzw(ref mut h) => h.read(buf),
+        }
+    }
+}
+
+impl<R: BufRead> BufRead for SniffedReader<R> {
+    fn fill_buf(&mut self) -> Result<&[u8], std::io::Error> {
+        match self {
+            Self::Plain(ref mut bufreader) => bufreader.fill_buf(),
+            #[cfg(feature = "flate2")]
+            Self::Gz(ref mut bufreader) => bufreader.fill_buf(),
+            #[cfg(feature = "lzw")]
+            Self::Lzw(ref mut bufreader) => bufreader.fill_buf(),
+        }
+    }
+    fn consume(&mut self, s: usize) {
+        match self {
+            Self::Plain(ref mut bufreader) => bufreader.consume(s),
+            #[cfg(feature = "flate2")]
+            Self::Gz(ref mut bufreader) => bufreader.consume(s),
+            #[cfg(feature = "lzw")]
+            Self::Lzw(ref mut bufreader) => bufreader.consume(s),
+        }
+    }
+}
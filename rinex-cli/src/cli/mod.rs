@@ -220,6 +220,22 @@ Use this option to also calculate it from radio messages (for comparison purpose
                 .action(ArgAction::SetTrue)
                 .help("Hide statistical annotations that might be present in some plots.
 This has no effect on applications compiled without plot and statistical options.")
+        )
+        .arg(
+            Arg::new("sp3-ref")
+                .long("sp3-ref")
+                .value_name("FILE")
+                .action(ArgAction::Set)
+                .help("Compare the SP3 product to this reference SP3 product (for example: rapid versus final orbits).
+The report then comprises per SV radial, along track, cross track and clock differences.")
+        )
+        .arg(
+            Arg::new("sp3-helmert")
+                .long("sp3-helmert")
+                .action(ArgAction::SetTrue)
+                .requires("sp3-ref")
+                .help("Estimate and remove a 7 parameter Helmert transformation between both SP3 frames,
+prior the --sp3-ref comparison.")
         )
         .next_help_heading("Preprocessing")
             .arg(Arg::new("gps-filter")
@@ -436,6 +452,14 @@ Otherwise it gets automatically picked up."))
     pub fn force_report_synthesis(&self) -> bool {
         self.matches.get_flag("report-force")
     }
+    /// Reference SP3 product, to compare to
+    pub fn sp3_reference(&self) -> Option<&String> {
+        self.matches.get_one::<String>("sp3-ref")
+    }
+    /// Helmert transformation between compared SP3 frames
+    pub fn sp3_helmert(&self) -> bool {
+        self.matches.get_flag("sp3-helmert")
+    }
     /*
      * We hash all vital CLI information.
      * This helps in determining whether we need to update an existing report
@@ -459,6 +483,10 @@ Otherwise it gets automatically picked up."))
         if let Some(ecef) = self.manual_ecef() {
             string.push_str(&format!("{:?}", ecef));
         }
+        if let Some(reference) = self.sp3_reference() {
+            string.push_str(reference);
+            string.push_str(&format!("{}", self.sp3_helmert()));
+        }
         string.hash(&mut hasher);
         hasher.finish()
     }
@@ -1,6 +1,6 @@
 // Positioning OPMODE
 use clap::{value_parser, Arg, ArgAction, Command};
-use rinex::prelude::Duration;
+use rinex::{navigation::NavMsgType, prelude::Duration};
 
 fn shared_args(cmd: Command) -> Command {
     let cmd = cmd
@@ -49,6 +49,38 @@ and refer to the tutorials/ folder shipped with the RINEX repo.
         )
     };
 
+    let cmd = cmd
+        .next_help_heading("Ephemeris selection")
+        .arg(
+            Arg::new("eph-rt")
+                .long("eph-rt")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("eph-toe")
+                .help("Use the latest ephemeris frame transmitted before each epoch (real-time emulation).
+    Otherwise, the latest valid frame published before each epoch is used."),
+        )
+        .arg(
+            Arg::new("eph-toe")
+                .long("eph-toe")
+                .action(ArgAction::SetTrue)
+                .help("Use the ephemeris frame with closest ToE, on either side of each epoch (post processing).
+    Otherwise, the latest valid frame published before each epoch is used."),
+        )
+        .arg(
+            Arg::new("eph-healthy")
+                .long("eph-healthy")
+                .action(ArgAction::SetTrue)
+                .help("Only use ephemeris frames that report a healthy vehicle."),
+        )
+        .arg(
+            Arg::new("eph-msg")
+                .long("eph-msg")
+                .value_name("NAV_MSG")
+                .value_parser(value_parser!(NavMsgType))
+                .action(ArgAction::Set)
+                .help("Only use ephemeris frames of this message type (like LNAV, CNAV, INAV or FNAV)."),
+        );
+
     let cmd =
         cmd.next_help_heading("CGGTTS (special resolution for clock comparison / time transfer)");
 
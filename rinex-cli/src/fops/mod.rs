@@ -20,6 +20,10 @@ use rinex::{
     prod::{DataSource, DetailedProductionAttributes, ProductionAttributes, FFU, PPU},
 };
 
+use rinex_qc::prelude::{ProductType, QcContext};
+
+use crate::Error;
+
 /*
  * Parses share RINEX production attributes.
  * This helps accurate file production,
@@ -123,3 +127,35 @@ fn output_filename(
         rinex.standard_filename(short, gzip, Some(prod))
     }
 }
+
+/*
+ * Returns SP3 file name (without extension) and extension,
+ * to be used when generating SP3 files, from the input file name.
+ * Compression extensions are dropped: we only produce plain SP3.
+ */
+fn sp3_filename(ctx_data: &QcContext) -> Result<(String, String), Error> {
+    let path = ctx_data
+        .files(ProductType::HighPrecisionOrbit)
+        .and_then(|paths| paths.first())
+        .ok_or(Error::SP3FilenameError)?;
+
+    let filename = path
+        .file_name()
+        .ok_or(Error::SP3FilenameError)?
+        .to_string_lossy()
+        .to_string();
+
+    let mut iter = filename.split('.');
+    let name = iter.next().unwrap_or_default().to_string();
+
+    let extension = iter
+        .filter(|ext| !matches!(*ext, "gz" | "Z"))
+        .collect::<Vec<_>>()
+        .join(".");
+
+    if extension.is_empty() {
+        Ok((name, "sp3".to_string()))
+    } else {
+        Ok((name, extension))
+    }
+}
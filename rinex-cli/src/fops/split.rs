@@ -2,8 +2,9 @@ use crate::cli::Context;
 use crate::Error;
 use clap::ArgMatches;
 use rinex::prelude::Epoch;
-use rinex::Split;
-use rinex_qc::prelude::ProductType;
+use rinex_qc::prelude::{ProductType, Split};
+
+use crate::fops::sp3_filename;
 
 /*
  * Splits input files at specified Time Instant
@@ -115,5 +116,37 @@ pub fn split(ctx: &Context, matches: &ArgMatches) -> Result<(), Error> {
             info!("{} RINEX \"{}\" has been generated", product, output);
         }
     }
+    if let Some(sp3) = ctx_data.sp3() {
+        let product = ProductType::HighPrecisionOrbit;
+        let (sp3_a, sp3_b) = sp3
+            .split(*split_instant)
+            .unwrap_or_else(|e| panic!("failed to split {}: {}", product, e));
+
+        let (filename, extension) = sp3_filename(ctx_data)?;
+
+        for sp3 in [sp3_a, sp3_b] {
+            // split instant may be out of the time frame
+            let first_epoch = match sp3.first_epoch() {
+                Some(first_epoch) => first_epoch,
+                None => continue,
+            };
+
+            let (y, m, d, hh, mm, ss, _) = first_epoch.to_gregorian_utc();
+            let file_suffix = format!(
+                "{}{}{}_{}{}{}{}",
+                y, m, d, hh, mm, ss, first_epoch.time_scale
+            );
+
+            let output = ctx
+                .workspace
+                .root
+                .join(format!("{}-{}.{}", filename, file_suffix, extension))
+                .to_string_lossy()
+                .to_string();
+
+            sp3.to_file(&output)?;
+            info!("{} \"{}\" has been generated", product, output);
+        }
+    }
     Ok(())
 }
@@ -5,7 +5,9 @@ use crate::Error;
 use clap::ArgMatches;
 use rinex::prelude::Duration;
 use rinex::prod::DetailedProductionAttributes;
-use rinex_qc::prelude::{Filter, Preprocessing, ProductType};
+use rinex_qc::prelude::{Filter, Preprocessing, ProductType, Split};
+
+use crate::fops::sp3_filename;
 
 /*
  * Time reframing: subdivide a RINEX into a batch of equal duration
@@ -31,7 +33,6 @@ pub fn time_binning(
         (ProductType::MeteoObservation, "METEO"),
         (ProductType::BroadcastNavigation, "BRDC"),
         (ProductType::HighPrecisionClock, "CLOCK"),
-        (ProductType::HighPrecisionOrbit, "SP3"),
     ] {
         // input data determination
         if let Some(rinex) = ctx_data.rinex(product) {
@@ -88,5 +89,28 @@ pub fn time_binning(
             }
         }
     }
+    if let Some(sp3) = ctx_data.sp3() {
+        let product = ProductType::HighPrecisionOrbit;
+        ctx.workspace.create_subdir("SP3");
+
+        let (filename, extension) = sp3_filename(ctx_data)?;
+
+        let batches = sp3
+            .split_dt(*duration)
+            .unwrap_or_else(|e| panic!("failed to split {}: {}", product, e));
+
+        for (batch, sp3) in batches.iter().enumerate() {
+            let output = ctx
+                .workspace
+                .root
+                .join("SP3")
+                .join(format!("{}-{:02}.{}", filename, batch, extension))
+                .to_string_lossy()
+                .to_string();
+
+            sp3.to_file(&output)?;
+            info!("{} \"{}\" has been generated", product, output);
+        }
+    }
     Ok(())
 }
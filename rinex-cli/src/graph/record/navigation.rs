@@ -274,7 +274,7 @@ fn plot_sv_clock_corrections(ctx: &CtxClockCorrections, plot_ctx: &mut PlotConte
     for (product, vehicles) in ctx {
         for ts in vehicles
             .iter()
-            .map(|(sv, _)| sv.constellation.timescale().unwrap())
+            .map(|(sv, _)| Ephemeris::timescale(sv.constellation).unwrap())
             .unique()
         {
             match product {
@@ -299,7 +299,7 @@ fn plot_sv_clock_corrections(ctx: &CtxClockCorrections, plot_ctx: &mut PlotConte
             }
             for (index, (sv, data)) in vehicles
                 .iter()
-                .filter(|(sv, _data)| sv.constellation.timescale().unwrap() == ts)
+                .filter(|(sv, _data)| Ephemeris::timescale(sv.constellation).unwrap() == ts)
                 .enumerate()
             {
                 let epochs = data.iter().map(|(t, _)| *t).collect::<Vec<_>>();
@@ -334,7 +334,7 @@ fn plot_system_time(
     for (product, vehicles) in corrections {
         for ts in vehicles
             .iter()
-            .map(|(sv, _)| sv.constellation.timescale().unwrap())
+            .map(|(sv, _)| Ephemeris::timescale(sv.constellation).unwrap())
             .unique()
         {
             match product {
@@ -351,7 +351,7 @@ fn plot_system_time(
             for (_, state_vehicles) in states.iter().filter(|(k, _)| *k == product) {
                 for (_index, (sv, _corrections)) in vehicles
                     .iter()
-                    .filter(|(sv, _data)| sv.constellation.timescale() == Some(ts))
+                    .filter(|(sv, _data)| Ephemeris::timescale(sv.constellation) == Some(ts))
                     .enumerate()
                 {
                     if let Some((_, _data)) = state_vehicles
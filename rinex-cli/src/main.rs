@@ -42,6 +42,8 @@ pub enum Error {
     StdioError(#[from] std::io::Error),
     #[error("rinex error")]
     RinexError(#[from] rinex::Error),
+    #[error("sp3 error")]
+    SP3Error(#[from] sp3::prelude::Error),
     #[error("missing OBS RINEX")]
     MissingObservationRinex,
     #[error("missing (BRDC) NAV RINEX")]
@@ -52,6 +54,8 @@ pub enum Error {
     MissingMeteoRinex,
     #[error("missing Clock RINEX")]
     MissingClockRinex,
+    #[error("failed to determine output SP3 filename")]
+    SP3FilenameError,
     #[error("merge ops failure")]
     MergeError(#[from] rinex::merge::Error),
     #[error("split ops failure")]
@@ -332,6 +336,16 @@ pub fn main() -> Result<(), Error> {
     let cfg = cli.qc_config();
     let mut report = Report::new(&cli, &ctx, cfg);
 
+    // SP3 comparison
+    if let Some(path) = cli.sp3_reference() {
+        if ctx.data.has_sp3() {
+            let reference = SP3::from_path(Path::new(path))?;
+            report.compare_sp3(&ctx, &reference, cli.sp3_helmert());
+        } else {
+            error!("--sp3-ref requires SP3 data to compare to");
+        }
+    }
+
     // customization
     for extra in extra_pages {
         report.customize(extra);
@@ -51,8 +51,9 @@ impl ClockStateProvider for Clock<'_, '_> {
                 self.consume_many(3);
             }
         }
+        let two_sided = self.eph.borrow().is_two_sided();
         let (toc, _, eph) = self.eph.borrow_mut().select(t, sv)?;
-        let dt = eph.clock_correction(toc, t, sv, 8)?;
+        let dt = eph.clock_correction(toc, t, sv, 8, two_sided)?;
         debug!("{}({}) estimated clock correction: {}", t, sv, dt);
         Some(ClockCorrection::without_relativistic_correction(dt))
     }
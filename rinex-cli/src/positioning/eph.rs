@@ -1,5 +1,5 @@
 use crate::cli::Context;
-use rinex::navigation::Ephemeris;
+use rinex::navigation::{Ephemeris, NavMsgType, SelectionPolicy};
 use rinex::prelude::{Epoch, SV};
 use std::collections::HashMap;
 
@@ -7,20 +7,25 @@ pub struct EphemerisSource<'a> {
     eos: bool,
     toc: Epoch,
     sv: SV,
-    buffer: HashMap<SV, Vec<(Epoch, Ephemeris)>>,
-    iter: Box<dyn Iterator<Item = (SV, &'a Epoch, &'a Ephemeris)> + 'a>,
+    policy: SelectionPolicy,
+    buffer: HashMap<SV, Vec<(Epoch, NavMsgType, Ephemeris)>>,
+    iter: Box<dyn Iterator<Item = (SV, &'a Epoch, NavMsgType, &'a Ephemeris)> + 'a>,
 }
 
 impl<'a> EphemerisSource<'a> {
-    pub fn from_ctx(ctx: &'a Context) -> Self {
+    pub fn from_ctx(ctx: &'a Context, policy: SelectionPolicy) -> Self {
         if let Some(brdc) = ctx.data.brdc_navigation() {
             info!("Ephemeris data source created.");
             let mut s = Self {
                 eos: false,
                 toc: Epoch::default(),
                 sv: SV::default(),
+                policy,
                 buffer: HashMap::with_capacity(32),
-                iter: Box::new(brdc.ephemeris().map(|(toc, (_, sv, eph))| (sv, toc, eph))),
+                iter: Box::new(
+                    brdc.ephemeris()
+                        .map(|(toc, (msg, sv, eph))| (sv, toc, msg, eph)),
+                ),
             };
             s.consume_many(32); // fill in with some data
             s
@@ -30,17 +35,18 @@ impl<'a> EphemerisSource<'a> {
                 eos: true,
                 toc: Epoch::default(),
                 sv: SV::default(),
+                policy,
                 buffer: Default::default(),
                 iter: Box::new([].into_iter()),
             }
         }
     }
     fn consume_one(&mut self) {
-        if let Some((sv, toc, eph)) = self.iter.next() {
+        if let Some((sv, toc, msg, eph)) = self.iter.next() {
             if let Some(buffer) = self.buffer.get_mut(&sv) {
-                buffer.push((*toc, eph.clone()));
+                buffer.push((*toc, msg, eph.clone()));
             } else {
-                self.buffer.insert(sv, vec![(*toc, eph.clone())]);
+                self.buffer.insert(sv, vec![(*toc, msg, eph.clone())]);
             }
             self.sv = sv;
             self.toc = *toc;
@@ -58,31 +64,17 @@ impl<'a> EphemerisSource<'a> {
     }
     fn try_select(&self, t: Epoch, sv: SV) -> Option<(Epoch, Epoch, &Ephemeris)> {
         let buffer = self.buffer.get(&sv)?;
-        let sv_ts = sv.constellation.timescale()?;
-        if sv.constellation.is_sbas() {
+        self.policy.select(
+            sv,
+            t,
             buffer
                 .iter()
-                .filter_map(|(toc_i, eph_i)| {
-                    if t >= *toc_i {
-                        Some((*toc_i, *toc_i, eph_i))
-                    } else {
-                        None
-                    }
-                })
-                .min_by_key(|(toc_i, _, _)| (t - *toc_i).abs())
-        } else {
-            buffer
-                .iter()
-                .filter_map(|(toc_i, eph_i)| {
-                    if eph_i.is_valid(sv, t) && t >= *toc_i {
-                        let toe_i = eph_i.toe(sv_ts)?;
-                        Some((*toc_i, toe_i, eph_i))
-                    } else {
-                        None
-                    }
-                })
-                .min_by_key(|(toc_i, _, _)| (t - *toc_i).abs())
-        }
+                .map(|(toc_i, msg_i, eph_i)| (*toc_i, *msg_i, eph_i)),
+        )
+    }
+    /// True if selected frames may apply prior their ToE
+    pub fn is_two_sided(&self) -> bool {
+        self.policy.is_two_sided()
     }
     pub fn select(&mut self, t: Epoch, sv: SV) -> Option<(Epoch, Epoch, Ephemeris)> {
         let mut attempt = 0;
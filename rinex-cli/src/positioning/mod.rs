@@ -33,6 +33,7 @@ pub use clock::ClockStateProvider;
 
 use rinex::{
     carrier::Carrier,
+    navigation::{NavMsgType, SelectionCriteria, SelectionPolicy},
     prelude::{Constellation, Rinex},
 };
 
@@ -323,8 +324,25 @@ pub fn precise_positioning(
     // print config to be used
     info!("Using {:?} method", cfg.method);
 
+    // ephemeris selection policy
+    let mut eph_policy = SelectionPolicy::default()
+        .with_healthy_only(matches.get_flag("eph-healthy"))
+        .with_sbas_forward_only(true);
+    if matches.get_flag("eph-rt") {
+        eph_policy = eph_policy.with_criteria(SelectionCriteria::LatestTransmitted);
+    }
+    if matches.get_flag("eph-toe") {
+        eph_policy = eph_policy
+            .with_criteria(SelectionCriteria::NearestToe)
+            .with_sbas_forward_only(false);
+    }
+    if let Some(msg) = matches.get_one::<NavMsgType>("eph-msg") {
+        eph_policy = eph_policy.with_msg_type(*msg);
+    }
+    info!("Ephemeris selection policy: {:?}", eph_policy);
+
     // create data providers
-    let eph = RefCell::new(EphemerisSource::from_ctx(ctx));
+    let eph = RefCell::new(EphemerisSource::from_ctx(ctx, eph_policy));
     let clocks = Clock::new(&ctx, &eph);
     let orbits = Orbits::new(&ctx, &eph);
     let mut rtk_reference = RemoteRTKReference::from_ctx(&ctx);
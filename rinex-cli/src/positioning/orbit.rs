@@ -168,8 +168,9 @@ impl OrbitSource for Orbits<'_, '_> {
             None
         }; //precise
 
+        let two_sided = self.eph.borrow().is_two_sided();
         let keplerian = if let Some((toc, _, eph)) = self.eph.borrow_mut().select(t, sv) {
-            let orbit = eph.kepler2position(sv, toc, t)?;
+            let orbit = eph.kepler2position(sv, toc, t, two_sided)?;
             let state = orbit.to_cartesian_pos_vel();
             let (x_km, y_km, z_km) = (state[0], state[1], state[2]);
             debug!(
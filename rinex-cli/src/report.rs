@@ -10,6 +10,7 @@ use std::{
 use crate::cli::{Cli, Context};
 
 use rinex_qc::prelude::{QcConfig, QcExtraPage, QcReport, Render};
+use sp3::prelude::SP3;
 
 /// Quality check report
 pub enum Report {
@@ -60,6 +61,13 @@ impl Report {
             Self::Pending(QcReport::new(&ctx.data, cfg))
         }
     }
+    /// Compare SP3 data to a reference SP3 product.
+    /// Preserved reports already comprise this comparison.
+    pub fn compare_sp3(&mut self, ctx: &Context, reference: &SP3, helmert: bool) {
+        if let Self::Pending(report) = self {
+            report.add_sp3_comparison(&ctx.data, reference, helmert);
+        }
+    }
     /// Customize report with extra page
     pub fn customize(&mut self, page: QcExtraPage) {
         match self {
@@ -23,7 +23,7 @@ use anise::{
 use sp3::prelude::SP3;
 
 use qc_traits::{
-    processing::{Filter, Preprocessing, Repair, RepairTrait},
+    processing::{Filter, FilterItem, Preprocessing, Repair, RepairTrait},
     Merge, MergeError,
 };
 
@@ -416,6 +416,22 @@ impl QcContext {
     pub fn observation_mut(&mut self) -> Option<&mut Rinex> {
         self.data_mut(ProductType::Observation)?.as_mut_rinex()
     }
+    /// Returns mutable reference to inner [ProductType::Observation] data,
+    /// along with a reference to inner [ProductType::BroadcastNavigation] data.
+    fn observation_mut_with_brdc(&mut self) -> (Option<&mut Rinex>, Option<&Rinex>) {
+        let (mut obs, mut nav) = (None, None);
+        for (product, data) in self.blob.iter_mut() {
+            match product {
+                ProductType::Observation => obs = data.as_mut_rinex(),
+                ProductType::BroadcastNavigation => {
+                    let data: &BlobData = data;
+                    nav = data.as_rinex();
+                },
+                _ => {},
+            }
+        }
+        (obs, nav)
+    }
     /// Returns mutable reference to inner [ProductType::DORIS] RINEX data
     pub fn doris_mut(&mut self) -> Option<&mut Rinex> {
         self.data_mut(ProductType::DORIS)?.as_mut_rinex()
@@ -596,9 +612,20 @@ impl QcContext {
     }
     /// Apply preprocessing filter algorithm to mutable [Self].
     /// Filter will apply to all data contained in the context.
+    /// Health masks apply to Observations through the Navigation context, see [Rinex::mask_with_nav].
     pub fn filter_mut(&mut self, filter: &Filter) {
-        if let Some(data) = self.observation_mut() {
-            data.filter_mut(filter);
+        match filter {
+            Filter::Mask(mask) if mask.item == FilterItem::HealthItem => {
+                // Observations do not describe health: use NAV context
+                if let (Some(data), Some(nav)) = self.observation_mut_with_brdc() {
+                    data.mask_with_nav_mut(mask, nav);
+                }
+            },
+            _ => {
+                if let Some(data) = self.observation_mut() {
+                    data.filter_mut(filter);
+                }
+            },
         }
         if let Some(data) = self.brdc_navigation_mut() {
             data.filter_mut(filter);
@@ -23,8 +23,10 @@ pub mod prelude {
     };
     // Pub re-export
     pub use crate::plot::{Marker, MarkerSymbol, Mode, Plot};
+    #[cfg(feature = "sp3")]
+    pub use crate::report::SP3ComparisonReport;
     pub use maud::{html, Markup, Render};
-    pub use qc_traits::processing::{Filter, Preprocessing, Repair, RepairTrait};
+    pub use qc_traits::processing::{Filter, Preprocessing, Repair, RepairTrait, Split};
     pub use rinex::prelude::{Almanac, Error as RinexError, Rinex};
     #[cfg(feature = "sp3")]
     pub use sp3::prelude::{Error as SP3Error, SP3};
@@ -18,6 +18,9 @@ use rinex::RINEXReport;
 mod orbit;
 use orbit::OrbitReport;
 
+#[cfg(feature = "sp3")]
+pub use orbit::SP3ComparisonReport;
+
 #[cfg(feature = "sp3")]
 mod sp3;
 
@@ -28,6 +31,9 @@ mod sp3;
 #[cfg(feature = "sp3")]
 use sp3::SP3Report;
 
+#[cfg(feature = "sp3")]
+use ::sp3::prelude::SP3;
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("non supported RINEX format")]
@@ -151,6 +157,9 @@ pub struct QcReport {
     // navi: Option<QcNavi>,
     /// Orbital projections (only when compatible)
     orbit: Option<OrbitReport>,
+    /// Comparison to a reference SP3 product (only when requested)
+    #[cfg(feature = "sp3")]
+    sp3_comparison: Option<SP3ComparisonReport>,
     /// In depth analysis per input product.
     /// In summary mode, these do not exist (empty).
     products: HashMap<ProductType, ProductReport>,
@@ -170,6 +179,8 @@ impl QcReport {
         let summary_only = cfg.report == QcReportType::Summary;
         Self {
             custom_chapters: Vec::new(),
+            #[cfg(feature = "sp3")]
+            sp3_comparison: None,
             // navi: {
             //    if summary.navi.nav_compatible && !summary_only {
             //        Some(QcNavi::new(context))
@@ -242,6 +253,33 @@ impl QcReport {
     pub fn add_chapter(&mut self, chapter: QcExtraPage) {
         self.custom_chapters.push(chapter);
     }
+    /// Compares the SP3 product of this [QcContext] to a `reference` [SP3]
+    /// (for example final versus rapid orbits) and adds the comparison chapter
+    /// to the report. When `helmert` is true, a 7 parameter Helmert transformation
+    /// is estimated and removed prior forming the residuals.
+    /// This has no effect if the context does not contain SP3 data.
+    #[cfg(feature = "sp3")]
+    pub fn add_sp3_comparison(&mut self, context: &QcContext, reference: &SP3, helmert: bool) {
+        if let Some(sp3) = context.sp3() {
+            self.sp3_comparison = Some(SP3ComparisonReport::new(sp3, reference, helmert));
+        }
+    }
+    /// Renders the SP3 comparison chapter, when it exists
+    #[cfg(feature = "sp3")]
+    fn sp3_comparison_page(&self) -> Markup {
+        html! {
+            @if let Some(comparison) = &self.sp3_comparison {
+                div id="sp3-cmp" class="container is-main" style="display:none" {
+                    (comparison.render())
+                }
+            }
+        }
+    }
+    /// Renders the SP3 comparison chapter, when it exists
+    #[cfg(not(feature = "sp3"))]
+    fn sp3_comparison_page(&self) -> Markup {
+        html! {}
+    }
     /// Generates a menu bar to nagivate [Self]
     #[cfg(not(feature = "sp3"))]
     fn menu_bar(&self) -> Markup {
@@ -322,6 +360,11 @@ impl QcReport {
                             (orbit.html_inline_menu_bar())
                         }
                     }
+                    @if let Some(comparison) = &self.sp3_comparison {
+                        li {
+                            (comparison.html_inline_menu_bar())
+                        }
+                    }
                     @for chapter in self.custom_chapters.iter() {
                         li {
                             (chapter.tab.render())
@@ -398,6 +441,7 @@ impl Render for QcReport {
                                             (orbit.render())
                                         }
                                     }
+                                    (self.sp3_comparison_page())
                                     div id="extra-chapters" class="container" style="display:block" {
                                         @for chapter in self.custom_chapters.iter() {
                                             div id=(chapter.html_id) class="container is-main" style="display:none" {
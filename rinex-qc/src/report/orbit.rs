@@ -12,7 +12,10 @@ use crate::{
 };
 
 #[cfg(feature = "sp3")]
-use sp3::prelude::SP3;
+use crate::prelude::QcExtraPage;
+
+#[cfg(feature = "sp3")]
+use sp3::prelude::{Comparison, Helmert, Residual, ResidualRms, SP3};
 
 #[cfg(feature = "sp3")]
 struct BrdcSp3Report {
@@ -148,6 +151,254 @@ impl Render for BrdcSp3Report {
     }
 }
 
+#[cfg(feature = "sp3")]
+struct SP3ComparisonTab {}
+
+#[cfg(feature = "sp3")]
+impl Render for SP3ComparisonTab {
+    fn render(&self) -> Markup {
+        html! {
+            a id="menu:sp3-cmp" {
+                span class="icon" {
+                    i class="fa-solid fa-code-compare" {}
+                }
+                "SP3 Comparison"
+            }
+        }
+    }
+}
+
+/// [SP3ComparisonReport] compares two [SP3] products, for example
+/// final versus rapid orbits. It is integrated to the report with
+/// [crate::prelude::QcReport::add_sp3_comparison].
+#[cfg(feature = "sp3")]
+pub struct SP3ComparisonReport {
+    agency: String,
+    reference_agency: String,
+    helmert: Option<Helmert>,
+    rms: BTreeMap<SV, ResidualRms>,
+    radial_plot: Plot,
+    along_track_plot: Plot,
+    cross_track_plot: Plot,
+    clock_plot: Option<Plot>,
+}
+
+#[cfg(feature = "sp3")]
+impl SP3ComparisonReport {
+    /// Compares `sp3` to the `reference` product. When `helmert` is true,
+    /// a 7 parameter Helmert transformation is estimated and removed
+    /// prior forming the residuals.
+    pub fn new(sp3: &SP3, reference: &SP3, helmert: bool) -> Self {
+        let comparison = sp3.compare(reference, helmert);
+        let rms = comparison.rms();
+        Self {
+            agency: sp3.agency.clone(),
+            reference_agency: reference.agency.clone(),
+            helmert: comparison.helmert,
+            radial_plot: Self::residual_plot(
+                &comparison,
+                "sp3_cmp_radial",
+                "Radial differences",
+                "Error [m]",
+                |r| Some(r.radial),
+            ),
+            along_track_plot: Self::residual_plot(
+                &comparison,
+                "sp3_cmp_along",
+                "Along track differences",
+                "Error [m]",
+                |r| Some(r.along_track),
+            ),
+            cross_track_plot: Self::residual_plot(
+                &comparison,
+                "sp3_cmp_cross",
+                "Cross track differences",
+                "Error [m]",
+                |r| Some(r.cross_track),
+            ),
+            clock_plot: if rms.values().any(|rms| rms.clock.is_some()) {
+                Some(Self::residual_plot(
+                    &comparison,
+                    "sp3_cmp_clock",
+                    "Clock differences",
+                    "Error [ns]",
+                    |r| r.clock.map(|clock| clock * 1.0E9),
+                ))
+            } else {
+                None
+            },
+            rms,
+        }
+    }
+    fn residual_plot<F: Fn(&Residual) -> Option<f64>>(
+        comparison: &Comparison,
+        html_id: &str,
+        title: &str,
+        y_label: &str,
+        value: F,
+    ) -> Plot {
+        let mut series = BTreeMap::<SV, (Vec<Epoch>, Vec<f64>)>::new();
+        for (k, residual) in comparison.residuals.iter() {
+            if let Some(value) = value(residual) {
+                let (t, y) = series.entry(k.sv).or_default();
+                t.push(k.epoch);
+                y.push(value);
+            }
+        }
+        let mut plot = Plot::timedomain_plot(html_id, title, y_label, true);
+        for (sv_index, (sv, (t, y))) in series.into_iter().enumerate() {
+            let trace = Plot::timedomain_chart(
+                &sv.to_string(),
+                Mode::Markers,
+                MarkerSymbol::Diamond,
+                &t,
+                y,
+                sv_index < 4,
+            );
+            plot.add_trace(trace);
+        }
+        plot
+    }
+    pub(crate) fn html_inline_menu_bar(&self) -> Markup {
+        SP3ComparisonTab {}.render()
+    }
+    /// Turns [SP3ComparisonReport] into a [QcExtraPage], to customize
+    /// a report built without [crate::prelude::QcReport::add_sp3_comparison].
+    pub fn formalize(self) -> QcExtraPage {
+        QcExtraPage {
+            tab: Box::new(SP3ComparisonTab {}),
+            html_id: "sp3-cmp".to_string(),
+            content: Box::new(self),
+        }
+    }
+}
+
+#[cfg(feature = "sp3")]
+impl Render for SP3ComparisonReport {
+    fn render(&self) -> Markup {
+        html! {
+            div class="table-container" {
+                table class="table is-bordered" {
+                    tr {
+                        th class="is-info" {
+                            "Compared"
+                        }
+                        td {
+                            (self.agency.clone())
+                        }
+                    }
+                    tr {
+                        th class="is-info" {
+                            "Reference"
+                        }
+                        td {
+                            (self.reference_agency.clone())
+                        }
+                    }
+                    @if let Some(helmert) = self.helmert {
+                        tr {
+                            th {
+                                button aria-label="Helmert transformation removed prior comparison" data-balloon-pos="right" {
+                                    "Helmert"
+                                }
+                            }
+                            td {
+                                table class="table is-bordered" {
+                                    tr {
+                                        th { "Translation [m]" }
+                                        td {
+                                            (format!("x={:.4}, y={:.4}, z={:.4}", helmert.translation.0, helmert.translation.1, helmert.translation.2))
+                                        }
+                                    }
+                                    tr {
+                                        th { "Rotation [mas]" }
+                                        td {
+                                            (format!("x={:.4}, y={:.4}, z={:.4}", helmert.rotation.0, helmert.rotation.1, helmert.rotation.2))
+                                        }
+                                    }
+                                    tr {
+                                        th { "Scale [ppb]" }
+                                        td {
+                                            (format!("{:.4}", helmert.scale))
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    tr {
+                        th class="is-info" {
+                            "RMS"
+                        }
+                        td {
+                            table class="table is-bordered" {
+                                tr {
+                                    th { "SV" }
+                                    th { "Radial [m]" }
+                                    th { "Along track [m]" }
+                                    th { "Cross track [m]" }
+                                    th { "3D [m]" }
+                                    th { "Clock [ns]" }
+                                    th { "Samples" }
+                                }
+                                @for (sv, rms) in self.rms.iter() {
+                                    tr {
+                                        td { (sv.to_string()) }
+                                        td { (format!("{:.4}", rms.radial)) }
+                                        td { (format!("{:.4}", rms.along_track)) }
+                                        td { (format!("{:.4}", rms.cross_track)) }
+                                        td { (format!("{:.4}", rms.position)) }
+                                        @if let Some(clock) = rms.clock {
+                                            td { (format!("{:.4}", clock * 1.0E9)) }
+                                        } @else {
+                                            td { "N/A" }
+                                        }
+                                        td { (rms.samples.to_string()) }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    tr {
+                        th class="is-info" {
+                            "Radial"
+                        }
+                        td {
+                            (self.radial_plot.render())
+                        }
+                    }
+                    tr {
+                        th class="is-info" {
+                            "Along track"
+                        }
+                        td {
+                            (self.along_track_plot.render())
+                        }
+                    }
+                    tr {
+                        th class="is-info" {
+                            "Cross track"
+                        }
+                        td {
+                            (self.cross_track_plot.render())
+                        }
+                    }
+                    @if let Some(clock_plot) = &self.clock_plot {
+                        tr {
+                            th class="is-info" {
+                                "Clock"
+                            }
+                            td {
+                                (clock_plot.render())
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
 pub struct OrbitReport {
     sky_plot: Plot,
     elev_plot: Plot,
@@ -29,6 +29,19 @@ pub enum CalibrationMethod {
     Converted,
 }
 
+impl std::fmt::Display for CalibrationMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Unknown => write!(f, ""),
+            Self::Chamber => write!(f, "CHAMBER"),
+            Self::Field => write!(f, "FIELD"),
+            Self::Robot => write!(f, "ROBOT"),
+            Self::Copied => write!(f, "COPIED"),
+            Self::Converted => write!(f, "CONVERTED"),
+        }
+    }
+}
+
 /// Calibration information
 #[derive(Default, Clone, Debug, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
@@ -21,6 +21,8 @@ pub struct SvAntenna {
     pub igs_type: String,
     /// Spacecraft to which this antenna is attached to
     pub sv: SV,
+    /// Space Vehicle Number (SVN) code, like "G032"
+    pub svnn: String,
     /// Cospar information
     pub cospar: Cospar,
 }
@@ -36,6 +38,16 @@ pub struct Cospar {
     pub launch_code: char,
 }
 
+impl std::fmt::Display for Cospar {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{:04}-{}{}",
+            self.launch_year, self.launch_vehicle, self.launch_code
+        )
+    }
+}
+
 impl std::str::FromStr for Cospar {
     type Err = SvAntennaParsingError;
     fn from_str(content: &str) -> Result<Self, Self::Err> {
@@ -54,8 +66,22 @@ impl std::str::FromStr for Cospar {
 
         Ok(Self {
             launch_year: year,
-            launch_vehicle: s[4..6].to_string(),
+            launch_vehicle: s[5..8].to_string(),
             launch_code: s[8..9].chars().next().unwrap(),
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+    #[test]
+    fn cospar() {
+        let cospar = Cospar::from_str("1992-079A").unwrap();
+        assert_eq!(cospar.launch_year, 1992);
+        assert_eq!(cospar.launch_vehicle, "079");
+        assert_eq!(cospar.launch_code, 'A');
+        assert_eq!(cospar.to_string(), "1992-079A");
+    }
+}
@@ -1,6 +1,9 @@
 //! Antex - special RINEX type specific structures
 use crate::carrier::Carrier;
 
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
 pub enum Pattern {
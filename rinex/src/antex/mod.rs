@@ -4,15 +4,15 @@ pub mod frequency;
 pub mod pcv;
 pub mod record;
 
+pub use frequency::Pattern;
 pub use pcv::Pcv;
-// pub use frequency::{Frequency, Pattern};
 
 pub use antenna::{
     Antenna, AntennaMatcher, AntennaSpecific, Calibration, CalibrationMethod, Cospar, RxAntenna,
     SvAntenna,
 };
 
-pub use record::{FrequencyDependentData, Record};
+pub use record::{carrier_data, FrequencyDependentData, Record};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
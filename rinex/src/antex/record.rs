@@ -5,9 +5,12 @@ use thiserror::Error;
 
 use super::{
     antenna::SvAntennaParsingError, Antenna, AntennaSpecific, Calibration, CalibrationMethod,
-    Cospar, RxAntenna, SvAntenna,
+    Cospar, Pattern, RxAntenna, SvAntenna,
+};
+use crate::{
+    carrier, fmt_rinex, linspace::Linspace, merge, merge::Merge, prelude::Constellation, Carrier,
+    Epoch,
 };
-use crate::{carrier, linspace::Linspace, merge, merge::Merge, Carrier, Epoch};
 
 #[cfg(feature = "serde")]
 use serde::Serialize;
@@ -21,7 +24,8 @@ pub(crate) fn is_new_epoch(content: &str) -> bool {
 }
 
 /// Phase pattern description.
-/// We currently do not support azimuth dependent phase patterns.
+/// Only describes the azimuth independent pattern: see
+/// [FrequencyDependentData::azimuth_patterns] for azimuth dependent phase patterns.
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
 pub enum AntennaPhasePattern {
@@ -43,13 +47,18 @@ pub struct FrequencyDependentData {
     /// Antenna Reference point (ARP), if this is an [`RxAntenna`],
     /// or the Spacecraft Mass Center, if this is an [`SvAntenna`].
     pub apc_eccentricity: (f64, f64, f64),
-    /// Antenna Phase Pattern.
-    /// We currently do not support Azimuth Dependent phase patterns.
+    /// Non azimuth dependent (NOAZI) Antenna Phase Pattern,
+    /// values in millimeters on the antenna zenith grid.
     pub phase_pattern: AntennaPhasePattern,
+    /// Azimuth dependent Antenna Phase Patterns, if any, as
+    /// (azimuth in degrees, values in millimeters on the antenna zenith grid).
+    pub azimuth_patterns: Vec<(f64, Vec<f64>)>,
 }
 
 /// ANTEX RINEX record content.
-/// Data is a list of Antenna containing several [Frequency] items.
+/// Data is a list of Antenna containing several frequency blocks,
+/// indexed by their frequency code, as described in the file ("G01", "E05"..).
+/// Use [carrier_data] to retrieve the block that describes a [Carrier].
 /// We do not parse RMS frequencies at the moment, but it will
 /// easily be unlocked in near future.
 /*TODO
@@ -88,7 +97,7 @@ pub struct FrequencyDependentData {
 /// }
 /// ```
 */
-pub type Record = Vec<(Antenna, HashMap<Carrier, FrequencyDependentData>)>;
+pub type Record = Vec<(Antenna, HashMap<String, FrequencyDependentData>)>;
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -144,8 +153,16 @@ pub enum Error {
     ZenithGridEndParsing,
     #[error("failed to parse spacing of zenith grid")]
     ZenithGridSpacingParsing,
+    #[error("failed to parse azimuth increment")]
+    AzimuthIncrementParsing,
+    #[error("failed to parse phase pattern")]
+    PhasePatternParsing,
 }
 
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
 fn parse_datetime(content: &str) -> Result<Epoch, Error> {
     let mut parser = content.split('-');
 
@@ -222,10 +239,13 @@ fn parse_validity_epoch(content: &str) -> Result<Epoch, Error> {
             .parse::<u8>()
             .map_err(|_| Error::DatetimeSecondsParsing)?;
 
-        nanos = ss[dot + 1..]
-            .trim()
+        // fractional part, expressed in nanoseconds
+        let fract = ss[dot + 1..].trim();
+        let fract = if fract.len() > 9 { &fract[..9] } else { fract };
+        nanos = fract
             .parse::<u32>()
             .map_err(|_| Error::DatetimeNanosParsing)?;
+        nanos *= 10_u32.pow(9 - fract.len() as u32);
     } else {
         secs = ss
             .parse::<u8>()
@@ -237,19 +257,94 @@ fn parse_validity_epoch(content: &str) -> Result<Epoch, Error> {
     ))
 }
 
+/*
+ * Returns true if this line is a phase pattern description,
+ * either NOAZI or azimuth dependent
+ */
+fn is_pattern_line(line: &str) -> bool {
+    if line.trim_start().starts_with("NOAZI") {
+        return true;
+    }
+    if line.len() < 8 {
+        return false;
+    }
+    line[..8].trim().parse::<f64>().is_ok()
+        && !line.contains("NORTH / EAST / UP")
+        && !line.contains("FREQUENCY")
+}
+
+/*
+ * Parses one phase pattern description
+ */
+fn parse_pattern(line: &str) -> Result<Pattern, Error> {
+    let (azimuth, rem) = line.split_at(8);
+    let values = rem
+        .split_ascii_whitespace()
+        .map(|item| item.parse::<f64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| Error::PhasePatternParsing)?;
+    let azimuth = azimuth.trim();
+    if azimuth.eq("NOAZI") {
+        Ok(Pattern::NonAzimuthDependent(values))
+    } else {
+        let azimuth = azimuth
+            .parse::<f64>()
+            .map_err(|_| Error::PhasePatternParsing)?;
+        Ok(Pattern::AzimuthDependent((azimuth, values)))
+    }
+}
+
+/// Returns the frequency block of an antenna, that describes given [Carrier].
+/// L1, L2 and L5 are shared by several constellations (G01, J01, S01..):
+/// the block of the `preferred` [Constellation] prevails, when described.
+pub fn carrier_data(
+    inner: &HashMap<String, FrequencyDependentData>,
+    carrier: Carrier,
+    preferred: Constellation,
+) -> Option<&FrequencyDependentData> {
+    inner
+        .iter()
+        .filter_map(|(code, data)| {
+            let sv = SV::from_str(code).ok()?;
+            if Carrier::from_sv(sv).ok()? == carrier {
+                Some((sv.constellation != preferred, code, data))
+            } else {
+                None
+            }
+        })
+        .min_by(|(a, a_code, _), (b, b_code, _)| (a, a_code).cmp(&(b, b_code)))
+        .map(|(_, _, data)| data)
+}
+
 /// Parses entire Antenna block
 /// and all inner frequency entries
 pub(crate) fn parse_antenna(
     content: &str,
-) -> Result<(Antenna, HashMap<Carrier, FrequencyDependentData>), Error> {
+) -> Result<(Antenna, HashMap<String, FrequencyDependentData>), Error> {
     let lines = content.lines();
     let mut antenna = Antenna::default();
-    let mut inner = HashMap::<Carrier, FrequencyDependentData>::new();
-    let mut frequency = Carrier::default();
+    let mut inner = HashMap::<String, FrequencyDependentData>::new();
+    let mut frequency = String::new();
     let mut freq_data = FrequencyDependentData::default();
     let mut valid_from = Epoch::default();
+    let mut in_frequency = false;
 
     for line in lines {
+        if in_frequency && is_pattern_line(line) {
+            match parse_pattern(line)? {
+                Pattern::NonAzimuthDependent(values) => {
+                    freq_data.phase_pattern =
+                        AntennaPhasePattern::AzimuthIndependentPattern(values);
+                },
+                Pattern::AzimuthDependent(pattern) => {
+                    freq_data.azimuth_patterns.push(pattern);
+                },
+            }
+            continue;
+        }
+        if line.len() < 60 {
+            continue;
+        }
         let (content, marker) = line.split_at(60);
         if marker.contains("TYPE / SERIAL NO") {
             let (ant_igs, rem) = content.split_at(16); // IGS V.1.4 does not follow the specs ?
@@ -265,6 +360,7 @@ pub(crate) fn parse_antenna(
                 false => AntennaSpecific::SvAntenna(SvAntenna {
                     igs_type: ant_igs.trim().to_string(),
                     sv: SV::from_str(block1)?,
+                    svnn: block2.to_string(),
                     cospar: Cospar::from_str(block3)?,
                 }),
                 true => AntennaSpecific::RxAntenna(RxAntenna {
@@ -307,19 +403,20 @@ pub(crate) fn parse_antenna(
             let sinex = content.split_at(20).0;
             antenna.sinex_code = sinex.trim().to_string();
         } else if marker.contains("DAZI") {
-            //let dazi = content.split_at(20).0.trim();
-            //if let Ok(dazi) = f64::from_str(dazi) {
-            //    antenna = antenna.with_dazi(dazi)
-            //}
+            let dazi = content.split_at(20).0.trim();
+            let dazi = dazi
+                .parse::<f64>()
+                .map_err(|_| Error::AzimuthIncrementParsing)?;
+            antenna = antenna.with_dazi(dazi);
         } else if marker.contains("# OF FREQUENCIES") {
             /*
              * we actually do not care about this field
              * it is easy to determine it from the current infrastructure
              */
         } else if marker.contains("START OF FREQUENCY") {
-            let svnn = content.split_at(10).0;
-            let sv = SV::from_str(svnn.trim())?;
-            frequency = carrier::Carrier::from_sv(sv)?;
+            frequency = content.split_at(10).0.trim().to_string();
+            freq_data = FrequencyDependentData::default();
+            in_frequency = true;
         } else if marker.contains("NORTH / EAST / UP") {
             let (north, rem) = content.split_at(10);
             let (east, rem) = rem.split_at(10);
@@ -362,29 +459,146 @@ pub(crate) fn parse_antenna(
                 spacing,
             };
         } else if marker.contains("END OF FREQUENCY") {
-            inner.insert(frequency, freq_data.clone());
+            inner.insert(frequency.clone(), freq_data.clone());
+            in_frequency = false;
         } else if marker.contains("END OF ANTENNA") {
             break; // end of this block, considered as an `epoch`
                    // if we make a parallel with other types of RINEX
-        } else {
-            // inside phase pattern
         }
-        //    } else if marker.contains("SINEX CODE") {
-        //        let sinex = content.split_at(10).0;
-        //        antenna = antenna.with_sinex_code(sinex.trim())
-        //        if line.contains("NOAZI") {
-        //            frequency = frequency.add_pattern(Pattern::NonAzimuthDependent(values.clone()))
-        //        } else {
-        //            let angle = f64::from_str(content.trim()).unwrap();
-        //            frequency =
-        //                frequency.add_pattern(Pattern::AzimuthDependent((angle, values.clone())))
-        //        }
-        //    }
     }
 
     Ok((antenna, inner))
 }
 
+/*
+ * Formats the calibration date, as expected by "METH / BY / # / DATE"
+ */
+fn fmt_datetime(epoch: &Epoch) -> String {
+    let (y, m, d, _, _, _, _) = epoch.to_gregorian_utc();
+    format!("{:02}-{}-{:02}", y % 100, MONTHS[m as usize - 1], d)
+}
+
+/*
+ * Formats calibration validity FROM/UNTIL field
+ */
+fn fmt_validity_epoch(epoch: &Epoch) -> String {
+    let (y, m, d, hh, mm, ss, ns) = epoch.to_gregorian_utc();
+    format!(
+        "{:6}{:6}{:6}{:6}{:6}{:13.7}",
+        y,
+        m,
+        d,
+        hh,
+        mm,
+        ss as f64 + ns as f64 * 1.0E-9
+    )
+}
+
+/*
+ * Formats one phase pattern description
+ */
+fn fmt_pattern(pattern: &Pattern) -> String {
+    let (mut line, values) = match pattern {
+        Pattern::NonAzimuthDependent(values) => ("   NOAZI".to_string(), values),
+        Pattern::AzimuthDependent((azimuth, values)) => (format!("{:8.1}", azimuth), values),
+    };
+    for value in values {
+        line.push_str(&format!("{:8.2}", value));
+    }
+    line
+}
+
+/// Formats entire Antenna block and all inner frequency entries
+pub(crate) fn fmt_antenna(
+    antenna: &Antenna,
+    inner: &HashMap<String, FrequencyDependentData>,
+) -> String {
+    let mut lines = Vec::<String>::new();
+    lines.push(fmt_rinex("", "START OF ANTENNA"));
+
+    match &antenna.specific {
+        AntennaSpecific::RxAntenna(rx) => {
+            lines.push(fmt_rinex(
+                &format!(
+                    "{:<20}{:<20}",
+                    rx.igs_type,
+                    rx.serial_number.as_deref().unwrap_or("")
+                ),
+                "TYPE / SERIAL NO",
+            ));
+        },
+        AntennaSpecific::SvAntenna(sv) => {
+            lines.push(fmt_rinex(
+                &format!(
+                    "{:<20}{:<20}{:<10}{}",
+                    sv.igs_type,
+                    sv.sv.to_string(),
+                    sv.svnn,
+                    sv.cospar
+                ),
+                "TYPE / SERIAL NO",
+            ));
+        },
+    }
+
+    let cal = &antenna.calibration;
+    lines.push(fmt_rinex(
+        &format!(
+            "{:<20}{:<20}{:6}    {}",
+            cal.method.to_string(),
+            cal.agency,
+            cal.number,
+            fmt_datetime(&cal.date)
+        ),
+        "METH / BY / # / DATE",
+    ));
+    lines.push(fmt_rinex(&format!("{:8.1}", antenna.azi_inc), "DAZI"));
+    lines.push(fmt_rinex(
+        &format!(
+            "  {:6.1}{:6.1}{:6.1}",
+            antenna.zenith_grid.start, antenna.zenith_grid.end, antenna.zenith_grid.spacing
+        ),
+        "ZEN1 / ZEN2 / DZEN",
+    ));
+
+    // sort frequencies, for reproducible outputs
+    let mut frequencies = inner.iter().collect::<Vec<_>>();
+    frequencies.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    lines.push(fmt_rinex(
+        &format!("{:6}", frequencies.len()),
+        "# OF FREQUENCIES",
+    ));
+
+    if let Some((from, until)) = &cal.validity_period {
+        lines.push(fmt_rinex(&fmt_validity_epoch(from), "VALID FROM"));
+        lines.push(fmt_rinex(&fmt_validity_epoch(until), "VALID UNTIL"));
+    }
+    if !antenna.sinex_code.is_empty() {
+        lines.push(fmt_rinex(&antenna.sinex_code, "SINEX CODE"));
+    }
+
+    for (code, data) in frequencies {
+        lines.push(fmt_rinex(&format!("   {}", code), "START OF FREQUENCY"));
+        let (north, east, up) = data.apc_eccentricity;
+        lines.push(fmt_rinex(
+            &format!("{:10.2}{:10.2}{:10.2}", north, east, up),
+            "NORTH / EAST / UP",
+        ));
+        let AntennaPhasePattern::AzimuthIndependentPattern(noazi) = &data.phase_pattern;
+        if !noazi.is_empty() {
+            lines.push(fmt_pattern(&Pattern::NonAzimuthDependent(noazi.clone())));
+        }
+        for pattern in &data.azimuth_patterns {
+            lines.push(fmt_pattern(&Pattern::AzimuthDependent(pattern.clone())));
+        }
+        lines.push(fmt_rinex(&format!("   {}", code), "END OF FREQUENCY"));
+    }
+
+    lines.push(fmt_rinex("", "END OF ANTENNA"));
+    lines.join("\n") + "\n"
+}
+
 impl Merge for Record {
     /// Merges `rhs` into `Self` without mutable access at the expense of more memcopies
     fn merge(&self, rhs: &Self) -> Result<Self, merge::Error> {
@@ -395,7 +609,7 @@ impl Merge for Record {
     /// Merges `rhs` into `Self`
     fn merge_mut(&mut self, rhs: &Self) -> Result<(), merge::Error> {
         for (antenna, subset) in rhs.iter() {
-            for (carrier, freqdata) in subset.iter() {
+            for (code, freqdata) in subset.iter() {
                 /*
                  * determine whether self contains this antenna & signal or not
                  */
@@ -404,20 +618,20 @@ impl Merge for Record {
                 for (lhs_ant, subset) in self.iter_mut() {
                     if lhs_ant == antenna {
                         has_ant |= true;
-                        for (lhs_carrier, _) in subset.iter_mut() {
-                            if lhs_carrier == carrier {
+                        for (lhs_code, _) in subset.iter_mut() {
+                            if lhs_code == code {
                                 has_signal |= true;
                                 break;
                             }
                         }
                         if !has_signal {
-                            subset.insert(*carrier, freqdata.clone());
+                            subset.insert(code.clone(), freqdata.clone());
                         }
                     }
                 }
                 if !has_ant {
-                    let mut inner = HashMap::<Carrier, FrequencyDependentData>::new();
-                    inner.insert(*carrier, freqdata.clone());
+                    let mut inner = HashMap::<String, FrequencyDependentData>::new();
+                    inner.insert(code.clone(), freqdata.clone());
                     self.push((antenna.clone(), inner));
                 }
             }
@@ -443,4 +657,96 @@ mod test {
             "   G01                                                      START OF FREQUENCY";
         assert!(!is_new_epoch(content));
     }
+    #[test]
+    fn test_datetime() {
+        let epoch = parse_datetime("23-SEP-20").unwrap();
+        assert_eq!(fmt_datetime(&epoch), "23-SEP-20");
+        let epoch = parse_validity_epoch("2008    10    16    23    59   59.9999999").unwrap();
+        assert_eq!(
+            fmt_validity_epoch(&epoch),
+            "  2008    10    16    23    59   59.9999999"
+        );
+    }
+    #[test]
+    fn test_pattern() {
+        for (line, expected) in [
+            (
+                "   NOAZI   -1.01   -0.93   -0.67",
+                Pattern::NonAzimuthDependent(vec![-1.01, -0.93, -0.67]),
+            ),
+            (
+                "     5.0   -1.01   -0.87   -0.59",
+                Pattern::AzimuthDependent((5.0, vec![-1.01, -0.87, -0.59])),
+            ),
+        ] {
+            assert!(is_pattern_line(line));
+            let pattern = parse_pattern(line).unwrap();
+            assert_eq!(pattern, expected);
+            assert_eq!(fmt_pattern(&pattern), line);
+        }
+        assert!(!is_pattern_line(
+            "     -0.22     -0.01    154.88                              NORTH / EAST / UP"
+        ));
+    }
+    #[test]
+    fn test_frequency_blocks() {
+        let content = [
+            fmt_rinex("", "START OF ANTENNA"),
+            fmt_rinex("TROSAR25.R4      LEIT727259", "TYPE / SERIAL NO"),
+            fmt_rinex(
+                "CHAMBER             IGG, Univ. Bonn          1    23-SEP-20",
+                "METH / BY / # / DATE",
+            ),
+            fmt_rinex("     5.0", "DAZI"),
+            fmt_rinex("     0.0  90.0   5.0", "ZEN1 / ZEN2 / DZEN"),
+            fmt_rinex("     3", "# OF FREQUENCIES"),
+            fmt_rinex("   S01", "START OF FREQUENCY"),
+            fmt_rinex("     -0.22     -0.01    154.88", "NORTH / EAST / UP"),
+            "   NOAZI   -1.01   -0.93".to_string(),
+            "     0.0   -1.01   -0.90".to_string(),
+            "   360.0   -1.01   -0.90".to_string(),
+            fmt_rinex("   S01", "END OF FREQUENCY"),
+            fmt_rinex("   J05", "START OF FREQUENCY"),
+            fmt_rinex("      0.34     -0.62    164.34", "NORTH / EAST / UP"),
+            "   NOAZI    2.92    2.83".to_string(),
+            fmt_rinex("   J05", "END OF FREQUENCY"),
+            fmt_rinex("   I09", "START OF FREQUENCY"),
+            fmt_rinex("      0.32     -0.63    160.39", "NORTH / EAST / UP"),
+            "   NOAZI    1.10    1.20".to_string(),
+            fmt_rinex("   I09", "END OF FREQUENCY"),
+            fmt_rinex("", "END OF ANTENNA"),
+        ]
+        .join("\n");
+
+        let (antenna, mut inner) = parse_antenna(&content).unwrap();
+
+        // frequency codes without a GPS equivalent are preserved
+        let mut codes = inner.keys().cloned().collect::<Vec<_>>();
+        codes.sort();
+        assert_eq!(codes, vec!["I09", "J05", "S01"]);
+
+        let s01 = &inner["S01"];
+        assert_eq!(
+            s01.phase_pattern,
+            AntennaPhasePattern::AzimuthIndependentPattern(vec![-1.01, -0.93])
+        );
+        assert_eq!(
+            s01.azimuth_patterns,
+            vec![(0.0, vec![-1.01, -0.90]), (360.0, vec![-1.01, -0.90])]
+        );
+
+        // no GPS block: other L1 blocks are used
+        let l1 = carrier_data(&inner, Carrier::L1, Constellation::GPS).unwrap();
+        assert_eq!(l1.apc_eccentricity, (-0.22, -0.01, 154.88));
+
+        // phase pattern modifications are formatted
+        inner.get_mut("J05").unwrap().phase_pattern =
+            AntennaPhasePattern::AzimuthIndependentPattern(vec![3.0, 2.9]);
+
+        let formatted = fmt_antenna(&antenna, &inner);
+        assert!(formatted.contains("   NOAZI    3.00    2.90"));
+
+        let (_, parsed) = parse_antenna(&formatted).unwrap();
+        assert_eq!(parsed, inner);
+    }
 }
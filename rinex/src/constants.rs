@@ -20,6 +20,21 @@ impl Omega {
     pub const GAL: f64 = 7.2921151467E-5;
 }
 
+/// PZ-90 Earth model, used in Glonass orbit integration
+/// (Glonass ICD Appendix A.3.1.2).
+pub(crate) struct PZ90;
+
+impl PZ90 {
+    /// Earth gravitational constant (km^3.s^-2)
+    pub const GM_KM3_S2: f64 = 398600.4418;
+    /// Earth equatorial radius (km)
+    pub const AE_KM: f64 = 6378.136;
+    /// Second zonal harmonic of the geopotential
+    pub const J2: f64 = 1.08262575E-3;
+    /// Earth rotation rate (rad.s^-1)
+    pub const OMEGA: f64 = Omega::GLO;
+}
+
 /// - 2 * sqrt(gm) / c / c
 #[allow(dead_code)]
 pub(crate) struct DtrF;
@@ -11,7 +11,7 @@ use gnss_rs::domes::Error as DomesParsingError;
 pub(crate) mod record;
 pub(crate) mod station;
 
-pub use record::Record;
+pub use record::{ClockOffset, Record};
 pub use station::Station;
 
 #[cfg(feature = "processing")]
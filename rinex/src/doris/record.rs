@@ -1,14 +1,17 @@
 use hifitime::Epoch;
 use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
 use thiserror::Error;
 
 use crate::{
     doris::Station,
+    epoch,
     epoch::{parse_in_timescale, ParsingError as EpochParsingError},
     header::Header,
     observable::Observable,
-    observation::EpochFlag,
+    observation::{flag::Error as FlagParsingError, EpochFlag},
     prelude::TimeScale,
+    types::Type,
 };
 
 #[cfg(feature = "processing")]
@@ -30,10 +33,26 @@ pub struct ObservationData {
     pub m2: Option<u8>,
 }
 
+/// DORIS receiver clock offset, as described by each measurement epoch
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClockOffset {
+    /// Receiver clock offset, in seconds
+    pub offset: f64,
+    /// True if this offset was extrapolated
+    pub extrapolated: bool,
+}
+
 /// DORIS RINEX Record content.
-/// Measurements are stored by Kind, by Station and by TAI sampling instant.
-pub type Record =
-    BTreeMap<(Epoch, EpochFlag), BTreeMap<Station, HashMap<Observable, ObservationData>>>;
+/// Measurements are stored by TAI sampling instant, along with
+/// the receiver [ClockOffset] at that instant, then by Station and by Kind.
+pub type Record = BTreeMap<
+    (Epoch, EpochFlag),
+    (
+        Option<ClockOffset>,
+        BTreeMap<Station, HashMap<Observable, ObservationData>>,
+    ),
+>;
 
 /// Returns true if following line matches a new DORIS measurement
 pub(crate) fn is_new_epoch(line: &str) -> bool {
@@ -44,8 +63,35 @@ pub(crate) fn is_new_epoch(line: &str) -> bool {
 pub enum Error {
     #[error("failed to parse epoch")]
     EpochError(#[from] EpochParsingError),
+    #[error("failed to parse epoch flag")]
+    FlagError(#[from] FlagParsingError),
     #[error("failed to parse data")]
     ParseFloatError(#[from] std::num::ParseFloatError),
+    #[error("failed to parse m1/m2 flag")]
+    ParseIntError(#[from] std::num::ParseIntError),
+    #[error("missing header field(s): badly formed DORIS RINEX")]
+    MissingHeaderFields,
+    #[error("badly formed DORIS RINEX: no stations defined")]
+    NoStations,
+    #[error("badly formed DORIS RINEX: no observables defined")]
+    NoObservables,
+    #[error("failed to identify DORIS station")]
+    UnknownStation,
+    #[error("failed to determine observable")]
+    UnknownObservable,
+}
+
+/*
+ * DORIS observables are described by single letter codes for
+ * meteo sensors, which differs from Meteo RINEX
+ */
+pub(crate) fn fmt_observable(observable: &Observable) -> String {
+    match observable {
+        Observable::Pressure => "P".to_string(),
+        Observable::Temperature => "T".to_string(),
+        Observable::HumidityRate => "H".to_string(),
+        observable => observable.to_string(),
+    }
 }
 
 /// DORIS measurement parsing process
@@ -55,126 +101,184 @@ pub(crate) fn parse_epoch(
 ) -> Result<
     (
         (Epoch, EpochFlag),
+        Option<ClockOffset>,
         BTreeMap<Station, HashMap<Observable, ObservationData>>,
     ),
     Error,
 > {
     let mut obs_idx = 0usize;
     let mut epoch = Epoch::default();
-    let flag = EpochFlag::default();
+    let mut flag = EpochFlag::default();
+    let mut clock_offset = Option::<ClockOffset>::None;
     let mut station = Option::<Station>::None;
     let mut buffer = BTreeMap::<Station, HashMap<Observable, ObservationData>>::new();
 
-    let doris = header
-        .doris
-        .as_ref()
-        .expect("missing header field(s): badly formed DORIS RINEX");
+    let doris = header.doris.as_ref().ok_or(Error::MissingHeaderFields)?;
 
     let observables = &doris.observables;
     let stations = &doris.stations;
 
-    assert!(
-        !stations.is_empty(),
-        "badly formed DORIS RINEX: no stations defined"
-    );
-    assert!(
-        !observables.is_empty(),
-        "badly formed DORIS RINEX: no observables defined"
-    );
+    if stations.is_empty() {
+        return Err(Error::NoStations);
+    }
+    if observables.is_empty() {
+        return Err(Error::NoObservables);
+    }
 
     for (lindex, line) in content.lines().enumerate() {
         match lindex {
             0 => {
                 /* 1st line gives TAI timestamp, flag, clock offset */
                 let line = line.split_at(2).1; // "> "
-                let offset = "YYYY MM DD HH MM SS.NNNNNNNNN  0".len();
-                let (date, _rem) = line.split_at(offset);
+                let offset = "YYYY MM DD HH MM SS.NNNNNNNNN".len();
+                let (date, rem) = line.split_at(offset);
                 epoch = parse_in_timescale(date, TimeScale::TAI)?;
+
+                let (flag_str, rem) = rem.split_at(std::cmp::min(3, rem.len()));
+                flag = EpochFlag::from_str(flag_str.trim())?;
+
+                // skip number of stations
+                let rem = rem.split_at(std::cmp::min(3, rem.len())).1;
+
+                let (offset_str, rem) = rem.split_at(std::cmp::min(19, rem.len()));
+                let offset_str = offset_str.trim();
+                if !offset_str.is_empty() {
+                    clock_offset = Some(ClockOffset {
+                        offset: offset_str.parse::<f64>()?,
+                        extrapolated: rem.trim().eq("1"),
+                    });
+                }
             },
             _ => {
-                let (id, _remainder) = line.split_at(4);
-                //println!("ID : \"{}\" - REMAINDER : \"{}\"", id, remainder); //DBEUG
-
-                if obs_idx == 0 {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                if line.starts_with('D') {
                     // parse station identifier
-                    assert!(id.len() > 1, "badly formed DORIS station identifier");
+                    let (id, _) = line.split_at(3);
                     let key = id[1..]
                         .trim()
                         .parse::<u16>()
-                        .unwrap_or_else(|e| panic!("failed to identify DORIS station: {:?}", e));
+                        .map_err(|_| Error::UnknownStation)?;
 
                     station = stations
                         .iter()
                         .filter(|station| station.key == key)
                         .reduce(|k, _| k)
                         .cloned();
+                    obs_idx = 0;
                 }
 
-                let identified_station =
-                    station.as_ref().expect("failed to identify DORIS station");
+                let identified_station = station.as_ref().ok_or(Error::UnknownStation)?;
+
+                // consume this line: up to 5 measurements per line
+                let nb_obs = std::cmp::min(5, observables.len().saturating_sub(obs_idx));
+                for i in 0..nb_obs {
+                    let offset = 3 + i * 16;
+                    if offset >= line.len() {
+                        break;
+                    }
+                    let content = &line[offset..std::cmp::min(line.len(), offset + 16)];
+                    let (obs, flags) = content.split_at(std::cmp::min(14, content.len()));
 
-                // consume this line
-                let mut offset = 5;
-                let max_offset = line.len();
-                while offset < line.len() {
-                    let content = &line[offset..std::cmp::min(max_offset, offset + 16)];
-                    let obs = &content[..12];
-                    let m1 = &content[12..13].trim();
-                    let m2 = &content[13..14].trim();
+                    let obs = obs.trim();
+                    if obs.is_empty() {
+                        continue; // missing measurement
+                    }
 
-                    //println!("obs \"{}\"", obs); //DEBUG
-                    //println!("m1 \"{}\"", m1); //DEBUG
-                    //println!("m2 \"{}\"", m2); //DEBUG
+                    let value = obs.parse::<f64>()?;
 
-                    let value = obs
-                        .trim()
-                        .parse::<f64>()
-                        .unwrap_or_else(|e| panic!("failed to parse observation: {:?}", e));
+                    let m1 = flags.get(0..1).unwrap_or("").trim();
+                    let m2 = flags.get(1..2).unwrap_or("").trim();
 
                     let m1 = if !m1.is_empty() {
-                        Some(m1.parse::<u8>().unwrap_or_else(|e| {
-                            panic!("failed to parse observation m1 flag: {:?}", e)
-                        }))
+                        Some(m1.parse::<u8>()?)
                     } else {
                         None
                     };
 
                     let m2 = if !m2.is_empty() {
-                        Some(m2.parse::<u8>().unwrap_or_else(|e| {
-                            panic!("failed to parse observation m2 flag: {:?}", e)
-                        }))
+                        Some(m2.parse::<u8>()?)
                     } else {
                         None
                     };
 
-                    let observable = observables.get(obs_idx).unwrap_or_else(|| {
-                        panic!(
-                            "failed to determine observable for {:?}({:?}) @ {}",
-                            identified_station, epoch, obs_idx
-                        )
-                    });
+                    let observable = observables
+                        .get(obs_idx + i)
+                        .ok_or(Error::UnknownObservable)?;
 
                     let obsdata = ObservationData { value, m1, m2 };
 
-                    if let Some(station) = buffer.get_mut(identified_station) {
-                        station.insert(observable.clone(), obsdata);
-                    } else {
-                        let inner =
-                            HashMap::from_iter([(Observable::default(), obsdata)].into_iter());
-                        buffer.insert(identified_station.clone(), inner);
-                    }
+                    buffer
+                        .entry(identified_station.clone())
+                        .or_default()
+                        .insert(observable.clone(), obsdata);
+                }
+                obs_idx += nb_obs;
+            },
+        }
+    }
+    Ok(((epoch, flag), clock_offset, buffer))
+}
+
+/// Formats one DORIS measurement epoch
+pub(crate) fn fmt_epoch(
+    epoch: &Epoch,
+    flag: &EpochFlag,
+    clock_offset: &Option<ClockOffset>,
+    stations: &BTreeMap<Station, HashMap<Observable, ObservationData>>,
+    header: &Header,
+) -> String {
+    let mut lines = String::with_capacity(128 * stations.len());
+
+    lines.push_str(&format!(
+        "> {}  {}{:3}",
+        epoch::format(*epoch, Type::DORIS, 3),
+        flag,
+        stations.len()
+    ));
+
+    if let Some(clock_offset) = clock_offset {
+        lines.push_str(&format!(
+            "{:19.9} {}",
+            clock_offset.offset,
+            if clock_offset.extrapolated { 1 } else { 0 }
+        ));
+    }
+
+    lines.push('\n');
 
-                    offset += 16;
-                    obs_idx += 1;
+    let observables = match &header.doris {
+        Some(doris) => &doris.observables,
+        None => return lines,
+    };
+
+    for (station, data) in stations.iter() {
+        let mut line = format!("D{:02}", station.key);
+        for (index, observable) in observables.iter().enumerate() {
+            if index > 0 && (index % 5) == 0 {
+                lines.push_str(line.trim_end());
+                lines.push('\n');
+                line = "   ".to_string();
+            }
+            if let Some(data) = data.get(observable) {
+                line.push_str(&format!("{:14.3}", data.value));
+                match data.m1 {
+                    Some(m1) => line.push_str(&format!("{:1}", m1)),
+                    None => line.push(' '),
                 }
-                if obs_idx == observables.len() {
-                    obs_idx = 0;
-                    station = None;
+                match data.m2 {
+                    Some(m2) => line.push_str(&format!("{:1}", m2)),
+                    None => line.push(' '),
                 }
-            },
+            } else {
+                line.push_str(&format!("{:16}", ""));
+            }
         }
+        lines.push_str(line.trim_end());
+        lines.push('\n');
     }
-    Ok(((epoch, flag), buffer))
+    lines
 }
 
 #[cfg(feature = "processing")]
@@ -246,7 +350,7 @@ pub(crate) fn doris_decim_mut(rec: &mut Record, f: &DecimationFilter) {
 
 #[cfg(test)]
 mod test {
-    use super::{is_new_epoch, parse_epoch};
+    use super::{fmt_epoch, is_new_epoch, parse_epoch, ClockOffset};
     use crate::{
         doris::record::ObservationData,
         doris::HeaderFields as DorisHeader,
@@ -302,7 +406,7 @@ D01  -3237877.052    -2291024.044    21903595.62311  21903633.08011      -113.10
 D02  -2069899.788     -407871.014     4677242.25714   4677392.20614      -119.050 7
          -111.000 7       437.801        1007.000 0        -2.000 0        74.000 0";
 
-        let ((e, flag), content) =
+        let ((e, flag), clock_offset, content) =
             parse_epoch(&header, content).expect("failed to parse DORIS epoch");
 
         assert_eq!(
@@ -311,6 +415,14 @@ D02  -2069899.788     -407871.014     4677242.25714   4677392.20614      -119.05
             "parsed wrong epoch"
         );
         assert_eq!(flag, EpochFlag::Ok, "parsed wrong epoch flag");
+        assert_eq!(
+            clock_offset,
+            Some(ClockOffset {
+                offset: -0.151364695,
+                extrapolated: false,
+            }),
+            "parsed wrong clock offset"
+        );
 
         let station = Station {
             key: 1,
@@ -331,7 +443,7 @@ D02  -2069899.788     -407871.014     4677242.25714   4677392.20614      -119.05
 
         for (observable, data) in [
             (
-                Observable::from_str("L1C").unwrap(),
+                Observable::from_str("L1").unwrap(),
                 ObservationData {
                     m1: None,
                     m2: None,
@@ -436,7 +548,7 @@ D02  -2069899.788     -407871.014     4677242.25714   4677392.20614      -119.05
 
         for (observable, data) in [
             (
-                Observable::from_str("L1C").unwrap(),
+                Observable::from_str("L1").unwrap(),
                 ObservationData {
                     m1: None,
                     m2: None,
@@ -521,5 +633,17 @@ D02  -2069899.788     -407871.014     4677242.25714   4677392.20614      -119.05
                 .unwrap_or_else(|| panic!("failed to identify {:?}", observable));
             assert_eq!(value, &data, "wrong value parsed for {:?}", observable);
         }
+
+        // reciprocal
+        let formatted = fmt_epoch(&e, &flag, &clock_offset, &content, &header);
+        assert_eq!(
+            formatted,
+            "> 2024 01 01 00 00 28.999947700  0  2       -0.151364695 0
+D02  -2069899.788     -407871.014     4677242.25714   4677392.20614      -119.050 7
+         -111.000 7       437.801        1007.000 0        -2.000 0        74.000 0
+D01  -3237877.052    -2291024.044    21903595.62311  21903633.08011      -113.100 7
+          -98.400 7       437.801        1002.000 1       -20.000 1        82.000 1
+"
+        );
     }
 }
@@ -66,7 +66,7 @@ impl std::fmt::Display for Station {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
             f,
-            "D{:02}  {} {:<29} {}  {}   {}",
+            "D{:02}  {} {:<29} {}  {} {:>3}",
             self.key, self.label, self.site, self.domes, self.gen, self.k_factor
         )
     }
@@ -112,6 +112,22 @@ mod test {
                     key: 17,
                 },
             ),
+            (
+                "D12  GR4B GRASSE                        10002S019  3 -15",
+                Station {
+                    label: "GR4B".to_string(),
+                    site: "GRASSE".to_string(),
+                    domes: DOMES {
+                        area: 100,
+                        site: 2,
+                        sequential: 19,
+                        point: DOMESTrackingPoint::Instrument,
+                    },
+                    gen: 3,
+                    k_factor: -15,
+                    key: 12,
+                },
+            ),
         ] {
             let station = Station::from_str(desc).unwrap();
             assert_eq!(station, expected, "station parsing error");
@@ -102,9 +102,10 @@ pub(crate) fn format(epoch: Epoch, t: Type, revision: u8) -> String {
                 format!("{:04} {:02} {:02} {:02} {:02} {:02}", y, m, d, hh, mm, ss)
             }
         },
-        Type::IonosphereMaps => format!(
-            "{:04}   {:>2}    {:>2}    {:>2}    {:>2}    {:>2}",
-            y, m, d, hh, mm, ss
+        Type::IonosphereMaps => format!("{:6}{:6}{:6}{:6}{:6}{:6}", y, m, d, hh, mm, ss),
+        Type::DORIS => format!(
+            "{:04} {:02} {:02} {:02} {:02} {:02}.{:09}",
+            y, m, d, hh, mm, ss, nanos
         ),
         _ => {
             if revision < 3 {
@@ -632,6 +633,11 @@ mod test {
             assert!(epoch.is_ok(), "failed to parse IONEX/UTC epoch");
             let epoch = epoch.unwrap();
             assert_eq!(epoch, expected, "invalid IONEX/UTC epoch");
+            assert_eq!(
+                format(epoch, Type::IonosphereMaps, 1),
+                desc.trim_end(),
+                "invalid IONEX epoch formatting"
+            );
         }
     }
     #[test]
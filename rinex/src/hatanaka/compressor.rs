@@ -1,540 +1,519 @@
 //! RINEX compression module
 use super::{numdiff::NumDiff, textdiff::TextDiff, Error};
-use crate::is_rinex_comment;
-use crate::{Constellation, Observable, SV};
+use crate::{
+    header::Header, is_rinex_comment, observation::Crinex, types::Type, version::Version,
+    Constellation, Observable, SV,
+};
 use std::collections::HashMap;
+use std::io::{BufRead, Write};
 use std::str::FromStr;
 
-#[derive(Default, PartialEq)]
-pub enum State {
-    #[default]
-    EpochDescriptor,
-    Body,
-}
+/// Compression order of each new arc
+const ARC_ORDER: usize = 3;
 
-impl State {
-    /// Resets Finite State Machine
-    pub fn reset(&mut self) {
-        *self = Self::default()
-    }
+/// Like RNX2CRX, we start a new arc when the differenced value
+/// no longer fits in 10 digits (upper part over 10^5 x 10^5).
+const ARC_LIMIT: i64 = 100_000;
+
+/// Observation kernels of a satellite, as compressed in previous epoch
+#[derive(Debug, Clone)]
+struct SvKernels {
+    /// one numerical kernel per observable, None when observation was blank
+    obs: Vec<Option<NumDiff>>,
+    /// LLI and SSI flags kernel
+    flags: TextDiff,
 }
 
-/// Structure to compress RINEX data
+/// Structure to compress Observation RINEX data into CRINEX,
+/// following the algorithm of the historical RNX2CRX program.
+/// RINEX2 is compressed to CRINEX1, RINEX3 and RINEX4 to CRINEX3,
+/// including mixed constellations, clock offsets and special events.
+/// Lines are fed in the order they appear in the record:
+/// compressed content is released once an epoch is complete.
+#[derive(Debug, Clone, Default)]
 pub struct Compressor {
-    /// finite state machine
-    state: State,
-    /// True only for first epoch ever processed
-    first_epoch: bool,
-    /// epoch line ptr
-    epoch_ptr: u8,
-    /// epoch descriptor
-    epoch_descriptor: String,
-    /// flags descriptor being constructed
-    flags_descriptor: String,
-    /// vehicles counter in next body
-    nb_vehicles: usize,
-    /// vehicle pointer
-    vehicle_ptr: usize,
-    /// obs pointer
-    obs_ptr: usize,
-    /// Epoch differentiator
-    epoch_diff: TextDiff,
-    /// Clock offset differentiator
-    clock_diff: NumDiff,
-    /// Vehicle differentiators
-    sv_diff: HashMap<SV, HashMap<usize, (NumDiff, TextDiff, TextDiff)>>,
-    /// Pending kernel re-initialization
-    forced_init: HashMap<SV, Vec<usize>>,
+    /// content of the epoch being gathered
+    epoch: Vec<String>,
+    /// number of lines describing the epoch being gathered, once known
+    epoch_size: Option<usize>,
+    /// special event records that still need to be passed as is
+    special_records: usize,
+    /// epoch descriptor kernel, None when next epoch needs to be fully described
+    epoch_descriptor: Option<TextDiff>,
+    /// clock offset kernel, None when previous epoch did not have a clock offset
+    clock_diff: Option<NumDiff>,
+    /// kernels of each satellite, from previous epoch
+    sv_diff: HashMap<SV, SvKernels>,
 }
 
-fn format_epoch_descriptor(content: &str) -> String {
-    let mut result = String::new();
-    result.push('&');
-    for line in content.lines() {
-        result.push_str(line.trim()) // removes all \tab
-    }
-    result.push('\n');
-    result
+/// Returns content from given column range, blank if it does not exist
+fn columns(content: &str, start: usize, end: usize) -> &str {
+    let end = std::cmp::min(end, content.len());
+    content.get(start..end).unwrap_or("")
 }
 
-impl Default for Compressor {
-    fn default() -> Self {
-        Self {
-            first_epoch: true,
-            epoch_ptr: 0,
-            epoch_descriptor: String::new(),
-            flags_descriptor: String::new(),
-            state: State::default(),
-            nb_vehicles: 0,
-            vehicle_ptr: 0,
-            obs_ptr: 0,
-            epoch_diff: TextDiff::new(),
-            clock_diff: NumDiff::new(NumDiff::MAX_COMPRESSION_ORDER).unwrap(),
-            sv_diff: HashMap::new(),
-            forced_init: HashMap::new(),
-        }
+/// Parses a readable value into an integer number,
+/// exactly as written: decimal point is simply dropped.
+fn parse_value(content: &str) -> Option<i64> {
+    let content = content.trim();
+    if content.is_empty() {
+        None
+    } else {
+        content.replace('.', "").parse::<i64>().ok()
     }
 }
 
-impl Compressor {
-    /// Identifies amount of vehicles to be provided in next iterations
-    /// by analyzing epoch descriptor
-    fn determine_nb_vehicles(&self, content: &str) -> Result<usize, Error> {
-        if content.len() < 33 {
-            Err(Error::MalformedEpochDescriptor)
-        } else {
-            let nb = &content[30..32];
-            if let Ok(u) = nb.trim().parse::<u16>() {
-                //println!("Identified {} vehicles", u); //DEBUG
-                Ok(u.into())
-            } else {
-                Err(Error::MalformedEpochDescriptor)
-            }
-        }
+/// Identifies satellite from its 3 character descriptor.
+/// Constellation may be omitted in RINEX2, in which case
+/// the file constellation applies.
+fn parse_sv(content: &str, constellation: &Constellation) -> Result<SV, Error> {
+    if content.len() < 3 {
+        return Err(Error::VehicleIdentificationError);
     }
+    let (system, prn) = content.split_at(1);
+    let constellation = match system {
+        " " => match constellation {
+            Constellation::Mixed => Constellation::GPS,
+            c => *c,
+        },
+        system => Constellation::from_str(system).map_err(|_| Error::VehicleIdentificationError)?,
+    };
+    let prn = prn.trim().parse::<u8>()?;
+    Ok(SV::new(constellation, prn))
+}
 
-    /// Identifies vehicle from previously stored epoch descriptor
-    fn current_vehicle(&self, constellation: &Constellation) -> Result<SV, Error> {
-        let sv_size = 3;
-        let epoch_size = 32;
-        let vehicle_offset = self.vehicle_ptr * sv_size;
-        let min = epoch_size + vehicle_offset;
-        let max = min + sv_size;
-        let vehicle = &mut self.epoch_descriptor[min..max].trim().to_string();
-        if let Some(constell_id) = vehicle.chars().nth(0) {
-            if constell_id.is_ascii_digit() {
-                // in old RINEX + mono constell context
-                //   it is possible that constellation ID is omitted..
-                vehicle.insert_str(0, &format!("{:x}", constellation));
-            }
-            let sv = SV::from_str(vehicle)?;
-            //println!("VEHICULE: {}", sv); //DEBUG
-            Ok(sv)
-        } else {
-            Err(Error::VehicleIdentificationError)
-        }
-    }
+/// Returns number of observables for given satellite
+fn nb_observables(
+    rnx_major: u8,
+    observables: &HashMap<Constellation, Vec<Observable>>,
+    sv: &SV,
+) -> Result<usize, Error> {
+    let codes = match sv.constellation.is_sbas() {
+        true => observables.get(&Constellation::SBAS),
+        false => observables.get(&sv.constellation),
+    };
+    let codes = match codes {
+        Some(codes) => Some(codes),
+        // RINEX2 shares the same list among all systems
+        None if rnx_major < 3 => observables.values().next(),
+        None => None,
+    };
+    codes
+        .map(|codes| codes.len())
+        .ok_or(Error::VehicleIdentificationError)
+}
 
-    /// Concludes current vehicle
-    fn conclude_vehicle(&mut self, content: &str) -> String {
-        let mut result = content.to_string();
-        //println!(">>> VEHICULE CONCLUDED"); //DEBUG
-        // conclude line with lli/ssi flags
-        let flags = self.flags_descriptor.trim_end();
-        if !flags.is_empty() {
-            result.push_str(flags);
-        }
-        result.push('\n');
-        self.flags_descriptor.clear();
-        // move to next vehicle
-        self.obs_ptr = 0;
-        self.vehicle_ptr += 1;
-        if self.vehicle_ptr == self.nb_vehicles {
-            self.conclude_epoch();
-        }
-        result
+impl Compressor {
+    /// Returns (flag, number of satellites or special records) of a new epoch
+    fn parse_epoch_descriptor(rnx_major: u8, content: &str) -> Result<(u8, usize), Error> {
+        let (flag, nb) = match rnx_major {
+            1 | 2 => (columns(content, 26, 29), columns(content, 29, 32)),
+            _ => {
+                if !content.starts_with('>') {
+                    return Err(Error::MalformedEpochDescriptor);
+                }
+                (columns(content, 29, 32), columns(content, 32, 35))
+            },
+        };
+        let flag = flag
+            .trim()
+            .parse::<u8>()
+            .map_err(|_| Error::MalformedEpochDescriptor)?;
+        let nb = nb
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| Error::MalformedEpochDescriptor)?;
+        Ok((flag, nb))
     }
-
-    /// Concludes current epoch
-    fn conclude_epoch(&mut self) {
-        //DEBUG
-        //println!(">>> EPOCH CONCLUDED \n");
-        self.epoch_ptr = 0;
-        self.vehicle_ptr = 0;
-        self.epoch_descriptor.clear();
-        self.state.reset();
+    /// Returns satellite identities of the epoch being gathered.
+    /// In RINEX2, they're all described in the epoch lines,
+    /// in RINEX3 each one starts a new line.
+    fn epoch_vehicles(
+        &self,
+        rnx_major: u8,
+        nb_sv: usize,
+        constellation: &Constellation,
+    ) -> Result<Vec<SV>, Error> {
+        let mut vehicles = Vec::with_capacity(nb_sv);
+        for i in 0..nb_sv {
+            let sv = match rnx_major {
+                1 | 2 => {
+                    let line = &self.epoch[i / 12];
+                    let offset = 32 + (i % 12) * 3;
+                    parse_sv(columns(line, offset, offset + 3), constellation)?
+                },
+                _ => parse_sv(columns(&self.epoch[i + 1], 0, 3), constellation)?,
+            };
+            vehicles.push(sv);
+        }
+        Ok(vehicles)
     }
-
-    /// Schedule given kernel for reinitizalition
-    /// due to omitted data field.
-    /// We only do so if kernel was previously initialized
-    fn schedule_kernel_init(&mut self, sv: SV, index: usize) {
-        if let Some(indexes) = self.sv_diff.get(&sv) {
-            if indexes.contains_key(&index) {
-                if let Some(indexes) = self.forced_init.get_mut(&sv) {
-                    if !indexes.contains(&index) {
-                        indexes.push(index);
-                    }
-                } else {
-                    self.forced_init.insert(sv, vec![index]);
+    /// Determines how many lines describe the epoch being gathered.
+    /// In RINEX2, this is only known once all epoch lines were gathered.
+    fn determine_epoch_size(
+        &self,
+        rnx_major: u8,
+        observables: &HashMap<Constellation, Vec<Observable>>,
+        constellation: &Constellation,
+    ) -> Result<Option<usize>, Error> {
+        let (_, nb_sv) = Self::parse_epoch_descriptor(rnx_major, &self.epoch[0])?;
+        match rnx_major {
+            1 | 2 => {
+                let nb_lines = std::cmp::max(1, num_integer::div_ceil(nb_sv, 12));
+                if self.epoch.len() < nb_lines {
+                    return Ok(None);
                 }
-                //DEBUG
-                //println!("PENDING: {:?}", self.forced_init);
-            }
+                let mut size = nb_lines;
+                for sv in self.epoch_vehicles(rnx_major, nb_sv, constellation)? {
+                    let nb_obs = nb_observables(rnx_major, observables, &sv)?;
+                    size += num_integer::div_ceil(nb_obs, 5);
+                }
+                Ok(Some(size))
+            },
+            _ => Ok(Some(nb_sv + 1)),
         }
     }
-
-    /// Compresses given RINEX data to CRINEX
-    pub fn compress(
+    /// Compresses the epoch that was gathered
+    fn compress_epoch(
         &mut self,
-        _rnx_major: u8,
+        rnx_major: u8,
         observables: &HashMap<Constellation, Vec<Observable>>,
         constellation: &Constellation,
-        content: &str,
     ) -> Result<String, Error> {
-        let mut result: String = String::new();
-        let mut lines = content.lines();
+        let mut result = String::with_capacity(1024);
+        let (_, nb_sv) = Self::parse_epoch_descriptor(rnx_major, &self.epoch[0])?;
+        let vehicles = self.epoch_vehicles(rnx_major, nb_sv, constellation)?;
 
-        loop {
-            let line: &str = match lines.next() {
-                Some(l) => {
-                    if l.trim().is_empty() {
-                        // line completely empty
-                        // ==> determine if we were expecting content
-                        if self.state == State::Body {
-                            // previously active
-                            if self.obs_ptr > 0 {
-                                // previously active
-                                // identify current SV
-                                if let Ok(sv) = self.current_vehicle(constellation) {
-                                    // nb of obs for this constellation
-                                    let sv_nb_obs = observables[&sv.constellation].len();
-                                    let nb_missing = std::cmp::min(5, sv_nb_obs - self.obs_ptr);
-                                    //println!("Early empty line - missing {} field(s)", nb_missing); //DEBUG
-                                    for i in 0..nb_missing {
-                                        result.push(' '); // empty whitespace, on each missing observable
-                                                          // to remain retro compatible with official tools
-                                        self.flags_descriptor.push_str("  "); // both missing
-                                        self.schedule_kernel_init(sv, self.obs_ptr + i);
-                                    }
-                                    self.obs_ptr += nb_missing;
-                                    if self.obs_ptr == sv_nb_obs {
-                                        // vehicle completion
-                                        result = self.conclude_vehicle(&result);
-                                    }
+        // epoch descriptor: epoch, flag and satellites (in CRINEX3),
+        // clock offset is described on its own line
+        let (descriptor, clock_offset) = match rnx_major {
+            1 | 2 => {
+                let nb_lines = std::cmp::max(1, num_integer::div_ceil(nb_sv, 12));
+                let mut descriptor = String::from("&");
+                descriptor.push_str(columns(&self.epoch[0], 1, 68));
+                for line in self.epoch.iter().take(nb_lines).skip(1) {
+                    descriptor.push_str(columns(line, 32, 68));
+                }
+                (descriptor, parse_value(columns(&self.epoch[0], 68, 80)))
+            },
+            _ => {
+                let mut descriptor = format!("{:<41}", columns(&self.epoch[0], 0, 35));
+                for sv in self.epoch.iter().skip(1) {
+                    descriptor.push_str(&format!("{:<3}", columns(sv, 0, 3)));
+                }
+                (descriptor, parse_value(columns(&self.epoch[0], 35, 80)))
+            },
+        };
+        match &mut self.epoch_descriptor {
+            Some(kernel) => result.push_str(&kernel.compress(&descriptor)),
+            None => {
+                let mut kernel = TextDiff::new();
+                kernel.init(&descriptor);
+                self.epoch_descriptor = Some(kernel);
+                result.push_str(&descriptor);
+            },
+        }
+        result.push('\n');
 
-                                    if nb_missing > 0 {
-                                        continue;
-                                    }
-                                }
-                            }
-                        }
+        match clock_offset {
+            Some(offset) => match &mut self.clock_diff {
+                Some(kernel) => {
+                    let compressed = kernel.compress(offset);
+                    if compressed.abs() / ARC_LIMIT > ARC_LIMIT {
+                        kernel.init(ARC_ORDER, offset)?;
+                        result.push_str(&format!("{}&{}", ARC_ORDER, offset));
+                    } else {
+                        result.push_str(&compressed.to_string());
                     }
-                    l
                 },
-                None => break, // done iterating
+                None => {
+                    let mut kernel = NumDiff::new(NumDiff::MAX_COMPRESSION_ORDER)?;
+                    kernel.init(ARC_ORDER, offset)?;
+                    self.clock_diff = Some(kernel);
+                    result.push_str(&format!("{}&{}", ARC_ORDER, offset));
+                },
+            },
+            None => self.clock_diff = None,
+        }
+        result.push('\n');
+
+        // observations: satellites that were not present in previous epoch
+        // are fully described
+        let mut sv_diff: HashMap<SV, SvKernels> = HashMap::with_capacity(nb_sv);
+        let mut line_offset = match rnx_major {
+            1 | 2 => std::cmp::max(1, num_integer::div_ceil(nb_sv, 12)),
+            _ => 1,
+        };
+        for sv in vehicles {
+            let nb_obs = nb_observables(rnx_major, observables, &sv)?;
+            let content = match rnx_major {
+                1 | 2 => {
+                    let nb_lines = num_integer::div_ceil(nb_obs, 5);
+                    let mut content = String::with_capacity(nb_lines * 80);
+                    for line in self.epoch.iter().skip(line_offset).take(nb_lines) {
+                        content.push_str(&format!("{:<80}", line));
+                    }
+                    line_offset += nb_lines;
+                    content
+                },
+                _ => {
+                    let content = columns(&self.epoch[line_offset], 3, 3 + nb_obs * 16);
+                    line_offset += 1;
+                    content.to_string()
+                },
             };
+            let content = format!("{:<width$}", content, width = nb_obs * 16);
 
-            // println!("\nWorking from LINE : \"{}\"", line); //DEBUG
+            let previous = self.sv_diff.remove(&sv);
+            let described = previous.is_some();
+            let mut kernels = match previous {
+                Some(previous) => previous,
+                None => SvKernels {
+                    obs: vec![None; nb_obs],
+                    flags: TextDiff::new(),
+                },
+            };
+            kernels.obs.resize(nb_obs, None);
 
-            // [0] : COMMENTS (special case)
-            if is_rinex_comment(line) {
-                if line.contains("RINEX FILE SPLICE") {
-                    // [0*] SPLICE special comments
-                    //      merged RINEX Files
-                    self.state.reset();
-                    //self.pointer = 0
+            let mut fields = Vec::<String>::with_capacity(nb_obs);
+            let mut flags = String::with_capacity(nb_obs * 2);
+            let mut blanks = Vec::<usize>::new();
+
+            for (index, kernel) in kernels.obs.iter_mut().enumerate() {
+                let field = columns(&content, index * 16, (index + 1) * 16);
+                match parse_value(columns(field, 0, 14)) {
+                    Some(value) => {
+                        let compressed = match kernel {
+                            Some(kernel) => {
+                                let compressed = kernel.compress(value);
+                                if compressed.abs() / ARC_LIMIT > ARC_LIMIT {
+                                    kernel.init(ARC_ORDER, value)?;
+                                    format!("{}&{}", ARC_ORDER, value)
+                                } else {
+                                    compressed.to_string()
+                                }
+                            },
+                            None => {
+                                let mut new = NumDiff::new(NumDiff::MAX_COMPRESSION_ORDER)?;
+                                new.init(ARC_ORDER, value)?;
+                                *kernel = Some(new);
+                                format!("{}&{}", ARC_ORDER, value)
+                            },
+                        };
+                        fields.push(compressed);
+                        flags.push_str(&format!("{:<2}", columns(field, 14, 16)));
+                    },
+                    None => {
+                        *kernel = None;
+                        fields.push(String::new());
+                        flags.push_str("  ");
+                        blanks.push(index);
+                    },
                 }
-                result // feed content as is
-                    .push_str(line);
-                result // \n dropped by .lines()
-                    .push('\n');
-                continue;
             }
 
-            match self.state {
-                State::EpochDescriptor => {
-                    if self.epoch_ptr == 0 {
-                        // 1st line
-                        // identify #systems
-                        self.nb_vehicles = self.determine_nb_vehicles(line)?;
+            let compressed_flags = match described {
+                true => {
+                    let compressed = kernels.flags.compress(&flags);
+                    if rnx_major < 3 {
+                        // CRINEX1: flags of blank observations are not described
+                        let mut compressed =
+                            format!("{:<width$}", compressed, width = nb_obs * 2).into_bytes();
+                        for index in blanks {
+                            compressed[index * 2] = b' ';
+                            compressed[index * 2 + 1] = b' ';
+                        }
+                        String::from_utf8_lossy(&compressed).trim_end().to_string()
+                    } else {
+                        compressed
+                    }
+                },
+                false => {
+                    kernels.flags.init(&flags);
+                    match rnx_major {
+                        1 | 2 => flags.trim_end().to_string(),
+                        _ => flags.replace(' ', "&"),
                     }
-                    self.epoch_ptr += 1;
-                    self.epoch_descriptor.push_str(line);
+                },
+            };
 
-                    //TODO
-                    //pour clock offsets
-                    /*if line.len() > 60-12 {
-                        Some(line.split_at(60-12).1.trim())
-                    } else {
-                        None*/
-                    //TODO
-                    // if we did have clock offset,
-                    //  append in a new line
-                    //  otherwise append a BLANK
-                    self.epoch_descriptor.push('\n');
+            let mut line = fields.join(" ");
+            line.push(' ');
+            line.push_str(&compressed_flags);
+            result.push_str(line.trim_end());
+            result.push('\n');
 
-                    let nb_lines = num_integer::div_ceil(self.nb_vehicles, 12) as u8;
-                    if self.epoch_ptr == nb_lines {
-                        // end of descriptor
-                        // format to CRINEX
-                        self.epoch_descriptor = format_epoch_descriptor(&self.epoch_descriptor);
-                        if self.first_epoch {
-                            //println!("INIT EPOCH with \"{}\"", self.epoch_descriptor); //DEBUG
-                            self.epoch_diff.init(&self.epoch_descriptor);
-                            result.push_str(&self.epoch_descriptor);
-                            /////////////////////////////////////
-                            //TODO
-                            //missing clock offset field here
-                            //next line should not always be empty
-                            /////////////////////////////////////
-                            result.push('\n');
-                            self.first_epoch = false;
-                        } else {
-                            result.push_str(
-                                self.epoch_diff.compress(&self.epoch_descriptor).trim_end(),
-                            );
-                            result.push('\n');
-                            /////////////////////////////////////
-                            //TODO
-                            //missing clock offset field here
-                            //next line should not always be empty
-                            /////////////////////////////////////
-                            result.push('\n');
-                        }
+            sv_diff.insert(sv, kernels);
+        }
 
-                        self.obs_ptr = 0;
-                        self.vehicle_ptr = 0;
-                        self.flags_descriptor.clear();
-                        self.state = State::Body;
+        // satellites that disappeared will be fully described if they come back
+        self.sv_diff = sv_diff;
+        Ok(result)
+    }
+    /// Compresses the epoch being gathered, if it is now complete
+    fn try_compress_epoch(
+        &mut self,
+        rnx_major: u8,
+        observables: &HashMap<Constellation, Vec<Observable>>,
+        constellation: &Constellation,
+    ) -> Result<Option<String>, Error> {
+        if self.epoch_size.is_none() {
+            self.epoch_size = self.determine_epoch_size(rnx_major, observables, constellation)?;
+        }
+        if self.epoch_size == Some(self.epoch.len()) {
+            let compressed = self.compress_epoch(rnx_major, observables, constellation)?;
+            self.epoch.clear();
+            self.epoch_size = None;
+            Ok(Some(compressed))
+        } else {
+            Ok(None)
+        }
+    }
+    /// Resets the [Compressor]: next epoch is fully described,
+    /// like at the beginning of a new file.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+    /// Compresses given Observation RINEX content, which may span several lines.
+    /// Returns compressed lines, possibly none if the current epoch is not complete yet.
+    ///   - rnx_major: RINEX revision, which determines the CRINEX revision
+    ///   - observables: list of observables, per constellation, as described in the header
+    ///   - constellation: file constellation, for RINEX2 partial satellite identities
+    ///   - content: readable line(s)
+    pub fn compress(
+        &mut self,
+        rnx_major: u8,
+        observables: &HashMap<Constellation, Vec<Observable>>,
+        constellation: &Constellation,
+        content: &str,
+    ) -> Result<String, Error> {
+        let mut result = String::new();
+        // blank lines are meaningful in RINEX2
+        let content = content.strip_suffix('\n').unwrap_or(content);
+        for line in content.split('\n') {
+            let line = line.trim_end_matches('\r');
+            if self.special_records > 0 {
+                // special event records are preserved
+                result.push_str(line);
+                result.push('\n');
+                self.special_records -= 1;
+                continue;
+            }
+            if is_rinex_comment(line) {
+                result.push_str(line);
+                result.push('\n');
+                continue;
+            }
+            if self.epoch.is_empty() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let (flag, nb) = Self::parse_epoch_descriptor(rnx_major, line)?;
+                if flag > 1 {
+                    // special event: described as is,
+                    // following epoch will be fully described
+                    match rnx_major {
+                        1 | 2 => {
+                            result.push('&');
+                            result.push_str(columns(line, 1, line.len()));
+                        },
+                        _ => result.push_str(line),
                     }
+                    result.push('\n');
+                    self.special_records = nb;
+                    self.epoch_descriptor = None;
+                    continue;
+                }
+            }
+            self.epoch.push(line.to_string());
+            match self.try_compress_epoch(rnx_major, observables, constellation) {
+                Ok(Some(compressed)) => result.push_str(&compressed),
+                Ok(None) => {},
+                Err(e) => {
+                    // drop this epoch entirely
+                    self.epoch.clear();
+                    self.epoch_size = None;
+                    return Err(e);
                 },
-                State::Body => {
-                    // nb of obs in this line
-                    let nb_obs_line = num_integer::div_ceil(line.len(), 17);
-                    // identify current satellite using stored epoch description
-                    if let Ok(sv) = self.current_vehicle(constellation) {
-                        // nb of obs for this constellation
-                        let sv_nb_obs = observables[&sv.constellation].len();
-                        if self.obs_ptr + nb_obs_line > sv_nb_obs {
-                            // facing an overflow
-                            // this means all final fields were omitted,
-                            // ==> handle this case
-                            //println!("SV {} final fields were omitted", sv); //DEBUG
-                            for index in self.obs_ptr..sv_nb_obs + 1 {
-                                self.schedule_kernel_init(sv, index);
-                                result.push(' '); // put an empty space on missing observables
-                                                  // this is how RNX2CRX (official) behaves,
-                                                  // if we don't do this we break retro compatibility
-                                self.flags_descriptor.push_str("  ");
-                            }
-                            result = self.conclude_vehicle(&result);
-                            if self.state == State::EpochDescriptor {
-                                // epoch got also concluded
-                                // --> rewind fsm
-                                self.nb_vehicles = self.determine_nb_vehicles(line)?;
-                                self.epoch_ptr = 1; // we already have a new descriptor
-                                self.epoch_descriptor.push_str(line);
-                                self.epoch_descriptor.push('\n');
-                                continue; // avoid end of this loop,
-                                          // as this vehicle is now concluded
-                            }
-                        }
-
-                        // compress all observables
-                        // and store flags for line completion
-                        let mut observables = line;
-                        for _ in 0..nb_obs_line {
-                            let index = std::cmp::min(16, observables.len()); // avoid overflow
-                                                                              // as some data flags might be omitted
-                            let (data, rem) = observables.split_at(index);
-                            let (obsdata, flags) = data.split_at(14);
-                            observables = rem;
-                            if let Ok(obsdata) = obsdata.trim().parse::<f64>() {
-                                let obsdata = f64::round(obsdata * 1000.0) as i64;
-                                if flags.trim().is_empty() {
-                                    // Both Flags ommited
-                                    //println!("OBS \"{}\" LLI \"X\" SSI \"X\"", obsdata); //DEBUG
-                                    // data compression
-                                    if let Some(sv_diffs) = self.sv_diff.get_mut(&sv) {
-                                        // retrieve observable state
-                                        if let Some(diffs) = sv_diffs.get_mut(&self.obs_ptr) {
-                                            let compressed: i64;
-                                            // forced re/init is pending
-                                            if let Some(indexes) = self.forced_init.get_mut(&sv) {
-                                                if indexes.contains(&self.obs_ptr) {
-                                                    // forced reinit pending
-                                                    compressed = obsdata;
-                                                    diffs.0.init(3, obsdata).unwrap();
-                                                    diffs.1.init(" ");
-                                                    diffs.2.init(" ");
-                                                    //println!("FORCED REINIT WITH FLAGS \"{}\"", self.flags_descriptor); //DEBUG
-                                                    result.push_str(&format!("3&{} ", compressed)); //append obs
-                                                                                                    // remove from pending list,
-                                                                                                    // so we only force it once
-                                                    for i in 0..indexes.len() {
-                                                        if indexes[i] == self.obs_ptr {
-                                                            indexes.remove(i);
-                                                            break;
-                                                        }
-                                                    }
-                                                    if indexes.is_empty() {
-                                                        self.forced_init.remove(&sv);
-                                                    }
-                                                } else {
-                                                    // compress data
-                                                    compressed = diffs.0.compress(obsdata);
-                                                    result.push_str(&format!("{} ", compressed));
-                                                    //append obs
-                                                }
-                                            } else {
-                                                // compress data
-                                                compressed = diffs.0.compress(obsdata);
-                                                result.push_str(&format!("{} ", compressed));
-                                                //append obs
-                                            }
-
-                                            let _ = diffs.1.compress(" ");
-                                            let _ = diffs.2.compress(" ");
-                                            // ==> empty flags fields
-                                            self.flags_descriptor.push_str("  ");
-                                        } else {
-                                            // first time dealing with this observable
-                                            let mut diff: (NumDiff, TextDiff, TextDiff) = (
-                                                NumDiff::new(NumDiff::MAX_COMPRESSION_ORDER)?,
-                                                TextDiff::new(),
-                                                TextDiff::new(),
-                                            );
-                                            diff.0.init(3, obsdata).unwrap();
-                                            result.push_str(&format!("3&{} ", obsdata)); //append obs
-                                            diff.1.init(" "); // BLANK
-                                            diff.2.init(" "); // BLANK
-                                            self.flags_descriptor.push_str("  ");
-                                            sv_diffs.insert(self.obs_ptr, diff);
-                                        }
-                                    } else {
-                                        // first time dealing with this vehicle
-                                        let mut diff: (NumDiff, TextDiff, TextDiff) = (
-                                            NumDiff::new(NumDiff::MAX_COMPRESSION_ORDER)?,
-                                            TextDiff::new(),
-                                            TextDiff::new(),
-                                        );
-                                        diff.0.init(3, obsdata).unwrap();
-                                        result.push_str(&format!("3&{} ", obsdata)); //append obs
-                                        diff.1.init(" "); // BLANK
-                                        diff.2.init(" "); // BLANK
-                                        self.flags_descriptor.push_str("  ");
-                                        let mut map: HashMap<usize, (NumDiff, TextDiff, TextDiff)> =
-                                            HashMap::new();
-                                        map.insert(self.obs_ptr, diff);
-                                        self.sv_diff.insert(sv, map);
-                                    }
-                                } else {
-                                    //flags.len() >=1 : Not all Flags ommited
-                                    let (lli, ssi) = flags.split_at(1);
-                                    //println!("OBS \"{}\" - LLI \"{}\" - SSI \"{}\"", obsdata, lli, ssi); //DEBUG
-                                    if let Some(sv_diffs) = self.sv_diff.get_mut(&sv) {
-                                        // retrieve observable state
-                                        if let Some(diffs) = sv_diffs.get_mut(&self.obs_ptr) {
-                                            // compress data
-                                            let compressed: i64;
-                                            // forced re/init is pending
-                                            if let Some(indexes) = self.forced_init.get_mut(&sv) {
-                                                if indexes.contains(&self.obs_ptr) {
-                                                    // forced init pending
-                                                    compressed = obsdata;
-                                                    result.push_str(&format!("3&{} ", compressed));
-                                                    diffs.0.init(3, obsdata).unwrap();
-                                                    // remove from pending list,
-                                                    // so we only force it once
-                                                    for i in 0..indexes.len() {
-                                                        if indexes[i] == self.obs_ptr {
-                                                            indexes.remove(i);
-                                                            break;
-                                                        }
-                                                    }
-                                                    if indexes.is_empty() {
-                                                        self.forced_init.remove(&sv);
-                                                    }
-                                                } else {
-                                                    compressed = diffs.0.compress(obsdata);
-                                                    result.push_str(&format!("{} ", compressed));
-                                                }
-                                            } else {
-                                                compressed = diffs.0.compress(obsdata);
-                                                result.push_str(&format!("{} ", compressed));
-                                            }
+            }
+        }
+        Ok(result)
+    }
+}
 
-                                            let lli = diffs.1.compress(lli);
-                                            self.flags_descriptor.push_str(&lli);
+/// Compresses an Observation RINEX data stream into CRINEX,
+/// like the historical RNX2CRX program does: the header section is preserved,
+/// preceded by the CRINEX descriptor, and the record is compressed line by line,
+/// with constant memory footprint. Returns the [Header] that was identified.
+/// CRINEX revision is dictated by the RINEX revision: CRINEX1 for RINEX2,
+/// CRINEX3 for RINEX3 and RINEX4, whatever `crinex.version`.
+/// ```
+/// use rinex::hatanaka::rnx2crx;
+/// use rinex::observation::Crinex;
+/// use rinex::reader::BufferedReader;
+///
+/// let reader = BufferedReader::new("../test_resources/OBS/V3/DUTH0630.22O")
+///     .unwrap();
+/// let mut compressed = Vec::<u8>::new();
+/// let header = rnx2crx(reader, &mut compressed, &Crinex::default())
+///     .unwrap();
+/// assert_eq!(header.version.major, 3);
+/// ```
+pub fn rnx2crx<R: BufRead, W: Write>(
+    reader: R,
+    writer: &mut W,
+    crinex: &Crinex,
+) -> Result<Header, crate::Error> {
+    let mut lines = reader.lines();
+    let mut content = String::with_capacity(4096);
+    for line in lines.by_ref() {
+        let line = line?;
+        content.push_str(&line);
+        content.push('\n');
+        if line.contains("END OF HEADER") {
+            break;
+        }
+    }
+    let header = Header::new(&mut content.as_bytes())?;
+    if header.rinex_type != Type::ObservationData {
+        return Err(Error::NotObsRinexData.into());
+    }
+    let obs = header.obs.as_ref().ok_or(Error::NotObsRinexData)?;
+    if obs.crinex.is_some() {
+        return Err(Error::AlreadyCompressed.into());
+    }
+    let constellation = header.constellation.unwrap_or(Constellation::Mixed);
 
-                                            let ssi = diffs.2.compress(ssi);
-                                            self.flags_descriptor.push_str(&ssi);
-                                        } else {
-                                            // first time dealing with this observable
-                                            let mut diff: (NumDiff, TextDiff, TextDiff) = (
-                                                NumDiff::new(NumDiff::MAX_COMPRESSION_ORDER)?,
-                                                TextDiff::new(),
-                                                TextDiff::new(),
-                                            );
-                                            diff.0.init(3, obsdata).unwrap();
-                                            diff.1.init(lli);
-                                            diff.2.init(ssi);
-                                            result.push_str(&format!("3&{} ", obsdata)); //append obs
-                                            if !lli.is_empty() {
-                                                self.flags_descriptor.push_str(lli);
-                                            } else {
-                                                self.flags_descriptor.push(' ');
-                                            }
+    let crinex = crinex.with_version(Version {
+        major: match header.version.major {
+            1 | 2 => 1,
+            _ => 3,
+        },
+        minor: 0,
+    });
 
-                                            if !ssi.is_empty() {
-                                                self.flags_descriptor.push_str(ssi);
-                                            } else {
-                                                // SSI omitted
-                                                self.flags_descriptor.push(' ');
-                                            }
-                                            sv_diffs.insert(self.obs_ptr, diff);
-                                        }
-                                    } else {
-                                        // first time dealing with this vehicle
-                                        let mut diff: (NumDiff, TextDiff, TextDiff) = (
-                                            NumDiff::new(NumDiff::MAX_COMPRESSION_ORDER)?,
-                                            TextDiff::new(),
-                                            TextDiff::new(),
-                                        );
-                                        diff.0.init(3, obsdata).unwrap();
-                                        result.push_str(&format!("3&{} ", obsdata)); //append obs
-                                        diff.1.init(lli);
-                                        diff.2.init(ssi);
-                                        self.flags_descriptor.push_str(lli);
-                                        if !ssi.is_empty() {
-                                            self.flags_descriptor.push_str(ssi);
-                                        } else {
-                                            // SSI omitted
-                                            diff.2.init(" "); // BLANK
-                                            self.flags_descriptor.push(' ');
-                                        }
-                                        let mut map: HashMap<usize, (NumDiff, TextDiff, TextDiff)> =
-                                            HashMap::new();
-                                        map.insert(self.obs_ptr, diff);
-                                        self.sv_diff.insert(sv, map);
-                                    }
-                                }
-                            } else {
-                                //obsdata::f64::from_str()
-                                // when floating point parsing is in failure,
-                                // we know this observable is omitted
-                                result.push(' '); // put an empty space on missing observables
-                                                  // this is how RNX2CRX (official) behaves,
-                                                  // if we don't do this we break retro compatibility
-                                self.flags_descriptor.push_str("  ");
-                                self.schedule_kernel_init(sv, self.obs_ptr);
-                            }
-                            self.obs_ptr += 1;
-                            //println!("OBS {}/{}", self.obs_ptr, sv_nb_obs); //DEBUG
+    writeln!(writer, "{}", crinex)?;
+    writer.write_all(content.as_bytes())?;
 
-                            if self.obs_ptr > sv_nb_obs {
-                                // unexpected overflow
-                                return Err(Error::MalformedEpochBody); // too many observables were found
-                            }
-                        } //for i..nb_obs in this line
+    let mut compressor = Compressor::default();
+    for line in lines {
+        let line = line?;
+        let compressed =
+            compressor.compress(header.version.major, &obs.codes, &constellation, &line)?;
+        writer.write_all(compressed.as_bytes())?;
+    }
+    writer.flush()?;
+    Ok(header)
+}
 
-                        if self.obs_ptr == sv_nb_obs {
-                            // vehicle completion
-                            result = self.conclude_vehicle(&result);
-                        }
-                    } else {
-                        // sv::from_str()
-                        // failed to identify which vehicle we're dealing with
-                        return Err(Error::VehicleIdentificationError);
-                    }
-                },
-            } //match(state)
-        } //main loop
-        result.push('\n');
-        Ok(result)
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(parse_value("  20243517.560"), Some(20243517560));
+        assert_eq!(parse_value("     -1242.766"), Some(-1242766));
+        assert_eq!(parse_value("          .000"), Some(0));
+        assert_eq!(parse_value("              "), None);
     }
-    //notes:
-    //si le flag est absent: "&" pour insérer un espace
-    //tous les flags sont foutus a la fin en guise de dernier mot
 }
@@ -4,7 +4,7 @@ use thiserror::Error;
 pub mod compressor;
 pub mod numdiff;
 pub mod textdiff;
-pub use compressor::Compressor;
+pub use compressor::{rnx2crx, Compressor};
 
 pub mod decompressor;
 pub use decompressor::Decompressor;
@@ -17,6 +17,8 @@ pub enum Error {
     NotACrinex,
     #[error("This is not an Observation file")]
     NotObsRinexData,
+    #[error("This file is already compressed")]
+    AlreadyCompressed,
     #[error("Non supported CRX revision")]
     NonSupportedCrxVersion,
     #[error("First epoch not delimited by \"&\"")]
@@ -1,4 +1,4 @@
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TextDiff {
     pub buffer: String,
 }
@@ -56,37 +56,29 @@ impl TextDiff {
         &self.buffer
     }
 
-    /// Compresses given data
+    /// Compresses given data, like RNX2CRX does:
+    /// unchanged characters are replaced by a space,
+    /// characters turned into a space are marked by '&'.
+    /// Trailing whitespaces are dropped.
     pub fn compress(&mut self, data: &str) -> String {
-        let mut result = String::new();
-        let inner: Vec<_> = self.buffer.chars().collect();
-        self.buffer.clear();
-        let to_compress: Vec<_> = data.chars().collect();
-
-        for i in 0..inner.len() {
-            if let Some(c) = to_compress.get(i) {
-                self.buffer.push_str(&c.to_string());
-                if c != &inner[i] {
-                    result.push_str(&c.to_string());
-                } else {
-                    result.push(' ');
-                }
-            }
-        }
-
-        for i in inner.len()..data.len() {
-            if let Some(c) = to_compress.get(i) {
-                if c.is_ascii_whitespace() {
-                    self.buffer.push('&');
-                    result.push('&');
-                } else {
-                    self.buffer.push_str(&c.to_string());
-                    result.push_str(&c.to_string());
-                }
+        let old = self.buffer.as_bytes();
+        let new = data.as_bytes();
+        let len = std::cmp::max(old.len(), new.len());
+        let mut result = String::with_capacity(len);
+        for i in 0..len {
+            let o = old.get(i).copied().unwrap_or(b' ');
+            let n = new.get(i).copied().unwrap_or(b' ');
+            if n == o {
+                result.push(' ');
+            } else if n == b' ' {
+                result.push('&');
+            } else {
+                result.push(n as char);
             }
         }
-
-        result.to_string()
+        result.truncate(result.trim_end().len());
+        self.buffer = data.to_string();
+        result
     }
 }
 
@@ -151,12 +143,12 @@ mod test {
 
         diff.init("0");
         let compressed = diff.compress("0");
-        assert_eq!(compressed, " ");
+        assert_eq!(compressed, "");
         let compressed = diff.compress("4");
         assert_eq!(compressed, "4");
 
         let compressed = diff.compress("4  ");
-        assert_eq!(compressed, " &&");
+        assert_eq!(compressed, "");
 
         let compressed = diff.compress("0");
         assert_eq!(compressed, "0");
@@ -165,30 +157,55 @@ mod test {
         diff.init("Default Phrase 1234");
         let to_compress = "DEfault Phrase 1234";
         let result = diff.compress(to_compress);
-        assert_eq!(result, " E                 ");
+        assert_eq!(result, " E");
 
         let to_compress = "DEfault Phrase 1234";
         let result = diff.compress(to_compress);
-        assert_eq!(result, "                   ");
+        assert_eq!(result, "");
 
         let to_compress = "DEFault Phrase 1234";
         let result = diff.compress(to_compress);
-        assert_eq!(result, "  F                ");
+        assert_eq!(result, "  F");
 
         let to_compress = "DEFault Phrase 1234  ";
         let result = diff.compress(to_compress);
-        assert_eq!(result, "                   &&");
+        assert_eq!(result, "");
 
         let to_compress = " EFault Phrase 1234  ";
         let result = diff.compress(to_compress);
-        assert_eq!(result, "                     ");
+        assert_eq!(result, "&");
 
         let to_compress = "__ abcd Phrase 1222    ";
         let result = diff.compress(to_compress);
-        assert_eq!(result, "__  bcd          22  &&");
+        assert_eq!(result, "__& bcd          22");
 
         diff.init(" ");
         assert_eq!(diff.compress("3"), "3");
-        assert_eq!(diff.compress("3"), " ");
+        assert_eq!(diff.compress("3"), "");
+
+        // shortened content
+        diff.init("G01G02");
+        assert_eq!(diff.compress("G01G03"), "     3");
+        assert_eq!(diff.compress("G01"), "   &&&");
+
+        diff.init("");
+        assert_eq!(diff.compress(" 8 9"), " 8 9");
+        assert_eq!(diff.compress("  "), " & &");
+    }
+    #[test]
+    fn test_reciprocity() {
+        let mut compressor = TextDiff::new();
+        let mut decompressor = TextDiff::new();
+        compressor.init("DEFault Phrase 1234");
+        decompressor.init("DEFault Phrase 1234");
+        for content in [
+            " EFault Phrase 1234",
+            "__ abcd Phrase 1222",
+            "__ abcd Phrase 1200",
+            "x_ ab d Phrase 1200",
+        ] {
+            let compressed = compressor.compress(content);
+            assert_eq!(decompressor.decompress(&compressed), content);
+        }
     }
 }
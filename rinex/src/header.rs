@@ -3,7 +3,9 @@ use crate::{
     antex, clock,
     clock::ClockProfileType,
     clock::WorkClock,
+    doris,
     doris::{Error as DorisError, HeaderFields as DorisHeader, Station as DorisStation},
+    epoch,
     epoch::parse_ionex_utc as parse_ionex_utc_epoch,
     fmt_comment, fmt_rinex,
     ground_position::GroundPosition,
@@ -23,7 +25,6 @@ use crate::{
     observation,
     observation::{Crinex, HeaderFields as ObservationHeader},
     prelude::{Constellation, Duration, Epoch, TimeScale, COSPAR, DOMES, SV},
-    reader::BufferedReader,
     types::Type,
     version::Version,
 };
@@ -241,7 +242,7 @@ macro_rules! parse_float_error {
 
 impl Header {
     /// Builds a `Header` from stream reader
-    pub fn new(reader: &mut BufferedReader) -> Result<Header, ParsingError> {
+    pub fn new<R: BufRead>(reader: &mut R) -> Result<Header, ParsingError> {
         let mut rinex_type = Type::default();
         let mut constellation: Option<Constellation> = None;
         let mut version = Version::default();
@@ -755,7 +756,7 @@ impl Header {
                     .or(Err(parse_int_error!("RCV CLOCK OFFS APPL", value)))?;
 
                 observation.clock_offset_applied = n > 0;
-            } else if marker.contains("# OF SATELLITES") {
+            } else if marker.contains("# OF SATELLITES") && rinex_type != Type::IonosphereMaps {
                 // ---> we don't need this info,
                 //     user can determine it by analyzing the record
             } else if marker.contains("PRN / # OF OBS") {
@@ -1053,7 +1054,7 @@ impl Header {
                 if let Ok(f) = f32::from_str(content.trim()) {
                     ionex = ionex.with_base_radius(f);
                 }
-            } else if marker.contains("MAPPING FUCTION") {
+            } else if marker.contains("MAPPING FUNCTION") {
                 if let Ok(mf) = ionex::MappingFunction::from_str(content.trim()) {
                     ionex = ionex.with_mapping_function(mf);
                 }
@@ -1088,8 +1089,13 @@ impl Header {
                 let grid = Self::parse_grid(content)?;
                 ionex = ionex.with_longitude_grid(grid);
             } else if marker.contains("PRN / BIAS / RMS") {
-                // differential PR code analysis
-                //TODO
+                // IONEX differential PR code analysis
+                let (src, bias) = Self::parse_ionex_prn_bias(content, &ionex.reference)?;
+                ionex = ionex.with_dcb(src, bias);
+            } else if marker.contains("STATION / BIAS / RMS") {
+                // IONEX differential PR code analysis
+                let (src, bias) = Self::parse_ionex_station_bias(content, &ionex.reference)?;
+                ionex = ionex.with_dcb(src, bias);
             } else if marker.contains("L2 / L1 DATE OFFSET") {
                 // DORIS special case
                 let content = content[1..].trim();
@@ -1098,6 +1104,9 @@ impl Header {
                     .or(Err(parse_float_error!("doris l2/l1 date offset", content)))?;
 
                 doris.l2_l1_date_offset = Duration::from_microseconds(l2l1_date_offset);
+            } else if marker.contains("SATELLITE NAME") {
+                // DORIS special case
+                doris.satellite = content.trim().to_string();
             } else if marker.contains("STATION REFERENCE") {
                 // DORIS special case
                 let station = DorisStation::from_str(content.trim())?;
@@ -1422,9 +1431,44 @@ impl Header {
                     )
                 )
             },
-            Type::DORIS => todo!("doris formatting"),
-            Type::AntennaData => todo!("antex formatting"),
-            Type::IonosphereMaps => todo!("ionex formatting"),
+            Type::DORIS => {
+                writeln!(
+                    f,
+                    "{}",
+                    fmt_rinex(
+                        &format!("{:6}.{:02}           O                   D", major, minor),
+                        "RINEX VERSION / TYPE"
+                    )
+                )
+            },
+            Type::AntennaData => {
+                let constellation = self.constellation.unwrap_or(Constellation::Mixed);
+                writeln!(
+                    f,
+                    "{}",
+                    fmt_rinex(
+                        &format!("{:6}.{:01}            {:x}", major, minor, constellation),
+                        "ANTEX VERSION / SYST"
+                    )
+                )
+            },
+            Type::IonosphereMaps => {
+                let system = match &self.ionex {
+                    Some(ionex) => ionex.reference.to_string(),
+                    None => String::from("GNSS"),
+                };
+                writeln!(
+                    f,
+                    "{}",
+                    fmt_rinex(
+                        &format!(
+                            "{:6}.{:01}            IONOSPHERE MAPS     {:<20}",
+                            major, minor, system
+                        ),
+                        "IONEX VERSION / TYPE"
+                    )
+                )
+            },
         }
     }
     /*
@@ -1437,8 +1481,8 @@ impl Header {
             Type::NavigationData => Ok(()),
             Type::ClockData => self.fmt_clock_rinex(f),
             Type::IonosphereMaps => self.fmt_ionex(f),
-            Type::AntennaData => Ok(()), // FIXME
-            Type::DORIS => Ok(()),       // FIXME
+            Type::AntennaData => self.fmt_antex(f),
+            Type::DORIS => self.fmt_doris(f),
         }
     }
     /*
@@ -1479,78 +1523,302 @@ impl Header {
         }
         Ok(())
     }
+    /*
+     * DORIS fields formatting
+     */
+    fn fmt_doris(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if let Some(doris) = &self.doris {
+            writeln!(f, "{}", fmt_rinex(&doris.satellite, "SATELLITE NAME"))?;
+            if let Some(cospar) = &self.cospar {
+                writeln!(f, "{}", fmt_rinex(&cospar.to_string(), "COSPAR NUMBER"))?;
+            }
+            // list of observables
+            let mut descriptor = format!("D{:5}", doris.observables.len());
+            for (i, observable) in doris.observables.iter().enumerate() {
+                if (i % 13) == 0 && (i > 0) {
+                    descriptor.push_str("      "); // TAB
+                }
+                descriptor.push_str(&format!("{:>4}", doris::record::fmt_observable(observable)));
+            }
+            writeln!(f, "{}", fmt_rinex(&descriptor, "SYS / # / OBS TYPES"))?;
+            // time of first, last obs (TAI)
+            for (epoch, marker) in [
+                (doris.time_of_first_obs, "TIME OF FIRST OBS"),
+                (doris.time_of_last_obs, "TIME OF LAST OBS"),
+            ] {
+                if let Some(epoch) = epoch {
+                    let (y, m, d, hh, mm, ss, nanos) = epoch::epoch_decompose(epoch);
+                    writeln!(
+                        f,
+                        "{}",
+                        fmt_rinex(
+                            &format!(
+                                "  {:04}    {:02}    {:02}    {:02}    {:02}   {:02}.{:07}     DOR",
+                                y,
+                                m,
+                                d,
+                                hh,
+                                mm,
+                                ss,
+                                nanos / 100
+                            ),
+                            marker
+                        )
+                    )?;
+                }
+            }
+            // data scaling, grouped by factor
+            let mut factors = doris.scaling.values().copied().collect::<Vec<_>>();
+            factors.sort();
+            factors.dedup();
+            for factor in factors {
+                let observables = doris
+                    .observables
+                    .iter()
+                    .filter(|obs| doris.scaling.get(obs) == Some(&factor))
+                    .collect::<Vec<_>>();
+                let mut descriptor = format!("D {:4}  {:2}", factor, observables.len());
+                for observable in observables {
+                    descriptor.push_str(&format!(
+                        " {:>3}",
+                        doris::record::fmt_observable(observable)
+                    ));
+                }
+                writeln!(f, "{}", fmt_rinex(&descriptor, "SYS / SCALE FACTOR"))?;
+            }
+            writeln!(
+                f,
+                "{}",
+                fmt_rinex(
+                    &format!(
+                        "D{:16.3}",
+                        doris.l2_l1_date_offset.to_unit(Unit::Microsecond)
+                    ),
+                    "L2 / L1 DATE OFFSET"
+                )
+            )?;
+            writeln!(
+                f,
+                "{}",
+                fmt_rinex(&format!("{:6}", doris.stations.len()), "# OF STATIONS")
+            )?;
+            let mut stations = doris.stations.iter().collect::<Vec<_>>();
+            stations.sort_by_key(|station| station.key);
+            for station in stations {
+                writeln!(
+                    f,
+                    "{}",
+                    fmt_rinex(&station.to_string(), "STATION REFERENCE")
+                )?;
+            }
+        }
+        Ok(())
+    }
+    /*
+     * ANTEX fields formatting
+     */
+    fn fmt_antex(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if let Some(antex) = &self.antex {
+            let (pcv, rel_type) = match &antex.pcv_type {
+                antex::Pcv::Absolute => ("A", ""),
+                antex::Pcv::Relative(rel_type) => ("R", rel_type.as_str()),
+            };
+            writeln!(
+                f,
+                "{}",
+                fmt_rinex(
+                    &format!(
+                        "{:<20}{:<20}{}",
+                        pcv,
+                        rel_type,
+                        antex.reference_ant_sn.as_deref().unwrap_or("")
+                    ),
+                    "PCV TYPE / REFANT"
+                )
+            )?;
+        }
+        Ok(())
+    }
     /*
      * IONEX fields formatting
      */
     fn fmt_ionex(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         if let Some(ionex) = &self.ionex {
+            // description, wrapped on several lines
+            if let Some(desc) = &ionex.description {
+                let mut rem = desc.as_str();
+                while rem.len() > 60 {
+                    let offset = rem[..60].rfind(' ').unwrap_or(60);
+                    writeln!(f, "{}", fmt_rinex(&rem[..offset], "DESCRIPTION"))?;
+                    rem = rem[offset..].trim_start();
+                }
+                writeln!(f, "{}", fmt_rinex(rem, "DESCRIPTION"))?;
+            }
+            // time of first map
             writeln!(
                 f,
                 "{}",
-                fmt_rinex(&format!("{:6}", ionex.map_dimension), "MAP DIMENSION")
+                fmt_rinex(
+                    &epoch::format(
+                        ionex.epoch_of_first_map,
+                        Type::IonosphereMaps,
+                        self.version.major
+                    ),
+                    "EPOCH OF FIRST MAP"
+                )
             )?;
-            // h grid
-            let (start, end, spacing) = (
-                ionex.grid.height.start,
-                ionex.grid.height.end,
-                ionex.grid.height.spacing,
-            );
+            // time of last map
             writeln!(
                 f,
                 "{}",
                 fmt_rinex(
-                    &format!("{} {} {}", start, end, spacing),
-                    "HGT1 / HGT2 / DHGT"
+                    &epoch::format(
+                        ionex.epoch_of_last_map,
+                        Type::IonosphereMaps,
+                        self.version.major
+                    ),
+                    "EPOCH OF LAST MAP"
                 )
             )?;
-            // lat grid
-            let (start, end, spacing) = (
-                ionex.grid.latitude.start,
-                ionex.grid.latitude.end,
-                ionex.grid.latitude.spacing,
-            );
+            // interval: '0' in case of varying intervals
+            let interval = self
+                .sampling_interval
+                .map(|dt| dt.to_seconds().round() as u32)
+                .unwrap_or(0);
+            writeln!(f, "{}", fmt_rinex(&format!("{:6}", interval), "INTERVAL"))?;
+            if interval > 0 {
+                let span = (ionex.epoch_of_last_map - ionex.epoch_of_first_map).to_seconds();
+                let nb_maps = (span / interval as f64).round() as u32 + 1;
+                writeln!(
+                    f,
+                    "{}",
+                    fmt_rinex(&format!("{:6}", nb_maps), "# OF MAPS IN FILE")
+                )?;
+            }
+            // mapping func
+            let mapping = match ionex.mapping {
+                Some(ionex::MappingFunction::CosZ) => "COSZ",
+                Some(ionex::MappingFunction::QFac) => "QFAC",
+                None => "NONE",
+            };
+            writeln!(
+                f,
+                "{}",
+                fmt_rinex(&format!("  {}", mapping), "MAPPING FUNCTION")
+            )?;
+            // elevation cutoff
             writeln!(
                 f,
                 "{}",
                 fmt_rinex(
-                    &format!("{} {} {}", start, end, spacing),
-                    "LAT1 / LAT2 / DLAT"
+                    &format!("{:8.1}", ionex.elevation_cutoff),
+                    "ELEVATION CUTOFF"
                 )
             )?;
-            // lon grid
-            let (start, end, spacing) = (
-                ionex.grid.longitude.start,
-                ionex.grid.longitude.end,
-                ionex.grid.longitude.spacing,
-            );
             writeln!(
                 f,
                 "{}",
                 fmt_rinex(
-                    &format!("{} {} {}", start, end, spacing),
-                    "LON1 / LON2 / DLON"
+                    ionex.observables.as_deref().unwrap_or(""),
+                    "OBSERVABLES USED"
                 )
             )?;
-            // elevation cutoff
+            if ionex.nb_stations > 0 {
+                writeln!(
+                    f,
+                    "{}",
+                    fmt_rinex(&format!("{:6}", ionex.nb_stations), "# OF STATIONS")
+                )?;
+            }
+            if ionex.nb_satellites > 0 {
+                writeln!(
+                    f,
+                    "{}",
+                    fmt_rinex(&format!("{:6}", ionex.nb_satellites), "# OF SATELLITES")
+                )?;
+            }
             writeln!(
                 f,
                 "{}",
-                fmt_rinex(&format!("{}", ionex.elevation_cutoff), "ELEVATION CUTOFF")
+                fmt_rinex(&format!("{:8.1}", ionex.base_radius), "BASE RADIUS")
             )?;
-            // mapping func
-            if let Some(func) = &ionex.mapping {
+            writeln!(
+                f,
+                "{}",
+                fmt_rinex(&format!("{:6}", ionex.map_dimension), "MAP DIMENSION")
+            )?;
+            // grid definitions
+            for (grid, marker) in [
+                (&ionex.grid.height, "HGT1 / HGT2 / DHGT"),
+                (&ionex.grid.latitude, "LAT1 / LAT2 / DLAT"),
+                (&ionex.grid.longitude, "LON1 / LON2 / DLON"),
+            ] {
                 writeln!(
                     f,
                     "{}",
-                    fmt_rinex(&format!("{:?}", func), "MAPPING FUNCTION")
+                    fmt_rinex(
+                        &format!("  {:6.1}{:6.1}{:6.1}", grid.start, grid.end, grid.spacing),
+                        marker
+                    )
+                )?;
+            }
+            // scaling
+            writeln!(
+                f,
+                "{}",
+                fmt_rinex(&format!("{:6}", ionex.exponent), "EXPONENT")
+            )?;
+            // differential code biases
+            if !ionex.dcbs.is_empty() {
+                writeln!(
+                    f,
+                    "{}",
+                    fmt_rinex("DIFFERENTIAL CODE BIASES", "START OF AUX DATA")
+                )?;
+                let mut sv_dcbs = Vec::<(SV, (f64, f64))>::new();
+                let mut station_dcbs = Vec::<((Constellation, &String), (f64, f64))>::new();
+                for (src, dcb) in &ionex.dcbs {
+                    match src {
+                        ionex::BiasSource::SpaceVehicle(sv) => sv_dcbs.push((*sv, *dcb)),
+                        ionex::BiasSource::Station(constellation, name) => {
+                            station_dcbs.push(((*constellation, name), *dcb))
+                        },
+                    }
+                }
+                sv_dcbs.sort_by(|(a, _), (b, _)| a.cmp(b));
+                station_dcbs.sort_by(|(a, _), (b, _)| a.cmp(b));
+                for (sv, (bias, rms)) in sv_dcbs {
+                    writeln!(
+                        f,
+                        "{}",
+                        fmt_rinex(
+                            &format!(
+                                "   {:x}{:02}{:10.3}{:10.3}",
+                                sv.constellation, sv.prn, bias, rms
+                            ),
+                            "PRN / BIAS / RMS"
+                        )
+                    )?;
+                }
+                for ((constellation, name), (bias, rms)) in station_dcbs {
+                    writeln!(
+                        f,
+                        "{}",
+                        fmt_rinex(
+                            &format!(
+                                "   {:x} {:<20}{:10.3}{:10.3}",
+                                constellation, name, bias, rms
+                            ),
+                            "STATION / BIAS / RMS"
+                        )
+                    )?;
+                }
+                writeln!(
+                    f,
+                    "{}",
+                    fmt_rinex("DIFFERENTIAL CODE BIASES", "END OF AUX DATA")
                 )?;
-            } else {
-                writeln!(f, "{}", fmt_rinex("NONE", "MAPPING FUNCTION"))?;
             }
-            // time of first map
-            writeln!(f, "{}", fmt_rinex("TODO", "EPOCH OF FIRST MAP"))?;
-            // time of last map
-            writeln!(f, "{}", fmt_rinex("TODO", "EPOCH OF LAST MAP"))?;
         }
         Ok(())
     }
@@ -1701,6 +1969,89 @@ impl Header {
             Ok(grid)
         }
     }
+    /*
+     * Identifies the constellation of an IONEX DCB estimate,
+     * a blank system refers to the reference constellation
+     */
+    fn ionex_bias_constellation(
+        system: &str,
+        reference: &ionex::RefSystem,
+    ) -> Result<Constellation, ParsingError> {
+        let system = system.trim();
+        if system.is_empty() {
+            match reference {
+                ionex::RefSystem::GnssConstellation(Constellation::Mixed) => Ok(Constellation::GPS),
+                ionex::RefSystem::GnssConstellation(c) => Ok(*c),
+                _ => Ok(Constellation::GPS),
+            }
+        } else {
+            Ok(Constellation::from_str(system)?)
+        }
+    }
+    /*
+     * Parse IONEX DCB estimate for one satellite
+     */
+    fn parse_ionex_prn_bias(
+        content: &str,
+        reference: &ionex::RefSystem,
+    ) -> Result<(ionex::BiasSource, (f64, f64)), ParsingError> {
+        let (system, rem) = content.split_at(4);
+        let (prn, rem) = rem.split_at(2);
+        let (bias, rem) = rem.split_at(10);
+        let (rms, _) = rem.split_at(10);
+
+        let constellation = Self::ionex_bias_constellation(system, reference)?;
+
+        let prn = prn.trim();
+        let prn = prn
+            .parse::<u8>()
+            .or(Err(parse_int_error!("PRN / BIAS / RMS", prn)))?;
+
+        let bias = bias.trim();
+        let bias = bias
+            .parse::<f64>()
+            .or(Err(parse_float_error!("PRN / BIAS / RMS", bias)))?;
+
+        let rms = rms.trim();
+        let rms = rms
+            .parse::<f64>()
+            .or(Err(parse_float_error!("PRN / BIAS / RMS", rms)))?;
+
+        Ok((
+            ionex::BiasSource::SpaceVehicle(SV::new(constellation, prn)),
+            (bias, rms),
+        ))
+    }
+    /*
+     * Parse IONEX DCB estimate for one station
+     */
+    fn parse_ionex_station_bias(
+        content: &str,
+        reference: &ionex::RefSystem,
+    ) -> Result<(ionex::BiasSource, (f64, f64)), ParsingError> {
+        let (system, rem) = content.split_at(5);
+        let (name, rem) = rem.split_at(20);
+        let (bias, rem) = rem.split_at(10);
+        let (rms, _) = rem.split_at(10);
+
+        let bias = bias.trim();
+        let bias = bias
+            .parse::<f64>()
+            .or(Err(parse_float_error!("STATION / BIAS / RMS", bias)))?;
+
+        let rms = rms.trim();
+        let rms = rms
+            .parse::<f64>()
+            .or(Err(parse_float_error!("STATION / BIAS / RMS", rms)))?;
+
+        Ok((
+            ionex::BiasSource::Station(
+                Self::ionex_bias_constellation(system, reference)?,
+                name.trim().to_string(),
+            ),
+            (bias, rms),
+        ))
+    }
     /*
      * Parse CRINEX special header
      */
@@ -1890,6 +2241,14 @@ impl std::fmt::Display for Header {
         }
 
         self.fmt_rinex_version_type(f)?;
+
+        // ANTEX: only specific fields follow
+        if self.rinex_type == Type::AntennaData {
+            self.fmt_antex(f)?;
+            self.fmt_comments(f)?;
+            return writeln!(f, "{}", fmt_rinex("", "END OF HEADER"));
+        }
+
         self.fmt_comments(f)?;
 
         // PGM / RUN BY / DATE
@@ -1902,6 +2261,12 @@ impl std::fmt::Display for Header {
             )
         )?;
 
+        // IONEX: only specific fields follow
+        if self.rinex_type == Type::IonosphereMaps {
+            self.fmt_ionex(f)?;
+            return writeln!(f, "{}", fmt_rinex("", "END OF HEADER"));
+        }
+
         // OBSERVER / AGENCY
         writeln!(
             f,
@@ -2265,7 +2630,31 @@ pub(crate) fn header_mask_mut(hd: &mut Header, f: &MaskFilter) {
 
 #[cfg(test)]
 mod test {
-    use super::parse_formatted_month;
+    use super::{parse_formatted_month, Header};
+    use crate::{ionex, prelude::Constellation};
+    #[test]
+    fn ionex_station_bias_parser() {
+        let reference = ionex::RefSystem::GnssConstellation(Constellation::Mixed);
+        for (content, constellation) in [
+            (
+                "   E ALIC                    -1.234     0.012",
+                Constellation::Galileo,
+            ),
+            (
+                "     ALIC                    -1.234     0.012",
+                Constellation::GPS,
+            ),
+        ] {
+            let (src, (bias, rms)) = Header::parse_ionex_station_bias(content, &reference)
+                .unwrap_or_else(|e| panic!("failed to parse \"{}\": {}", content, e));
+            assert_eq!(
+                src,
+                ionex::BiasSource::Station(constellation, "ALIC".to_string())
+            );
+            assert_eq!(bias, -1.234);
+            assert_eq!(rms, 0.012);
+        }
+    }
     #[test]
     fn formatted_month_parser() {
         for (desc, expected) in [("Jan", 1), ("Feb", 2), ("Mar", 3), ("Nov", 11), ("Dec", 12)] {
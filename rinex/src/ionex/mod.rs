@@ -2,7 +2,7 @@
 use std::collections::HashMap;
 use strum_macros::EnumString;
 
-use crate::prelude::{Epoch, SV};
+use crate::prelude::{Constellation, Epoch, SV};
 
 #[cfg(feature = "processing")]
 use crate::prelude::TimeScale;
@@ -50,8 +50,9 @@ impl std::fmt::Display for MappingFunction {
 pub enum BiasSource {
     /// Referenced against a given vehicle
     SpaceVehicle(SV),
-    /// Referenced for an observation station on Earth
-    Station(String),
+    /// Referenced for an observation station on Earth,
+    /// for given [Constellation]
+    Station(Constellation, String),
 }
 
 /// `IONEX` specific header fields
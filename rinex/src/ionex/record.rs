@@ -1,6 +1,6 @@
 use crate::{merge, merge::Merge, prelude::Duration, prelude::*, split, split::Split};
 
-use crate::epoch;
+use crate::{epoch, fmt_rinex, types::Type};
 use std::collections::{BTreeMap, HashMap};
 use std::str::FromStr;
 use thiserror::Error;
@@ -18,12 +18,30 @@ pub(crate) fn is_new_rms_plane(line: &str) -> bool {
     line.contains("START OF RMS MAP")
 }
 
-/*
- * Don't know what Height maps are actually
- */
-// pub(crate) fn is_new_height_map(line: &str) -> bool {
-//     line.contains("START OF HEIGHT MAP")
-// }
+pub(crate) fn is_new_height_map(line: &str) -> bool {
+    line.contains("START OF HEIGHT MAP")
+}
+
+/// Type of map, as described in IONEX files
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum MapType {
+    /// Total Electron Content map
+    TEC,
+    /// Root Mean Square map, expressed in TECu
+    RMS,
+    /// Height map, expressed in km
+    Height,
+}
+
+impl std::fmt::Display for MapType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::TEC => write!(f, "TEC"),
+            Self::RMS => write!(f, "RMS"),
+            Self::Height => write!(f, "HEIGHT"),
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -32,6 +50,37 @@ pub struct TEC {
     pub tec: f64,
     /// RMS(tec)
     pub rms: Option<f64>,
+    /// Possible height (in km) at this grid point,
+    /// as described by IONEX height maps
+    pub height: Option<f64>,
+    /// True when [Self::tec] was actually described by a TEC map,
+    /// and not only by RMS or Height maps
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) has_tec: bool,
+}
+
+impl TEC {
+    /// Builds a new [TEC] estimate, described by a TEC map
+    pub fn new(tec: f64) -> Self {
+        Self {
+            tec,
+            rms: None,
+            height: None,
+            has_tec: true,
+        }
+    }
+    /// Copies and returns [TEC] with given RMS(tec)
+    pub fn with_rms(&self, rms: f64) -> Self {
+        let mut s = self.clone();
+        s.rms = Some(rms);
+        s
+    }
+    /// Copies and returns [TEC] with given height (in km)
+    pub fn with_height(&self, height: f64) -> Self {
+        let mut s = self.clone();
+        s.height = Some(height);
+        s
+    }
 }
 
 pub type TECPlane = HashMap<(i32, i32), TEC>;
@@ -74,17 +123,49 @@ pub enum Error {
     CoordinatesParsing(String, String),
     #[error("failed to parse epoch")]
     EpochParsing(#[from] epoch::ParsingError),
+    #[error("unknown map type")]
+    UnknownMapType,
 }
 
 /*
- * Merges `rhs` into `lhs`
+ * Converts a latitude or longitude coordinate (ddeg) to record key
  */
-fn merge_plane_mut(lhs: &mut TECPlane, rhs: &TECPlane) {
-    for (coord, tec) in rhs {
-        if lhs.get(coord).is_none() {
-            lhs.insert(*coord, tec.clone());
-        }
-    }
+pub(crate) fn coordinate_key(ddeg: f64) -> i32 {
+    (ddeg.round() * 1000.0_f64) as i32
+}
+
+/*
+ * Converts an altitude (km) to record key
+ */
+pub(crate) fn altitude_key(km: f64) -> i32 {
+    (km.round() * 100.0_f64) as i32
+}
+
+/*
+ * Stores one parsed value into given plane
+ */
+fn insert_value(plane: &mut TECPlane, map_type: MapType, coords: (i32, i32), value: f64) {
+    let tec = match map_type {
+        MapType::TEC => TEC {
+            tec: value,
+            rms: None,
+            height: None,
+            has_tec: true,
+        },
+        MapType::RMS => TEC {
+            tec: 0.0_f64, // DONT CARE
+            rms: Some(value),
+            height: None,
+            has_tec: false,
+        },
+        MapType::Height => TEC {
+            tec: 0.0_f64, // DONT CARE
+            rms: None,
+            height: Some(value),
+            has_tec: false,
+        },
+    };
+    plane.insert(coords, tec);
 }
 
 /*
@@ -92,16 +173,17 @@ fn merge_plane_mut(lhs: &mut TECPlane, rhs: &TECPlane) {
  *  - a TEC map
  *  - an RMS tec map
  *  - an height map
- * Returns: Epoth(t), nth Map index, latitude, altitude and TEC plane accross longitudes
+ * Returns: Epoch(t), type of map, and one TEC plane per altitude
+ * (a single one in case of 2D maps).
  */
-pub(crate) fn parse_plane(
+pub(crate) fn parse_map(
     content: &str,
     header: &mut Header,
-    is_rms_plane: bool,
-) -> Result<(Epoch, i32, TECPlane), Error> {
+) -> Result<(Epoch, MapType, BTreeMap<i32, TECPlane>), Error> {
     let lines = content.lines();
     let mut epoch = Epoch::default();
-    let mut plane = TECPlane::with_capacity(128);
+    let mut map_type = Option::<MapType>::None;
+    let mut planes = BTreeMap::<i32, TECPlane>::new();
 
     // this can't fail at this point
     let ionex = header
@@ -119,7 +201,14 @@ pub(crate) fn parse_plane(
         if line.len() > 60 {
             let (content, marker) = line.split_at(60);
             if marker.contains("START OF") {
-                continue; // skip that one
+                if is_new_tec_plane(line) {
+                    map_type = Some(MapType::TEC);
+                } else if is_new_rms_plane(line) {
+                    map_type = Some(MapType::RMS);
+                } else if is_new_height_map(line) {
+                    map_type = Some(MapType::Height);
+                }
+                continue;
             } else if marker.contains("END OF") && marker.contains("MAP") {
                 let index = content.split_at(6).0;
                 let index = index.trim();
@@ -127,7 +216,8 @@ pub(crate) fn parse_plane(
                     .parse::<u32>()
                     .or(Err(Error::MapIndexParsing(index.to_string())))?;
 
-                return Ok((epoch, altitude, plane));
+                let map_type = map_type.ok_or(Error::UnknownMapType)?;
+                return Ok((epoch, map_type, planes));
             } else if marker.contains("LAT/LON1/LON2/DLON/H") {
                 // grid definition for next block
                 let (_, rem) = content.split_at(2);
@@ -147,11 +237,6 @@ pub(crate) fn parse_plane(
                 )))?;
 
                 let (_lon2, rem) = rem.split_at(6);
-                //let lon2 = lon2.trim();
-                //let lon2 = f64::from_str(lon2).or(Err(Error::CoordinatesParsing(
-                //    String::from("longitude"),
-                //    lon2.to_string(),
-                //)))?;
 
                 let (dlon_str, rem) = rem.split_at(6);
                 let dlon_str = dlon_str.trim();
@@ -167,13 +252,10 @@ pub(crate) fn parse_plane(
                     h.to_string(),
                 )))?;
 
-                altitude = (alt.round() * 100.0_f64) as i32;
-                latitude = (lat.round() * 1000.0_f64) as i32;
-                longitude = (lon1.round() * 1000.0_f64) as i32;
-                dlon = (dlon_f64.round() * 1000.0_f64) as i32;
-
-                // debug
-                // println!("NEW GRID : h: {} lat : {} lon : {}, dlon: {}", altitude, latitude, longitude, dlon);
+                altitude = altitude_key(alt);
+                latitude = coordinate_key(lat);
+                longitude = coordinate_key(lon1);
+                dlon = coordinate_key(dlon_f64);
             } else if marker.contains("EPOCH OF CURRENT MAP") {
                 epoch = epoch::parse_utc(content)?;
             } else if marker.contains("EXPONENT") {
@@ -183,65 +265,199 @@ pub(crate) fn parse_plane(
                 }
             } else {
                 // parsing TEC values
+                let map_type = map_type.ok_or(Error::UnknownMapType)?;
+                let plane = planes.entry(altitude).or_default();
                 for item in line.split_ascii_whitespace() {
                     if let Ok(v) = item.trim().parse::<i32>() {
-                        let mut value = v as f64;
                         // current scaling
-                        value *= 10.0_f64.powf(ionex.exponent as f64);
-
-                        let tec = match is_rms_plane {
-                            true => {
-                                TEC {
-                                    tec: 0.0_f64, // DONT CARE
-                                    rms: Some(value),
-                                }
-                            },
-                            false => TEC {
-                                tec: value,
-                                rms: None,
-                            },
-                        };
-
-                        plane.insert((latitude, longitude), tec);
+                        let value = v as f64 * 10.0_f64.powf(ionex.exponent as f64);
+                        insert_value(plane, map_type, (latitude, longitude), value);
                     }
-
                     longitude += dlon;
-                    //debug
-                    //println!("longitude: {}", longitude);
                 }
             }
         } else {
             // less than 60 characters
             // parsing TEC values
+            let map_type = map_type.ok_or(Error::UnknownMapType)?;
+            let plane = planes.entry(altitude).or_default();
             for item in line.split_ascii_whitespace() {
                 if let Ok(v) = item.trim().parse::<i32>() {
-                    let mut value = v as f64;
                     // current scaling
-                    value *= 10.0_f64.powf(ionex.exponent as f64);
+                    let value = v as f64 * 10.0_f64.powf(ionex.exponent as f64);
+                    insert_value(plane, map_type, (latitude, longitude), value);
+                }
+                longitude += dlon;
+            }
+        }
+    }
+    let map_type = map_type.ok_or(Error::UnknownMapType)?;
+    Ok((epoch, map_type, planes))
+}
 
-                    let tec = match is_rms_plane {
-                        true => {
-                            TEC {
-                                tec: 0.0_f64, // DONT CARE
-                                rms: Some(value),
-                            }
-                        },
-                        false => TEC {
-                            tec: value,
-                            rms: None,
-                        },
-                    };
-
-                    plane.insert((latitude, longitude), tec);
+/*
+ * Stores a parsed plane into the record.
+ * Several maps (TEC, RMS, Height) describe the same (epoch, altitude) plane,
+ * we only update the fields described by this map type.
+ */
+pub(crate) fn insert_plane_mut(
+    rec: &mut Record,
+    key: (Epoch, i32),
+    map_type: MapType,
+    plane: TECPlane,
+) {
+    if let Some(rec_plane) = rec.get_mut(&key) {
+        for (coords, tec) in plane {
+            if let Some(rec_tec) = rec_plane.get_mut(&coords) {
+                match map_type {
+                    MapType::TEC => {
+                        rec_tec.tec = tec.tec;
+                        rec_tec.has_tec = true;
+                    },
+                    MapType::RMS => rec_tec.rms = tec.rms,
+                    MapType::Height => rec_tec.height = tec.height,
                 }
+            } else {
+                rec_plane.insert(coords, tec);
+            }
+        }
+    } else {
+        rec.insert(key, plane);
+    }
+}
 
-                longitude += dlon;
-                //debug
-                //println!("longitude: {}", longitude);
+/*
+ * Returns the list of coordinates described by this linear space
+ */
+fn grid_points(start: f64, end: f64, spacing: f64) -> Vec<f64> {
+    if spacing == 0.0 {
+        return vec![start];
+    }
+    let n = ((end - start) / spacing).round() as i64;
+    (0..=n.max(0)).map(|i| start + i as f64 * spacing).collect()
+}
+
+/*
+ * Selects the scaling to be used when formatting given values,
+ * so values are both accurate and fit in the I5 format.
+ * Returns the preferred exponent by default.
+ */
+fn map_exponent(values: &[f64], preferred: i8) -> i8 {
+    let fits = |e: i8| {
+        values.iter().all(|v| {
+            let scaled = (v / 10.0_f64.powi(e as i32)).round();
+            // 9999 is the missing data marker
+            (-9999.0..=99999.0).contains(&scaled) && scaled != 9999.0
+        })
+    };
+    let accurate = |e: i8| {
+        values.iter().all(|v| {
+            let scaled = v / 10.0_f64.powi(e as i32);
+            (scaled - scaled.round()).abs() < 1.0E-3
+        })
+    };
+    let mut exponent = preferred;
+    while !accurate(exponent) && exponent > -5 && fits(exponent - 1) {
+        exponent -= 1;
+    }
+    while !fits(exponent) && exponent < 5 {
+        exponent += 1;
+    }
+    exponent
+}
+
+/*
+ * Formats one map (TEC, RMS or Height) for all altitudes
+ * described at this epoch. `exponent` is the scaling currently in use
+ * in the file, which gets updated if this map requires a new scaling.
+ */
+pub(crate) fn fmt_map(
+    index: usize,
+    epoch: &Epoch,
+    map_type: MapType,
+    planes: &[(i32, &TECPlane)],
+    header: &Header,
+    exponent: &mut i8,
+) -> String {
+    let mut lines = String::with_capacity(8192);
+    let ionex = header
+        .ionex
+        .as_ref()
+        .expect("faulty ionex context: missing specific header definitions");
+
+    let (lat_grid, lon_grid) = (&ionex.grid.latitude, &ionex.grid.longitude);
+    let latitudes = grid_points(lat_grid.start, lat_grid.end, lat_grid.spacing);
+    let longitudes = grid_points(lon_grid.start, lon_grid.end, lon_grid.spacing);
+
+    let value = |tec: &TEC| match map_type {
+        MapType::TEC => Some(tec.tec).filter(|_| tec.has_tec),
+        MapType::RMS => tec.rms,
+        MapType::Height => tec.height,
+    };
+
+    // determine scaling to be used for this map
+    let values = planes
+        .iter()
+        .flat_map(|(_, plane)| plane.values().filter_map(value))
+        .collect::<Vec<_>>();
+    let map_exponent = map_exponent(&values, *exponent);
+
+    lines.push_str(&fmt_rinex(
+        &format!("{:6}", index),
+        &format!("START OF {} MAP", map_type),
+    ));
+    lines.push('\n');
+    lines.push_str(&fmt_rinex(
+        &epoch::format(*epoch, Type::IonosphereMaps, header.version.major),
+        "EPOCH OF CURRENT MAP",
+    ));
+    lines.push('\n');
+
+    if map_exponent != *exponent {
+        lines.push_str(&fmt_rinex(&format!("{:6}", map_exponent), "EXPONENT"));
+        lines.push('\n');
+        *exponent = map_exponent;
+    }
+
+    let scaling = 10.0_f64.powi(map_exponent as i32);
+
+    for (altitude, plane) in planes {
+        let altitude = *altitude as f64 / 100.0;
+        for lat in &latitudes {
+            lines.push_str(&fmt_rinex(
+                &format!(
+                    "  {:6.1}{:6.1}{:6.1}{:6.1}{:6.1}",
+                    lat, lon_grid.start, lon_grid.end, lon_grid.spacing, altitude
+                ),
+                "LAT/LON1/LON2/DLON/H",
+            ));
+            lines.push('\n');
+            // same keys as the parser, that browses longitudes by steps
+            let lat = coordinate_key(*lat);
+            let (lon1, dlon) = (
+                coordinate_key(lon_grid.start),
+                coordinate_key(lon_grid.spacing),
+            );
+            for i in 0..longitudes.len() {
+                let lon = lon1 + i as i32 * dlon;
+                let scaled = match plane.get(&(lat, lon)).and_then(value) {
+                    Some(value) => (value / scaling).round() as i64,
+                    None => 9999, // missing value
+                };
+                lines.push_str(&format!("{:5}", scaled));
+                if (i % 16) == 15 || i == longitudes.len() - 1 {
+                    lines.push('\n');
+                }
             }
         }
     }
-    Ok((epoch, altitude, plane))
+
+    lines.push_str(&fmt_rinex(
+        &format!("{:6}", index),
+        &format!("END OF {} MAP", map_type),
+    ));
+    lines.push('\n');
+    lines
 }
 
 impl Merge for Record {
@@ -257,11 +473,20 @@ impl Merge for Record {
             if let Some(lhs_plane) = self.get_mut(eh) {
                 for (latlon, plane) in plane {
                     if let Some(tec) = lhs_plane.get_mut(latlon) {
+                        if plane.has_tec && !tec.has_tec {
+                            tec.tec = plane.tec;
+                            tec.has_tec = true;
+                        }
                         if let Some(rms) = plane.rms {
                             if tec.rms.is_none() {
                                 tec.rms = Some(rms);
                             }
                         }
+                        if let Some(height) = plane.height {
+                            if tec.height.is_none() {
+                                tec.height = Some(height);
+                            }
+                        }
                     } else {
                         lhs_plane.insert(*latlon, plane.clone());
                     }
@@ -381,12 +606,16 @@ mod test {
         assert!(is_new_rms_plane(
             "1                                                      START OF RMS MAP"
         ));
-        // assert_eq!(
-        //     is_new_height_map(
-        //         "1                                                      START OF HEIGHT MAP"
-        //     ),
-        //     true
-        // );
+        assert!(is_new_height_map(
+            "1                                                      START OF HEIGHT MAP"
+        ));
+    }
+    #[test]
+    fn test_map_exponent() {
+        assert_eq!(map_exponent(&[12.3, 45.6], -1), -1);
+        // 999.9 would be formatted as the missing data marker
+        assert_eq!(map_exponent(&[999.9], -1), 0);
+        assert_eq!(map_exponent(&[-999.9], -1), -1);
     }
     //#[test]
     //fn test_merge_map2d() {
@@ -54,7 +54,12 @@ pub enum ObsSystem {
 
 impl std::fmt::Display for ObsSystem {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        f.write_str(&self.to_string())
+        match self {
+            Self::BENt => f.write_str("BEN"),
+            Self::ENVisat => f.write_str("ENV"),
+            Self::ERS => f.write_str("ERS"),
+            Self::IRI => f.write_str("IRI"),
+        }
     }
 }
 
@@ -75,7 +80,11 @@ pub enum Model {
 
 impl std::fmt::Display for Model {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        f.write_str(&self.to_string())
+        match self {
+            Self::MIX => f.write_str("MIX"),
+            Self::NNS => f.write_str("NNS"),
+            Self::TOP => f.write_str("TOP"),
+        }
     }
 }
 
@@ -88,7 +97,8 @@ impl Default for RefSystem {
 impl std::fmt::Display for RefSystem {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
-            Self::GnssConstellation(c) => c.fmt(f),
+            Self::GnssConstellation(Constellation::Mixed) => f.write_str("GNSS"),
+            Self::GnssConstellation(c) => write!(f, "{:X}", c),
             Self::ObservationSystem(s) => s.fmt(f),
             Self::Model(m) => m.fmt(f),
         }
@@ -122,5 +132,9 @@ mod test {
             default,
             RefSystem::GnssConstellation(Constellation::default())
         );
+        for desc in ["GNSS", "GPS", "ENV", "TOP"] {
+            let system = RefSystem::from_str(desc).unwrap();
+            assert_eq!(system.to_string(), desc);
+        }
     }
 }
@@ -5,7 +5,6 @@
 
 extern crate gnss_rs as gnss;
 
-#[cfg(feature = "qc")]
 extern crate rinex_qc_traits as qc_traits;
 
 pub mod antex;
@@ -51,13 +50,16 @@ extern crate num_derive;
 extern crate lazy_static;
 
 pub mod reader;
-use reader::BufferedReader;
+use reader::{BufferedReader, SniffedReader};
 
 pub mod writer;
 use writer::BufferedWriter;
 
 use std::collections::{BTreeMap, HashMap};
-use std::io::Write; //, Read};
+use std::io::{BufRead, BufWriter, Write}; //, Read};
+
+#[cfg(feature = "flate2")]
+use flate2::{write::GzEncoder, Compression};
 use std::path::Path;
 use std::str::FromStr;
 
@@ -65,7 +67,7 @@ use itertools::Itertools;
 use thiserror::Error;
 
 use antex::{Antenna, AntennaSpecific, FrequencyDependentData};
-use doris::record::ObservationData as DorisObservationData;
+use doris::record::{ClockOffset as DorisClockOffset, ObservationData as DorisObservationData};
 use epoch::epoch_decompose;
 use ionex::TECPlane;
 use navigation::NavFrame;
@@ -272,6 +274,8 @@ pub enum Error {
     RecordError(#[from] record::Error),
     #[error("file i/o error")]
     IoError(#[from] std::io::Error),
+    #[error("hatanaka compression error")]
+    HatanakaError(#[from] hatanaka::Error),
 }
 
 impl Rinex {
@@ -366,7 +370,7 @@ impl Rinex {
     /// This can be used to "force" compression of a RINEX1 into CRINEX3
     pub fn rnx2crnx3(&self) -> Self {
         let mut s = self.clone();
-        s.rnx2crnx1_mut();
+        s.rnx2crnx3_mut();
         s
     }
 
@@ -818,14 +822,9 @@ impl Rinex {
         let fullpath = path.to_string_lossy().to_string();
 
         // create buffered reader
-        let mut reader = BufferedReader::new(&fullpath)?;
+        let reader = BufferedReader::new(&fullpath)?;
 
-        // Parse header fields
-        let mut header = Header::new(&mut reader)?;
-
-        // Parse file body (record content)
-        // Comments might serve some fileops like "splice".
-        let (record, comments) = record::parse_record(&mut reader, &mut header)?;
+        let mut rinex = Self::from_reader(reader)?;
 
         // Parse / identify production attributes
         // that only exist in the filename.
@@ -841,11 +840,40 @@ impl Rinex {
             _ => None,
         };
 
+        rinex.prod_attr = prod_attr;
+        Ok(rinex)
+    }
+
+    /// Builds a `RINEX` from any [BufRead] data source,
+    /// for example an in-memory buffer or a network stream.
+    /// gzip (requires "flate2" feature) and .Z (requires "lzw" feature)
+    /// compressions are identified by content sniffing,
+    /// CRINEX is identified by its header.
+    /// Production attributes are only described by file names,
+    /// they are not defined here.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let content = std::fs::read("../test_resources/OBS/V2/AJAC3550.21O")
+    ///     .unwrap();
+    /// let rinex = Rinex::from_reader(content.as_slice())
+    ///     .unwrap();
+    /// assert!(rinex.is_observation_rinex());
+    /// ```
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Rinex, Error> {
+        let mut reader = SniffedReader::new(reader)?;
+
+        // Parse header fields
+        let mut header = Header::new(&mut reader)?;
+
+        // Parse file body (record content)
+        // Comments might serve some fileops like "splice".
+        let (record, comments) = record::parse_record(&mut reader, &mut header)?;
+
         Ok(Rinex {
             header,
             record,
             comments,
-            prod_attr,
+            prod_attr: None,
         })
     }
 
@@ -854,6 +882,11 @@ impl Rinex {
         self.header.rinex_type == types::Type::AntennaData
     }
 
+    /// Returns true if this is a DORIS RINEX
+    pub fn is_doris(&self) -> bool {
+        self.header.rinex_type == types::Type::DORIS
+    }
+
     /// Returns true if this is a CLOCK RINEX
     pub fn is_clock_rinex(&self) -> bool {
         self.header.rinex_type == types::Type::ClockData
@@ -1126,10 +1159,51 @@ impl Rinex {
     ///     files that do not follow naming conventions
     pub fn to_file(&self, path: &str) -> Result<(), Error> {
         let mut writer = BufferedWriter::new(path)?;
+        self.to_writer(&mut writer)
+    }
+
+    /// Writes self into any [Write]able sink, like stdout,
+    /// a socket or an in-memory buffer.
+    /// Both header + record will strictly follow RINEX standards.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///   .unwrap();
+    /// let mut buf = Vec::<u8>::new();
+    /// assert!(rnx.to_writer(&mut buf).is_ok());
+    /// let copy = Rinex::from_reader(buf.as_slice())
+    ///   .unwrap();
+    /// assert_eq!(copy.epoch().count(), rnx.epoch().count());
+    /// ```
+    pub fn to_writer<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        let mut writer = BufWriter::new(writer);
         write!(writer, "{}", self.header)?;
         self.record.to_file(&self.header, &mut writer)?;
+        writer.flush()?;
         Ok(())
     }
+
+    /// Writes self into any [Write]able sink, with gzip compression,
+    /// in a single pass. The sink is returned once the gzip stream is complete.
+    /// [Self::to_file] selects gzip compression from the ".gz" file extension.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+    ///   .unwrap();
+    /// let buf = rnx.to_gzip_writer(Vec::<u8>::new())
+    ///   .unwrap();
+    /// // gzip is identified by content sniffing
+    /// let copy = Rinex::from_reader(buf.as_slice())
+    ///   .unwrap();
+    /// assert_eq!(copy.epoch().count(), rnx.epoch().count());
+    /// ```
+    #[cfg(feature = "flate2")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "flate2")))]
+    pub fn to_gzip_writer<W: Write>(&self, writer: W) -> Result<W, Error> {
+        let mut encoder = GzEncoder::new(writer, Compression::new(6));
+        self.to_writer(&mut encoder)?;
+        Ok(encoder.finish()?)
+    }
 }
 
 /*
@@ -1586,7 +1660,7 @@ impl Rinex {
         } else if self.record.as_doris().is_some() {
             Box::new(
                 self.doris()
-                    .flat_map(|(_, stations)| {
+                    .flat_map(|(_, (_, stations))| {
                         stations
                             .iter()
                             .flat_map(|(_, observables)| observables.iter().map(|(k, _)| k))
@@ -1716,7 +1790,10 @@ impl Rinex {
         dyn Iterator<
                 Item = (
                     &(Epoch, EpochFlag),
-                    &BTreeMap<Station, HashMap<Observable, DorisObservationData>>,
+                    &(
+                        Option<DorisClockOffset>,
+                        BTreeMap<Station, HashMap<Observable, DorisObservationData>>,
+                    ),
                 ),
             > + '_,
     > {
@@ -1730,7 +1807,7 @@ impl Rinex {
     /// ANTEX antennas specifications browsing
     pub fn antennas(
         &self,
-    ) -> Box<dyn Iterator<Item = &(Antenna, HashMap<Carrier, FrequencyDependentData>)> + '_> {
+    ) -> Box<dyn Iterator<Item = &(Antenna, HashMap<String, FrequencyDependentData>)> + '_> {
         Box::new(
             self.record
                 .as_antex()
@@ -2240,7 +2317,8 @@ impl Rinex {
 
 #[cfg(feature = "nav")]
 use crate::navigation::{
-    BdModel, EopMessage, Ephemeris, IonMessage, KbModel, NavMsgType, NgModel, StoMessage,
+    BdModel, EopMessage, Ephemeris, IonMessage, KbModel, NavMsgType, NgModel, SelectionPolicy,
+    StoMessage,
 };
 
 /*
@@ -2324,10 +2402,21 @@ impl Rinex {
         }))
     }
     /// Returns [SV] [Orbit]al state vector (if we can) at specified [Epoch] `t`.
-    /// Self must be NAV RINEX.
+    /// Self must be NAV RINEX. Ephemeris frame is selected by [Self::sv_ephemeris].
     pub fn sv_orbit(&self, sv: SV, t: Epoch) -> Option<Orbit> {
-        let (toc, _, eph) = self.sv_ephemeris(sv, t)?;
-        eph.kepler2position(sv, toc, t)
+        self.sv_orbit_with_policy(sv, t, &SelectionPolicy::default())
+    }
+    /// Returns [SV] [Orbit]al state vector (if we can) at specified [Epoch] `t`,
+    /// using the Ephemeris frame selected by this [SelectionPolicy].
+    /// Self must be NAV RINEX.
+    pub fn sv_orbit_with_policy(
+        &self,
+        sv: SV,
+        t: Epoch,
+        policy: &SelectionPolicy,
+    ) -> Option<Orbit> {
+        let (toc, _, eph) = self.sv_ephemeris_with_policy(sv, t, policy)?;
+        eph.kepler2position(sv, toc, t, policy.is_two_sided())
     }
     /// Returns [SV] attitude vector (if we can) at specified [Epoch] `t`
     /// with respect to specified reference point expressed as an [Orbit].
@@ -2347,32 +2436,74 @@ impl Rinex {
     }
     /// Ephemeris selection method. Use this method to select Ephemeris
     /// for [SV] at [Epoch], to be used in navigation.
+    /// Frame is selected with the default [SelectionPolicy]: latest ToC prior `t`,
+    /// among frames that are valid at `t`.
     /// Returns (ToC, ToE and ephemeris frame).
-    /// Note that ToE = ToC for GEO/SBAS vehicles, because this field does not exist.
+    /// Note that ToE = ToC for GEO/SBAS and Glonass vehicles, because this field does not exist.
+    /// GEO/SBAS and Glonass frames are valid on both sides of their ToC, because
+    /// their state vector is propagated forward or backward in time.
     pub fn sv_ephemeris(&self, sv: SV, t: Epoch) -> Option<(Epoch, Epoch, &Ephemeris)> {
-        let sv_ts = sv.constellation.timescale()?;
-        if sv.constellation.is_sbas() {
-            let (toc, (_, _, eph)) = self
-                .ephemeris()
-                .filter(|(t_i, (_, sv_i, eph_i))| sv == *sv_i)
-                .reduce(|k, _| k)?;
-            Some((*toc, *toc, eph))
-        } else {
+        self.sv_ephemeris_with_policy(sv, t, &SelectionPolicy::default())
+    }
+    /// Ephemeris selection method, following desired [SelectionPolicy].
+    /// Returns (ToC, ToE and ephemeris frame), refer to [Self::sv_ephemeris].
+    /// ```
+    /// use std::str::FromStr;
+    /// use rinex::prelude::*;
+    /// use rinex::navigation::{NavMsgType, SelectionCriteria, SelectionPolicy};
+    /// let rinex = Rinex::from_file("../test_resources/NAV/V4/KMS300DNK_R_20221591000_01H_MN.rnx.gz")
+    ///     .unwrap();
+    /// let e01 = SV::new(Constellation::Galileo, 1);
+    /// let t = Epoch::from_str("2022-06-08T10:10:00 GST").unwrap();
+    /// // only F/NAV frames are considered, closest ToE on either side of t
+    /// let policy = SelectionPolicy::default()
+    ///     .with_criteria(SelectionCriteria::NearestToe)
+    ///     .with_msg_type(NavMsgType::FNAV);
+    /// let (toc, _, eph) = rinex.sv_ephemeris_with_policy(e01, t, &policy)
+    ///     .unwrap();
+    /// assert_eq!(toc, t);
+    /// assert_eq!(eph.clock_bias, -4.921233630739E-04);
+    /// ```
+    pub fn sv_ephemeris_with_policy(
+        &self,
+        sv: SV,
+        t: Epoch,
+        policy: &SelectionPolicy,
+    ) -> Option<(Epoch, Epoch, &Ephemeris)> {
+        policy.select(
+            sv,
+            t,
             self.ephemeris()
-                .filter_map(|(t_i, (_, sv_i, eph_i))| {
+                .filter_map(|(toc_i, (msg_i, sv_i, eph_i))| {
                     if sv_i == sv {
-                        if eph_i.is_valid(sv, t) && t >= *t_i {
-                            let toe = eph_i.toe(sv_ts)?;
-                            Some((*t_i, toe, eph_i))
-                        } else {
-                            None
-                        }
+                        Some((*toc_i, msg_i, eph_i))
                     } else {
                         None
                     }
-                })
-                .min_by_key(|(toc_i, _, _)| (t - *toc_i).abs())
+                }),
+        )
+    }
+    /// Returns Glonass FDMA frequency channel (k) of each [SV] described by [Self].
+    /// Channels declared in the header (Observation RINEX) are completed by
+    /// the channel broadcast in Glonass NAV frames (Navigation RINEX).
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rinex = Rinex::from_file("../test_resources/NAV/V2/amel0010.21g")
+    ///     .unwrap();
+    /// let channels = rinex.glonass_fdma_channels();
+    /// let r01 = SV::new(Constellation::Glonass, 1);
+    /// assert_eq!(channels.get(&r01), Some(&1));
+    /// ```
+    pub fn glonass_fdma_channels(&self) -> HashMap<SV, i8> {
+        let mut channels = self.header.glo_channels.clone();
+        for (_, (_, sv, eph)) in self.ephemeris() {
+            if sv.constellation == Constellation::Glonass {
+                if let Some(channel) = eph.glonass_channel() {
+                    channels.entry(sv).or_insert(channel);
+                }
+            }
         }
+        channels
     }
     /// [SV] embedded clock offset (s), drift (s.s⁻¹) and drift rate (s.s⁻²) Iterator.
     /// ```
@@ -2933,7 +3064,26 @@ impl Merge for Rinex {
 }
 
 impl Split for Rinex {
-    /// Splits `Self` at desired epoch
+    /// Splits `Self` at desired epoch,
+    /// retaining |e(k) < epoch| ("before"), as left component,
+    /// and |e(k) >= epoch| ("inclusive after"), as right component.
+    /// Fails if self is not indexed by `Epoch`.
+    /// ```
+    /// use rinex::Split; // .split()
+    /// use rinex::prelude::*; // Rinex
+    /// use std::str::FromStr;
+    /// let rnx = Rinex::from_file("../test_resources/OBS/V2/delf0010.21o")
+    ///     .unwrap();
+    /// let epoch = Epoch::from_str("2021-01-01T00:01:00 GPST")
+    ///   .unwrap();
+    /// let (rnx_a, rnx_b) = rnx.split(epoch)
+    ///     .unwrap();
+    /// let epochs   : Vec<_> = rnx.epoch().collect();
+    /// let a_epochs : Vec<_> = rnx_a.epoch().collect();
+    /// let b_epochs : Vec<_> = rnx_b.epoch().collect();
+    /// assert_eq!(a_epochs.len(), 2);
+    /// assert_eq!(b_epochs.len(),  epochs.len() -2);
+    /// ```
     fn split(&self, epoch: Epoch) -> Result<(Self, Self), split::Error> {
         let (r0, r1) = self.record.split(epoch)?;
         Ok((
@@ -2951,7 +3101,10 @@ impl Split for Rinex {
             },
         ))
     }
-    fn split_dt(&self, _duration: Duration) -> Result<Vec<Self>, split::Error> {
+    fn split_dt(&self, duration: Duration) -> Result<Vec<Self>, split::Error> {
+        if duration <= Duration::ZERO {
+            return Err(split::Error::InvalidDuration);
+        }
         Ok(Vec::new())
     }
 }
@@ -3001,6 +3154,57 @@ impl Masking for Rinex {
     }
 }
 
+#[cfg(all(feature = "nav", feature = "processing"))]
+use qc_traits::processing::{FilterItem, MaskOperand};
+
+#[cfg(all(feature = "nav", feature = "processing"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "nav", feature = "processing"))))]
+impl Rinex {
+    /// Applies [MaskFilter] to Self, using this Navigation [Rinex] as context.
+    /// This is required by health masks applied to Observation RINEX:
+    /// the health of each [SV] is determined at each [Epoch], from the
+    /// Ephemeris frame selected with the default [SelectionPolicy].
+    /// "=healthy" retains healthy vehicles, "!=healthy" retains unhealthy vehicles.
+    /// Vehicles for which health cannot be determined are always retained.
+    /// Any other mask is applied like [Masking::mask_mut].
+    pub fn mask_with_nav(&self, mask: &MaskFilter, nav: &Rinex) -> Self {
+        let mut s = self.clone();
+        s.mask_with_nav_mut(mask, nav);
+        s
+    }
+    /// Mutable implementation of [Self::mask_with_nav].
+    pub fn mask_with_nav_mut(&mut self, mask: &MaskFilter, nav: &Rinex) {
+        if mask.item != FilterItem::HealthItem || !self.is_observation_rinex() {
+            self.mask_mut(mask);
+            return;
+        }
+        let healthy = match mask.operand {
+            MaskOperand::Equals => true,
+            MaskOperand::NotEquals => false,
+            _ => return, // does not apply
+        };
+        let mut frames = HashMap::<SV, Vec<(Epoch, NavMsgType, &Ephemeris)>>::new();
+        for (toc, (msg, sv, eph)) in nav.ephemeris() {
+            frames.entry(sv).or_default().push((*toc, msg, eph));
+        }
+        let policy = SelectionPolicy::default();
+        if let Some(rec) = self.record.as_mut_obs() {
+            rec.retain(|(t, _), (_, svnn)| {
+                svnn.retain(|sv, _| {
+                    let selected = frames
+                        .get(sv)
+                        .and_then(|sv_frames| policy.select(*sv, *t, sv_frames.iter().copied()));
+                    match selected {
+                        Some((_, _, eph)) => eph.is_healthy(*sv) == healthy,
+                        None => true,
+                    }
+                });
+                !svnn.is_empty()
+            });
+        }
+    }
+}
+
 #[cfg(feature = "processing")]
 #[cfg_attr(docsrs, doc(cfg(feature = "processing")))]
 impl Decimate for Rinex {
@@ -3245,7 +3449,7 @@ impl Rinex {
     pub fn antex_valid_calibrations(
         &self,
         now: Epoch,
-    ) -> Box<dyn Iterator<Item = (&Antenna, &HashMap<Carrier, FrequencyDependentData>)> + '_> {
+    ) -> Box<dyn Iterator<Item = (&Antenna, &HashMap<String, FrequencyDependentData>)> + '_> {
         Box::new(self.antennas().filter_map(move |(ant, data)| {
             if ant.is_valid(now) {
                 Some((ant, data))
@@ -3266,8 +3470,7 @@ impl Rinex {
             .filter_map(|(ant, freqdata)| match &ant.specific {
                 AntennaSpecific::SvAntenna(sv_ant) => {
                     if sv_ant.sv == sv {
-                        freqdata
-                            .get(&freq)
+                        antex::carrier_data(freqdata, freq, sv.constellation)
                             .map(|freqdata| freqdata.apc_eccentricity)
                     } else {
                         None
@@ -3293,8 +3496,7 @@ impl Rinex {
                 AntennaSpecific::RxAntenna(rx_ant) => match &to_match {
                     AntennaMatcher::IGSCode(code) => {
                         if rx_ant.igs_type.to_lowercase().eq(code) {
-                            freqdata
-                                .get(&freq)
+                            antex::carrier_data(freqdata, freq, Constellation::GPS)
                                 .map(|freqdata| freqdata.apc_eccentricity)
                         } else {
                             None
@@ -3302,8 +3504,7 @@ impl Rinex {
                     },
                     AntennaMatcher::SerialNumber(sn) => {
                         if rx_ant.igs_type.to_lowercase().eq(sn) {
-                            freqdata
-                                .get(&freq)
+                            antex::carrier_data(freqdata, freq, Constellation::GPS)
                                 .map(|freqdata| freqdata.apc_eccentricity)
                         } else {
                             None
@@ -3339,7 +3540,7 @@ impl Rinex {
     ///     println!("{}@{}: {} °C", station.domes, epoch, value);
     /// }
     pub fn doris_temperature(&self) -> Box<dyn Iterator<Item = (Epoch, &Station, f64)> + '_> {
-        Box::new(self.doris().flat_map(|((epoch, _), stations)| {
+        Box::new(self.doris().flat_map(|((epoch, _), (_, stations))| {
             stations.iter().flat_map(move |(station, observables)| {
                 observables.iter().filter_map(move |(observable, data)| {
                     if *observable == Observable::Temperature {
@@ -3360,7 +3561,7 @@ impl Rinex {
     ///     println!("{}@{}: {} hPa", station.domes, epoch, value);
     /// }
     pub fn doris_pressure(&self) -> Box<dyn Iterator<Item = (Epoch, &Station, f64)> + '_> {
-        Box::new(self.doris().flat_map(|((epoch, _), stations)| {
+        Box::new(self.doris().flat_map(|((epoch, _), (_, stations))| {
             stations.iter().flat_map(move |(station, observables)| {
                 observables.iter().filter_map(move |(observable, data)| {
                     if *observable == Observable::Pressure {
@@ -3381,7 +3582,7 @@ impl Rinex {
     ///     println!("{}@{}: {}%", station.domes, epoch, value);
     /// }
     pub fn doris_humidity(&self) -> Box<dyn Iterator<Item = (Epoch, &Station, f64)> + '_> {
-        Box::new(self.doris().flat_map(|((epoch, _), stations)| {
+        Box::new(self.doris().flat_map(|((epoch, _), (_, stations))| {
             stations.iter().flat_map(move |(station, observables)| {
                 observables.iter().filter_map(move |(observable, data)| {
                     if *observable == Observable::HumidityRate {
@@ -3404,7 +3605,7 @@ impl Rinex {
     pub fn doris_phase(
         &self,
     ) -> Box<dyn Iterator<Item = (Epoch, &Station, &Observable, f64)> + '_> {
-        Box::new(self.doris().flat_map(|((epoch, _), stations)| {
+        Box::new(self.doris().flat_map(|((epoch, _), (_, stations))| {
             stations.iter().flat_map(move |(station, observables)| {
                 observables.iter().filter_map(move |(observable, data)| {
                     if observable.is_phase_observable() {
@@ -3427,7 +3628,7 @@ impl Rinex {
     pub fn doris_pseudo_range(
         &self,
     ) -> Box<dyn Iterator<Item = (Epoch, &Station, &Observable, f64)> + '_> {
-        Box::new(self.doris().flat_map(move |((epoch, _), stations)| {
+        Box::new(self.doris().flat_map(move |((epoch, _), (_, stations))| {
             stations.iter().flat_map(move |(station, observables)| {
                 observables.iter().filter_map(move |(observable, data)| {
                     if observable.is_pseudorange_observable() {
@@ -3460,7 +3661,7 @@ impl Rinex {
     pub fn doris_rx_power(
         &self,
     ) -> Box<dyn Iterator<Item = (Epoch, &Station, &Observable, f64)> + '_> {
-        Box::new(self.doris().flat_map(|((epoch, _), stations)| {
+        Box::new(self.doris().flat_map(|((epoch, _), (_, stations))| {
             stations.iter().flat_map(move |(station, observables)| {
                 observables.iter().filter_map(move |(observable, data)| {
                     if observable.is_power_observable() {
@@ -3472,6 +3673,21 @@ impl Rinex {
             })
         }))
     }
+    /// Returns receiver clock offset Iterator, as described by each DORIS measurement epoch.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rinex = Rinex::from_file("../test_resources/DOR/V3/cs2rx18164.gz")
+    ///     .unwrap();
+    /// for (epoch, clock_offset) in rinex.doris_clock_offset() {
+    ///     println!("{}: {} s (extrapolated: {})", epoch, clock_offset.offset, clock_offset.extrapolated);
+    /// }
+    pub fn doris_clock_offset(&self) -> Box<dyn Iterator<Item = (Epoch, &DorisClockOffset)> + '_> {
+        Box::new(
+            self.doris().filter_map(|((epoch, _), (clock_offset, _))| {
+                Some((*epoch, clock_offset.as_ref()?))
+            }),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -1,5 +1,5 @@
-use super::{orbits::closest_nav_standards, NavMsgType, OrbitItem};
-use crate::constants::Constants;
+use super::{orbits::closest_nav_standards, GeoHealth, NavMsgType, OrbitItem};
+use crate::constants::{Constants, PZ90};
 use crate::{
     constants, epoch,
     prelude::{Constellation, Duration, Epoch, TimeScale, SV},
@@ -52,7 +52,7 @@ pub enum Error {
 /// EphemerisHelper
 /// # Action
 /// - Helps calculate satellite orbits described by Keplerian orbital elements, inculding
-/// GPS、BDS、Galieo
+/// GPS、BDS、Galieo、QZSS and IRNSS
 /// - Helps calculate relativistic effects(todo)
 #[cfg(feature = "nav")]
 #[cfg_attr(docsrs, doc(cfg(feature = "nav")))]
@@ -238,7 +238,10 @@ impl EphemerisHelper {
     /// get ecef position
     pub fn position(&self) -> Option<Vector3> {
         match self.sv.constellation {
-            Constellation::GPS | Constellation::Galileo => Some(self.ecef_position()),
+            Constellation::GPS
+            | Constellation::Galileo
+            | Constellation::QZSS
+            | Constellation::IRNSS => Some(self.ecef_position()),
             Constellation::BeiDou => {
                 if self.sv.is_beidou_geo() {
                     Some(self.beidou_geo_ecef_position())
@@ -259,9 +262,11 @@ impl EphemerisHelper {
             Some(self.beidou_geo_ecef_pv())
         } else {
             match self.sv.constellation {
-                Constellation::GPS | Constellation::Galileo | Constellation::BeiDou => {
-                    Some(self.ecef_pv())
-                },
+                Constellation::GPS
+                | Constellation::Galileo
+                | Constellation::BeiDou
+                | Constellation::QZSS
+                | Constellation::IRNSS => Some(self.ecef_pv()),
                 _ => {
                     warn!("{} is not supported", self.sv.constellation);
                     None
@@ -367,22 +372,94 @@ impl Ephemeris {
     pub(crate) fn get_week(&self) -> Option<u32> {
         self.orbits.get("week").and_then(|value| value.as_u32())
     }
+    /// [TimeScale] in which Ephemeris frames published by this [Constellation]
+    /// are expressed. QZSS frames are expressed in [TimeScale::QZSST], while
+    /// NavIC/IRNSS frames follow [TimeScale::GPST], to which the IRNSS week counter is aligned.
+    pub fn timescale(c: Constellation) -> Option<TimeScale> {
+        match c {
+            Constellation::QZSS => Some(TimeScale::QZSST),
+            Constellation::IRNSS => Some(TimeScale::GPST),
+            c => c.timescale(),
+        }
+    }
     /// Returns TGD (if value exists) as [Duration]
     pub fn tgd(&self) -> Option<Duration> {
         let tgd_s = self.get_orbit_f64("tgd")?;
         Some(Duration::from_seconds(tgd_s))
     }
+    /// Returns Glonass FDMA frequency channel number (k),
+    /// only exists in Glonass frames.
+    pub fn glonass_channel(&self) -> Option<i8> {
+        self.orbits.get("channel").and_then(|value| value.as_i8())
+    }
     /// Return ToE expressed as [Epoch]
     pub fn toe(&self, sv_ts: TimeScale) -> Option<Epoch> {
         // TODO: in CNAV V4 TOC is said to be TOE... ...
-        let week = self.get_week()?;
         let sec = self.get_orbit_f64("toe")?;
+        self.week_epoch(sec, sv_ts)
+    }
+    /// Returns transmission time of message (t_tm) expressed as [Epoch].
+    /// This does not exist in Glonass and SBAS frames, and unknown
+    /// transmission times (0.9999E9) are not reported.
+    pub fn transmission_time(&self, sv_ts: TimeScale) -> Option<Epoch> {
+        let sec = self.orbits.get("t_tm").and_then(|value| value.as_f64())?;
+        if sec >= 0.9999E9 {
+            return None;
+        }
+        self.week_epoch(sec, sv_ts)
+    }
+    /// Returns true if Self describes a healthy [SV], that may be used in navigation.
+    /// The broadcast health flags are combined with the broadcast accuracy:
+    /// - GPS, QZSS, BeiDou and IRNSS: null health flag, URA is predicted
+    /// - Galileo: no DVS nor HS bit asserted, SISA is predicted (not NAPA)
+    /// - Glonass: null health flag (Bn MSB), accuracy index (FT) is predicted
+    /// - SBAS: ranging is turned on (when MT17 health is available), URA is available.
+    ///
+    /// Fields that Self does not describe are not taken into account.
+    pub fn is_healthy(&self, sv: SV) -> bool {
+        let health = ["health", "svHealth", "satH1"]
+            .iter()
+            .find_map(|field| self.orbits.get(*field));
+        let raw_f64 = |field: &str| self.orbits.get(field).and_then(|value| value.as_f64());
+
+        if sv.constellation.is_sbas() {
+            if let Some(flags) = health.and_then(|health| health.as_geo_health()) {
+                if flags.contains(GeoHealth::URA_UNAVAILABLE) {
+                    return false;
+                }
+                if !flags.contains(GeoHealth::HEALTH_UNAVAILABLE)
+                    && flags.contains(GeoHealth::RANGING_OFF)
+                {
+                    return false;
+                }
+            }
+            return raw_f64("accuracyCode").unwrap_or_default() <= MAX_URA_M;
+        }
+
+        if let Some(health) = health {
+            if health.to_f64() != 0.0 {
+                return false;
+            }
+        }
+
+        match sv.constellation {
+            Constellation::Galileo => raw_f64("sisa").unwrap_or_default() >= 0.0,
+            Constellation::Glonass => raw_f64("urai").unwrap_or_default() < GLO_FT_NOT_USED,
+            _ => raw_f64("svAccuracy").unwrap_or_default() <= MAX_URA_M,
+        }
+    }
+    /*
+     * Converts seconds within week counter of Self to [Epoch]
+     */
+    fn week_epoch(&self, sec: f64, sv_ts: TimeScale) -> Option<Epoch> {
+        let week = self.get_week()?;
         let week_dur = Duration::from_days((week * 7) as f64);
         let sec_dur = Duration::from_seconds(sec);
         match sv_ts {
-            TimeScale::GPST | TimeScale::QZSST | TimeScale::GST => {
+            TimeScale::GPST | TimeScale::GST => {
                 Some(Epoch::from_duration(week_dur + sec_dur, TimeScale::GPST))
             },
+            TimeScale::QZSST => Some(Epoch::from_duration(week_dur + sec_dur, TimeScale::QZSST)),
             TimeScale::BDT => Some(Epoch::from_bdt_duration(week_dur + sec_dur)),
             _ => {
                 error!("{} is not supported", sv_ts);
@@ -428,10 +505,7 @@ impl Ephemeris {
         };
         //log::debug!("\"{}\"={}", svnn, sv);
 
-        let ts = sv
-            .constellation
-            .timescale()
-            .ok_or(Error::TimescaleIdentification(sv))?;
+        let ts = Self::timescale(sv.constellation).ok_or(Error::TimescaleIdentification(sv))?;
 
         //log::debug!("V2/V3 CONTENT \"{}\" TIMESCALE {}", line, ts);
 
@@ -581,21 +655,28 @@ impl Ephemeris {
     /// Total seconds elapsed between `t` and ToE, expressed in appropriate timescale.
     /// NB: this does not apply to GEO Ephemerides but only MEO.
     fn t_k(&self, sv: SV, t: Epoch) -> Option<f64> {
-        let sv_ts = sv.timescale()?;
+        let sv_ts = Self::timescale(sv.constellation)?;
         let toe = self.toe(sv_ts)?;
         let dt = t.to_time_scale(sv_ts) - toe;
         Some(dt.to_seconds())
     }
     /// Form ephemerisHelper.
     /// This does not apply to SBAS and Glonass.
-    fn ephemeris_helper(&self, sv: SV, t_sv: Epoch, t: Epoch) -> Option<EphemerisHelper> {
+    /// `t` prior ToE is only tolerated when `two_sided` is set.
+    fn ephemeris_helper(
+        &self,
+        sv: SV,
+        t_sv: Epoch,
+        t: Epoch,
+        two_sided: bool,
+    ) -> Option<EphemerisHelper> {
         // const
         let gm_m3_s2 = Constants::gm(sv);
         let omega = Constants::omega(sv);
         let dtr_f = Constants::dtr_f(sv);
 
         let t_k = self.t_k(sv, t)?;
-        if t_k < 0.0 {
+        if t_k < 0.0 && !two_sided {
             error!("t_k < 0.0: bad op");
             return None;
         }
@@ -749,17 +830,26 @@ impl Ephemeris {
     }
     /// Calculates Clock correction for [SV] at [Epoch] based on [Self]
     /// and ToC [Epoch] of publication of [Self] from the free running clock.
+    /// SBAS vehicles only broadcast aGf0 and aGf1, stored as clock bias and drift.
+    /// `t` prior ToC is only tolerated when `two_sided` is set, for frames
+    /// that are valid on either side of their ToE (see [Self::is_valid]).
     pub fn clock_correction(
         &self,
         toc: Epoch,
         t: Epoch,
         sv: SV,
         max_iter: usize,
+        two_sided: bool,
     ) -> Option<Duration> {
-        let sv_ts = sv.constellation.timescale()?;
+        let sv_ts = Self::timescale(sv.constellation)?;
         let t_sv = t.to_time_scale(sv_ts);
         let toc_sv = toc.to_time_scale(sv_ts);
-        if t_sv < toc_sv {
+        // Glonass and SBAS frames apply on both sides of ToC
+        if t_sv < toc_sv
+            && !two_sided
+            && sv.constellation != Constellation::Glonass
+            && !sv.constellation.is_sbas()
+        {
             error!("t < t_oc: bad op!");
             None
         } else {
@@ -774,18 +864,19 @@ impl Ephemeris {
     /// Returns [SV] [Orbit]al state at t [Epoch].
     /// t_sv [Epoch] is the satellite free running clock.
     /// Self must be correctly selected from navigation record.
+    /// For Glonass, t_sv should be the ToC of Self, from which the state vector is integrated.
+    /// `t` prior ToE is only tolerated when `two_sided` is set, for frames
+    /// that are valid on either side of their ToE (see [Self::is_valid]).
     /// See [Bibliography::AsceAppendix3], [Bibliography::JLe19] and [Bibliography::BeiDouICD]
-    pub fn kepler2position(&self, sv: SV, t_sv: Epoch, t: Epoch) -> Option<Orbit> {
-        if sv.constellation.is_sbas() || sv.constellation == Constellation::Glonass {
-            let (x_km, y_km, z_km) = (
-                self.get_orbit_f64("satPosX")?,
-                self.get_orbit_f64("satPosY")?,
-                self.get_orbit_f64("satPosZ")?,
-            );
-            // TODO: velocity + integration
-            Some(Orbit::from_position(x_km, y_km, z_km, t, IAU_EARTH_FRAME))
+    pub fn kepler2position(&self, sv: SV, t_sv: Epoch, t: Epoch, two_sided: bool) -> Option<Orbit> {
+        if sv.constellation == Constellation::Glonass {
+            let state = self.glonass_state(t_sv, t)?;
+            Some(Orbit::from_cartesian_pos_vel(state, t, IAU_EARTH_FRAME))
+        } else if sv.constellation.is_sbas() {
+            let state = self.sbas_state(t_sv, t)?;
+            Some(Orbit::from_cartesian_pos_vel(state, t, IAU_EARTH_FRAME))
         } else {
-            let helper = self.ephemeris_helper(sv, t_sv, t)?;
+            let helper = self.ephemeris_helper(sv, t_sv, t, two_sided)?;
             let pos = helper.ecef_position();
             let vel = helper.ecef_velocity();
             Some(Orbit::from_cartesian_pos_vel(
@@ -805,27 +896,80 @@ impl Ephemeris {
         t_sv: Epoch,
         t: Epoch,
     ) -> Option<((f64, f64, f64), (f64, f64, f64))> {
-        if sv.constellation.is_sbas() || sv.constellation == Constellation::Glonass {
-            let (pos_x_km, pos_y_km, pos_z_km) = (
-                self.get_orbit_f64("satPosX")?,
-                self.get_orbit_f64("satPosY")?,
-                self.get_orbit_f64("satPosZ")?,
-            );
-            let (vel_x_km, vel_y_km, vel_z_km) = (
-                self.get_orbit_f64("velX")?,
-                self.get_orbit_f64("velY")?,
-                self.get_orbit_f64("velZ")?,
-            );
+        if sv.constellation == Constellation::Glonass {
+            let state = self.glonass_state(t_sv, t)?;
+            Some((
+                (state[0], state[1], state[2]),
+                (state[3], state[4], state[5]),
+            ))
+        } else if sv.constellation.is_sbas() {
+            let state = self.sbas_state(t_sv, t)?;
             Some((
-                (pos_x_km, pos_y_km, pos_z_km),
-                (vel_x_km, vel_y_km, vel_z_km),
+                (state[0], state[1], state[2]),
+                (state[3], state[4], state[5]),
             ))
         } else {
-            let helper = self.ephemeris_helper(sv, t_sv, t)?;
+            let helper = self.ephemeris_helper(sv, t_sv, t, false)?;
             let (pos, vel) = helper.position_velocity()?;
             Some(((pos.x, pos.y, pos.z), (vel.x, vel.y, vel.z)))
         }
     }
+    /// Glonass ECEF (PZ-90) state vector [km, km/s] at desired instant `t`,
+    /// obtained by numerical integration (4th order Runge-Kutta) of the state vector
+    /// broadcast at `toc`, as described in Glonass ICD Appendix A.3.1.2.
+    /// The integration runs backwards when `t` < `toc`.
+    fn glonass_state(&self, toc: Epoch, t: Epoch) -> Option<Vector6> {
+        let (mut state, accel) = self.broadcast_state()?;
+        let dt = (t - toc).to_seconds();
+        let steps = (dt.abs() / GLONASS_INTEGRATION_STEP_S).ceil().max(1.0) as usize;
+        let h = dt / steps as f64;
+        for _ in 0..steps {
+            let k1 = glonass_derivatives(&state, &accel);
+            let k2 = glonass_derivatives(&(state + k1 * h / 2.0), &accel);
+            let k3 = glonass_derivatives(&(state + k2 * h / 2.0), &accel);
+            let k4 = glonass_derivatives(&(state + k3 * h), &accel);
+            state += (k1 + k2 * 2.0 + k3 * 2.0 + k4) * h / 6.0;
+        }
+        Some(state)
+    }
+    /// SBAS ECEF state vector [km, km/s] at desired instant `t`, propagated from
+    /// the state vector broadcast at `toc`, assuming constant acceleration
+    /// (RTCA DO-229 GEO navigation message).
+    fn sbas_state(&self, toc: Epoch, t: Epoch) -> Option<Vector6> {
+        let (state, accel) = self.broadcast_state()?;
+        let dt = (t - toc).to_seconds();
+        let (x, y, z) = (state[0], state[1], state[2]);
+        let (vx, vy, vz) = (state[3], state[4], state[5]);
+        Some(Vector6::new(
+            x + vx * dt + accel[0] * dt.powi(2) / 2.0,
+            y + vy * dt + accel[1] * dt.powi(2) / 2.0,
+            z + vz * dt + accel[2] * dt.powi(2) / 2.0,
+            vx + accel[0] * dt,
+            vy + accel[1] * dt,
+            vz + accel[2] * dt,
+        ))
+    }
+    /// Broadcast ECEF state vector [km, km/s] and acceleration [km/s²],
+    /// as published by Glonass and SBAS vehicles.
+    fn broadcast_state(&self) -> Option<(Vector6, Vector3)> {
+        // null values are valid here
+        let field = |key: &str| self.orbits.get(key).and_then(|value| value.as_f64());
+        let state = Vector6::new(
+            field("satPosX")?,
+            field("satPosY")?,
+            field("satPosZ")?,
+            field("velX")?,
+            field("velY")?,
+            field("velZ")?,
+        );
+        // accelerations are frequently null and may be omitted
+        let accel = Vector3::new(
+            field("accelX").unwrap_or(0.0),
+            field("accelY").unwrap_or(0.0),
+            field("accelZ").unwrap_or(0.0),
+        );
+        Some((state, accel))
+    }
     /// [AzElRange] calculation attempt, for following SV as observed at RX,
     /// both coordinates expressed as [km] in fixed body [Frame] centered on Earth.
     pub fn elevation_azimuth_range(
@@ -842,14 +986,21 @@ impl Ephemeris {
             Orbit::from_position(rx_x_km, rx_y_km, rx_z_km, t, fixed_body_frame),
         )
     }
-    /// Returns True if Self is Valid at specified `t`.
+    /// Returns True if Self is Valid at specified `t`, that is
+    /// within [Self::max_dtoe] after ToE, or on either side of ToE when `two_sided` is set.
+    /// Two sided frames should then be propagated with `two_sided` as well,
+    /// see [Self::kepler2position] and [Self::clock_correction].
     /// NB: this only applies to MEO Ephemerides, not GEO Ephemerides,
     /// which should always be considered "valid".
-    pub fn is_valid(&self, sv: SV, t: Epoch) -> bool {
+    pub fn is_valid(&self, sv: SV, t: Epoch, two_sided: bool) -> bool {
         if let Some(max_dt) = Self::max_dtoe(sv.constellation) {
-            if let Some(sv_ts) = sv.constellation.timescale() {
+            if let Some(sv_ts) = Self::timescale(sv.constellation) {
                 if let Some(toe) = self.toe(sv_ts) {
-                    t > toe && (t - toe) < max_dt
+                    if two_sided {
+                        (t - toe).abs() <= max_dt
+                    } else {
+                        t > toe && (t - toe) < max_dt
+                    }
                 } else {
                     error!("{}({}): failed to determine ToE", t, sv);
                     false
@@ -888,6 +1039,42 @@ impl Ephemeris {
     }
 }
 
+/// Largest URA (m) that is still predicted (URA index 14).
+/// URA index 15 means no accuracy prediction is available (use at own risk).
+const MAX_URA_M: f64 = 6144.0;
+
+/// Glonass accuracy index (FT) that means no accuracy prediction is available.
+const GLO_FT_NOT_USED: f64 = 15.0;
+
+/// Glonass orbit integration step (s). The ICD tolerates up to a few minutes,
+/// this keeps the integration error well below the broadcast accuracy.
+#[cfg(feature = "nav")]
+const GLONASS_INTEGRATION_STEP_S: f64 = 60.0;
+
+/// Glonass equations of motion, expressed in the PZ-90 rotating frame
+/// (Glonass ICD Appendix A.3.1.2): central body, J2 zonal harmonic,
+/// centrifugal and Coriolis terms, plus broadcast lunisolar accelerations.
+/// `state` is (x, y, z, vx, vy, vz) [km, km/s], returns its time derivative.
+#[cfg(feature = "nav")]
+fn glonass_derivatives(state: &Vector6, accel: &Vector3) -> Vector6 {
+    let (x, y, z) = (state[0], state[1], state[2]);
+    let (vx, vy, vz) = (state[3], state[4], state[5]);
+    let r2 = x.powi(2) + y.powi(2) + z.powi(2);
+    let r = r2.sqrt();
+    let mu = PZ90::GM_KM3_S2 / r.powi(3);
+    let j2 = 1.5 * PZ90::J2 * PZ90::GM_KM3_S2 * PZ90::AE_KM.powi(2) / r.powi(5);
+    let z2_r2 = 5.0 * z.powi(2) / r2;
+    let omega = PZ90::OMEGA;
+    Vector6::new(
+        vx,
+        vy,
+        vz,
+        -mu * x - j2 * x * (1.0 - z2_r2) + omega.powi(2) * x + 2.0 * omega * vy + accel[0],
+        -mu * y - j2 * y * (1.0 - z2_r2) + omega.powi(2) * y - 2.0 * omega * vx + accel[1],
+        -mu * z - j2 * z * (3.0 - z2_r2) + accel[2],
+    )
+}
+
 /*
  * Parses constellation + revision dependent orbits data fields.
  * Retrieves all of this information from the databased stored and maintained
@@ -947,9 +1134,11 @@ fn parse_orbits(
             }
             /*
              * In NAV RINEX, unresolved data fields are either
-             * omitted (handled previously) or put a zeros
+             * omitted (handled previously) or put a zeros.
+             * NAV V4 null fields are valid values (like healthy flags),
+             * they are preserved so V4 frames are formatted back losslessly.
              */
-            if !content.contains(".000000000000E+00") {
+            if version.major > 3 || !content.contains(".000000000000E+00") {
                 if let Some((key, token)) = fields.get(key_index) {
                     //println!(
                     //    "Key \"{}\"(index: {}) | Token \"{}\" | Content \"{}\"",
@@ -1,32 +1,37 @@
+//! Satellite health indications, as broadcast in Navigation frames.
+//! [Health] (GPS / QZSS), [GeoHealth] (SBAS) and [GalHealth] (Galileo)
+//! are bit flags, an empty set meaning healthy.
+//! [IrnssHealth] and [GloHealth] are plain indicators.
 use bitflags::bitflags;
 
-/// GNSS / GPS orbit health indication
-#[derive(Default, Debug, Clone, FromPrimitive, PartialEq, PartialOrd)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub enum Health {
-    #[default]
-    Unhealthy = 0,
-    L1Healthy = 1,
-    L2Healthy = 2,
-    L1L2Healthy = 3,
-    L5Healthy = 4,
-    L1L5Healthy = 5,
-    L2L5Healthy = 6,
-    L1L2L5Healthy = 7,
+bitflags! {
+    /// GPS / QZSS orbit health indication, as broadcast in legacy NAV frames:
+    /// 6 bit word (ICD-GPS-200 20.3.3.3.1.4). The MSB summarizes
+    /// the NAV data health, the 5 LSBs describe the signal components.
+    /// Empty means all signals are healthy.
+    #[derive(Debug, Default, Copy, Clone)]
+    #[derive(PartialEq, PartialOrd)]
+    #[cfg_attr(feature = "serde", derive(Serialize))]
+    pub struct Health: u8 {
+        /// Signal components health (5 bit code)
+        const SIGNALS = 0x1f;
+        /// Some or all NAV data are bad
+        const NAV_DATA = 0x20;
+    }
 }
 
 impl std::fmt::UpperExp for Health {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            Self::Unhealthy => 0.0_f64.fmt(f),
-            Self::L1Healthy => 1.0_f64.fmt(f),
-            Self::L2Healthy => 2.0_f64.fmt(f),
-            Self::L1L2Healthy => 3.0_f64.fmt(f),
-            Self::L5Healthy => 4.0_f64.fmt(f),
-            Self::L1L5Healthy => 5.0_f64.fmt(f),
-            Self::L2L5Healthy => 6.0_f64.fmt(f),
-            Self::L1L2L5Healthy => 7.0_f64.fmt(f),
-        }
+        (self.bits() as f64).fmt(f)
+    }
+}
+
+impl num::FromPrimitive for Health {
+    fn from_i64(n: i64) -> Option<Self> {
+        u8::try_from(n).ok().and_then(Self::from_bits)
+    }
+    fn from_u64(n: u64) -> Option<Self> {
+        u8::try_from(n).ok().and_then(Self::from_bits)
     }
 }
 
@@ -48,21 +53,41 @@ impl std::fmt::UpperExp for IrnssHealth {
     }
 }
 
-/// SBAS/GEO orbit health indication
-#[derive(Default, Debug, Clone, FromPrimitive, PartialEq, PartialOrd)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub enum GeoHealth {
-    #[default]
-    Unknown = 0,
-    Reserved = 8,
+bitflags! {
+    /// SBAS/GEO orbit health indication, as described in RINEX3 8.3.3:
+    /// MT17 health bits, completed by availability flags.
+    /// Empty means the GEO is healthy.
+    #[derive(Debug, Default, Copy, Clone)]
+    #[derive(PartialEq, PartialOrd)]
+    #[cfg_attr(feature = "serde", derive(Serialize))]
+    pub struct GeoHealth: u8 {
+        /// Ranging is turned off
+        const RANGING_OFF = 0x01;
+        /// Corrections are turned off
+        const CORRECTIONS_OFF = 0x02;
+        /// Broadcast integrity is turned off
+        const INTEGRITY_OFF = 0x04;
+        /// Reserved bit
+        const RESERVED = 0x08;
+        /// Health bits are not available (MT17 not received)
+        const HEALTH_UNAVAILABLE = 0x10;
+        /// URA is not available
+        const URA_UNAVAILABLE = 0x20;
+    }
 }
 
 impl std::fmt::UpperExp for GeoHealth {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            Self::Unknown => 0.fmt(f),
-            Self::Reserved => 8.fmt(f),
-        }
+        (self.bits() as f64).fmt(f)
+    }
+}
+
+impl num::FromPrimitive for GeoHealth {
+    fn from_i64(n: i64) -> Option<Self> {
+        u8::try_from(n).ok().and_then(Self::from_bits)
+    }
+    fn from_u64(n: u64) -> Option<Self> {
+        u8::try_from(n).ok().and_then(Self::from_bits)
     }
 }
 
@@ -89,25 +114,30 @@ bitflags! {
     #[derive(Debug, Default, Copy, Clone)]
     #[derive(PartialEq, PartialOrd)]
     #[cfg_attr(feature = "serde", derive(Serialize))]
-    pub struct GalHealth: u8 {
+    pub struct GalHealth: u16 {
         const E1B_DVS = 0x01;
         const E1B_HS0 = 0x02;
         const E1B_HS1 = 0x04;
         const E5A_DVS = 0x08;
         const E5A_HS0 = 0x10;
         const E5A_HS1 = 0x20;
-        const E5B_HS0 = 0x40;
-        const E5B_HS1 = 0x80;
+        const E5B_DVS = 0x40;
+        const E5B_HS0 = 0x80;
+        const E5B_HS1 = 0x100;
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use num::FromPrimitive;
     #[test]
     fn test_gps() {
-        assert_eq!(Health::default(), Health::Unhealthy);
+        assert_eq!(Health::default(), Health::empty());
         assert_eq!(format!("{:E}", Health::default()), "0E0");
+        assert_eq!(Health::from_bits_retain(63), Health::all());
+        assert_eq!(Health::from_u32(32), Some(Health::NAV_DATA));
+        assert_eq!(Health::from_u32(64), None);
     }
     #[test]
     fn test_irnss() {
@@ -116,8 +146,12 @@ mod test {
     }
     #[test]
     fn test_geo_sbas() {
-        assert_eq!(GeoHealth::default(), GeoHealth::Unknown);
-        assert_eq!(format!("{:E}", Health::default()), "0E0");
+        assert_eq!(GeoHealth::default(), GeoHealth::empty());
+        assert_eq!(format!("{:E}", GeoHealth::default()), "0E0");
+        assert_eq!(GeoHealth::from_u32(8), Some(GeoHealth::RESERVED));
+        let health = GeoHealth::from_bits_retain(31);
+        assert!(health.contains(GeoHealth::HEALTH_UNAVAILABLE));
+        assert!(!health.contains(GeoHealth::URA_UNAVAILABLE));
     }
     #[test]
     fn test_glo() {
@@ -127,5 +161,8 @@ mod test {
     #[test]
     fn test_gal() {
         assert_eq!(GalHealth::default(), GalHealth::empty());
+        let health = GalHealth::from_bits_retain(455);
+        assert!(health.contains(GalHealth::E1B_DVS | GalHealth::E5B_DVS | GalHealth::E5B_HS1));
+        assert!(!health.contains(GalHealth::E5A_DVS));
     }
 }
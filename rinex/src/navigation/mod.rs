@@ -3,6 +3,7 @@ mod eopmessage;
 mod ephemeris;
 mod health;
 mod ionmessage;
+mod selection;
 mod stomessage;
 
 pub mod orbits;
@@ -10,10 +11,11 @@ pub mod record;
 
 pub use eopmessage::EopMessage;
 pub use ephemeris::Ephemeris;
-pub use health::{GeoHealth, GloHealth, Health, IrnssHealth};
+pub use health::{GalHealth, GeoHealth, GloHealth, Health, IrnssHealth};
 pub use ionmessage::{BdModel, IonMessage, KbModel, KbRegionCode, NgModel, NgRegionFlags};
 pub use orbits::OrbitItem;
 pub use record::{NavFrame, NavMsgType, Record};
+pub use selection::{SelectionCriteria, SelectionPolicy};
 pub use stomessage::StoMessage;
 
 use crate::epoch;
@@ -113,9 +113,8 @@ impl OrbitItem {
                 let unsigned = float as u32;
                 match constellation {
                     Constellation::GPS | Constellation::QZSS => {
-                        let flag: health::Health = num::FromPrimitive::from_u32(unsigned)
-                            .unwrap_or(health::Health::default());
-                        Ok(OrbitItem::Health(flag))
+                        let flags = health::Health::from_bits_retain(unsigned as u8);
+                        Ok(OrbitItem::Health(flags))
                     },
                     Constellation::Glonass => {
                         let flag: health::GloHealth = num::FromPrimitive::from_u32(unsigned)
@@ -123,8 +122,7 @@ impl OrbitItem {
                         Ok(OrbitItem::GloHealth(flag))
                     },
                     Constellation::Galileo => {
-                        let flags = health::GalHealth::from_bits(unsigned as u8)
-                            .unwrap_or(health::GalHealth::empty());
+                        let flags = health::GalHealth::from_bits_retain(unsigned as u16);
                         Ok(OrbitItem::GalHealth(flags))
                     },
                     Constellation::IRNSS => {
@@ -134,9 +132,8 @@ impl OrbitItem {
                     },
                     c => {
                         if c.is_sbas() {
-                            let flag: health::GeoHealth = num::FromPrimitive::from_u32(unsigned)
-                                .unwrap_or(health::GeoHealth::default());
-                            Ok(OrbitItem::GeoHealth(flag))
+                            let flags = health::GeoHealth::from_bits_retain(unsigned as u8);
+                            Ok(OrbitItem::GeoHealth(flags))
                         } else {
                             // Constellation::Mixed will not happen here,
                             // it's always defined in the database
@@ -156,14 +153,30 @@ impl OrbitItem {
             OrbitItem::I8(n) => format!("{:14.11E}", *n as f64),
             OrbitItem::U32(n) => format!("{:14.11E}", *n as f64),
             OrbitItem::F64(f) => format!("{:14.11E}", f),
-            OrbitItem::Health(h) => format!("{:14.11E}", h),
+            OrbitItem::Health(h) => format!("{:14.11E}", h.bits() as f64),
             OrbitItem::GloHealth(h) => format!("{:14.11E}", h),
-            OrbitItem::GeoHealth(h) => format!("{:14.11E}", h),
+            OrbitItem::GeoHealth(h) => format!("{:14.11E}", h.bits() as f64),
             OrbitItem::IrnssHealth(h) => format!("{:14.11E}", h),
             OrbitItem::GalHealth(h) => format!("{:14.11E}", h.bits() as f64),
             OrbitItem::GloStatus(h) => format!("{:14.11E}", h.bits() as f64),
         }
     }
+    /// Converts self to the floating point value
+    /// it is encoded with, in NAV RINEX
+    pub(crate) fn to_f64(&self) -> f64 {
+        match self {
+            OrbitItem::U8(n) => *n as f64,
+            OrbitItem::I8(n) => *n as f64,
+            OrbitItem::U32(n) => *n as f64,
+            OrbitItem::F64(f) => *f,
+            OrbitItem::Health(h) => h.bits() as f64,
+            OrbitItem::GloHealth(h) => h.clone() as u32 as f64,
+            OrbitItem::GeoHealth(h) => h.bits() as f64,
+            OrbitItem::IrnssHealth(h) => h.clone() as u32 as f64,
+            OrbitItem::GalHealth(h) => h.bits() as f64,
+            OrbitItem::GloStatus(h) => h.bits() as f64,
+        }
+    }
     /// Unwraps OrbitItem as f64
     pub fn as_f64(&self) -> Option<f64> {
         match self {
@@ -195,14 +208,14 @@ impl OrbitItem {
     /// Unwraps Self as GPS/QZSS orbit Health indication
     pub fn as_gps_health(&self) -> Option<health::Health> {
         match self {
-            OrbitItem::Health(h) => Some(h.clone()),
+            OrbitItem::Health(h) => Some(*h),
             _ => None,
         }
     }
     /// Unwraps Self as GEO/SBAS orbit Health indication
     pub fn as_geo_health(&self) -> Option<health::GeoHealth> {
         match self {
-            OrbitItem::GeoHealth(h) => Some(h.clone()),
+            OrbitItem::GeoHealth(h) => Some(*h),
             _ => None,
         }
     }
@@ -339,6 +352,8 @@ mod test {
             (Constellation::QZSS, Version::new(4, 0), NavMsgType::LNAV),
             (Constellation::QZSS, Version::new(4, 0), NavMsgType::CNAV),
             (Constellation::QZSS, Version::new(4, 0), NavMsgType::CNV2),
+            (Constellation::IRNSS, Version::new(3, 0), NavMsgType::LNAV),
+            (Constellation::IRNSS, Version::new(4, 0), NavMsgType::LNAV),
             (Constellation::BeiDou, Version::new(3, 0), NavMsgType::LNAV),
             (Constellation::BeiDou, Version::new(4, 0), NavMsgType::D1),
             (Constellation::BeiDou, Version::new(4, 0), NavMsgType::D2),
@@ -43,8 +43,8 @@ use crate::{
 };
 
 use super::{
-    orbits::closest_nav_standards, BdModel, EopMessage, Ephemeris, IonMessage, KbModel, NgModel,
-    StoMessage,
+    orbits::closest_nav_standards, BdModel, EopMessage, Ephemeris, IonMessage, KbModel,
+    KbRegionCode, NgModel, StoMessage,
 };
 
 use hifitime::Duration;
@@ -208,6 +208,13 @@ impl NavFrame {
             _ => None,
         }
     }
+    /// Returns health status of the vehicle, as described by this frame.
+    /// Only Ephemeris frames describe it, None is returned otherwise.
+    /// See [Ephemeris::is_healthy] for more information.
+    pub fn is_healthy(&self) -> Option<bool> {
+        let (_, sv, eph) = self.as_eph()?;
+        Some(eph.is_healthy(sv))
+    }
 }
 
 /// Navigation Record content:
@@ -255,8 +262,10 @@ pub(crate) fn is_new_epoch(line: &str, v: Version) -> bool {
     }
 }
 
-/// Builds `Record` entry for `NavigationData`
-pub(crate) fn parse_epoch(
+/// Builds `Record` entry for `NavigationData`, from the content
+/// of one epoch. Combined with [crate::record::is_new_epoch], this permits
+/// to process a NAV RINEX one frame at a time.
+pub fn parse_epoch(
     version: Version,
     constell: Constellation,
     content: &str,
@@ -285,10 +294,7 @@ fn parse_v4_record_entry(content: &str) -> Result<(Epoch, NavFrame), Error> {
     let sv = SV::from_str(svnn.trim())?;
     let msg_type = NavMsgType::from_str(rem.trim())?;
 
-    let ts = sv
-        .constellation
-        .timescale()
-        .ok_or(Error::TimescaleIdentification(sv))?;
+    let ts = Ephemeris::timescale(sv.constellation).ok_or(Error::TimescaleIdentification(sv))?;
 
     let (epoch, fr): (Epoch, NavFrame) = match frame_class {
         FrameClass::Ephemeris => {
@@ -452,97 +458,186 @@ fn fmt_epoch_v2v3(epoch: &Epoch, data: &Vec<NavFrame>, header: &Header) -> Resul
     Ok(lines)
 }
 
+/*
+ * Formats given value in the D19.12 fashion expected by modern NAV RINEX,
+ * with a double digit exponent
+ */
+fn fmt_nav_value(value: f64) -> String {
+    let formatted = format!("{:.12E}", value);
+    match formatted.split_once('E') {
+        Some((mantissa, exponent)) => {
+            let exponent = exponent.parse::<i32>().unwrap_or(0);
+            let sign = if exponent < 0 { '-' } else { '+' };
+            let value = format!("{}E{}{:02}", mantissa, sign, exponent.abs());
+            format!("{:>19}", value)
+        },
+        None => format!("{:>19}", formatted),
+    }
+}
+
 fn fmt_epoch_v4(epoch: &Epoch, data: &Vec<NavFrame>, header: &Header) -> Result<String, Error> {
     let mut lines = String::with_capacity(128);
     for fr in data.iter() {
-        if let Some(fr) = fr.as_eph() {
-            let (msgtype, sv, ephemeris) = fr;
-            lines.push_str(&format!("> {} {} {}\n", FrameClass::Ephemeris, sv, msgtype));
-            match &header.constellation {
-                Some(Constellation::Mixed) => {
-                    // Mixed constellation context
-                    // we need to fully describe the vehicle
-                    lines.push_str(&sv.to_string());
-                    lines.push(' ');
-                },
-                Some(_) => {
-                    // Unique constellation context:
-                    // in V2 format, only PRN is shown
-                    lines.push_str(&format!("{:02} ", sv.prn));
-                },
-                None => panic!("producing data with no constellation previously defined"),
-            }
-            lines.push_str(&format!(
-                "{} ",
-                epoch::format(*epoch, Type::NavigationData, header.version.major)
-            ));
-            lines.push_str(&format!(
-                "{:14.13E} {:14.13E} {:14.13E}\n",
-                ephemeris.clock_bias, ephemeris.clock_drift, ephemeris.clock_drift_rate
-            ));
+        let (class, msg, sv) = match fr {
+            NavFrame::Eph(msg, sv, _) => (FrameClass::Ephemeris, msg, sv),
+            NavFrame::Sto(msg, sv, _) => (FrameClass::SystemTimeOffset, msg, sv),
+            NavFrame::Eop(msg, sv, _) => (FrameClass::EarthOrientation, msg, sv),
+            NavFrame::Ion(msg, sv, _) => (FrameClass::IonosphericModel, msg, sv),
+        };
+        // frames are always expressed in the timescale of the publishing system
+        let ts =
+            Ephemeris::timescale(sv.constellation).ok_or(Error::TimescaleIdentification(*sv))?;
+        let datetime = epoch::format(
+            epoch.to_time_scale(ts),
+            Type::NavigationData,
+            header.version.major,
+        );
 
-            // locate closest revision in DB
-            let closest_orbits_definition =
-                match closest_nav_standards(sv.constellation, header.version, NavMsgType::LNAV) {
-                    Some(v) => v,
-                    _ => return Err(Error::OrbitRevision),
-                };
+        lines.push_str(&format!("> {} {} {}\n", class, sv, msg));
 
-            let mut index = 0;
-            for (key, _) in closest_orbits_definition.items.iter() {
-                index += 1;
-                if let Some(data) = ephemeris.orbits.get(*key) {
-                    lines.push_str(&format!(" {}", data.to_string()));
-                } else {
-                    // data is missing: either not parsed or not provided
-                    lines.push_str("              ");
-                }
-                if (index % 4) == 0 {
-                    lines.push_str("\n   "); //TODO: do not TAB when writing last line of grouping
-                }
-            }
-        } else if let Some(fr) = fr.as_sto() {
-            let (msg, sv, sto) = fr;
-            lines.push_str(&format!(
-                "> {} {} {}\n",
-                FrameClass::SystemTimeOffset,
-                sv,
-                msg
-            ));
-            lines.push_str(&format!(
-                "    {} {}    {}\n",
-                epoch::format(*epoch, Type::NavigationData, header.version.major),
-                sto.system,
-                sto.utc
-            ));
-            lines.push_str(&format!(
-                "   {:14.13E} {:14.13E} {:14.13E} {:14.13E}\n",
-                sto.t_tm as f64, sto.a.0, sto.a.1, sto.a.2
-            ));
-        } else if let Some(_fr) = fr.as_eop() {
-            todo!("NAV V4: EOP: we have no example as of today");
-            //(x, xr, xrr), (y, yr, yrr), t_tm, (dut, dutr, dutrr)) = frame.as_eop()
+        match fr {
+            NavFrame::Eph(msg, sv, ephemeris) => {
+                lines.push_str(&fmt_ephemeris_v4(&datetime, *msg, *sv, ephemeris, header)?);
+            },
+            NavFrame::Sto(_, _, sto) => {
+                let line = format!("    {} {:<18} {:<18} {}", datetime, sto.system, "", sto.utc);
+                lines.push_str(line.trim_end());
+                lines.push('\n');
+                lines.push_str(&format!(
+                    "    {}{}{}{}\n",
+                    fmt_nav_value(sto.t_tm as f64),
+                    fmt_nav_value(sto.a.0),
+                    fmt_nav_value(sto.a.1),
+                    fmt_nav_value(sto.a.2),
+                ));
+            },
+            NavFrame::Eop(_, _, eop) => {
+                lines.push_str(&format!(
+                    "    {}{}{}{}\n",
+                    datetime,
+                    fmt_nav_value(eop.x.0),
+                    fmt_nav_value(eop.x.1),
+                    fmt_nav_value(eop.x.2),
+                ));
+                lines.push_str(&format!(
+                    "{:23}{}{}{}\n",
+                    "",
+                    fmt_nav_value(eop.y.0),
+                    fmt_nav_value(eop.y.1),
+                    fmt_nav_value(eop.y.2),
+                ));
+                lines.push_str(&format!(
+                    "    {}{}{}{}\n",
+                    fmt_nav_value(eop.t_tm as f64),
+                    fmt_nav_value(eop.delta_ut1.0),
+                    fmt_nav_value(eop.delta_ut1.1),
+                    fmt_nav_value(eop.delta_ut1.2),
+                ));
+            },
+            NavFrame::Ion(_, _, ion) => {
+                lines.push_str(&fmt_ion_v4(&datetime, ion));
+            },
         }
-        // EOP
-        else if let Some(fr) = fr.as_ion() {
-            let (msg, sv, ion) = fr;
-            lines.push_str(&format!(
-                "> {} {} {}\n",
-                FrameClass::EarthOrientation,
-                sv,
-                msg
-            ));
-            match ion {
-                IonMessage::KlobucharModel(_model) => todo!("ION:Kb"),
-                IonMessage::NequickGModel(_model) => todo!("ION:Ng"),
-                IonMessage::BdgimModel(_model) => todo!("ION:Bd"),
+    }
+    Ok(lines)
+}
+
+/*
+ * Formats Ephemeris frame body (V4)
+ */
+fn fmt_ephemeris_v4(
+    datetime: &str,
+    msg: NavMsgType,
+    sv: SV,
+    ephemeris: &Ephemeris,
+    header: &Header,
+) -> Result<String, Error> {
+    // SBAS frames specificity:
+    // week counter is described in place of the clock drift rate
+    let (constellation, clock_drift_rate) = match sv.constellation.is_sbas() {
+        true => (
+            Constellation::SBAS,
+            ephemeris.get_week().unwrap_or(0) as f64,
+        ),
+        false => (sv.constellation, ephemeris.clock_drift_rate),
+    };
+
+    let mut lines = format!(
+        "{} {}{}{}{}\n",
+        sv,
+        datetime,
+        fmt_nav_value(ephemeris.clock_bias),
+        fmt_nav_value(ephemeris.clock_drift),
+        fmt_nav_value(clock_drift_rate),
+    );
+
+    // locate closest standards in DB
+    let closest_orbits_definition =
+        closest_nav_standards(constellation, header.version, msg).ok_or(Error::OrbitRevision)?;
+
+    for chunk in closest_orbits_definition.items.chunks(4) {
+        lines.push_str("    ");
+        for (key, _) in chunk {
+            if let Some(data) = ephemeris.orbits.get(*key) {
+                lines.push_str(&fmt_nav_value(data.to_f64()));
+            } else {
+                // data is missing: either not parsed or not provided
+                lines.push_str(&" ".repeat(19));
             }
-        } // ION
+        }
+        lines.push('\n');
     }
-    lines = fmt_rework(4, &lines);
     Ok(lines)
 }
 
+/*
+ * Formats Ionospheric model frame body (V4)
+ */
+fn fmt_ion_v4(datetime: &str, ion: &IonMessage) -> String {
+    match ion {
+        IonMessage::KlobucharModel(model) => {
+            let mut lines = format!(
+                "    {}{}{}{}\n    {}{}{}{}\n    {}",
+                datetime,
+                fmt_nav_value(model.alpha.0),
+                fmt_nav_value(model.alpha.1),
+                fmt_nav_value(model.alpha.2),
+                fmt_nav_value(model.alpha.3),
+                fmt_nav_value(model.beta.0),
+                fmt_nav_value(model.beta.1),
+                fmt_nav_value(model.beta.2),
+                fmt_nav_value(model.beta.3),
+            );
+            if model.region == KbRegionCode::JapanArea {
+                lines.push_str(&fmt_nav_value(1.0));
+            }
+            lines.push('\n');
+            lines
+        },
+        IonMessage::NequickGModel(model) => format!(
+            "    {}{}{}{}\n    {}\n",
+            datetime,
+            fmt_nav_value(model.a.0),
+            fmt_nav_value(model.a.1),
+            fmt_nav_value(model.a.2),
+            fmt_nav_value(model.region.bits() as f64),
+        ),
+        IonMessage::BdgimModel(model) => format!(
+            "    {}{}{}{}\n    {}{}{}{}\n    {}{}\n",
+            datetime,
+            fmt_nav_value(model.alpha.0),
+            fmt_nav_value(model.alpha.1),
+            fmt_nav_value(model.alpha.2),
+            fmt_nav_value(model.alpha.3),
+            fmt_nav_value(model.alpha.4),
+            fmt_nav_value(model.alpha.5),
+            fmt_nav_value(model.alpha.6),
+            fmt_nav_value(model.alpha.7),
+            fmt_nav_value(model.alpha.8),
+        ),
+    }
+}
+
 impl Merge for Record {
     /// Merges `rhs` into `Self` without mutable access at the expense of more memcopies
     fn merge(&self, rhs: &Self) -> Result<Self, merge::Error> {
@@ -636,6 +731,13 @@ fn mask_mut_equal(rec: &mut Record, target: &FilterItem) {
                 !frames.is_empty()
             });
         },
+        FilterItem::HealthItem => {
+            rec.retain(|_, frames| {
+                // Only applies to Ephemeris frames
+                frames.retain(|fr| fr.is_healthy() != Some(false));
+                !frames.is_empty()
+            });
+        },
         _ => {}, // Other items: either not supported, or do not apply
     }
 }
@@ -682,6 +784,13 @@ fn mask_mut_ineq(rec: &mut Record, target: &FilterItem) {
                 !frames.is_empty()
             });
         },
+        FilterItem::HealthItem => {
+            rec.retain(|_, frames| {
+                // Only applies to Ephemeris frames
+                frames.retain(|fr| fr.is_healthy() != Some(true));
+                !frames.is_empty()
+            });
+        },
         _ => {}, // Other items: either not supported, or do not apply
     }
 }
@@ -1534,6 +1643,56 @@ mod test {
         }
     }
     #[test]
+    fn format_v4_frames() {
+        let header = Header::default()
+            .with_version(Version::new(4, 0))
+            .with_constellation(Constellation::Mixed);
+        for content in [
+            "> EPH G02 LNAV
+G02 2022 06 08 10 00 00-6.528543308377E-04 3.410605131648E-13 0.000000000000E+00
+     9.600000000000E+01 3.384375000000E+01 4.106242470052E-09-2.157708626665E+00
+     1.812353730202E-06 2.041313482914E-02 9.221956133842E-06 5.153679471970E+03
+     2.952000000000E+05 3.594905138016E-07 2.277502707678E+00 2.756714820862E-07
+     9.659928182326E-01 2.033437500000E+02-1.414485730254E+00-7.679605600684E-09
+     5.857386840816E-11 1.000000000000E+00 2.213000000000E+03 0.000000000000E+00
+     2.800000000000E+00 0.000000000000E+00-1.769512891769E-08 9.600000000000E+01
+     2.880180000000E+05 4.000000000000E+00
+",
+            "> STO G23 LNAV
+    2023 03 14 16 51 12 GPUT                                  UTC(USNO)
+     5.340000000000E+02-3.725290298462E-09-2.664535259100E-15 0.000000000000E+00
+",
+            "> STO C21 CNVX
+    2023 03 12 00 20 00 BDGA
+     2.286000000000E+03-2.502929419279E-08 2.664535259100E-14 6.776263578034E-21
+",
+            "> EOP C30 CNVX
+    2023 03 11 00 00 00-4.079341888428E-02 6.389617919922E-04 0.000000000000E+00
+                        3.462553024292E-01 2.998828887939E-03 0.000000000000E+00
+     6.048300000000E+05-1.820898056030E-02-5.761086940765E-04 0.000000000000E+00
+",
+            "> ION G12 LNAV
+    2023 03 12 00 08 54 3.259629011154E-08 7.450580596924E-09-1.788139343262E-07
+     0.000000000000E+00 1.351680000000E+05 0.000000000000E+00-2.621440000000E+05
+     1.310720000000E+05
+",
+            "> ION E02 IFNV
+    2023 03 12 00 11 25 1.607500000000E+02 3.710937500000E-01 9.063720703125E-03
+     0.000000000000E+00
+",
+            "> ION C30 CNVX
+    2023 03 12 00 00 00 3.550000000000E+01 2.000000000000E+00 1.200000000000E+01
+     1.337500000000E+01-1.725000000000E+01 1.125000000000E+00 1.000000000000E+00
+     3.000000000000E+00 3.750000000000E-01
+",
+        ] {
+            let (epoch, frame) = parse_epoch(Version::new(4, 0), Constellation::Mixed, content)
+                .unwrap_or_else(|e| panic!("failed to parse \"{}\": {:?}", content, e));
+            let formatted = fmt_epoch(&epoch, &vec![frame], &header).unwrap();
+            assert_eq!(formatted, content);
+        }
+    }
+    #[test]
     fn format_rework() {
         let content = "1000123  -123123e-1 -1.23123123e0 -0.123123e-4";
         assert_eq!(
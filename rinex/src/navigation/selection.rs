@@ -0,0 +1,159 @@
+//! Broadcast Ephemeris selection
+use super::{Ephemeris, NavMsgType};
+use crate::prelude::{Constellation, Epoch, SV};
+
+/// [SelectionCriteria] describes how one Ephemeris frame is preferred
+/// among all frames of a [SV] that are valid at a given [Epoch].
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SelectionCriteria {
+    /// Select the latest frame published (ToC) before `t`,
+    /// among frames that are valid at `t` ([Ephemeris::is_valid]).
+    /// Glonass and SBAS frames apply on both sides of their ToC,
+    /// because their state vector is propagated forward or backward in time,
+    /// unless [SelectionPolicy::sbas_forward_only] is set.
+    /// This is the selection of [crate::Rinex::sv_ephemeris].
+    #[default]
+    ClosestToc,
+    /// Select the frame whose ToE is closest to `t`,
+    /// on either side of `t`. This is typically preferred
+    /// in post processing.
+    NearestToe,
+    /// Select the latest frame that was transmitted
+    /// before `t`. This emulates a real-time receiver
+    /// that only knows about frames it has already decoded.
+    /// Transmission time of message is used when the frame describes it,
+    /// ToC otherwise (Glonass and SBAS frames).
+    LatestTransmitted,
+}
+
+/// [SelectionPolicy] used in Ephemeris frame selection, shared by
+/// [crate::Rinex::sv_ephemeris_with_policy] and [crate::Rinex::sv_orbit_with_policy].
+/// ```
+/// use rinex::navigation::{NavMsgType, SelectionCriteria, SelectionPolicy};
+///
+/// // real-time emulation, using only healthy Galileo I/NAV frames
+/// let policy = SelectionPolicy::default()
+///     .with_criteria(SelectionCriteria::LatestTransmitted)
+///     .with_healthy_only(true)
+///     .with_msg_type(NavMsgType::INAV);
+/// ```
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SelectionPolicy {
+    /// [SelectionCriteria] among valid frames
+    pub criteria: SelectionCriteria,
+    /// Discard frames that report an unhealthy vehicle
+    pub healthy_only: bool,
+    /// Only consider this [NavMsgType] when defined
+    pub msg: Option<NavMsgType>,
+    /// Only propagate SBAS frames forward in time, from their ToC,
+    /// whatever their age. Otherwise, SBAS frames apply within
+    /// [Ephemeris::max_dtoe] on either side of their ToC.
+    pub sbas_forward_only: bool,
+}
+
+impl SelectionPolicy {
+    /// Copies and returns [SelectionPolicy] with desired [SelectionCriteria]
+    pub fn with_criteria(&self, criteria: SelectionCriteria) -> Self {
+        let mut s = *self;
+        s.criteria = criteria;
+        s
+    }
+    /// Copies and returns [SelectionPolicy] that discards (or not) unhealthy frames
+    pub fn with_healthy_only(&self, healthy_only: bool) -> Self {
+        let mut s = *self;
+        s.healthy_only = healthy_only;
+        s
+    }
+    /// Copies and returns [SelectionPolicy] restricted to this [NavMsgType]
+    pub fn with_msg_type(&self, msg: NavMsgType) -> Self {
+        let mut s = *self;
+        s.msg = Some(msg);
+        s
+    }
+    /// Copies and returns [SelectionPolicy] that only propagates SBAS frames
+    /// forward in time (or not)
+    pub fn with_sbas_forward_only(&self, forward_only: bool) -> Self {
+        let mut s = *self;
+        s.sbas_forward_only = forward_only;
+        s
+    }
+    /// Returns true if selected frames may apply prior their ToE,
+    /// in which case they should be propagated with `two_sided` set,
+    /// see [Ephemeris::kepler2position] and [Ephemeris::clock_correction].
+    pub fn is_two_sided(&self) -> bool {
+        self.criteria != SelectionCriteria::ClosestToc
+    }
+    /// Selects the Ephemeris frame to be used for [SV] at [Epoch] `t`,
+    /// among (ToC, [NavMsgType], [Ephemeris]) frames published by this [SV].
+    /// Frames are only considered within [Ephemeris::max_dtoe] of their ToE,
+    /// on either side of `t` when [Self::is_two_sided].
+    /// Returns (ToC, ToE and ephemeris frame), with ToE = ToC
+    /// for Glonass and SBAS vehicles, because this field does not exist.
+    pub fn select<'a, I: Iterator<Item = (Epoch, NavMsgType, &'a Ephemeris)>>(
+        &self,
+        sv: SV,
+        t: Epoch,
+        frames: I,
+    ) -> Option<(Epoch, Epoch, &'a Ephemeris)> {
+        let sv_ts = Ephemeris::timescale(sv.constellation)?;
+        let max_dtoe = Ephemeris::max_dtoe(sv.constellation)?;
+        let state_vector = sv.constellation == Constellation::Glonass || sv.constellation.is_sbas();
+
+        let candidates = frames.filter_map(|(toc_i, msg_i, eph_i)| {
+            if let Some(msg) = self.msg {
+                if msg_i != msg {
+                    return None;
+                }
+            }
+            if self.healthy_only && !eph_i.is_healthy(sv) {
+                return None;
+            }
+            if state_vector {
+                if sv.constellation.is_sbas() && self.sbas_forward_only {
+                    if t < toc_i {
+                        return None;
+                    }
+                } else if (t - toc_i).abs() > max_dtoe {
+                    return None;
+                }
+                return Some((toc_i, toc_i, eph_i));
+            }
+            let valid = if self.is_two_sided() {
+                eph_i.is_valid(sv, t, true)
+            } else {
+                eph_i.is_valid(sv, t, false) && t >= toc_i
+            };
+            if !valid {
+                return None;
+            }
+            let toe_i = eph_i.toe(sv_ts)?;
+            Some((toc_i, toe_i, eph_i))
+        });
+
+        match self.criteria {
+            SelectionCriteria::ClosestToc => {
+                candidates.min_by_key(|(toc_i, _, _)| (t - *toc_i).abs())
+            },
+            SelectionCriteria::NearestToe => {
+                candidates.min_by_key(|(_, toe_i, _)| (t - *toe_i).abs())
+            },
+            SelectionCriteria::LatestTransmitted => candidates
+                .filter_map(|(toc_i, toe_i, eph_i)| {
+                    let t_tm = if state_vector {
+                        toc_i
+                    } else {
+                        eph_i.transmission_time(sv_ts).unwrap_or(toc_i)
+                    };
+                    if t_tm <= t {
+                        Some((t_tm, (toc_i, toe_i, eph_i)))
+                    } else {
+                        None
+                    }
+                })
+                .max_by_key(|(t_tm, _)| *t_tm)
+                .map(|(_, selected)| selected),
+        }
+    }
+}
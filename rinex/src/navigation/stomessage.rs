@@ -37,6 +37,8 @@ impl StoMessage {
 
         let (epoch, rem) = line.split_at(23);
         let (system, _) = rem.split_at(5);
+        // UTC ID comes last: after the SBAS ID field
+        let utc = rem.get(38..).unwrap_or("");
         let epoch = epoch::parse_in_timescale(epoch.trim(), ts)?;
 
         let line = match lines.next() {
@@ -45,8 +47,7 @@ impl StoMessage {
         };
         let (time, rem) = line.split_at(23);
         let (a0, rem) = rem.split_at(19);
-        let (a1, rem) = rem.split_at(19);
-        let (a2, rem) = rem.split_at(19);
+        let (a1, a2) = rem.split_at(19);
 
         let t_tm = f64::from_str(time.trim())?;
         Ok((
@@ -59,7 +60,7 @@ impl StoMessage {
                     f64::from_str(a1.trim()).unwrap_or(0.0_f64),
                     f64::from_str(a2.trim()).unwrap_or(0.0_f64),
                 ),
-                utc: rem.trim().to_string(),
+                utc: utc.trim().to_string(),
             },
         ))
     }
@@ -10,6 +10,9 @@ pub use flag::EpochFlag;
 mod snr;
 pub use snr::SNR;
 
+mod stream;
+pub use stream::{ObservationEpoch, ObservationStream, ObservationWriter};
+
 #[cfg(docsrs)]
 use crate::Bibliography;
 
@@ -0,0 +1,421 @@
+//! Streamed Observation RINEX parsing and production
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::io::{BufRead, Error as IoError, ErrorKind, Lines, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::{
+    fmt_rinex,
+    hatanaka::{Compressor, Decompressor},
+    header::Header,
+    is_rinex_comment,
+    observable::Observable,
+    observation::{
+        record::{fmt_epoch, is_new_epoch, parse_epoch},
+        EpochFlag, ObservationData,
+    },
+    prelude::{Constellation, Epoch, TimeScale, SV},
+    reader::{BufferedReader, SniffedReader},
+    record::{observation_timescale, Error as RecordError},
+    types::Type,
+    Error,
+};
+
+/// Observation epoch, as described in [crate::observation::Record]
+pub type ObservationEpoch = (
+    (Epoch, EpochFlag),
+    Option<f64>,
+    BTreeMap<SV, HashMap<Observable, ObservationData>>,
+);
+
+/// [ObservationStream] decodes Observation RINEX and CRINEX epochs lazily,
+/// one at a time, with constant memory footprint.
+/// Epochs that we fail to parse are skipped, like when parsing
+/// an entire file. CRINEX decompression errors are returned
+/// and terminate the stream.
+/// ```
+/// use rinex::prelude::*;
+/// use rinex::observation::ObservationStream;
+/// let stream = ObservationStream::from_file("../test_resources/CRNX/V3/ACOR00ESP_R_20213550000_01D_30S_MO.crx")
+///     .unwrap();
+/// assert!(stream.header.is_crinex());
+/// for epoch in stream {
+///     let ((epoch, flag), clock_offset, vehicles) = epoch.unwrap();
+///     for (sv, observations) in vehicles {
+///         for (observable, data) in observations {
+///             // data.obs, data.lli, data.snr
+///         }
+///     }
+/// }
+/// ```
+pub struct ObservationStream<R: BufRead> {
+    /// [Header] parsed when opening the stream
+    pub header: Header,
+    /// [TimeScale] all epochs are expressed in
+    timescale: TimeScale,
+    /// line browser
+    lines: Lines<SniffedReader<R>>,
+    /// CRINEX decompressor
+    decompressor: Decompressor,
+    /// current epoch content
+    epoch_content: String,
+    /// decoded epochs, not consumed yet
+    pending: VecDeque<ObservationEpoch>,
+    /// true once the data source is consumed
+    eos: bool,
+}
+
+impl ObservationStream<BufferedReader> {
+    /// Opens given Observation RINEX or CRINEX file for streamed parsing,
+    /// with possible .gz or .Z decompression.
+    pub fn from_file(path: &str) -> Result<Self, Error> {
+        Self::from_path(Path::new(path))
+    }
+    /// See [Self::from_file]
+    pub fn from_path(path: &Path) -> Result<Self, Error> {
+        let fullpath = path.to_string_lossy().to_string();
+        let reader = BufferedReader::new(&fullpath)?;
+        Self::new(reader)
+    }
+}
+
+impl<R: BufRead> ObservationStream<R> {
+    /// Builds an [ObservationStream] from any [BufRead] data source.
+    /// The [Header] is parsed immediately, the record is parsed on demand.
+    /// Compression is identified by content sniffing, CRINEX by its header.
+    pub fn new(reader: R) -> Result<Self, Error> {
+        let mut reader = SniffedReader::new(reader)?;
+        let header = Header::new(&mut reader)?;
+        if header.rinex_type != Type::ObservationData {
+            return Err(Error::RecordError(RecordError::TypeError(
+                header.rinex_type.to_string(),
+            )));
+        }
+        let timescale = observation_timescale(&header)?;
+        Ok(Self {
+            header,
+            timescale,
+            lines: reader.lines(),
+            decompressor: Decompressor::new(),
+            epoch_content: String::with_capacity(6 * 64),
+            pending: VecDeque::new(),
+            eos: false,
+        })
+    }
+    /// Recovers readable content from a new line,
+    /// decompressing it in case of CRINEX.
+    /// Headers that do not define a constellation are decompressed
+    /// as [Constellation::Mixed]: vehicles are then fully identified by the record.
+    fn recover(&mut self, line: String) -> Result<String, Error> {
+        let obs = self.header.obs.as_ref().ok_or(Error::RecordError(
+            RecordError::BadObservationDataDefinition,
+        ))?;
+        match &obs.crinex {
+            Some(crinex) => {
+                let constellation = self.header.constellation.unwrap_or(Constellation::Mixed);
+                let content = self.decompressor.decompress(
+                    crinex.version.major,
+                    &constellation,
+                    self.header.version.major,
+                    &obs.codes,
+                    // we might encounter empty lines
+                    // like missing clock offsets
+                    // and .lines() will destroy them
+                    &(line + "\n"),
+                )?;
+                Ok(content)
+            },
+            None => {
+                if line.is_empty() {
+                    // we might encounter empty lines
+                    // and the following parsers (.lines() iterator)
+                    // do not like it
+                    Ok(String::from("\n"))
+                } else {
+                    Ok(line)
+                }
+            },
+        }
+    }
+    /// Tries to decode buffered content
+    fn parse_epoch_content(&mut self) {
+        if let Ok(epoch) = parse_epoch(&self.header, &self.epoch_content, self.timescale) {
+            self.pending.push_back(epoch);
+        }
+        self.epoch_content.clear();
+    }
+}
+
+impl<R: BufRead> Iterator for ObservationStream<R> {
+    type Item = Result<ObservationEpoch, Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(epoch) = self.pending.pop_front() {
+                return Some(Ok(epoch));
+            }
+            if self.eos {
+                return None;
+            }
+            let line = match self.lines.next() {
+                Some(Ok(line)) => line,
+                _ => {
+                    // --> try to build an epoch out of current residues
+                    self.eos = true;
+                    self.parse_epoch_content();
+                    continue;
+                },
+            };
+            if is_rinex_comment(&line) {
+                continue;
+            }
+            let content = match self.recover(line) {
+                Ok(content) => content,
+                Err(e) => {
+                    // corrupt CRINEX: following lines can't be recovered
+                    self.eos = true;
+                    self.epoch_content.clear();
+                    return Some(Err(e));
+                },
+            };
+            for line in content.lines() {
+                // in case of CRINEX -> RINEX < 3 being recovered,
+                // we have more than 1 ligne to process
+                if is_new_epoch(line, self.header.version) && !self.epoch_content.is_empty() {
+                    self.parse_epoch_content();
+                }
+                self.epoch_content.push_str(line);
+                self.epoch_content.push('\n');
+            }
+        }
+    }
+}
+
+/// [ObservationWriter] produces Observation RINEX or CRINEX one epoch at a time,
+/// for example for receiver logging. The [Header] is written along the first epoch,
+/// TIME OF FIRST OBS is defined by the first epoch if not already specified.
+/// Hatanaka compression is applied when the [Header] describes a CRINEX,
+/// see [Header::with_crinex].
+/// [ObservationWriter] does not buffer: wrap slow sinks in a [std::io::BufWriter].
+/// Observations are counted while writing: the optional # OF SATELLITES
+/// and PRN / # OF OBS header fields are produced when the header is
+/// updated in place, see [Self::finalize_in_place].
+/// ```
+/// use rinex::prelude::*;
+/// use rinex::observation::{ObservationStream, ObservationWriter};
+/// let stream = ObservationStream::from_file("../test_resources/OBS/V3/DUTH0630.22O")
+///     .unwrap();
+/// let mut writer = ObservationWriter::new(Vec::<u8>::new(), &stream.header);
+/// for epoch in stream {
+///     let (key, clock_offset, vehicles) = epoch.unwrap();
+///     writer.write_epoch(key, clock_offset, &vehicles)
+///         .unwrap();
+/// }
+/// let (buf, header) = writer.finalize()
+///     .unwrap();
+/// let obs = header.obs.as_ref().unwrap();
+/// assert!(obs.time_of_last_obs.is_some());
+/// ```
+pub struct ObservationWriter<W: Write> {
+    /// data sink
+    writer: W,
+    /// [Header], updated as epochs are produced
+    header: Header,
+    /// CRINEX compressor
+    compressor: Compressor,
+    /// Header position, when header is to be updated in place
+    header_offset: Option<u64>,
+    /// Length of the header, once it has been written
+    header_len: Option<usize>,
+    /// Number of observations, per vehicle and per observable
+    nb_obs: BTreeMap<SV, HashMap<Observable, u32>>,
+}
+
+impl<W: Write> ObservationWriter<W> {
+    /// Builds a new [ObservationWriter] that will produce given [Header]
+    /// and the following epochs.
+    pub fn new(writer: W, header: &Header) -> Self {
+        Self {
+            writer,
+            header: header.clone(),
+            compressor: Compressor::default(),
+            header_offset: None,
+            header_len: None,
+            nb_obs: BTreeMap::new(),
+        }
+    }
+    /// Returns [Header] as it stands
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+    /// Returns the number of observations written so far,
+    /// per vehicle and per observable
+    pub fn nb_observations(&self) -> &BTreeMap<SV, HashMap<Observable, u32>> {
+        &self.nb_obs
+    }
+    /// Formats # OF SATELLITES and PRN / # OF OBS, following
+    /// the order of observables described by the [Header]
+    fn fmt_nb_observations(&self) -> String {
+        let mut content = String::new();
+        if self.nb_obs.is_empty() {
+            return content;
+        }
+        let codes = match &self.header.obs {
+            Some(obs) => &obs.codes,
+            None => return content,
+        };
+        content.push_str(&fmt_rinex(
+            &format!("{:6}", self.nb_obs.len()),
+            "# OF SATELLITES",
+        ));
+        content.push('\n');
+        for (sv, counts) in &self.nb_obs {
+            let observables = match sv.constellation.is_sbas() {
+                true => codes.get(&Constellation::SBAS),
+                false => codes.get(&sv.constellation),
+            };
+            if let Some(observables) = observables {
+                let mut descriptor = format!("   {:x}", sv);
+                for (i, observable) in observables.iter().enumerate() {
+                    if (i % 9) == 0 && i > 0 {
+                        content.push_str(&fmt_rinex(&descriptor, "PRN / # OF OBS"));
+                        content.push('\n');
+                        descriptor = "      ".to_string();
+                    }
+                    let count = counts.get(observable).copied().unwrap_or(0);
+                    descriptor.push_str(&format!("{:6}", count));
+                }
+                content.push_str(&fmt_rinex(&descriptor, "PRN / # OF OBS"));
+                content.push('\n');
+            }
+        }
+        content
+    }
+    /// Formats the [Header], completed by the observation counts
+    fn fmt_header(&self) -> String {
+        let header = self.header.to_string();
+        let counts = self.fmt_nb_observations();
+        // counts are described prior END OF HEADER
+        match header.trim_end().rfind('\n') {
+            Some(pos) if !counts.is_empty() => {
+                format!("{}{}{}", &header[..pos + 1], counts, &header[pos + 1..])
+            },
+            _ => header,
+        }
+    }
+    /// Writes header, if not done yet
+    fn write_header(&mut self) -> Result<(), Error> {
+        if self.header_len.is_none() {
+            let header = self.fmt_header();
+            self.writer.write_all(header.as_bytes())?;
+            self.header_len = Some(header.len());
+        }
+        Ok(())
+    }
+    /// Formats and writes a new epoch, possibly Hatanaka compressed.
+    pub fn write_epoch(
+        &mut self,
+        key: (Epoch, EpochFlag),
+        clock_offset: Option<f64>,
+        vehicles: &BTreeMap<SV, HashMap<Observable, ObservationData>>,
+    ) -> Result<(), Error> {
+        let (epoch, flag) = key;
+        let obs = self.header.obs.as_mut().ok_or(Error::RecordError(
+            RecordError::BadObservationDataDefinition,
+        ))?;
+        if self.header_len.is_none() {
+            if obs.time_of_first_obs.is_none() {
+                obs.time_of_first_obs = Some(epoch);
+            }
+            if self.header_offset.is_some() {
+                // reserves TIME OF LAST OBS, updated on finalization
+                obs.time_of_last_obs = Some(epoch);
+            }
+            self.write_header()?;
+        }
+
+        let content = fmt_epoch(epoch, flag, &clock_offset, vehicles, &self.header);
+        let obs = self.header.obs.as_mut().unwrap();
+        if obs.crinex.is_some() {
+            let major = self.header.version.major;
+            let constellation = self.header.constellation.unwrap_or(Constellation::Mixed);
+            for line in content.lines() {
+                let line = line.to_owned() + "\n"; // helps the following .lines() iterator
+                                                   // embedded in compression method
+                let compressed =
+                    self.compressor
+                        .compress(major, &obs.codes, &constellation, &line)?;
+                write!(self.writer, "{}", compressed)?;
+            }
+        } else {
+            writeln!(self.writer, "{}", content)?;
+        }
+        obs.time_of_last_obs = Some(epoch);
+
+        for (sv, observations) in vehicles {
+            let counts = self.nb_obs.entry(*sv).or_default();
+            for observable in observations.keys() {
+                *counts.entry(observable.clone()).or_default() += 1;
+            }
+        }
+        Ok(())
+    }
+    /// Terminates the production: flushes the data sink, returns it
+    /// with the updated [Header] (TIME OF FIRST and LAST OBS).
+    /// The header that was streamed is not modified: see [Self::finalize_in_place].
+    pub fn finalize(mut self) -> Result<(W, Header), Error> {
+        self.write_header()?;
+        self.writer.flush()?;
+        Ok((self.writer, self.header))
+    }
+}
+
+impl<W: Read + Write + Seek> ObservationWriter<W> {
+    /// Builds a new [ObservationWriter] for a seekable data sink,
+    /// whose header section is updated in place on [Self::finalize_in_place].
+    /// The data sink must be readable, because the header section grows
+    /// with the observation counts: the following epochs are then shifted.
+    pub fn new_seekable(mut writer: W, header: &Header) -> Result<Self, Error> {
+        let offset = writer.stream_position()?;
+        let mut s = Self::new(writer, header);
+        s.header_offset = Some(offset);
+        Ok(s)
+    }
+    /// Terminates the production: the header section is rewritten
+    /// with updated TIME OF LAST OBS, # OF SATELLITES and PRN / # OF OBS,
+    /// then the data sink is flushed and returned, with the updated [Header].
+    pub fn finalize_in_place(mut self) -> Result<(W, Header), Error> {
+        self.write_header()?;
+        if let (Some(offset), Some(len)) = (self.header_offset, self.header_len) {
+            let header = self.fmt_header();
+            if header.len() < len {
+                return Err(Error::IoError(IoError::new(
+                    ErrorKind::InvalidData,
+                    "header length shrunk: can't update in place",
+                )));
+            }
+            let end = self.writer.stream_position()?;
+            let delta = (header.len() - len) as u64;
+            if delta > 0 {
+                self.shift(offset + len as u64, end, delta)?;
+            }
+            self.writer.seek(SeekFrom::Start(offset))?;
+            self.writer.write_all(header.as_bytes())?;
+            self.writer.seek(SeekFrom::Start(end + delta))?;
+        }
+        self.finalize()
+    }
+    /// Shifts the content in `start..end` by `delta` bytes, towards the end of the data sink.
+    /// Content is moved from the end, so it is never overwritten prior being moved.
+    fn shift(&mut self, start: u64, end: u64, delta: u64) -> Result<(), Error> {
+        let mut buf = [0_u8; 4096];
+        let mut pos = end;
+        while pos > start {
+            let size = (pos - start).min(buf.len() as u64) as usize;
+            pos -= size as u64;
+            self.writer.seek(SeekFrom::Start(pos))?;
+            self.writer.read_exact(&mut buf[..size])?;
+            self.writer.seek(SeekFrom::Start(pos + delta))?;
+            self.writer.write_all(&buf[..size])?;
+        }
+        Ok(())
+    }
+}
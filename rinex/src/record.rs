@@ -11,17 +11,17 @@ use super::{
     hatanaka::{Compressor, Decompressor},
     header, ionex, is_rinex_comment, merge,
     merge::Merge,
-    meteo, navigation, observation,
-    reader::BufferedReader,
-    split,
+    meteo, navigation, observation, split,
     split::Split,
     types::Type,
-    writer::BufferedWriter,
     *,
 };
 
+use crate::ionex::record::MapType;
 use crate::navigation::record::parse_epoch as parse_nav_epoch;
 
+use itertools::Itertools;
+
 use hifitime::Duration;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -147,12 +147,8 @@ impl Record {
             _ => None,
         }
     }
-    /// Streams into given file writer
-    pub fn to_file(
-        &self,
-        header: &header::Header,
-        writer: &mut BufferedWriter,
-    ) -> Result<(), Error> {
+    /// Streams into given writer
+    pub fn to_file<W: Write>(&self, header: &header::Header, writer: &mut W) -> Result<(), Error> {
         match &header.rinex_type {
             Type::MeteoData => {
                 let record = self.as_meteo().unwrap();
@@ -171,16 +167,13 @@ impl Record {
                         observation::record::fmt_epoch(*epoch, *flag, clock_offset, data, header);
                     if obs_fields.crinex.is_some() {
                         let major = header.version.major;
-                        let constell = &header.constellation.as_ref().unwrap();
+                        let constell = header.constellation.unwrap_or(Constellation::Mixed);
                         for line in epoch.lines() {
                             let line = line.to_owned() + "\n"; // helps the following .lines() iterator
                                                                // embedded in compression method
-                            if let Ok(compressed) =
-                                compressor.compress(major, &obs_fields.codes, constell, &line)
-                            {
-                                // println!("compressed \"{}\"", compressed); // DEBUG
-                                writeln!(writer, "{}", compressed)?;
-                            }
+                            let compressed =
+                                compressor.compress(major, &obs_fields.codes, &constell, &line)?;
+                            write!(writer, "{}", compressed)?;
                         }
                     } else {
                         writeln!(writer, "{}", epoch)?;
@@ -206,41 +199,60 @@ impl Record {
                 }
             },
             Type::IonosphereMaps => {
-                if let Some(_r) = self.as_ionex() {
-                    //for (index, (epoch, (_map, _, _))) in r.iter().enumerate() {
-                    //    let _ = write!(writer, "{:6}                                                      START OF TEC MAP", index);
-                    //    let _ = write!(
-                    //        writer,
-                    //        "{}                        EPOCH OF CURRENT MAP",
-                    //        epoch::format(*epoch, None, Type::IonosphereMaps, 1)
-                    //    );
-                    //    let _ = write!(writer, "{:6}                                                      END OF TEC MAP", index);
-                    //}
-                    // /*
-                    //  * not efficient browsing, but matches provided examples and common formatting.
-                    //  * RMS and Height maps are passed after TEC maps.
-                    //  */
-                    //for (index, (epoch, (_, _map, _))) in r.iter().enumerate() {
-                    //    let _ = write!(writer, "{:6}                                                      START OF RMS MAP", index);
-                    //    let _ = write!(
-                    //        writer,
-                    //        "{}                        EPOCH OF CURRENT MAP",
-                    //        epoch::format(*epoch, None, Type::IonosphereMaps, 1)
-                    //    );
-                    //    let _ = write!(writer, "{:6}                                                      END OF RMS MAP", index);
-                    //}
-                    //for (index, (epoch, (_, _, _map))) in r.iter().enumerate() {
-                    //    let _ = write!(writer, "{:6}                                                      START OF HEIGHT MAP", index);
-                    //    let _ = write!(
-                    //        writer,
-                    //        "{}                        EPOCH OF CURRENT MAP",
-                    //        epoch::format(*epoch, None, Type::IonosphereMaps, 1)
-                    //    );
-                    //    let _ = write!(writer, "{:6}                                                      END OF HEIGHT MAP", index);
-                    //}
+                if let Some(rec) = self.as_ionex() {
+                    let mut exponent = header
+                        .ionex
+                        .as_ref()
+                        .map(|ionex| ionex.exponent)
+                        .unwrap_or(-1);
+                    // TEC maps come first, then RMS and Height maps, if any
+                    for map_type in [MapType::TEC, MapType::RMS, MapType::Height] {
+                        let mut index = 0;
+                        for (epoch, planes) in &rec.iter().chunk_by(|((epoch, _), _)| *epoch) {
+                            let planes = planes
+                                .filter(|(_, plane)| match map_type {
+                                    MapType::TEC => plane.values().any(|tec| tec.has_tec),
+                                    MapType::RMS => plane.values().any(|tec| tec.rms.is_some()),
+                                    MapType::Height => {
+                                        plane.values().any(|tec| tec.height.is_some())
+                                    },
+                                })
+                                .map(|((_, altitude), plane)| (*altitude, plane))
+                                .collect::<Vec<_>>();
+                            if planes.is_empty() {
+                                continue;
+                            }
+                            index += 1;
+                            let map = ionex::record::fmt_map(
+                                index,
+                                &epoch,
+                                map_type,
+                                &planes,
+                                header,
+                                &mut exponent,
+                            );
+                            write!(writer, "{}", map)?;
+                        }
+                    }
+                    writeln!(writer, "{}", fmt_rinex("", "END OF FILE"))?;
+                }
+            },
+            Type::AntennaData => {
+                if let Some(rec) = self.as_antex() {
+                    for (antenna, inner) in rec {
+                        write!(writer, "{}", antex::record::fmt_antenna(antenna, inner))?;
+                    }
+                }
+            },
+            Type::DORIS => {
+                if let Some(rec) = self.as_doris() {
+                    for ((epoch, flag), (clock_offset, stations)) in rec.iter() {
+                        let epoch =
+                            doris::record::fmt_epoch(epoch, flag, clock_offset, stations, header);
+                        write!(writer, "{}", epoch)?;
+                    }
                 }
             },
-            _ => panic!("record type not supported yet"),
         }
         Ok(())
     }
@@ -266,6 +278,8 @@ pub enum Error {
     BadObservationDataDefinition,
     #[error("failed to identify timescale")]
     ObservationDataTimescaleIdentification,
+    #[error("CRINEX compression error")]
+    HatanakaError(#[from] hatanaka::Error),
 }
 
 /// Returns true if given line matches the start   
@@ -278,7 +292,9 @@ pub fn is_new_epoch(line: &str, header: &header::Header) -> bool {
         Type::AntennaData => antex::record::is_new_epoch(line),
         Type::ClockData => clock::record::is_new_epoch(line),
         Type::IonosphereMaps => {
-            ionex::record::is_new_tec_plane(line) || ionex::record::is_new_rms_plane(line)
+            ionex::record::is_new_tec_plane(line)
+                || ionex::record::is_new_rms_plane(line)
+                || ionex::record::is_new_height_map(line)
         },
         Type::NavigationData => navigation::record::is_new_epoch(line, header.version),
         Type::ObservationData => observation::record::is_new_epoch(line, header.version),
@@ -287,10 +303,31 @@ pub fn is_new_epoch(line: &str, header: &header::Header) -> bool {
     }
 }
 
+/// Identifies the [TimeScale] of an Observation RINEX, either
+///   [+] by TIME OF FIRST header field
+///   [+] fixed system in case of old GPS/GLO Observation Data
+pub(crate) fn observation_timescale(header: &header::Header) -> Result<TimeScale, Error> {
+    let obs = header
+        .obs
+        .as_ref()
+        .ok_or(Error::BadObservationDataDefinition)?;
+    match header.constellation {
+        Some(Constellation::Mixed) | None => {
+            let time_of_first_obs = obs
+                .time_of_first_obs
+                .ok_or(Error::BadObservationDataDefinition)?;
+            Ok(time_of_first_obs.time_scale)
+        },
+        Some(constellation) => constellation
+            .timescale()
+            .ok_or(Error::ObservationDataTimescaleIdentification),
+    }
+}
+
 /// Builds a `Record`, `RINEX` file body content,
 /// which is constellation and `RINEX` file type dependent
-pub fn parse_record(
-    reader: &mut BufferedReader,
+pub fn parse_record<R: BufRead>(
+    reader: &mut R,
     header: &mut header::Header,
 ) -> Result<(Record, Comments), Error> {
     let mut first_epoch = true;
@@ -316,20 +353,8 @@ pub fn parse_record(
     //    [+] by TIME OF FIRST header field
     //    [+] fixed system in case of old GPS/GLO Observation Data
     let mut obs_ts = TimeScale::default();
-    if let Some(obs) = &header.obs {
-        match header.constellation {
-            Some(Constellation::Mixed) | None => {
-                let time_of_first_obs = obs
-                    .time_of_first_obs
-                    .ok_or(Error::BadObservationDataDefinition)?;
-                obs_ts = time_of_first_obs.time_scale;
-            },
-            Some(constellation) => {
-                obs_ts = constellation
-                    .timescale()
-                    .ok_or(Error::ObservationDataTimescaleIdentification)?;
-            },
-        }
+    if header.obs.is_some() {
+        obs_ts = observation_timescale(header)?;
     }
     // Clock RINEX TimeScale definition.
     //   Modern revisions define it in header directly.
@@ -356,7 +381,6 @@ pub fn parse_record(
     //    in this case we used the previously identified Epoch
     //    and attach other kinds of maps
     let mut ionx_rec = ionex::Record::new();
-    let mut ionex_rms_plane = false;
 
     for l in reader.lines() {
         // iterates one line at a time
@@ -435,7 +459,6 @@ pub fn parse_record(
             // in case of CRINEX -> RINEX < 3 being recovered,
             // we have more than 1 ligne to process
             let new_epoch = is_new_epoch(line, header);
-            ionex_rms_plane = ionex::record::is_new_rms_plane(line);
 
             if new_epoch && !first_epoch {
                 match &header.rinex_type {
@@ -460,8 +483,10 @@ pub fn parse_record(
                         }
                     },
                     Type::DORIS => {
-                        if let Ok((e, map)) = doris::record::parse_epoch(header, &epoch_content) {
-                            dor_rec.insert(e, map);
+                        if let Ok((e, clock_offset, map)) =
+                            doris::record::parse_epoch(header, &epoch_content)
+                        {
+                            dor_rec.insert(e, (clock_offset, map));
                         }
                     },
                     Type::MeteoData => {
@@ -490,32 +515,18 @@ pub fn parse_record(
                         atx_rec.push((antenna, content));
                     },
                     Type::IonosphereMaps => {
-                        if let Ok((epoch, altitude, plane)) =
-                            ionex::record::parse_plane(&epoch_content, header, ionex_rms_plane)
+                        if let Ok((epoch, map_type, planes)) =
+                            ionex::record::parse_map(&epoch_content, header)
                         {
-                            if ionex_rms_plane {
-                                if let Some(rec_plane) = ionx_rec.get_mut(&(epoch, altitude)) {
-                                    // provide RMS value for the entire plane
-                                    for ((_, rec_tec), (_, tec)) in
-                                        rec_plane.iter_mut().zip(plane.iter())
-                                    {
-                                        rec_tec.rms = tec.rms;
-                                    }
-                                } else {
-                                    // insert RMS values
-                                    ionx_rec.insert((epoch, altitude), plane);
-                                }
-                            } else if let Some(rec_plane) = ionx_rec.get_mut(&(epoch, altitude)) {
-                                // provide TEC value for the entire plane
-                                for ((_, rec_tec), (_, tec)) in
-                                    rec_plane.iter_mut().zip(plane.iter())
-                                {
-                                    rec_tec.tec = tec.tec;
-                                }
-                            } else {
-                                // insert TEC values
-                                ionx_rec.insert((epoch, altitude), plane);
+                            for (altitude, plane) in planes {
+                                ionex::record::insert_plane_mut(
+                                    &mut ionx_rec,
+                                    (epoch, altitude),
+                                    map_type,
+                                    plane,
+                                );
                             }
+                            comment_ts = epoch; // for comments classification & management
                         }
                     },
                 }
@@ -562,8 +573,8 @@ pub fn parse_record(
             }
         },
         Type::DORIS => {
-            if let Ok((e, map)) = doris::record::parse_epoch(header, &epoch_content) {
-                dor_rec.insert(e, map);
+            if let Ok((e, clock_offset, map)) = doris::record::parse_epoch(header, &epoch_content) {
+                dor_rec.insert(e, (clock_offset, map));
             }
         },
         Type::MeteoData => {
@@ -587,28 +598,17 @@ pub fn parse_record(
             }
         },
         Type::IonosphereMaps => {
-            if let Ok((epoch, altitude, plane)) =
-                ionex::record::parse_plane(&epoch_content, header, ionex_rms_plane)
+            if let Ok((epoch, map_type, planes)) = ionex::record::parse_map(&epoch_content, header)
             {
-                if ionex_rms_plane {
-                    if let Some(rec_plane) = ionx_rec.get_mut(&(epoch, altitude)) {
-                        // provide RMS value for the entire plane
-                        for ((_, rec_tec), (_, tec)) in rec_plane.iter_mut().zip(plane.iter()) {
-                            rec_tec.rms = tec.rms;
-                        }
-                    } else {
-                        // insert RMS values
-                        ionx_rec.insert((epoch, altitude), plane);
-                    }
-                } else if let Some(rec_plane) = ionx_rec.get_mut(&(epoch, altitude)) {
-                    // provide TEC value for the entire plane
-                    for ((_, rec_tec), (_, tec)) in rec_plane.iter_mut().zip(plane.iter()) {
-                        rec_tec.tec = tec.tec;
-                    }
-                } else {
-                    // insert TEC values
-                    ionx_rec.insert((epoch, altitude), plane);
+                for (altitude, plane) in planes {
+                    ionex::record::insert_plane_mut(
+                        &mut ionx_rec,
+                        (epoch, altitude),
+                        map_type,
+                        plane,
+                    );
                 }
+                comment_ts = epoch; // for comments classification & management
             }
         },
         Type::AntennaData => {
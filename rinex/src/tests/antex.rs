@@ -53,40 +53,40 @@ mod test {
         // specs for 3 freqz
         assert_eq!(freq_data.len(), 3);
 
-        // L1 frequency
+        // L1 frequency (SBAS)
         assert!(
-            freq_data.get(&Carrier::L1).is_some(),
-            "missing specs for L1 frequency"
+            freq_data.get("S01").is_some(),
+            "missing specs for S01 frequency"
         );
-        let l1_specs = freq_data.get(&Carrier::L1).unwrap();
+        let l1_specs = freq_data.get("S01").unwrap();
         assert_eq!(
             l1_specs.apc_eccentricity,
             (-0.22, -0.01, 154.88),
-            "bad APC for L1 frequency"
+            "bad APC for S01 frequency"
         );
 
-        // L5 frequency
+        // L5 frequency (QZSS)
         assert!(
-            freq_data.get(&Carrier::L5).is_some(),
-            "missing specs for L5 frequency"
+            freq_data.get("J05").is_some(),
+            "missing specs for J05 frequency"
         );
-        let l5_specs = freq_data.get(&Carrier::L5).unwrap();
+        let l5_specs = freq_data.get("J05").unwrap();
         assert_eq!(
             l5_specs.apc_eccentricity,
             (0.34, -0.62, 164.34),
-            "bad APC for L5 frequency"
+            "bad APC for J05 frequency"
         );
 
-        // B2B frequency
+        // B2B frequency (BeiDou)
         assert!(
-            freq_data.get(&Carrier::B2B).is_some(),
-            "missing specs for B2B frequency"
+            freq_data.get("C07").is_some(),
+            "missing specs for C07 frequency"
         );
-        let b2b_specs = freq_data.get(&Carrier::B2B).unwrap();
+        let b2b_specs = freq_data.get("C07").unwrap();
         assert_eq!(
             b2b_specs.apc_eccentricity,
             (0.32, -0.63, 160.39),
-            "bad APC for B2B frequency"
+            "bad APC for C07 frequency"
         );
 
         /*
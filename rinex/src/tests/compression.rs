@@ -1,10 +1,34 @@
 #[cfg(test)]
 mod test {
+    use crate::hatanaka::rnx2crx;
+    use crate::observation::Crinex;
     use crate::prelude::*;
     use crate::tests::toolkit::{random_name, test_against_model};
+    use std::fs::File;
+    use std::io::BufReader;
     use std::path::PathBuf;
+    /// Compresses given Observation RINEX, dumps it into a temporary file
+    /// and parses it back.
+    fn compress_and_parse(rnx: &Rinex) -> Rinex {
+        let compressed = rnx.rnx2crnx();
+        assert!(compressed.header.is_crinex());
+
+        let tmp_path = std::env::temp_dir().join(format!("test-{}.crx", random_name(8)));
+        let tmp_path = tmp_path.to_string_lossy().to_string();
+
+        assert!(
+            compressed.to_file(&tmp_path).is_ok(),
+            "failed to format compressed rinex"
+        );
+
+        let parsed = Rinex::from_file(&tmp_path);
+        let _ = std::fs::remove_file(&tmp_path);
+
+        let parsed = parsed.unwrap_or_else(|e| panic!("failed to parse compressed rinex: {}", e));
+        assert!(parsed.header.is_crinex());
+        parsed
+    }
     #[test]
-    #[ignore]
     fn crinex1() {
         let pool = vec![
             //("AJAC3550.21D", "AJAC3550.21O"),
@@ -42,7 +66,19 @@ mod test {
 
             // convert to CRINEX1
             println!("compressing \"{}\"..", rnx_path.to_string_lossy());
-            let dut = rnx.rnx2crnx1();
+            let dut = compress_and_parse(&rnx);
+            assert_eq!(
+                dut.header
+                    .obs
+                    .as_ref()
+                    .unwrap()
+                    .crinex
+                    .as_ref()
+                    .unwrap()
+                    .version
+                    .major,
+                1
+            );
 
             // parse model
             let model = Rinex::from_file(&crnx_path.to_string_lossy());
@@ -64,7 +100,6 @@ mod test {
         }
     }
     #[test]
-    #[ignore]
     fn crinex1_reciprocity() {
         let pool = vec![
             ("AJAC3550.21O"),
@@ -85,32 +120,20 @@ mod test {
 
             // compress
             let rnx = rnx.unwrap();
-            let compressed = rnx.rnx2crnx1();
-
-            let tmp_path = format!("test-{}.crx", random_name(8));
-
-            assert!(
-                compressed.to_file(&tmp_path).is_ok(),
-                "{}{}",
-                "failed to format compressed rinex",
-                testfile
-            );
+            let compressed = compress_and_parse(&rnx);
 
             // test reciprocity
             let uncompressed = compressed.crnx2rnx();
-            assert!(
-                rnx == uncompressed,
-                "{}{}",
-                "reciprocity test failed for \"{}\"",
-                testfile
+            assert!(!uncompressed.header.is_crinex());
+            test_against_model(
+                &uncompressed,
+                &rnx,
+                &format!("compression::crinex1_reciprocity::{}", testfile),
+                1.0E-6,
             );
-
-            // remove generated file
-            let _ = std::fs::remove_file(&tmp_path);
         }
     }
     #[test]
-    #[ignore]
     fn crinex3() {
         let pool = vec![
             (
@@ -152,7 +175,19 @@ mod test {
 
             // convert to CRINEX3
             println!("compressing \"{}\"..", rnx_path.to_string_lossy());
-            let dut = rnx.rnx2crnx1();
+            let dut = compress_and_parse(&rnx);
+            assert_eq!(
+                dut.header
+                    .obs
+                    .as_ref()
+                    .unwrap()
+                    .crinex
+                    .as_ref()
+                    .unwrap()
+                    .version
+                    .major,
+                3
+            );
 
             // parse model
             let model = Rinex::from_file(&crnx_path.to_string_lossy());
@@ -174,7 +209,6 @@ mod test {
         }
     }
     #[test]
-    #[ignore]
     fn crinex3_reciprocity() {
         let pool = vec![("pdel0010.21o")];
         for testfile in pool {
@@ -189,28 +223,89 @@ mod test {
 
             // compress
             let rnx = rnx.unwrap();
-            let compressed = rnx.rnx2crnx1();
-
-            let tmp_path = format!("test-{}.crx", random_name(8));
-
-            assert!(
-                compressed.to_file(&tmp_path).is_ok(),
-                "{}{}",
-                "failed to format compressed rinex",
-                testfile
-            );
+            let compressed = compress_and_parse(&rnx);
 
             // test reciprocity
             let uncompressed = compressed.crnx2rnx();
-            assert!(
-                rnx == uncompressed,
-                "{}{}",
-                "reciprocity test failed for \"{}\"",
-                testfile
+            assert!(!uncompressed.header.is_crinex());
+            test_against_model(
+                &uncompressed,
+                &rnx,
+                &format!("compression::crinex3_reciprocity::{}", testfile),
+                1.0E-6,
             );
+        }
+    }
+    #[test]
+    fn rnx2crx_parity() {
+        // RNX2CRX ver.4.0.7 and ver.4.1.0 compressed files
+        let pool = vec![
+            ("V2", "KOSG0010.95O", "V1", "KOSG0010.95D"),
+            ("V2", "aopr0010.17o", "V1", "aopr0010.17d"),
+            ("V2", "AJAC3550.21O", "V1", "AJAC3550.21D"),
+            ("V2", "npaz3550.21o", "V1", "npaz3550.21d"),
+            ("V2", "wsra0010.21o", "V1", "wsra0010.21d"),
+            ("V2", "zegv0010.21o", "V1", "zegv0010.21d"),
+            ("V2", "delf0010.21o", "V1", "delf0010.21d"),
+            (
+                "V3",
+                "ACOR00ESP_R_20213550000_01D_30S_MO.rnx",
+                "V3",
+                "ACOR00ESP_R_20213550000_01D_30S_MO.crx",
+            ),
+            ("V3", "DUTH0630.22O", "V3", "DUTH0630.22D"),
+            ("V3", "VLNS0010.22O", "V3", "VLNS0010.22D"),
+            ("V3", "VLNS0630.22O", "V3", "VLNS0630.22D"),
+            ("V3", "flrs0010.12o", "V3", "flrs0010.12d"),
+            ("V3", "pdel0010.21o", "V3", "pdel0010.21d"),
+        ];
+        for (rnx_rev, rnx_name, crnx_rev, crnx_name) in pool {
+            let rnx_path = format!("../test_resources/OBS/{}/{}", rnx_rev, rnx_name);
+            let crnx_path = format!("../test_resources/CRNX/{}/{}", crnx_rev, crnx_name);
+
+            let reader = BufReader::new(File::open(&rnx_path).unwrap());
+            let mut compressed = Vec::<u8>::new();
+            let header = rnx2crx(reader, &mut compressed, &Crinex::default())
+                .unwrap_or_else(|e| panic!("failed to compress \"{}\": {}", rnx_path, e));
+
+            let compressed = String::from_utf8(compressed).unwrap();
+            let crinex = header.obs.as_ref().unwrap().crinex.as_ref();
+            assert!(crinex.is_none(), "input header should not be modified");
+
+            // readable header is preserved, after CRINEX descriptor
+            let rnx = std::fs::read_to_string(&rnx_path).unwrap();
+            let mut lines = compressed.lines();
+            let first = lines.next().unwrap();
+            assert!(first.ends_with("CRINEX VERS   / TYPE"));
+            if rnx_rev == "V2" {
+                assert!(first.starts_with("1.0"));
+            } else {
+                assert!(first.starts_with("3.0"));
+            }
+            assert!(lines.next().unwrap().ends_with("CRINEX PROG / DATE"));
+            for expected in rnx.lines() {
+                assert_eq!(lines.next(), Some(expected));
+                if expected.contains("END OF HEADER") {
+                    break;
+                }
+            }
 
-            // remove generated file
-            let _ = std::fs::remove_file(&tmp_path);
+            // compressed record is identical to RNX2CRX
+            let model = std::fs::read_to_string(&crnx_path).unwrap();
+            let model = model
+                .lines()
+                .skip_while(|line| !line.contains("END OF HEADER"))
+                .skip(1)
+                .collect::<Vec<_>>();
+            let dut = lines.collect::<Vec<_>>();
+            assert_eq!(dut.len(), model.len(), "\"{}\": bad line count", rnx_path);
+            for (nth, (dut, expected)) in dut.iter().zip(model.iter()).enumerate() {
+                assert_eq!(
+                    dut, expected,
+                    "\"{}\": compressed record differs at line #{}",
+                    rnx_path, nth
+                );
+            }
         }
     }
 }
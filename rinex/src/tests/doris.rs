@@ -5,6 +5,7 @@ mod test {
 
     use crate::prelude::*;
     use std::path::Path;
+    use std::str::FromStr;
 
     #[test]
     #[cfg(feature = "flate2")]
@@ -77,5 +78,41 @@ mod test {
                 "D50  PDOC PONTA DELGADA                 31906S004  4   0",
             ],
         );
+
+        // one clock offset per measurement epoch
+        assert_eq!(rinex.doris_clock_offset().count(), rinex.epoch().count());
+
+        let (epoch, clock_offset) = rinex.doris_clock_offset().next().unwrap();
+        assert_eq!(
+            epoch,
+            Epoch::from_str("2018-06-13T00:00:33.179947800 TAI").unwrap()
+        );
+        assert_eq!(clock_offset.offset, -4.326631626);
+        assert!(!clock_offset.extrapolated);
+    }
+
+    #[test]
+    #[cfg(all(feature = "flate2", feature = "processing"))]
+    fn v3_cs2rx18164_decimated_clock_offsets() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("DOR")
+            .join("V3")
+            .join("cs2rx18164.gz");
+        let fullpath = path.to_string_lossy();
+        let rinex = Rinex::from_file(fullpath.as_ref()).unwrap();
+
+        let decimated = rinex.decimate(&DecimationFilter::modulo(2));
+        assert!(decimated.epoch().count() < rinex.epoch().count());
+
+        // clock offsets follow their measurement epoch
+        for (epoch, _) in decimated.doris_clock_offset() {
+            assert!(decimated.epoch().any(|t| t == epoch));
+        }
+        assert_eq!(
+            decimated.doris_clock_offset().count(),
+            decimated.epoch().count()
+        );
     }
 }
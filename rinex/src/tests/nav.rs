@@ -1679,6 +1679,466 @@ mod test {
             }
         }
     }
+    #[test]
+    #[cfg(feature = "nav")]
+    #[cfg(feature = "flate2")]
+    fn glonass_orbit_integration() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("NAV")
+            .join("V3")
+            .join("ESBC00DNK_R_20201770000_01D_MN.rnx.gz");
+        let rinex = Rinex::from_file(path.to_string_lossy().as_ref()).unwrap();
+
+        let glonass = rinex
+            .sv()
+            .filter(|sv| sv.constellation == Constellation::Glonass)
+            .collect::<Vec<_>>();
+        assert_eq!(glonass.len(), 23);
+
+        let channels = rinex.glonass_fdma_channels();
+        assert_eq!(channels.len(), 23, "missing frequency channels");
+        assert_eq!(channels.get(&sv!("R01")), Some(&1));
+
+        let mut tests = 0;
+        for sv in glonass {
+            let frames = rinex
+                .ephemeris()
+                .filter_map(
+                    |(toc, (_, sv_i, eph))| {
+                        if sv_i == sv {
+                            Some((*toc, eph))
+                        } else {
+                            None
+                        }
+                    },
+                )
+                .collect::<Vec<_>>();
+
+            for (toc, eph) in frames.iter() {
+                // state vector is preserved at ToC
+                let orbit = rinex
+                    .sv_orbit(sv, *toc)
+                    .unwrap_or_else(|| panic!("{}({}): sv_orbit failed", toc, sv));
+                let pos_km = orbit.to_cartesian_pos_vel();
+                assert_eq!(pos_km[0], eph.get_orbit_f64("satPosX").unwrap());
+                assert_eq!(pos_km[1], eph.get_orbit_f64("satPosY").unwrap());
+                assert_eq!(pos_km[2], eph.get_orbit_f64("satPosZ").unwrap());
+
+                // integration is feasible on both sides of ToC
+                let before = *toc - 10.0 * Unit::Minute;
+                assert!(rinex.sv_orbit(sv, before).is_some());
+                let after = *toc + 10.0 * Unit::Minute;
+                assert!(rinex.sv_orbit(sv, after).is_some());
+            }
+
+            // propagating one frame to the next publication
+            // should match the next state vector, within a few meters
+            for ((toc_0, eph_0), (toc_1, eph_1)) in frames.iter().tuple_windows() {
+                if *toc_1 - *toc_0 != 30.0 * Unit::Minute {
+                    continue;
+                }
+                let (pos_km, _) = eph_0.kepler2position_velocity(sv, *toc_0, *toc_1).unwrap();
+                let err_m = ((pos_km.0 - eph_1.get_orbit_f64("satPosX").unwrap()).powi(2)
+                    + (pos_km.1 - eph_1.get_orbit_f64("satPosY").unwrap()).powi(2)
+                    + (pos_km.2 - eph_1.get_orbit_f64("satPosZ").unwrap()).powi(2))
+                .sqrt()
+                    * 1.0E3;
+                assert!(
+                    err_m < 10.0,
+                    "{}({}): integration error too large: {} m",
+                    toc_0,
+                    sv,
+                    err_m
+                );
+                tests += 1;
+            }
+        }
+        assert_eq!(tests, 444);
+    }
+    // Propagates each LNAV frame to the ToE of the following frame,
+    // which should match the following frame solution, within `max_err_m`.
+    // Returns the number of tested frames.
+    #[cfg(feature = "nav")]
+    fn kepler_continuity(rinex: &Rinex, constellation: Constellation, max_err_m: f64) -> usize {
+        let mut tests = 0;
+        let sv_ts = Ephemeris::timescale(constellation).unwrap();
+        let vehicles = rinex
+            .sv()
+            .filter(|sv| sv.constellation == constellation)
+            .collect::<Vec<_>>();
+        assert!(!vehicles.is_empty(), "no {} vehicles", constellation);
+        for sv in vehicles {
+            let frames = rinex
+                .ephemeris()
+                .filter_map(|(toc, (msg, sv_i, eph))| {
+                    if sv_i == sv && msg == NavMsgType::LNAV {
+                        Some((*toc, eph.toe(sv_ts).unwrap(), eph))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+            for ((toc_0, toe_0, eph_0), (toc_1, toe_1, eph_1)) in frames.iter().tuple_windows() {
+                assert_eq!(toe_0.time_scale, sv_ts);
+                let dt = *toe_1 - *toe_0;
+                if dt <= Duration::ZERO || dt >= Ephemeris::max_dtoe(constellation).unwrap() {
+                    continue;
+                }
+                let (pos_0, vel_0) = eph_0
+                    .kepler2position_velocity(sv, *toc_0, *toe_1)
+                    .unwrap_or_else(|| panic!("{}({}): kepler solver failed", toe_1, sv));
+                let (pos_1, _) = eph_1
+                    .kepler2position_velocity(sv, *toc_1, *toe_1)
+                    .unwrap_or_else(|| panic!("{}({}): kepler solver failed", toe_1, sv));
+
+                let r_km = (pos_1.0.powi(2) + pos_1.1.powi(2) + pos_1.2.powi(2)).sqrt();
+                assert!(
+                    r_km > 38000.0 && r_km < 46000.0,
+                    "{}({}): invalid orbital radius {} km",
+                    toe_1,
+                    sv,
+                    r_km
+                );
+                let v_km_s = (vel_0.0.powi(2) + vel_0.1.powi(2) + vel_0.2.powi(2)).sqrt();
+                assert!(v_km_s < 4.0, "{}({}): invalid velocity", toe_1, sv);
+
+                let err_m = ((pos_0.0 - pos_1.0).powi(2)
+                    + (pos_0.1 - pos_1.1).powi(2)
+                    + (pos_0.2 - pos_1.2).powi(2))
+                .sqrt()
+                    * 1.0E3;
+                assert!(
+                    err_m < max_err_m,
+                    "{}({}): kepler solutions do not match: {} m",
+                    toe_1,
+                    sv,
+                    err_m
+                );
+
+                let dt = eph_0.clock_correction(*toc_0, *toe_1, sv, 8, false);
+                assert!(dt.is_some(), "{}({}): clock correction failed", toe_1, sv);
+                tests += 1;
+            }
+        }
+        tests
+    }
+    #[test]
+    #[cfg(feature = "nav")]
+    #[cfg(feature = "flate2")]
+    fn irnss_qzss_orbits() {
+        assert_eq!(
+            Ephemeris::timescale(Constellation::QZSS),
+            Some(TimeScale::QZSST)
+        );
+        assert_eq!(
+            Ephemeris::timescale(Constellation::IRNSS),
+            Some(TimeScale::GPST)
+        );
+
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("NAV")
+            .join("V4")
+            .join("BRD400DLR_S_20230710000_01D_MN.rnx.gz");
+        let rinex = Rinex::from_file(path.to_string_lossy().as_ref()).unwrap();
+
+        let irnss = rinex
+            .ephemeris()
+            .filter(|(_, (_, sv, _))| sv.constellation == Constellation::IRNSS)
+            .count();
+        assert_eq!(irnss, 283, "failed to parse some IRNSS frames");
+
+        let toc = Epoch::from_str("2023-03-12T20:05:36 GPST").unwrap();
+        let (_, (_, _, eph)) = rinex
+            .ephemeris()
+            .find(|(t, (_, sv, _))| **t == toc && *sv == sv!("I09"))
+            .expect("missing I09 frame");
+        assert_eq!(eph.get_week(), Some(2253));
+        assert_eq!(eph.toe(TimeScale::GPST), Some(toc));
+
+        // NavIC broadcast orbits are not as consistent as other systems
+        assert!(kepler_continuity(&rinex, Constellation::IRNSS, 100.0) > 250);
+        assert!(kepler_continuity(&rinex, Constellation::QZSS, 5.0) > 80);
+
+        let t = Epoch::from_str("2023-03-12T12:10:00 GPST").unwrap();
+        for sv in [sv!("I02"), sv!("I09"), sv!("J02"), sv!("J03")] {
+            assert!(
+                rinex.sv_orbit(sv, t).is_some(),
+                "{}({}): sv_orbit failed",
+                t,
+                sv
+            );
+        }
+
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("NAV")
+            .join("V3")
+            .join("ESBC00DNK_R_20201770000_01D_MN.rnx.gz");
+        let rinex = Rinex::from_file(path.to_string_lossy().as_ref()).unwrap();
+        assert!(kepler_continuity(&rinex, Constellation::QZSS, 5.0) > 10);
+    }
+    #[test]
+    #[cfg(feature = "nav")]
+    #[cfg(feature = "flate2")]
+    fn sbas_orbits() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("NAV")
+            .join("V4")
+            .join("BRD400DLR_S_20230710000_01D_MN.rnx.gz");
+        let rinex = Rinex::from_file(path.to_string_lossy().as_ref()).unwrap();
+
+        // quadratic propagation
+        let s28 = sv!("S28");
+        let toc = Epoch::from_str("2023-03-12T00:03:12 GPST").unwrap();
+        let t = toc + 60.0 * Unit::Second;
+        let (toc_i, toe_i, _) = rinex.sv_ephemeris(s28, t).unwrap();
+        assert_eq!(toc_i, toc);
+        assert_eq!(toe_i, toc);
+
+        let orbit = rinex.sv_orbit(s28, t).unwrap();
+        let state = orbit.to_cartesian_pos_vel();
+        let expected = [
+            5146.36984 - 1.371875E-3 * 60.0 - 2.5E-8 * 1800.0,
+            41841.50864 - 1.45625E-4 * 60.0 + 7.5E-8 * 1800.0,
+            7.75 + 2.516E-3 * 60.0 - 6.25E-8 * 1800.0,
+            -1.371875E-3 - 2.5E-8 * 60.0,
+            -1.45625E-4 + 7.5E-8 * 60.0,
+            2.516E-3 - 6.25E-8 * 60.0,
+        ];
+        for (i, expected) in expected.iter().enumerate() {
+            assert!(
+                (state[i] - expected).abs() < 1.0E-9,
+                "bad sbas state vector[{}]: {} expecting {}",
+                i,
+                state[i],
+                expected
+            );
+        }
+
+        // aGf0 + aGf1 clock model
+        let (_, _, eph) = rinex.sv_ephemeris(s28, t).unwrap();
+        let dt = eph.clock_correction(toc, t, s28, 8, false).unwrap();
+        let expected = 1.117587089539e-08 + 2.273736754432e-11 * 60.0;
+        assert!((dt.to_seconds() - expected).abs() < 1.0E-15);
+
+        // propagating one message to the next one
+        for sv in [sv!("S27"), s28, sv!("S32")] {
+            let frames = rinex
+                .ephemeris()
+                .filter_map(
+                    |(toc, (_, sv_i, eph))| {
+                        if sv_i == sv {
+                            Some((*toc, eph))
+                        } else {
+                            None
+                        }
+                    },
+                )
+                .collect::<Vec<_>>();
+            let mut errors = Vec::<f64>::new();
+            for ((toc_0, eph_0), (toc_1, eph_1)) in frames.iter().tuple_windows() {
+                if *toc_1 - *toc_0 > 10.0 * Unit::Minute {
+                    continue;
+                }
+                let (pos_0, _) = eph_0.kepler2position_velocity(sv, *toc_0, *toc_1).unwrap();
+                let (pos_1, _) = eph_1.kepler2position_velocity(sv, *toc_1, *toc_1).unwrap();
+                let err_m = ((pos_0.0 - pos_1.0).powi(2)
+                    + (pos_0.1 - pos_1.1).powi(2)
+                    + (pos_0.2 - pos_1.2).powi(2))
+                .sqrt()
+                    * 1.0E3;
+                errors.push(err_m);
+            }
+            assert!(errors.len() > 300, "{}: missing frames", sv);
+            errors.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let median = errors[errors.len() / 2];
+            assert!(
+                median < 1.0,
+                "{}: median propagation error {} m",
+                sv,
+                median
+            );
+        }
+    }
+    #[test]
+    #[cfg(feature = "nav")]
+    #[cfg(feature = "flate2")]
+    fn ephemeris_selection_policy() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("NAV")
+            .join("V4")
+            .join("KMS300DNK_R_20221591000_01H_MN.rnx.gz");
+        let rinex = Rinex::from_file(path.to_string_lossy().as_ref()).unwrap();
+
+        let e01 = sv!("E01");
+        let t = Epoch::from_str("2022-06-08T10:12:00 GST").unwrap();
+
+        // default: latest ToC, whatever the message
+        let (toc, toe, _) = rinex.sv_ephemeris(e01, t).unwrap();
+        assert_eq!(toc, Epoch::from_str("2022-06-08T10:10:00 GST").unwrap());
+        assert_eq!(toe, toc);
+
+        let nearest_toe = SelectionPolicy::default().with_criteria(SelectionCriteria::NearestToe);
+
+        let policy = nearest_toe.with_msg_type(NavMsgType::FNAV);
+        let (toc, _, eph) = rinex.sv_ephemeris_with_policy(e01, t, &policy).unwrap();
+        assert_eq!(toc, Epoch::from_str("2022-06-08T10:10:00 GST").unwrap());
+        assert_eq!(eph.clock_bias, -4.921233630739E-04);
+        assert_eq!(
+            eph.transmission_time(TimeScale::GST),
+            Some(Epoch::from_str("2022-06-08T10:22:20 GPST").unwrap())
+        );
+
+        // real-time: 10:10 frames are not transmitted yet,
+        // I/NAV 10:00 frame was transmitted at 10:11:05
+        let policy = SelectionPolicy::default().with_criteria(SelectionCriteria::LatestTransmitted);
+        let (toc, _, eph) = rinex.sv_ephemeris_with_policy(e01, t, &policy).unwrap();
+        assert_eq!(toc, Epoch::from_str("2022-06-08T10:00:00 GST").unwrap());
+        assert_eq!(eph.clock_bias, -4.921194631606E-04);
+
+        // F/NAV 10:00 frame was transmitted at 10:12:20
+        let policy = policy.with_msg_type(NavMsgType::FNAV);
+        let (toc, _, eph) = rinex.sv_ephemeris_with_policy(e01, t, &policy).unwrap();
+        assert_eq!(toc, Epoch::from_str("2022-06-08T09:50:00 GST").unwrap());
+        assert_eq!(eph.clock_bias, -4.921145737171E-04);
+        assert!(rinex.sv_orbit_with_policy(e01, t, &policy).is_some());
+
+        // nothing transmitted yet
+        let t = Epoch::from_str("2022-06-08T09:40:00 GST").unwrap();
+        assert!(rinex.sv_ephemeris_with_policy(e01, t, &policy).is_none());
+        assert!(rinex
+            .sv_ephemeris_with_policy(e01, t, &nearest_toe)
+            .is_some());
+
+        // two sided policy: frames apply on both sides of ToE
+        let t = Epoch::from_str("2022-06-08T09:30:00 GST").unwrap();
+        let (toc, _, eph) = rinex
+            .sv_ephemeris_with_policy(e01, t, &nearest_toe)
+            .unwrap();
+        assert_eq!(toc, Epoch::from_str("2022-06-08T09:40:00 GST").unwrap());
+        assert!(eph.is_valid(e01, t, true));
+        assert!(eph.clock_correction(toc, t, e01, 8, true).is_some());
+        assert!(rinex.sv_orbit_with_policy(e01, t, &nearest_toe).is_some());
+
+        // default policy: these are not valid prior ToE
+        assert!(!eph.is_valid(e01, t, false));
+        assert!(eph.clock_correction(toc, t, e01, 8, false).is_none());
+        assert!(eph.kepler2position(e01, toc, t, false).is_none());
+
+        // healthy only: E14 and E18 are flagged unhealthy all day long
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("NAV")
+            .join("V4")
+            .join("BRD400DLR_S_20230710000_01D_MN.rnx.gz");
+        let rinex = Rinex::from_file(path.to_string_lossy().as_ref()).unwrap();
+
+        let policy = nearest_toe.with_healthy_only(true);
+        for sv in [sv!("E14"), sv!("E18")] {
+            let (toc, _) = rinex
+                .ephemeris()
+                .find(|(_, (_, sv_i, _))| *sv_i == sv)
+                .unwrap();
+            let (_, _, eph) = rinex
+                .sv_ephemeris_with_policy(sv, *toc, &nearest_toe)
+                .unwrap();
+            assert!(!eph.is_healthy(sv));
+            assert!(rinex.sv_ephemeris_with_policy(sv, *toc, &policy).is_none());
+        }
+        let e01 = sv!("E01");
+        let (toc, _) = rinex
+            .ephemeris()
+            .find(|(_, (_, sv_i, _))| *sv_i == e01)
+            .unwrap();
+        assert!(rinex.sv_ephemeris_with_policy(e01, *toc, &policy).is_some());
+    }
+    #[test]
+    #[cfg(feature = "nav")]
+    #[cfg(feature = "flate2")]
+    fn sbas_ephemeris_selection() {
+        let test_resource = env!("CARGO_MANIFEST_DIR").to_owned()
+            + "/../test_resources/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz";
+        let rinex = Rinex::from_file(&test_resource).unwrap();
+
+        // S36 frames are published every 128s
+        let s36 = sv!("S36");
+        let (toc_0, toc_1) = rinex
+            .ephemeris()
+            .filter_map(|(toc, (_, sv, _))| if sv == s36 { Some(*toc) } else { None })
+            .take(2)
+            .collect_tuple()
+            .unwrap();
+
+        // closer to the next publication
+        let t = toc_1 - 40 * Unit::Second;
+
+        // default: closest ToC, on either side of t
+        let (toc, toe, _) = rinex.sv_ephemeris(s36, t).unwrap();
+        assert_eq!(toc, toc_1);
+        assert_eq!(toe, toc_1);
+        assert!(rinex.sv_orbit(s36, t).is_some());
+
+        // forward propagation only
+        let policy = SelectionPolicy::default().with_sbas_forward_only(true);
+        let (toc, _, _) = rinex.sv_ephemeris_with_policy(s36, t, &policy).unwrap();
+        assert_eq!(toc, toc_0);
+
+        // whatever the age of the frame
+        let t = toc_0 + 2 * Unit::Day;
+        assert!(rinex.sv_ephemeris(s36, t).is_none());
+        assert!(rinex.sv_ephemeris_with_policy(s36, t, &policy).is_some());
+    }
+    #[test]
+    #[cfg(feature = "nav")]
+    #[cfg(feature = "flate2")]
+    fn v4_galileo_orbit_layout() {
+        let test_resource = env!("CARGO_MANIFEST_DIR").to_owned()
+            + "/../test_resources/NAV/V4/KMS300DNK_R_20221591000_01H_MN.rnx.gz";
+        let rinex = Rinex::from_file(&test_resource).unwrap();
+
+        let e01 = sv!("E01");
+        let toc = Epoch::from_str("2022-06-08T09:40:00 GST").unwrap();
+
+        // a spare field follows the week counter: SISA and health open the next line
+        for (msg, bgd_e5b_e1, t_tm) in [
+            (NavMsgType::INAV, 4.656612873077E-10, 2.946650000000E+05),
+            (NavMsgType::FNAV, 0.0, 2.947400000000E+05),
+        ] {
+            let (_, (_, _, eph)) = rinex
+                .ephemeris()
+                .find(|(t, (msg_i, sv_i, _))| **t == toc && *msg_i == msg && *sv_i == e01)
+                .unwrap();
+            let value = |field: &str| eph.orbits.get(field).and_then(|item| item.as_f64());
+            assert_eq!(value("sisa"), Some(3.12), "{} bad SISA", msg);
+            assert_eq!(
+                eph.orbits
+                    .get("health")
+                    .and_then(|item| item.as_gal_health()),
+                Some(GalHealth::empty()),
+                "{} bad health",
+                msg
+            );
+            assert_eq!(
+                value("bgdE5aE1"),
+                Some(6.984919309616E-10),
+                "{} bad BGD",
+                msg
+            );
+            assert_eq!(value("bgdE5bE1"), Some(bgd_e5b_e1), "{} bad BGD", msg);
+            assert_eq!(value("t_tm"), Some(t_tm), "{} bad t_tm", msg);
+            assert!(eph.is_healthy(e01));
+        }
+    }
     // Computes TOE in said timescale
     fn toe_helper(week: f64, week_s: f64, ts: TimeScale) -> Epoch {
         if ts == TimeScale::GST {
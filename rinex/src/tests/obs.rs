@@ -4,6 +4,7 @@ mod test {
     use crate::observable;
     use crate::observation::SNR;
     use crate::tests::toolkit::obsrinex_check_observables;
+    use crate::tests::toolkit::test_against_model;
     use crate::tests::toolkit::test_observation_rinex;
     use crate::{erratic_time_frame, evenly_spaced_time_frame, tests::toolkit::TestTimeFrame};
     use crate::{observation::*, prelude::*};
@@ -1448,4 +1449,161 @@ mod test {
             test_combinations(combinations, signals);
         }
     */
+    #[test]
+    fn observation_stream() {
+        for path in [
+            "OBS/V2/AJAC3550.21O",
+            "OBS/V3/DUTH0630.22O",
+            "CRNX/V1/AJAC3550.21D",
+            "CRNX/V3/ACOR00ESP_R_20213550000_01D_30S_MO.crx",
+        ] {
+            let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("..")
+                .join("test_resources")
+                .join(path);
+            let model = Rinex::from_path(&path).unwrap();
+            let record = model.record.as_obs().unwrap();
+
+            let stream = ObservationStream::from_path(&path);
+            assert!(
+                stream.is_ok(),
+                "failed to open stream for {}",
+                path.display()
+            );
+            let stream = stream.unwrap();
+            assert_eq!(stream.header, model.header);
+
+            let mut count = 0;
+            for epoch in stream {
+                assert!(epoch.is_ok(), "stream error for {}", path.display());
+                let (key, clock_offset, vehicles) = epoch.unwrap();
+                let (model_offset, model_vehicles) =
+                    record.get(&key).expect("streamed unexpected epoch");
+                assert_eq!(&clock_offset, model_offset, "bad clock offset @ {:?}", key);
+                assert_eq!(&vehicles, model_vehicles, "bad content @ {:?}", key);
+                count += 1;
+            }
+            assert_eq!(count, record.len(), "missing epochs for {}", path.display());
+        }
+    }
+    #[test]
+    fn observation_stream_type_error() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("MET")
+            .join("V2")
+            .join("abvi0010.15m");
+        assert!(ObservationStream::from_path(&path).is_err());
+    }
+    #[test]
+    fn observation_writer() {
+        for path in [
+            "OBS/V2/AJAC3550.21O",
+            "OBS/V3/DUTH0630.22O",
+            "CRNX/V1/AJAC3550.21D",
+            "CRNX/V3/ACOR00ESP_R_20213550000_01D_30S_MO.crx",
+        ] {
+            let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("..")
+                .join("test_resources")
+                .join(path);
+            let model = Rinex::from_path(&path).unwrap();
+            let record = model.record.as_obs().unwrap();
+
+            let stream = ObservationStream::from_path(&path).unwrap();
+            let mut writer = ObservationWriter::new(Vec::<u8>::new(), &stream.header);
+            for epoch in stream {
+                let (key, clock_offset, vehicles) = epoch.unwrap();
+                assert!(writer.write_epoch(key, clock_offset, &vehicles).is_ok());
+            }
+            let (buf, header) = writer.finalize().unwrap();
+            let obs = header.obs.as_ref().unwrap();
+            assert_eq!(
+                obs.time_of_last_obs,
+                record.keys().last().map(|(e, _)| *e),
+                "bad TIME OF LAST OBS for {}",
+                path.display()
+            );
+
+            let copy = Rinex::from_reader(buf.as_slice());
+            assert!(copy.is_ok(), "failed to parse {} copy", path.display());
+            let copy = copy.unwrap();
+            assert_eq!(copy.header.is_crinex(), model.header.is_crinex());
+            let copy_record = copy.record.as_obs().unwrap();
+            assert_eq!(
+                copy_record.len(),
+                record.len(),
+                "missing epochs in {} copy",
+                path.display()
+            );
+            test_against_model(&copy, &model, &path.to_string_lossy(), 1.0E-6);
+        }
+    }
+    #[test]
+    fn observation_writer_in_place() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("OBS")
+            .join("V3")
+            .join("DUTH0630.22O");
+        let stream = ObservationStream::from_path(&path).unwrap();
+        let mut header = stream.header.clone();
+        if let Some(obs) = header.obs.as_mut() {
+            obs.time_of_last_obs = None;
+        }
+        let mut writer =
+            ObservationWriter::new_seekable(std::io::Cursor::new(Vec::<u8>::new()), &header)
+                .unwrap();
+        let mut last = None;
+        for epoch in stream {
+            let (key, clock_offset, vehicles) = epoch.unwrap();
+            writer.write_epoch(key, clock_offset, &vehicles).unwrap();
+            last = Some(key.0);
+        }
+        let nb_obs = writer.nb_observations().clone();
+        let (cursor, _) = writer.finalize_in_place().unwrap();
+        let bytes = cursor.into_inner();
+        let copy = Rinex::from_reader(bytes.as_slice()).unwrap();
+        let obs = copy.header.obs.as_ref().unwrap();
+        assert!(last.is_some());
+        assert_eq!(obs.time_of_last_obs, last);
+
+        // epochs were preserved, while the header grew
+        let model = Rinex::from_path(&path).unwrap();
+        assert_eq!(copy.epoch().count(), model.epoch().count());
+        test_against_model(&copy, &model, &path.to_string_lossy(), 1.0E-6);
+
+        // observation counts
+        let content = String::from_utf8(bytes).unwrap();
+        let nb_sv = model.sv().count();
+        assert_eq!(nb_obs.len(), nb_sv);
+
+        let line = content
+            .lines()
+            .find(|line| line.ends_with("# OF SATELLITES"))
+            .unwrap();
+        assert_eq!(line[..6].trim().parse::<usize>(), Ok(nb_sv));
+
+        let prn_lines = content
+            .lines()
+            .filter(|line| line.ends_with("PRN / # OF OBS") && !line.starts_with("      "))
+            .count();
+        assert_eq!(prn_lines, nb_sv);
+
+        let g01 = sv!("G01");
+        let expected = model
+            .record
+            .as_obs()
+            .unwrap()
+            .values()
+            .filter_map(|(_, vehicles)| vehicles.get(&g01))
+            .filter(|observations| observations.contains_key(&Observable::from_str("C1C").unwrap()))
+            .count();
+        assert_eq!(
+            nb_obs[&g01].get(&Observable::from_str("C1C").unwrap()),
+            Some(&(expected as u32))
+        );
+    }
 }
@@ -29,11 +29,14 @@ mod test {
                         continue; // not a test resource
                     }
 
-                    let mut is_gzip_encoded = entry.file_name().to_str().unwrap().ends_with(".gz");
-                    is_gzip_encoded |= entry.file_name().to_str().unwrap().ends_with(".Z");
+                    let is_gzip_encoded = entry.file_name().to_str().unwrap().ends_with(".gz");
                     if is_gzip_encoded && !cfg!(feature = "flate2") {
                         continue; // do not run in this build configuration
                     }
+                    let is_lzw_encoded = entry.file_name().to_str().unwrap().ends_with(".Z");
+                    if is_lzw_encoded && !cfg!(feature = "lzw") {
+                        continue; // do not run in this build configuration
+                    }
                     println!("Parsing \"{}\"", full_path);
                     let rinex = Rinex::from_file(full_path);
                     assert!(
@@ -166,4 +169,45 @@ mod test {
             }
         }
     }
+    #[test]
+    #[cfg(feature = "lzw")]
+    fn lzw_compressed_obs() {
+        let prefix = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("../test_resources/OBS/V2");
+        let plain = Rinex::from_path(&prefix.join("AJAC3550.21O")).unwrap();
+        let compressed = Rinex::from_path(&prefix.join("AJAC3550.21O.Z"));
+        assert!(
+            compressed.is_ok(),
+            "failed to parse AJAC3550.21O.Z: {:?}",
+            compressed.err()
+        );
+        let compressed = compressed.unwrap();
+        assert_eq!(compressed.header, plain.header);
+        assert_eq!(compressed.record, plain.record);
+    }
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn from_reader() {
+        let prefix = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("../test_resources/CRNX/V3");
+        // gzip + hatanaka compressed content,
+        // identified by content sniffing
+        let path = prefix.join("AJAC00FRA_R_20242090000_01D_30S_MO.crx.gz");
+        let content = std::fs::read(&path).unwrap();
+        let rinex = Rinex::from_reader(content.as_slice());
+        assert!(
+            rinex.is_ok(),
+            "failed to parse in-memory content: {:?}",
+            rinex.err()
+        );
+        let rinex = rinex.unwrap();
+        assert!(rinex.is_observation_rinex());
+        assert!(rinex.header.obs.as_ref().unwrap().crinex.is_some());
+
+        let model = Rinex::from_path(&path).unwrap();
+        assert_eq!(rinex.header, model.header);
+        assert_eq!(rinex.record, model.record);
+    }
 }
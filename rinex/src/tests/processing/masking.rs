@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod test {
     use crate::prelude::*;
+    use gnss_rs::sv;
     use itertools::Itertools;
     use qc_traits::processing::{Filter, FilterItem, MaskOperand, Preprocessing};
     use std::str::FromStr;
@@ -225,4 +226,68 @@ mod test {
         assert_eq!(dut.constellation().count(), 1);
         assert_eq!(dut.carrier().collect::<Vec<_>>(), vec![Carrier::G2(None)]);
     }
+    #[test]
+    #[cfg(feature = "nav")]
+    fn nav_health_v4_brd400dlr() {
+        let rinex =
+            Rinex::from_file("../test_resources/NAV/V4/BRD400DLR_S_20230710000_01D_MN.rnx.gz")
+                .unwrap();
+        let (e01, e14, e18) = (sv!("E01"), sv!("E14"), sv!("E18"));
+
+        let healthy = Filter::from_str("=healthy").unwrap();
+        let dut = rinex.filter(&healthy);
+        let svs = dut.sv().collect::<Vec<_>>();
+        assert!(svs.contains(&e01), "E01 should be retained");
+        assert!(!svs.contains(&e14), "E14 is unhealthy all day long");
+        assert!(!svs.contains(&e18), "E18 is unhealthy all day long");
+        for (_, (_, sv, eph)) in dut.ephemeris() {
+            assert!(
+                eph.is_healthy(sv),
+                "{} frame should have been masked out",
+                sv
+            );
+        }
+
+        let unhealthy = Filter::from_str("!=healthy").unwrap();
+        let dut = rinex.filter(&unhealthy);
+        let svs = dut.sv().collect::<Vec<_>>();
+        assert!(svs.contains(&e14));
+        assert!(svs.contains(&e18));
+        assert!(!svs.contains(&e01), "E01 is healthy all day long");
+    }
+    #[test]
+    #[cfg(all(feature = "nav", feature = "flate2"))]
+    fn obs_health_v3_esbc00dnk() {
+        let obs =
+            Rinex::from_file("../test_resources/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz")
+                .unwrap();
+        let nav =
+            Rinex::from_file("../test_resources/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+                .unwrap();
+
+        // SBAS vehicles do not publish their URA on that day
+        let s36 = sv!("S36");
+        let (_, _, eph) = nav.sv_ephemeris(s36, obs.first_epoch().unwrap()).unwrap();
+        assert!(!eph.is_healthy(s36));
+
+        let healthy = MaskFilter::from_str("=healthy").unwrap();
+        let dut = obs.mask_with_nav(&healthy, &nav);
+        assert_eq!(dut.sv().filter(|sv| sv.constellation.is_sbas()).count(), 0);
+        assert_eq!(
+            dut.sv()
+                .filter(|sv| sv.constellation == Constellation::GPS)
+                .count(),
+            31
+        );
+
+        let unhealthy = MaskFilter::from_str("!=healthy").unwrap();
+        let dut = obs.mask_with_nav(&unhealthy, &nav);
+        assert_eq!(dut.sv().filter(|sv| sv.constellation.is_sbas()).count(), 5);
+        assert_eq!(
+            dut.sv()
+                .filter(|sv| sv.constellation == Constellation::GPS)
+                .count(),
+            0
+        );
+    }
 }
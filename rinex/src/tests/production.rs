@@ -119,7 +119,6 @@ mod test {
     }
     #[test]
     #[cfg(feature = "flate2")]
-    #[ignore]
     fn nav_v4() {
         let folder = env!("CARGO_MANIFEST_DIR").to_owned() + "/../test_resources/NAV/V4/";
         for file in std::fs::read_dir(folder).unwrap() {
@@ -128,4 +127,63 @@ mod test {
             testbench(fp.to_str().unwrap());
         }
     }
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn ionex_v1() {
+        let folder = env!("CARGO_MANIFEST_DIR").to_owned() + "/../test_resources/IONEX/V1/";
+        for file in std::fs::read_dir(folder).unwrap() {
+            let fp = file.unwrap();
+            let fp = fp.path();
+            testbench(fp.to_str().unwrap());
+        }
+    }
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn antex_v1() {
+        let folder = env!("CARGO_MANIFEST_DIR").to_owned() + "/../test_resources/ATX/V1/";
+        for file in std::fs::read_dir(folder).unwrap() {
+            let fp = file.unwrap();
+            let fp = fp.path();
+            testbench(fp.to_str().unwrap());
+        }
+    }
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn doris_v3() {
+        let folder = env!("CARGO_MANIFEST_DIR").to_owned() + "/../test_resources/DOR/V3/";
+        for file in std::fs::read_dir(folder).unwrap() {
+            let fp = file.unwrap();
+            let fp = fp.path();
+            testbench(fp.to_str().unwrap());
+        }
+    }
+    #[test]
+    fn to_writer() {
+        let path = env!("CARGO_MANIFEST_DIR").to_owned() + "/../test_resources/OBS/V3/DUTH0630.22O";
+        let rnx = Rinex::from_file(&path).unwrap();
+        let mut buf = Vec::<u8>::new();
+        assert!(rnx.to_writer(&mut buf).is_ok());
+        let copy = Rinex::from_reader(buf.as_slice());
+        assert!(copy.is_ok(), "failed to parse in-memory copy");
+        let copy = copy.unwrap();
+        if copy != rnx {
+            test_against_model(&copy, &rnx, &path, 1.0E-6);
+        }
+    }
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn to_gzip_writer() {
+        let path = env!("CARGO_MANIFEST_DIR").to_owned() + "/../test_resources/OBS/V3/DUTH0630.22O";
+        let rnx = Rinex::from_file(&path).unwrap();
+        let buf = rnx.to_gzip_writer(Vec::<u8>::new());
+        assert!(buf.is_ok(), "gzip compression failed");
+        let buf = buf.unwrap();
+        assert_eq!(&buf[..2], &[0x1f, 0x8b], "not a gzip stream");
+        let copy = Rinex::from_reader(buf.as_slice());
+        assert!(copy.is_ok(), "failed to parse in-memory gzip copy");
+        let copy = copy.unwrap();
+        if copy != rnx {
+            test_against_model(&copy, &rnx, &path, 1.0E-6);
+        }
+    }
 }
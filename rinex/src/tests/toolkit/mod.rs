@@ -473,6 +473,183 @@ fn meteo_against_model(dut: &Rinex, model: &Rinex, filename: &str, _epsilon: f64
     }
 }
 
+/*
+ * DORIS thorough comparison
+ */
+fn doris_against_model(dut: &Rinex, model: &Rinex, filename: &str, epsilon: f64) {
+    let rec_dut = dut
+        .record
+        .as_doris()
+        .expect("failed to unwrap rinex record");
+    let rec_model = model
+        .record
+        .as_doris()
+        .expect("failed to unwrap rinex record");
+    for (k_model, (clk_model, stations_model)) in rec_model.iter() {
+        if let Some((clk_dut, stations_dut)) = rec_dut.get(k_model) {
+            assert_eq!(
+                clk_model, clk_dut,
+                "\"{}\" - {:?} - faulty clock offset",
+                filename, k_model
+            );
+            for (station, observables_model) in stations_model.iter() {
+                if let Some(observables_dut) = stations_dut.get(station) {
+                    for (observable, data_model) in observables_model.iter() {
+                        if let Some(data_dut) = observables_dut.get(observable) {
+                            assert!(
+                                (data_model.value - data_dut.value).abs() < epsilon,
+                                "\"{}\" - {:?} - {} \"{}\" - faulty value - expecting {} - got {}",
+                                filename,
+                                k_model,
+                                station.label,
+                                observable,
+                                data_model.value,
+                                data_dut.value
+                            );
+                            assert_eq!(
+                                (data_model.m1, data_model.m2),
+                                (data_dut.m1, data_dut.m2),
+                                "\"{}\" - {:?} - {} \"{}\" - faulty flags",
+                                filename,
+                                k_model,
+                                station.label,
+                                observable
+                            );
+                        } else {
+                            panic!(
+                                "\"{}\" - {:?} - {} missing \"{}\" observation",
+                                filename, k_model, station.label, observable
+                            );
+                        }
+                    }
+                } else {
+                    panic!(
+                        "\"{}\" - {:?} - missing station {}",
+                        filename, k_model, station.label
+                    );
+                }
+            }
+        } else {
+            panic!("\"{}\" - missing epoch {:?}", filename, k_model);
+        }
+    }
+    for k_dut in rec_dut.keys() {
+        if rec_model.get(k_dut).is_none() {
+            panic!("\"{}\" - parsed {:?} unexpectedly", filename, k_dut);
+        }
+    }
+}
+
+/*
+ * ANTEX thorough comparison
+ */
+fn antex_against_model(dut: &Rinex, model: &Rinex, filename: &str, _epsilon: f64) {
+    let rec_dut = dut
+        .record
+        .as_antex()
+        .expect("failed to unwrap rinex record");
+    let rec_model = model
+        .record
+        .as_antex()
+        .expect("failed to unwrap rinex record");
+    assert_eq!(
+        rec_dut.len(),
+        rec_model.len(),
+        "\"{}\" - bad number of antennas",
+        filename
+    );
+    for ((ant_dut, freqs_dut), (ant_model, freqs_model)) in rec_dut.iter().zip(rec_model.iter()) {
+        assert_eq!(ant_dut, ant_model, "\"{}\" - faulty antenna", filename);
+        for (carrier, data_model) in freqs_model.iter() {
+            if let Some(data_dut) = freqs_dut.get(carrier) {
+                assert_eq!(
+                    data_dut, data_model,
+                    "\"{}\" - {:?} - faulty {} specs",
+                    filename, ant_model.specific, carrier
+                );
+            } else {
+                panic!(
+                    "\"{}\" - {:?} - missing {} specs",
+                    filename, ant_model.specific, carrier
+                );
+            }
+        }
+        for carrier in freqs_dut.keys() {
+            if freqs_model.get(carrier).is_none() {
+                panic!(
+                    "\"{}\" - {:?} - parsed {} unexpectedly",
+                    filename, ant_model.specific, carrier
+                );
+            }
+        }
+    }
+}
+
+/*
+ * IONEX thorough comparison
+ */
+fn ionex_against_model(dut: &Rinex, model: &Rinex, filename: &str, epsilon: f64) {
+    let rec_dut = dut
+        .record
+        .as_ionex()
+        .expect("failed to unwrap rinex record");
+    let rec_model = model
+        .record
+        .as_ionex()
+        .expect("failed to unwrap rinex record");
+    for (k_model, plane_model) in rec_model.iter() {
+        if let Some(plane_dut) = rec_dut.get(k_model) {
+            for (coords, tec_model) in plane_model.iter() {
+                if let Some(tec_dut) = plane_dut.get(coords) {
+                    assert!(
+                        (tec_model.tec - tec_dut.tec).abs() < epsilon,
+                        "\"{}\" - {:?} - {:?} - faulty TEC - expecting {} - got {}",
+                        filename,
+                        k_model,
+                        coords,
+                        tec_model.tec,
+                        tec_dut.tec
+                    );
+                    for (model, dut, field) in [
+                        (tec_model.rms, tec_dut.rms, "RMS"),
+                        (tec_model.height, tec_dut.height, "height"),
+                    ] {
+                        match (model, dut) {
+                            (Some(model), Some(dut)) => assert!(
+                                (model - dut).abs() < epsilon,
+                                "\"{}\" - {:?} - {:?} - faulty {} - expecting {} - got {}",
+                                filename,
+                                k_model,
+                                coords,
+                                field,
+                                model,
+                                dut
+                            ),
+                            (None, None) => {},
+                            _ => panic!(
+                                "\"{}\" - {:?} - {:?} - {} mismatch",
+                                filename, k_model, coords, field
+                            ),
+                        }
+                    }
+                } else {
+                    panic!(
+                        "\"{}\" - {:?} - missing grid point {:?}",
+                        filename, k_model, coords
+                    );
+                }
+            }
+        } else {
+            panic!("\"{}\" - missing map {:?}", filename, k_model);
+        }
+    }
+    for k_dut in rec_dut.keys() {
+        if rec_model.get(k_dut).is_none() {
+            panic!("\"{}\" - parsed {:?} unexpectedly", filename, k_dut);
+        }
+    }
+}
+
 /*
  * Compares "dut" Device Under Test to given Model,
  * panics on unexpected content with detailed explanations.
@@ -486,6 +663,12 @@ pub fn test_against_model(dut: &Rinex, model: &Rinex, filename: &str, epsilon: f
         clocks_against_model(dut, model, filename, epsilon);
     } else if dut.is_navigation_rinex() {
         navigation_against_model(dut, model, filename, epsilon);
+    } else if dut.is_ionex() {
+        ionex_against_model(dut, model, filename, epsilon);
+    } else if dut.is_antex() {
+        antex_against_model(dut, model, filename, epsilon);
+    } else if dut.is_doris() {
+        doris_against_model(dut, model, filename, epsilon);
     }
 }
 
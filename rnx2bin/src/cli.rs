@@ -0,0 +1,62 @@
+use clap::{Arg, ArgAction, ArgMatches, ColorChoice, Command};
+use std::path::{Path, PathBuf};
+
+pub struct Cli {
+    /// arguments passed by user
+    pub matches: ArgMatches,
+}
+
+impl Cli {
+    pub fn new() -> Self {
+        Self {
+            matches: {
+                Command::new("rnx2bin")
+                    .author("Guillaume W. Bres <guillaume.bressaix@gmail.com>")
+                    .version(env!("CARGO_PKG_VERSION"))
+                    .about("RINEX to BINEX conversion tool")
+                    .arg_required_else_help(true)
+                    .color(ColorChoice::Always)
+                    .arg(
+                        Arg::new("filepath")
+                            .short('f')
+                            .long("fp")
+                            .help("Input RINEX file (NAV or any RINEX describing a site)")
+                            .required(true),
+                    )
+                    .arg(
+                        Arg::new("output")
+                            .short('o')
+                            .long("output")
+                            .action(ArgAction::Set)
+                            .help("Custom output filename. Otherwise, we name the BINEX stream after the input file."),
+                    )
+                    .arg(
+                        Arg::new("gzip")
+                            .short('z')
+                            .long("gzip")
+                            .action(ArgAction::SetTrue)
+                            .help("Gzip compress the BINEX stream"),
+                    )
+                    .arg(
+                        Arg::new("workspace")
+                            .short('w')
+                            .long("workspace")
+                            .help("Define custom workspace location"),
+                    )
+                    .get_matches()
+            },
+        }
+    }
+    pub fn input_path(&self) -> PathBuf {
+        Path::new(self.matches.get_one::<String>("filepath").unwrap()).to_path_buf()
+    }
+    pub fn output_name(&self) -> Option<&String> {
+        self.matches.get_one::<String>("output")
+    }
+    pub fn gzip(&self) -> bool {
+        self.matches.get_flag("gzip")
+    }
+    pub fn workspace(&self) -> Option<&String> {
+        self.matches.get_one::<String>("workspace")
+    }
+}
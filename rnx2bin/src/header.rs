@@ -0,0 +1,135 @@
+//! RINEX header fields to BINEX site records
+use binex::prelude::{MonumentGeoMetadata, MonumentGeoRecord, Solutions};
+
+use rinex::prelude::{Epoch, Header};
+
+/// Describes the site of this [Header] as a [MonumentGeoRecord], at [Epoch].
+/// Only fields that the [Header] describes are encoded.
+pub fn monument_geo(t: Epoch, header: &Header) -> MonumentGeoRecord {
+    let mut geo = MonumentGeoRecord {
+        epoch: t,
+        meta: MonumentGeoMetadata::RNX2BIN,
+        ..Default::default()
+    };
+
+    for comment in header.comments.iter() {
+        geo = geo.with_comment(comment);
+    }
+
+    if let Some(marker) = &header.geodetic_marker {
+        if !marker.name.is_empty() {
+            geo = geo.with_geodetic_marker_name(&marker.name);
+        }
+        if let Some(number) = marker.number() {
+            geo = geo.with_geodetic_marker_number(&number);
+        }
+    }
+
+    if !header.observer.is_empty() {
+        geo = geo.with_observer(&header.observer);
+    }
+
+    if !header.agency.is_empty() {
+        geo = geo.with_agency(&header.agency);
+    }
+
+    if let Some(rcvr) = &header.rcvr {
+        if !rcvr.model.is_empty() {
+            geo = geo.with_receiver_model(&rcvr.model);
+        }
+        if !rcvr.sn.is_empty() {
+            geo = geo.with_receiver_serial_number(&rcvr.sn);
+        }
+        if !rcvr.firmware.is_empty() {
+            geo = geo.with_receiver_firm_version(&rcvr.firmware);
+        }
+    }
+
+    if let Some(antenna) = &header.rcvr_antenna {
+        if !antenna.model.is_empty() {
+            geo = geo.with_antenna_model(&antenna.model);
+        }
+        if !antenna.sn.is_empty() {
+            geo = geo.with_antenna_serial_number(&antenna.sn);
+        }
+        if antenna.height.is_some() || antenna.eastern.is_some() || antenna.northern.is_some() {
+            geo = geo.with_antenna_offset(
+                antenna.height.unwrap_or_default(),
+                antenna.eastern.unwrap_or_default(),
+                antenna.northern.unwrap_or_default(),
+            );
+        }
+    }
+
+    if let Some(position) = &header.ground_position {
+        let (x, y, z) = position.to_ecef_wgs84();
+        geo = geo.with_antenna_ecef_position(x, y, z);
+    }
+
+    geo
+}
+
+/// Describes the reference position of this [Header] as
+/// a position [Solutions] frame, at [Epoch], when it is known.
+pub fn reference_position(t: Epoch, header: &Header) -> Option<Solutions> {
+    let (x, y, z) = header.ground_position?.to_ecef_wgs84();
+    Some(Solutions::new(t).with_position_ecef_wgs84(x, y, z))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rinex::{
+        hardware::{Antenna, Receiver},
+        marker::GeodeticMarker,
+        prelude::GroundPosition,
+    };
+
+    #[test]
+    fn site_description() {
+        let t = Epoch::from_gpst_seconds(60.0);
+
+        let mut header = Header::basic_obs();
+        header.agency = "SDFE".to_string();
+        header.comments = vec!["converted".to_string()];
+        header.geodetic_marker = Some(
+            GeodeticMarker::default()
+                .with_name("ESBC")
+                .with_number("10118M001"),
+        );
+        header.rcvr = Some(Receiver {
+            model: "SEPT POLARX5".to_string(),
+            sn: "3013312".to_string(),
+            firmware: String::new(),
+        });
+        header.rcvr_antenna = Some(
+            Antenna::default()
+                .with_model("TRM57971.00")
+                .with_height(0.1),
+        );
+        header.ground_position = Some(GroundPosition::from_ecef_wgs84((
+            3582105.291,
+            532589.7313,
+            5232754.8054,
+        )));
+
+        let geo = monument_geo(t, &header);
+
+        assert_eq!(geo.epoch, t);
+        assert_eq!(geo.meta, MonumentGeoMetadata::RNX2BIN);
+        assert_eq!(geo.comments, vec!["converted".to_string()]);
+        assert_eq!(geo.geodetic_marker_name(), Some("ESBC"));
+        assert_eq!(geo.geodetic_marker_number(), Some("10118M001"));
+        assert_eq!(geo.agency(), Some("SDFE"));
+        assert_eq!(geo.receiver_model(), Some("SEPT POLARX5"));
+        assert_eq!(geo.receiver_serial_number(), Some("3013312"));
+        assert_eq!(geo.receiver_firm_version(), None);
+        assert_eq!(geo.antenna_model(), Some("TRM57971.00"));
+        assert_eq!(geo.antenna_offset3d, Some((0.1, 0.0, 0.0)));
+        assert!(geo.antenna_ecef3d.is_some());
+
+        let solutions = reference_position(t, &header).unwrap();
+        assert_eq!(solutions.epoch, t);
+        assert_eq!(solutions.frames.len(), 1);
+    }
+}
@@ -0,0 +1,263 @@
+//! RINEX to BINEX converter.
+//! Encodes the ephemerides and site description
+//! of a RINEX file, into a BINEX stream.
+//! Homepage: <https://github.com/georust/rinex>
+mod cli;
+mod header;
+mod nav;
+
+use cli::Cli;
+
+use std::fs::File;
+use std::io::{BufRead, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use binex::prelude::{Encoder, Error as BinexError, Message, Meta, Record};
+
+use rinex::{
+    navigation,
+    prelude::{Constellation, Epoch, Header, Rinex, RinexType},
+    reader::{BufferedReader, SniffedReader},
+    record::{is_new_epoch, parse_record},
+};
+
+/// Conversion errors
+#[derive(Debug)]
+enum Error {
+    /// Failed to encode the output BINEX stream
+    BinexError(BinexError),
+    /// Failed to read the input RINEX
+    RinexError(rinex::Error),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::BinexError(e) => write!(f, "binex error: {:?}", e),
+            Self::RinexError(e) => write!(f, "rinex error: {}", e),
+        }
+    }
+}
+
+impl From<BinexError> for Error {
+    fn from(e: BinexError) -> Self {
+        Self::BinexError(e)
+    }
+}
+
+impl From<rinex::Error> for Error {
+    fn from(e: rinex::Error) -> Self {
+        Self::RinexError(e)
+    }
+}
+
+impl From<rinex::header::ParsingError> for Error {
+    fn from(e: rinex::header::ParsingError) -> Self {
+        Self::RinexError(e.into())
+    }
+}
+
+impl From<rinex::record::Error> for Error {
+    fn from(e: rinex::record::Error) -> Self {
+        Self::RinexError(e.into())
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::RinexError(e.into())
+    }
+}
+
+/// Streams [Record]s into the BINEX [Encoder], one at a time.
+struct Stream<W: Write> {
+    meta: Meta,
+    encoder: Encoder<W>,
+    /// Total number of encoded messages
+    total: usize,
+}
+
+impl<W: Write> Stream<W> {
+    fn encode(&mut self, record: Record) -> Result<(), Error> {
+        self.encoder.encode(&Message::new(self.meta, record))?;
+        self.total += 1;
+        Ok(())
+    }
+
+    /// Encodes the site description of this [Header], at [Epoch].
+    fn site(&mut self, t: Epoch, header: &Header) -> Result<(), Error> {
+        self.encode(Record::new_monument_geo(header::monument_geo(t, header)))?;
+
+        if let Some(solutions) = header::reference_position(t, header) {
+            self.encode(Record::new_solutions(solutions))?;
+        }
+
+        Ok(())
+    }
+
+    /// Parses one NAV epoch and encodes its ephemeris frame, if any.
+    /// The site description is encoded ahead of the first frame.
+    fn nav_epoch(
+        &mut self,
+        header: &Header,
+        content: &str,
+        site_pending: &mut bool,
+    ) -> Result<(), Error> {
+        let constellation = header.constellation.unwrap_or(Constellation::Mixed);
+
+        let (toc, fr) =
+            match navigation::record::parse_epoch(header.version, constellation, content) {
+                Ok((toc, fr)) => (toc, fr),
+                Err(_) => return Ok(()),
+            };
+
+        if *site_pending {
+            self.site(toc, header)?;
+            *site_pending = false;
+        }
+
+        if let Some((msg, sv, eph)) = fr.as_eph() {
+            if let Some(frame) = nav::ephemeris_frame(toc, msg, sv, eph) {
+                self.encode(Record::new_ephemeris_frame(frame))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns true if this line is a RINEX comment
+fn is_comment(line: &str) -> bool {
+    line.len() > 60 && line.trim_end().ends_with("COMMENT")
+}
+
+fn workspace(cli: &Cli) -> PathBuf {
+    if let Some(workspace) = cli.workspace() {
+        Path::new(workspace).to_path_buf()
+    } else {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("WORKSPACE")
+    }
+}
+
+fn create_workspace(path: &Path) {
+    std::fs::create_dir_all(path).unwrap_or_else(|_| {
+        panic!(
+            "failed to create workspace \"{}\": permission denied",
+            path.to_string_lossy(),
+        )
+    });
+}
+
+/// Input file name, without extensions
+fn input_name(path: &Path) -> String {
+    path.file_name()
+        .expect("failed to determine input file name")
+        .to_str()
+        .expect("failed to determine input file name")
+        .split('.')
+        .next()
+        .expect("failed to determine input file name")
+        .to_string()
+}
+
+fn main() -> Result<(), Error> {
+    let cli = Cli::new();
+    let gzip = cli.gzip();
+
+    let input_path = cli.input_path();
+    let input_name = input_name(&input_path);
+    println!("converting \"{}\"..", input_name);
+
+    let reader = BufferedReader::new(&input_path.to_string_lossy())?;
+    let mut reader = SniffedReader::new(reader)?;
+
+    let mut header = Header::new(&mut reader)?;
+
+    let workspace_path = workspace(&cli).join(&input_name);
+
+    create_workspace(&workspace_path);
+
+    let output_name = match cli.output_name() {
+        Some(name) => name.clone(),
+        None => {
+            if gzip {
+                format!("{}.bnx.gz", input_name)
+            } else {
+                format!("{}.bnx", input_name)
+            }
+        },
+    };
+
+    let output_path = workspace_path.join(output_name);
+
+    let fd = File::create(&output_path)?;
+    let writer = BufWriter::new(fd);
+
+    let encoder = if gzip {
+        Encoder::new_gzip(writer)
+    } else {
+        Encoder::new(writer)
+    };
+
+    let mut stream = Stream {
+        // forward, big endian, standard CRC
+        meta: Meta {
+            reversed: false,
+            big_endian: true,
+            enhanced_crc: false,
+        },
+        encoder,
+        total: 0,
+    };
+
+    if header.rinex_type == RinexType::NavigationData {
+        // ephemerides are encoded one epoch at a time
+        let mut site_pending = true;
+        let mut content = String::with_capacity(8 * 80);
+
+        for line in reader.lines() {
+            let line = line?;
+
+            if is_comment(&line) {
+                continue;
+            }
+
+            if is_new_epoch(&line, &header) && !content.is_empty() {
+                stream.nav_epoch(&header, &content, &mut site_pending)?;
+                content.clear();
+            }
+
+            content.push_str(&line);
+            content.push('\n');
+        }
+
+        if !content.is_empty() {
+            stream.nav_epoch(&header, &content, &mut site_pending)?;
+        }
+    } else {
+        // site description only, timestamped by the first epoch
+        let t = match header.obs.as_ref().and_then(|obs| obs.time_of_first_obs) {
+            Some(t) => Some(t),
+            None => {
+                let (record, _) = parse_record(&mut reader, &mut header)?;
+                Rinex::new(header.clone(), record).first_epoch()
+            },
+        };
+
+        if let Some(t) = t {
+            stream.site(t, &header)?;
+        }
+    }
+
+    stream.encoder.into_inner()?;
+
+    println!(
+        "\"{}\" generated ({} messages)",
+        output_path.to_string_lossy(),
+        stream.total
+    );
+
+    Ok(())
+}
@@ -0,0 +1,324 @@
+//! RINEX navigation frames to BINEX ephemeris frames
+use std::f64::consts::PI;
+
+use binex::prelude::{EphemerisFrame, GALEphemeris, GLOEphemeris, GPSEphemeris, SBASEphemeris};
+
+use rinex::navigation::{Ephemeris, GloHealth, NavMsgType};
+use rinex::prelude::{Constellation, Epoch, SV};
+
+/// Converts one RINEX Ephemeris frame, published at ToC [Epoch],
+/// to a BINEX [EphemerisFrame]. Only legacy GPS, Galileo (I/NAV, F/NAV),
+/// Glonass and SBAS frames may be encoded.
+pub fn ephemeris_frame(
+    toc: Epoch,
+    msg: NavMsgType,
+    sv: SV,
+    eph: &Ephemeris,
+) -> Option<EphemerisFrame> {
+    match (sv.constellation, msg) {
+        (Constellation::GPS, NavMsgType::LNAV) => Some(EphemerisFrame::new_gps(gps(toc, sv, eph))),
+        (Constellation::Galileo, NavMsgType::LNAV | NavMsgType::INAV | NavMsgType::FNAV) => {
+            Some(EphemerisFrame::new_galileo(gal(toc, sv, eph)))
+        },
+        (Constellation::Glonass, NavMsgType::LNAV | NavMsgType::FDMA) => {
+            Some(EphemerisFrame::new_glonass(glo(toc, sv, eph)))
+        },
+        (c, NavMsgType::LNAV | NavMsgType::SBAS) if c.is_sbas() => {
+            Some(EphemerisFrame::new_sbas(sbas(toc, sv, eph)))
+        },
+        _ => None,
+    }
+}
+
+/// Returns orbit field as f64, null when not described
+fn orbit_f64(eph: &Ephemeris, field: &str) -> f64 {
+    eph.orbits
+        .get(field)
+        .and_then(|value| value.as_f64())
+        .unwrap_or(0.0)
+}
+
+/// Returns GPS week counter and second of week
+fn week_tow(t: Epoch) -> (u16, i32) {
+    let seconds = (t - Epoch::from_gpst_seconds(0.0)).to_seconds();
+    let week = (seconds / 604_800.0).floor();
+    (week as u16, (seconds - week * 604_800.0).round() as i32)
+}
+
+/// GPS ephemeris (0x01-01). BINEX encodes PRN - 1, the week counter
+/// that goes with ToC, and fit interval, L2P flag and L2 codes in uint2.
+fn gps(toc: Epoch, sv: SV, eph: &Ephemeris) -> GPSEphemeris {
+    let (week, tow) = week_tow(toc);
+
+    let fit_int = orbit_f64(eph, "fitInt") as u16;
+    let l2p = orbit_f64(eph, "l2pDataFlag") as u16;
+    let l2_codes = orbit_f64(eph, "l2Codes") as u16;
+
+    let sv_health = eph
+        .orbits
+        .get("health")
+        .and_then(|value| value.as_gps_health())
+        .map(|health| health.bits() as u16)
+        .unwrap_or_default();
+
+    GPSEphemeris {
+        sv_prn: sv.prn.saturating_sub(1),
+        toe: week,
+        tow: orbit_f64(eph, "t_tm") as i32,
+        toc: tow,
+        tgd: orbit_f64(eph, "tgd") as f32,
+        iodc: orbit_f64(eph, "iodc") as i32,
+        clock_offset: eph.clock_bias as f32,
+        clock_drift: eph.clock_drift as f32,
+        clock_drift_rate: eph.clock_drift_rate as f32,
+        iode: orbit_f64(eph, "iode") as i32,
+        delta_n_rad_s: orbit_f64(eph, "deltaN") as f32,
+        m0_rad: orbit_f64(eph, "m0"),
+        e: orbit_f64(eph, "e"),
+        sqrt_a: orbit_f64(eph, "sqrta"),
+        cic: orbit_f64(eph, "cic") as f32,
+        crc: orbit_f64(eph, "crc") as f32,
+        cis: orbit_f64(eph, "cis") as f32,
+        crs: orbit_f64(eph, "crs") as f32,
+        cuc: orbit_f64(eph, "cuc") as f32,
+        cus: orbit_f64(eph, "cus") as f32,
+        omega_0_rad: orbit_f64(eph, "omega0"),
+        omega_rad: orbit_f64(eph, "omega"),
+        i0_rad: orbit_f64(eph, "i0"),
+        omega_dot_rad_s: orbit_f64(eph, "omegaDot") as f32,
+        i_dot_rad_s: orbit_f64(eph, "idot") as f32,
+        ura_m: orbit_f64(eph, "svAccuracy") as f32,
+        sv_health,
+        uint2: (fit_int & 0xff) | ((l2p & 0x01) << 8) | ((l2_codes & 0x03) << 9),
+    }
+}
+
+/// Galileo ephemeris (0x01-04). BINEX encodes PRN - 1, a week counter
+/// aligned to GPS, and angular rates in semi circles.
+fn gal(toc: Epoch, sv: SV, eph: &Ephemeris) -> GALEphemeris {
+    let (week, tow) = week_tow(toc);
+
+    let sv_health = eph
+        .orbits
+        .get("health")
+        .and_then(|value| value.as_gal_health())
+        .map(|health| health.bits())
+        .unwrap_or_default();
+
+    GALEphemeris {
+        sv_prn: sv.prn.saturating_sub(1),
+        toe_week: week,
+        tow: orbit_f64(eph, "t_tm") as i32,
+        toe_s: tow,
+        bgd_e5a_e1_s: orbit_f64(eph, "bgdE5aE1") as f32,
+        bgd_e5b_e1_s: orbit_f64(eph, "bgdE5bE1") as f32,
+        iodnav: orbit_f64(eph, "iodnav") as i32,
+        clock_drift_rate: eph.clock_drift_rate as f32,
+        clock_drift: eph.clock_drift as f32,
+        clock_offset: eph.clock_bias as f32,
+        delta_n_semi_circles_s: (orbit_f64(eph, "deltaN") / PI) as f32,
+        m0_rad: orbit_f64(eph, "m0"),
+        e: orbit_f64(eph, "e"),
+        sqrt_a: orbit_f64(eph, "sqrta"),
+        cic: orbit_f64(eph, "cic") as f32,
+        crc: orbit_f64(eph, "crc") as f32,
+        cis: orbit_f64(eph, "cis") as f32,
+        crs: orbit_f64(eph, "crs") as f32,
+        cuc: orbit_f64(eph, "cuc") as f32,
+        cus: orbit_f64(eph, "cus") as f32,
+        omega_0_rad: orbit_f64(eph, "omega0"),
+        omega_rad: orbit_f64(eph, "omega"),
+        i0_rad: orbit_f64(eph, "i0"),
+        omega_dot_semi_circles: (orbit_f64(eph, "omegaDot") / PI) as f32,
+        idot_semi_circles_s: (orbit_f64(eph, "idot") / PI) as f32,
+        sisa: orbit_f64(eph, "sisa") as f32,
+        sv_health,
+        source: orbit_f64(eph, "dataSrc") as u16,
+    }
+}
+
+/// Glonass ephemeris (0x01-02): ToC is described by its day counter
+/// since GPST origin, and time of day, in UTC.
+fn glo(toc: Epoch, sv: SV, eph: &Ephemeris) -> GLOEphemeris {
+    let (y, m, d, hh, mm, ss, _) = toc.to_gregorian_utc();
+
+    let day = Epoch::from_gregorian_utc_at_midnight(y, m, d)
+        - Epoch::from_gregorian_utc_at_midnight(1980, 1, 6);
+
+    let sv_health = match eph
+        .orbits
+        .get("health")
+        .and_then(|value| value.as_glo_health())
+    {
+        Some(GloHealth::Healthy) => 0,
+        _ => 1,
+    };
+
+    GLOEphemeris {
+        slot: sv.prn.saturating_sub(1),
+        day: (day.to_seconds() / 86_400.0).round() as u16,
+        tod_s: hh as u32 * 3600 + mm as u32 * 60 + ss as u32,
+        clock_offset_s: eph.clock_bias,
+        clock_rel_freq_bias: eph.clock_drift,
+        x_km: orbit_f64(eph, "satPosX"),
+        vel_x_km: orbit_f64(eph, "velX"),
+        acc_x_km: orbit_f64(eph, "accelX"),
+        y_km: orbit_f64(eph, "satPosY"),
+        vel_y_km: orbit_f64(eph, "velY"),
+        acc_y_km: orbit_f64(eph, "accelY"),
+        z_km: orbit_f64(eph, "satPosZ"),
+        vel_z_km: orbit_f64(eph, "velZ"),
+        acc_z_km: orbit_f64(eph, "accelZ"),
+        sv_health,
+        freq_channel: eph.glonass_channel().unwrap_or_default(),
+        age_op_days: orbit_f64(eph, "ageOp") as u8,
+        l1_l2_gd: orbit_f64(eph, "L1L2GrpDelay"),
+        ..Default::default()
+    }
+}
+
+/// SBAS ephemeris (0x01-03): BINEX encodes the actual PRN (120-158).
+fn sbas(toc: Epoch, sv: SV, eph: &Ephemeris) -> SBASEphemeris {
+    let (week, tow) = week_tow(toc);
+
+    let uint1 = eph
+        .orbits
+        .get("health")
+        .and_then(|value| value.as_geo_health())
+        .map(|health| health.bits())
+        .unwrap_or_default();
+
+    SBASEphemeris {
+        sbas_prn: sv.prn + 100,
+        toe: week,
+        tow,
+        clock_offset: eph.clock_bias,
+        clock_drift: eph.clock_drift,
+        x_km: orbit_f64(eph, "satPosX"),
+        vel_x_km: orbit_f64(eph, "velX"),
+        acc_x_km: orbit_f64(eph, "accelX"),
+        y_km: orbit_f64(eph, "satPosY"),
+        vel_y_km: orbit_f64(eph, "velY"),
+        acc_y_km: orbit_f64(eph, "accelY"),
+        z_km: orbit_f64(eph, "satPosZ"),
+        vel_z_km: orbit_f64(eph, "velZ"),
+        acc_z_km: orbit_f64(eph, "accelZ"),
+        uint1,
+        ura: orbit_f64(eph, "accuracyCode") as u8,
+        iodn: orbit_f64(eph, "iodn") as u8,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rinex::navigation::{GalHealth, Health, OrbitItem};
+    use std::collections::HashMap;
+
+    #[test]
+    fn gps_ephemeris() {
+        let toc = Epoch::from_gpst_seconds(2_000.0 * 604_800.0 + 14_400.0);
+        let sv = SV::new(Constellation::GPS, 10);
+
+        let eph = Ephemeris {
+            clock_bias: 1.0E-4,
+            clock_drift: 0.0,
+            clock_drift_rate: 0.0,
+            orbits: HashMap::from([
+                ("iode".to_string(), OrbitItem::F64(12.0)),
+                ("sqrta".to_string(), OrbitItem::F64(5153.6)),
+                ("fitInt".to_string(), OrbitItem::F64(4.0)),
+                ("l2pDataFlag".to_string(), OrbitItem::F64(1.0)),
+                ("l2Codes".to_string(), OrbitItem::F64(1.0)),
+                ("week".to_string(), OrbitItem::U32(2_000)),
+                (
+                    "health".to_string(),
+                    OrbitItem::Health(Health::from_bits_retain(0)),
+                ),
+            ]),
+        };
+
+        let frame = ephemeris_frame(toc, NavMsgType::LNAV, sv, &eph).unwrap();
+        let gps = match frame {
+            EphemerisFrame::GPS(gps) => gps,
+            _ => panic!("bad frame"),
+        };
+
+        assert_eq!(gps.sv_prn, 9);
+        assert_eq!(gps.toe, 2_000);
+        assert_eq!(gps.toc, 14_400);
+        assert_eq!(gps.iode, 12);
+        assert_eq!(gps.sqrt_a, 5153.6);
+        assert_eq!(gps.uint2, 0x0304);
+
+        // modern frames are not supported
+        assert!(ephemeris_frame(toc, NavMsgType::CNAV, sv, &eph).is_none());
+    }
+
+    #[test]
+    fn galileo_ephemeris() {
+        let toc = Epoch::from_gpst_seconds(2_100.0 * 604_800.0 + 3_600.0);
+        let sv = SV::new(Constellation::Galileo, 3);
+
+        let eph = Ephemeris {
+            clock_bias: 0.0,
+            clock_drift: 0.0,
+            clock_drift_rate: 0.0,
+            orbits: HashMap::from([
+                ("idot".to_string(), OrbitItem::F64(PI * 1.0E-10)),
+                ("dataSrc".to_string(), OrbitItem::F64(516.0)),
+                (
+                    "health".to_string(),
+                    OrbitItem::GalHealth(GalHealth::E1B_DVS),
+                ),
+            ]),
+        };
+
+        let frame = ephemeris_frame(toc, NavMsgType::INAV, sv, &eph).unwrap();
+        let gal = match frame {
+            EphemerisFrame::GAL(gal) => gal,
+            _ => panic!("bad frame"),
+        };
+
+        assert_eq!(gal.sv_prn, 2);
+        assert_eq!(gal.toe_week, 2_100);
+        assert_eq!(gal.toe_s, 3_600);
+        assert_eq!(gal.source, 516);
+        assert_eq!(gal.sv_health, 1);
+        assert!((gal.idot_semi_circles_s - 1.0E-10).abs() < 1.0E-15);
+    }
+
+    #[test]
+    fn glonass_ephemeris() {
+        let toc = Epoch::from_gregorian_utc(1981, 1, 5, 1, 0, 0, 0);
+        let sv = SV::new(Constellation::Glonass, 1);
+
+        let eph = Ephemeris {
+            clock_bias: 1.0E-5,
+            clock_drift: 0.0,
+            clock_drift_rate: 0.0,
+            orbits: HashMap::from([
+                ("satPosX".to_string(), OrbitItem::F64(10_000.0)),
+                ("channel".to_string(), OrbitItem::I8(-4)),
+                (
+                    "health".to_string(),
+                    OrbitItem::GloHealth(GloHealth::Unhealthy),
+                ),
+            ]),
+        };
+
+        let frame = ephemeris_frame(toc, NavMsgType::LNAV, sv, &eph).unwrap();
+        let glo = match frame {
+            EphemerisFrame::GLO(glo) => glo,
+            _ => panic!("bad frame"),
+        };
+
+        assert_eq!(glo.slot, 0);
+        assert_eq!(glo.day, 365);
+        assert_eq!(glo.tod_s, 3_600);
+        assert_eq!(glo.x_km, 10_000.0);
+        assert_eq!(glo.freq_channel, -4);
+        assert_eq!(glo.sv_health, 1);
+        assert_eq!(glo.clock_offset_s, 1.0E-5);
+    }
+}
@@ -1,60 +1,113 @@
 mod cli;
 use cli::Cli;
-use rinex::{prelude::*, Error};
+use rinex::{
+    hatanaka::{self, rnx2crx},
+    observation::{Crinex, Record as ObsRecord},
+    prelude::*,
+    prod::{DetailedProductionAttributes, ProductionAttributes, FFU},
+    reader::BufferedReader,
+    record::Record,
+    Error,
+};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Builds the standard output filename from the header section only:
+/// the record is not parsed.
+fn standard_filename(input_path: &str, header: &Header, short: bool) -> String {
+    let mut attributes = Path::new(input_path)
+        .file_name()
+        .and_then(|name| ProductionAttributes::from_str(&name.to_string_lossy()).ok())
+        .unwrap_or_default();
+
+    if attributes.name.is_empty() {
+        if let Some(marker) = &header.geodetic_marker {
+            attributes.name = marker.name.clone();
+        }
+    }
+
+    let t0 = header.obs.as_ref().and_then(|obs| obs.time_of_first_obs);
+    let custom = match t0 {
+        Some(t0) => {
+            let (y, _, _, hh, mm, _, _) = t0.to_gregorian_utc();
+            attributes.year = y as u32;
+            attributes.doy = t0.day_of_year().round() as u32;
+            let details = attributes
+                .details
+                .get_or_insert_with(|| DetailedProductionAttributes {
+                    country: "CCC".to_string(),
+                    ..Default::default()
+                });
+            details.hh = hh;
+            details.mm = mm;
+            if let Some(dt) = header.sampling_interval {
+                details.ffu = Some(FFU::from(dt));
+            }
+            Some(attributes)
+        },
+        // TIME OF FIRST OBS is mandatory: stick to the input filename
+        None => None,
+    };
+
+    let crinex = Crinex::default();
+    let rinex = Rinex::new(
+        header.with_crinex(crinex),
+        Record::ObsRecord(ObsRecord::new()),
+    );
+    rinex.standard_filename(short, None, custom)
+}
+
 fn main() -> Result<(), Error> {
     let cli = Cli::new();
     let input_path = cli.input_path();
 
-    let mut rinex = Rinex::from_file(input_path)?; // parse
+    // header section only
+    let mut reader = BufferedReader::new(input_path)?;
+    let header = Header::new(&mut reader)?;
 
-    println!("Compressing \"{}\"..", input_path);
-    rinex.rnx2crnx_mut();
+    let mut crinex = Crinex::default();
 
-    // compression attributes
-    if cli.crx1() {
-        if let Some(obs) = &mut rinex.header.obs {
-            if let Some(crx) = &mut obs.crinex {
-                crx.version.major = 1; // force to V1
-            }
-        }
-    }
-    if cli.crx3() {
-        if let Some(obs) = &mut rinex.header.obs {
-            if let Some(crx) = &mut obs.crinex {
-                crx.version.major = 3; // force to V3
-            }
-        }
+    // CRINEX revision is dictated by the RINEX revision
+    crinex.version.major = match header.version.major {
+        1 | 2 => 1,
+        _ => 3,
+    };
+    if (cli.crx1() && crinex.version.major != 1) || (cli.crx3() && crinex.version.major != 3) {
+        eprintln!(
+            "RINEX{} can only be compressed to CRINEX{}",
+            header.version.major, crinex.version.major
+        );
+        return Err(hatanaka::Error::NonSupportedCrxVersion.into());
     }
+
+    // compression attributes
     if let Some(date) = cli.date() {
         let (y, m, d, _, _, _, _) = date.to_gregorian_utc();
         if let Some((hh, mm, ss)) = cli.time() {
-            if let Some(obs) = &mut rinex.header.obs {
-                if let Some(crx) = &mut obs.crinex {
-                    crx.date = Epoch::from_gregorian_utc(y, m, d, hh, mm, ss, 0);
-                }
-            }
-        } else if let Some(obs) = &mut rinex.header.obs {
-            if let Some(crx) = &mut obs.crinex {
-                crx.date = Epoch::from_gregorian_utc_at_midnight(y, m, d);
-            }
+            crinex.date = Epoch::from_gregorian_utc(y, m, d, hh, mm, ss, 0);
+        } else {
+            crinex.date = Epoch::from_gregorian_utc_at_midnight(y, m, d);
         }
     } else if let Some((hh, mm, ss)) = cli.time() {
         let today = Epoch::now().expect("failed to retrieve system time");
         let (y, m, d, _, _, _, _) = today.to_gregorian_utc();
-        if let Some(obs) = &mut rinex.header.obs {
-            if let Some(crx) = &mut obs.crinex {
-                crx.date = Epoch::from_gregorian_utc(y, m, d, hh, mm, ss, 0);
-            }
-        }
+        crinex.date = Epoch::from_gregorian_utc(y, m, d, hh, mm, ss, 0);
     }
 
     // output path
     let output_path = match cli.output_path() {
         Some(path) => path.clone(), // use customized name
-        _ => rinex.standard_filename(cli.matches.get_flag("short"), None, None),
+        _ => standard_filename(input_path, &header, cli.matches.get_flag("short")),
     };
 
-    rinex.to_file(&output_path)?;
+    println!("Compressing \"{}\"..", input_path);
+
+    // compression is streamed, like RNX2CRX does
+    let reader = BufferedReader::new(input_path)?;
+    let mut writer = BufWriter::new(File::create(&output_path)?);
+    rnx2crx(reader, &mut writer, &crinex)?;
     println!("{} generated", output_path);
     Ok(())
 }
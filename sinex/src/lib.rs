@@ -18,6 +18,8 @@ use description::Description;
 use header::{is_valid_header, Header};
 use reference::Reference;
 
+use rinex_readers::SniffedReader;
+
 use gnss::constellation::Constellation;
 
 fn is_comment(line: &str) -> bool {
@@ -123,7 +125,16 @@ pub struct Sinex {
 impl Sinex {
     pub fn from_file(file: &str) -> Result<Self, Error> {
         let file = std::fs::File::open(file)?;
-        let reader = BufReader::new(file);
+        Self::from_reader(BufReader::new(file))
+    }
+    /// Parses [Sinex] from any [BufRead] data source,
+    /// for example an in-memory buffer or a network stream.
+    /// gzip (requires "flate2" feature) and .Z (requires "lzw" feature)
+    /// compressions are identified by content sniffing.
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Self, Error> {
+        Self::parse(SniffedReader::new(reader)?)
+    }
+    fn parse<R: BufRead>(reader: R) -> Result<Self, Error> {
         let mut is_first = true;
         let mut header = Header::default();
         let mut reference: Reference = Reference::default();
@@ -135,7 +146,7 @@ impl Sinex {
         //let mut trop_description = troposphere::Description::default();
         //let mut trop_coordinates : Vec<troposphere::Coordinates> = Vec::new();
         for line in reader.lines() {
-            let line = &line.unwrap();
+            let line = &line?;
             if is_comment(line) {
                 continue;
             }
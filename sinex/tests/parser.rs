@@ -34,4 +34,49 @@ mod test {
             }
         }
     }
+    #[test]
+    fn test_from_reader() {
+        let path = env!("CARGO_MANIFEST_DIR").to_owned() + "/data/BIA/V1/example-1a.bia";
+        let content = std::fs::read(&path).unwrap();
+        let sinex = Sinex::from_reader(content.as_slice());
+        assert!(sinex.is_ok(), "failed to parse in-memory content");
+        let sinex = sinex.unwrap();
+
+        assert_eq!(
+            sinex.comments,
+            vec![
+                "CODE final product series for the IGS.",
+                "Published by Astronomical Institute, University of Bern.",
+                "URL: http://www.aiub.unibe.ch/download/CODE",
+                "DOI: 10.7892/boris.75876",
+            ]
+        );
+        assert_eq!(
+            sinex.acknowledgments,
+            vec![
+                "COD Center for Orbit Determination in Europe, AIUB, Switzerland",
+                "IGS International GNSS Service",
+            ]
+        );
+
+        let solutions = sinex.record.bias_solutions().unwrap();
+        assert_eq!(solutions.len(), 50);
+
+        let first = solutions.first().unwrap();
+        assert_eq!(first.btype, bias::BiasType::OSB);
+        assert_eq!(first.svn, "G063");
+        assert_eq!(first.prn, "G01");
+        assert_eq!(first.station, None);
+        assert_eq!(first.obs, (String::from("C1C"), None));
+        assert_eq!(first.unit, "ns");
+        assert_eq!(first.estimate, 10.2472);
+        assert_eq!(first.stddev, 0.0062);
+
+        let last = solutions.last().unwrap();
+        assert_eq!(last.svn, "R801");
+        assert_eq!(last.prn, "R26");
+        assert_eq!(last.obs, (String::from("C2P"), None));
+        assert_eq!(last.estimate, -0.45);
+        assert_eq!(last.stddev, 0.0256);
+    }
 }
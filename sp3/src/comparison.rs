@@ -0,0 +1,427 @@
+//! Comparison of two SP3 products
+use crate::{interpolation::lagrange_weights, Constellation, SP3Key, Vector3D, SP3, SV};
+use hifitime::Epoch;
+use std::collections::{BTreeMap, HashMap};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/*
+ * Earth rotation rate (rad/s), to express velocities in inertial frame
+ */
+const EARTH_ROTATION_RATE: f64 = 7.2921151467E-5;
+
+/*
+ * Milliarcseconds to radians
+ */
+const MAS_TO_RAD: f64 = std::f64::consts::PI / 180.0 / 3600.0 / 1000.0;
+
+/*
+ * Velocities are expressed in dm/s while positions are in km
+ */
+const DM_S_TO_KM_S: f64 = 1.0E-4;
+
+/// [Helmert] 7 parameter transformation, that projects the compared
+/// frame into the reference frame:
+/// `X' = X + T + D.X + R.X`, where R is the (small angle) rotation matrix
+/// `[[0, -Rz, Ry], [Rz, 0, -Rx], [-Ry, Rx, 0]]`.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Helmert {
+    /// (Tx, Ty, Tz) translation, in m
+    pub translation: Vector3D,
+    /// D scale factor, in ppb
+    pub scale: f64,
+    /// (Rx, Ry, Rz) rotation angles, in mas
+    pub rotation: Vector3D,
+}
+
+impl Helmert {
+    /// Applies [Helmert] transformation to given position, in km.
+    pub fn apply(&self, position: Vector3D) -> Vector3D {
+        let (x, y, z) = position;
+        let (tx, ty, tz) = (
+            self.translation.0 * 1.0E-3,
+            self.translation.1 * 1.0E-3,
+            self.translation.2 * 1.0E-3,
+        );
+        let d = self.scale * 1.0E-9;
+        let (rx, ry, rz) = (
+            self.rotation.0 * MAS_TO_RAD,
+            self.rotation.1 * MAS_TO_RAD,
+            self.rotation.2 * MAS_TO_RAD,
+        );
+        (
+            x + tx + d * x - rz * y + ry * z,
+            y + ty + rz * x + d * y - rx * z,
+            z + tz - ry * x + rx * y + d * z,
+        )
+    }
+    /*
+     * Least squares estimate, from (compared, reference) position pairs in km.
+     */
+    fn estimate(pairs: &[(Vector3D, Vector3D)]) -> Option<Self> {
+        if pairs.len() < 3 {
+            return None;
+        }
+        let mut n = [[0.0_f64; 7]; 7];
+        let mut b = [0.0_f64; 7];
+        for ((x, y, z), reference) in pairs {
+            let rows = [
+                [1.0, 0.0, 0.0, *x, 0.0, *z, -y],
+                [0.0, 1.0, 0.0, *y, -z, 0.0, *x],
+                [0.0, 0.0, 1.0, *z, *y, -x, 0.0],
+            ];
+            let dl = [reference.0 - x, reference.1 - y, reference.2 - z];
+            for (row, dl) in rows.iter().zip(dl.iter()) {
+                for (i, ri) in row.iter().enumerate() {
+                    b[i] += ri * dl;
+                    for (j, rj) in row.iter().enumerate() {
+                        n[i][j] += ri * rj;
+                    }
+                }
+            }
+        }
+        let p = solve(n, b)?;
+        Some(Self {
+            translation: (p[0] * 1.0E3, p[1] * 1.0E3, p[2] * 1.0E3),
+            scale: p[3] * 1.0E9,
+            rotation: (p[4] / MAS_TO_RAD, p[5] / MAS_TO_RAD, p[6] / MAS_TO_RAD),
+        })
+    }
+}
+
+/*
+ * Gauss-Jordan elimination with partial pivoting
+ */
+fn solve(mut n: [[f64; 7]; 7], mut b: [f64; 7]) -> Option<[f64; 7]> {
+    for c in 0..7 {
+        let pivot = (c..7).max_by(|i, j| n[*i][c].abs().total_cmp(&n[*j][c].abs()))?;
+        if n[pivot][c] == 0.0 {
+            return None;
+        }
+        n.swap(c, pivot);
+        b.swap(c, pivot);
+        let (row_c, b_c) = (n[c], b[c]);
+        for (r, (row, br)) in n.iter_mut().zip(b.iter_mut()).enumerate() {
+            if r != c {
+                let f = row[c] / row_c[c];
+                for (rj, cj) in row.iter_mut().zip(row_c.iter()).skip(c) {
+                    *rj -= f * cj;
+                }
+                *br -= f * b_c;
+            }
+        }
+    }
+    let mut x = [0.0_f64; 7];
+    for (i, xi) in x.iter_mut().enumerate() {
+        *xi = b[i] / n[i][i];
+        if !xi.is_finite() {
+            return None;
+        }
+    }
+    Some(x)
+}
+
+/// [Residual] describes the differences between the compared
+/// and reference products, for one [SV] at one [Epoch].
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Residual {
+    /// Radial position difference, in m
+    pub radial: f64,
+    /// Along track position difference, in m
+    pub along_track: f64,
+    /// Cross track position difference, in m
+    pub cross_track: f64,
+    /// Clock offset difference, in s, once the common offset
+    /// (mean difference of this [Constellation] at this [Epoch]) has been removed.
+    /// Only exists when both products provide clock offsets.
+    pub clock: Option<f64>,
+}
+
+/// [ResidualRms] per [SV] statistics
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ResidualRms {
+    /// Radial RMS, in m
+    pub radial: f64,
+    /// Along track RMS, in m
+    pub along_track: f64,
+    /// Cross track RMS, in m
+    pub cross_track: f64,
+    /// 3D position RMS, in m
+    pub position: f64,
+    /// Clock RMS, in s
+    pub clock: Option<f64>,
+    /// Number of compared samples
+    pub samples: usize,
+}
+
+/// [Comparison] of two [SP3] products (for example final versus rapid orbits),
+/// obtained with [SP3::compare]. Position differences (compared - reference)
+/// are projected in the radial, along-track and cross-track frame of the
+/// reference orbit.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Comparison {
+    /// [Helmert] transformation that was estimated between both frames and
+    /// applied to the compared positions, prior forming the [Residual]s.
+    pub helmert: Option<Helmert>,
+    /// [Residual]s, for each [SV] and [Epoch] described by both products
+    pub residuals: BTreeMap<SP3Key, Residual>,
+}
+
+fn norm(v: Vector3D) -> f64 {
+    (v.0 * v.0 + v.1 * v.1 + v.2 * v.2).sqrt()
+}
+
+fn dot(a: Vector3D, b: Vector3D) -> f64 {
+    a.0 * b.0 + a.1 * b.1 + a.2 * b.2
+}
+
+fn cross(a: Vector3D, b: Vector3D) -> Vector3D {
+    (
+        a.1 * b.2 - a.2 * b.1,
+        a.2 * b.0 - a.0 * b.2,
+        a.0 * b.1 - a.1 * b.0,
+    )
+}
+
+fn unit(v: Vector3D) -> Vector3D {
+    let n = norm(v);
+    (v.0 / n, v.1 / n, v.2 / n)
+}
+
+/*
+ * Velocity (km/s) of given sample: from the velocity record when it exists,
+ * otherwise derivative of the Lagrange polynomial over the 9 closest samples.
+ */
+fn velocity(samples: &[(Epoch, Vector3D, Option<Vector3D>)], index: usize) -> Option<Vector3D> {
+    let (t, _, velocity) = samples[index];
+    if let Some((vx, vy, vz)) = velocity {
+        return Some((vx * DM_S_TO_KM_S, vy * DM_S_TO_KM_S, vz * DM_S_TO_KM_S));
+    }
+    if samples.len() < 2 {
+        return None;
+    }
+    let size = samples.len().min(9);
+    let start = index.saturating_sub(size / 2).min(samples.len() - size);
+    let window = &samples[start..start + size];
+    let nodes = window
+        .iter()
+        .map(|(e, _, _)| (*e - t).to_seconds())
+        .collect::<Vec<_>>();
+    let (_, dw) = lagrange_weights(&nodes, 0.0);
+    Some(
+        window
+            .iter()
+            .zip(dw.iter())
+            .fold((0.0, 0.0, 0.0), |acc, ((_, (x, y, z), _), w)| {
+                (acc.0 + w * x, acc.1 + w * y, acc.2 + w * z)
+            }),
+    )
+}
+
+impl Comparison {
+    pub(crate) fn new(sp3: &SP3, reference: &SP3, helmert: bool) -> Self {
+        // reference states, per SV
+        let mut series = HashMap::<SV, Vec<(Epoch, Vector3D, Option<Vector3D>)>>::new();
+        for (k, v) in reference.data.iter() {
+            if v.position == (0.0, 0.0, 0.0) {
+                continue;
+            }
+            series
+                .entry(k.sv)
+                .or_default()
+                .push((k.epoch, v.position, v.velocity));
+        }
+
+        let common = sp3
+            .data
+            .iter()
+            .filter_map(|(k, v)| {
+                let rhs = reference.data.get(k)?;
+                if v.position == (0.0, 0.0, 0.0) || rhs.position == (0.0, 0.0, 0.0) {
+                    None
+                } else {
+                    Some((k, v, rhs))
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let helmert = if helmert {
+            let pairs = common
+                .iter()
+                .map(|(_, lhs, rhs)| (lhs.position, rhs.position))
+                .collect::<Vec<_>>();
+            Helmert::estimate(&pairs)
+        } else {
+            None
+        };
+
+        // clock differences, per epoch and constellation
+        let mut clock_offsets = HashMap::<(Epoch, Constellation), (f64, usize)>::new();
+        for (k, lhs, rhs) in common.iter() {
+            if let (Some(lhs), Some(rhs)) = (lhs.clock, rhs.clock) {
+                let offset = clock_offsets
+                    .entry((k.epoch, k.sv.constellation))
+                    .or_insert((0.0, 0));
+                offset.0 += lhs - rhs;
+                offset.1 += 1;
+            }
+        }
+
+        let mut residuals = BTreeMap::<SP3Key, Residual>::new();
+        for (k, lhs, rhs) in common.iter() {
+            let samples = match series.get(&k.sv) {
+                Some(samples) => samples,
+                None => continue,
+            };
+            let index = match samples.binary_search_by(|(e, _, _)| e.cmp(&k.epoch)) {
+                Ok(index) => index,
+                Err(_) => continue,
+            };
+            let v = match velocity(samples, index) {
+                Some(v) => v,
+                None => continue,
+            };
+
+            // inertial velocity
+            let r = rhs.position;
+            let v = (
+                v.0 - EARTH_ROTATION_RATE * r.1,
+                v.1 + EARTH_ROTATION_RATE * r.0,
+                v.2,
+            );
+
+            let radial = unit(r);
+            let cross_track = unit(cross(r, v));
+            let along_track = cross(cross_track, radial);
+
+            let position = match helmert {
+                Some(helmert) => helmert.apply(lhs.position),
+                None => lhs.position,
+            };
+            let dr = (
+                (position.0 - r.0) * 1.0E3,
+                (position.1 - r.1) * 1.0E3,
+                (position.2 - r.2) * 1.0E3,
+            );
+
+            let clock = match (lhs.clock, rhs.clock) {
+                (Some(lhs), Some(rhs)) => clock_offsets
+                    .get(&(k.epoch, k.sv.constellation))
+                    .map(|(sum, n)| lhs - rhs - sum / *n as f64),
+                _ => None,
+            };
+
+            residuals.insert(
+                (*k).clone(),
+                Residual {
+                    radial: dot(dr, radial),
+                    along_track: dot(dr, along_track),
+                    cross_track: dot(dr, cross_track),
+                    clock,
+                },
+            );
+        }
+        Self { helmert, residuals }
+    }
+    /// Returns [Residual]s of given [SV], in chronological order.
+    pub fn sv_residuals(&self, sv: SV) -> impl Iterator<Item = (Epoch, &Residual)> + '_ {
+        self.residuals
+            .iter()
+            .filter_map(move |(k, v)| if k.sv == sv { Some((k.epoch, v)) } else { None })
+    }
+    /// Returns [ResidualRms] for each [SV] described by both products.
+    pub fn rms(&self) -> BTreeMap<SV, ResidualRms> {
+        // accumulate squares
+        let mut ret = BTreeMap::<SV, (ResidualRms, usize)>::new();
+        for (k, v) in self.residuals.iter() {
+            let (rms, clocks) = ret.entry(k.sv).or_default();
+            rms.radial += v.radial.powi(2);
+            rms.along_track += v.along_track.powi(2);
+            rms.cross_track += v.cross_track.powi(2);
+            rms.samples += 1;
+            if let Some(clock) = v.clock {
+                *rms.clock.get_or_insert(0.0) += clock.powi(2);
+                *clocks += 1;
+            }
+        }
+        ret.into_iter()
+            .map(|(sv, (rms, clocks))| {
+                let n = rms.samples as f64;
+                let (radial, along_track, cross_track) =
+                    (rms.radial / n, rms.along_track / n, rms.cross_track / n);
+                (
+                    sv,
+                    ResidualRms {
+                        radial: radial.sqrt(),
+                        along_track: along_track.sqrt(),
+                        cross_track: cross_track.sqrt(),
+                        position: (radial + along_track + cross_track).sqrt(),
+                        clock: rms.clock.map(|sum| (sum / clocks as f64).sqrt()),
+                        samples: rms.samples,
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{solve, Helmert};
+    #[test]
+    fn linear_solver() {
+        let mut n = [[0.0_f64; 7]; 7];
+        for (i, row) in n.iter_mut().enumerate() {
+            for (j, nij) in row.iter_mut().enumerate() {
+                *nij = if i == j {
+                    4.0
+                } else {
+                    1.0 / (1.0 + i as f64 + j as f64)
+                };
+            }
+        }
+        let x = [1.0, -2.0, 3.0, -4.0, 5.0, -6.0, 7.0];
+        let mut b = [0.0_f64; 7];
+        for (i, bi) in b.iter_mut().enumerate() {
+            *bi = n[i].iter().zip(x.iter()).map(|(a, x)| a * x).sum();
+        }
+        let solved = solve(n, b).unwrap();
+        for (a, b) in solved.iter().zip(x.iter()) {
+            assert!((a - b).abs() < 1.0E-12);
+        }
+        assert!(solve([[0.0; 7]; 7], b).is_none());
+    }
+    #[test]
+    fn helmert_estimate() {
+        let helmert = Helmert {
+            translation: (1.0, -2.0, 0.5),
+            scale: 2.0,
+            rotation: (0.3, -0.2, 0.1),
+        };
+        let positions = [
+            (-14236.422933, 22111.689778, -2329.527637),
+            (-16648.167079, 19712.315777, 6403.879461),
+            (-11263.704073, 12785.253805, -20488.577610),
+            (24037.870616, -5698.700935, 9707.998781),
+            (13110.165457, 6608.784331, -22077.954231),
+        ];
+        let pairs = positions
+            .iter()
+            .map(|p| (*p, helmert.apply(*p)))
+            .collect::<Vec<_>>();
+        let estimate = Helmert::estimate(&pairs).unwrap();
+        assert!((estimate.translation.0 - 1.0).abs() < 1.0E-3);
+        assert!((estimate.translation.1 + 2.0).abs() < 1.0E-3);
+        assert!((estimate.translation.2 - 0.5).abs() < 1.0E-3);
+        assert!((estimate.scale - 2.0).abs() < 1.0E-3);
+        assert!((estimate.rotation.0 - 0.3).abs() < 1.0E-3);
+        assert!((estimate.rotation.1 + 0.2).abs() < 1.0E-3);
+        assert!((estimate.rotation.2 - 0.1).abs() < 1.0E-3);
+        assert!(Helmert::estimate(&pairs[..2]).is_none());
+    }
+}
@@ -0,0 +1,123 @@
+//! EP / EV correlation records parsing
+use crate::ParsingError;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+pub fn position_correlation_entry(content: &str) -> bool {
+    content.starts_with("EP")
+}
+
+pub fn velocity_correlation_entry(content: &str) -> bool {
+    content.starts_with("EV")
+}
+
+/// [Correlation] describes the EP (position and clock) or EV
+/// (velocity and clock rate) records that may follow a state vector.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Correlation {
+    /// (x, y, z) standard deviations, in mm for EP records,
+    /// 10^-4 mm/s for EV records.
+    pub sdev: (f64, f64, f64),
+    /// Clock standard deviation, in ps for EP records,
+    /// 10^-4 ps/s for EV records.
+    pub clock_sdev: f64,
+    /// x-y correlation coefficient
+    pub xy: f64,
+    /// x-z correlation coefficient
+    pub xz: f64,
+    /// x-clock correlation coefficient
+    pub xc: f64,
+    /// y-z correlation coefficient
+    pub yz: f64,
+    /// y-clock correlation coefficient
+    pub yc: f64,
+    /// z-clock correlation coefficient
+    pub zc: f64,
+}
+
+/*
+ * Correlation coefficients are encoded with 1E-7 scaling
+ */
+const CORRELATION_SCALING: f64 = 1.0E7;
+
+/*
+ * Parses integer field, blank fields are null
+ */
+fn parse_field(line: &str, start: usize, end: usize) -> Result<f64, ParsingError> {
+    let field = match line.get(start..end) {
+        Some(field) => field.trim(),
+        None => match line.get(start..) {
+            Some(field) => field.trim(),
+            None => return Ok(0.0),
+        },
+    };
+    if field.is_empty() {
+        return Ok(0.0);
+    }
+    let value = i64::from_str(field).or(Err(ParsingError::Correlation(field.to_string())))?;
+    Ok(value as f64)
+}
+
+impl FromStr for Correlation {
+    type Err = ParsingError;
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            sdev: (
+                parse_field(line, 4, 8)?,
+                parse_field(line, 9, 13)?,
+                parse_field(line, 14, 18)?,
+            ),
+            clock_sdev: parse_field(line, 19, 26)?,
+            xy: parse_field(line, 27, 35)? / CORRELATION_SCALING,
+            xz: parse_field(line, 36, 44)? / CORRELATION_SCALING,
+            xc: parse_field(line, 45, 53)? / CORRELATION_SCALING,
+            yz: parse_field(line, 54, 62)? / CORRELATION_SCALING,
+            yc: parse_field(line, 63, 71)? / CORRELATION_SCALING,
+            zc: parse_field(line, 72, 80)? / CORRELATION_SCALING,
+        })
+    }
+}
+
+impl Correlation {
+    /// Formats [Correlation] as EP (position) or EV (velocity) record
+    pub(crate) fn format(&self, velocity: bool) -> String {
+        format!(
+            "{}  {:4} {:4} {:4} {:7} {:8} {:8} {:8} {:8} {:8} {:8}",
+            if velocity { "EV" } else { "EP" },
+            self.sdev.0.round() as i64,
+            self.sdev.1.round() as i64,
+            self.sdev.2.round() as i64,
+            self.clock_sdev.round() as i64,
+            (self.xy * CORRELATION_SCALING).round() as i64,
+            (self.xz * CORRELATION_SCALING).round() as i64,
+            (self.xc * CORRELATION_SCALING).round() as i64,
+            (self.yz * CORRELATION_SCALING).round() as i64,
+            (self.yc * CORRELATION_SCALING).round() as i64,
+            (self.zc * CORRELATION_SCALING).round() as i64,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Correlation;
+    use std::str::FromStr;
+    #[test]
+    fn ep_record() {
+        let content =
+            "EP    55   55   55     222  1234567 -1234567  5999999      -30      -21 -1230000";
+        let corr = Correlation::from_str(content).unwrap();
+        assert_eq!(corr.sdev, (55.0, 55.0, 55.0));
+        assert_eq!(corr.clock_sdev, 222.0);
+        assert!((corr.xy - 0.1234567).abs() < 1.0E-9);
+        assert!((corr.xz - -0.1234567).abs() < 1.0E-9);
+        assert!((corr.xc - 0.5999999).abs() < 1.0E-9);
+        assert!((corr.yz - -0.0000030).abs() < 1.0E-9);
+        assert!((corr.yc - -0.0000021).abs() < 1.0E-9);
+        assert!((corr.zc - -0.1230000).abs() < 1.0E-9);
+        assert_eq!(corr.format(false), content);
+    }
+}
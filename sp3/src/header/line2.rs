@@ -45,6 +45,20 @@ impl std::str::FromStr for Line2 {
     }
 }
 
+impl std::fmt::Display for Line2 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "##{:5}{:16.8}{:15.8}{:6}{:16.13}",
+            self.week_counter.0,
+            self.week_counter.1,
+            self.epoch_interval.to_seconds(),
+            self.mjd.0,
+            self.mjd.1,
+        )
+    }
+}
+
 impl Line2 {
     pub(crate) fn to_parts(&self) -> ((u32, f64), Duration, (u32, f64)) {
         (self.week_counter, self.epoch_interval, self.mjd)
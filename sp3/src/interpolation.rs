@@ -0,0 +1,401 @@
+//! SP3 state vector interpolation
+use crate::{SP3Entry, Vector3D, SP3, SV};
+use hifitime::{Duration, Epoch};
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/*
+ * Velocities are expressed in dm/s while positions are in km
+ */
+const DM_S_TO_KM_S: f64 = 1.0E-4;
+
+/*
+ * Clock rates are expressed in 1E-4 us/s while clock offsets are in s
+ */
+const CLOCK_RATE_TO_S_S: f64 = 1.0E-10;
+
+/// Supported interpolation methods
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum InterpolationMethod {
+    /// Lagrange interpolation of given order, over order +1 samples.
+    /// Typical orders vary between 7 and 11 for 15' sampled SP3 files.
+    Lagrange(usize),
+    /// Piecewise (cubic) Hermite interpolation, between the two
+    /// samples surrounding the instant, using velocities.
+    /// Only applies to files that provide velocity records.
+    Hermite,
+}
+
+impl Default for InterpolationMethod {
+    fn default() -> Self {
+        Self::Lagrange(9)
+    }
+}
+
+/// [Interpolated] state vector, with error estimates. Error estimates
+/// are the deviation to the next lower order interpolation, over the same
+/// time window.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Interpolated {
+    /// Position vector, in km
+    pub position: Vector3D,
+    /// Position error estimate, in km
+    pub position_error: f64,
+    /// Velocity vector, in dm/s
+    pub velocity: Vector3D,
+    /// Velocity error estimate, in dm/s
+    pub velocity_error: f64,
+    /// Clock offset, in s, when the time window provides
+    /// clock offsets.
+    pub clock: Option<f64>,
+    /// Clock offset error estimate, in s
+    pub clock_error: Option<f64>,
+}
+
+/// [Interpolator] indexes [SP3] data per [SV], so that many
+/// [Epoch]s can be interpolated efficiently.
+/// ```
+/// use sp3::prelude::*;
+/// use std::str::FromStr;
+/// let sp3 = SP3::from_file("../test_resources/SP3/co108870.sp3")
+///     .unwrap();
+/// let interpolator = sp3.interpolator(InterpolationMethod::Lagrange(9));
+/// let g01 = SV::from_str("G01").unwrap();
+/// let t = Epoch::from_str("1997-01-05T06:07:30 GPST").unwrap();
+/// let interpolated = interpolator.interpolate(g01, t)
+///     .unwrap();
+/// // error estimate, in km
+/// assert!(interpolated.position_error < 1.0E-3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Interpolator {
+    method: InterpolationMethod,
+    interval: Duration,
+    series: HashMap<SV, Vec<(Epoch, SP3Entry)>>,
+}
+
+/*
+ * Lagrange weights (and their derivatives) at x, for given nodes
+ */
+pub(crate) fn lagrange_weights(nodes: &[f64], x: f64) -> (Vec<f64>, Vec<f64>) {
+    let mut weights = Vec::with_capacity(nodes.len());
+    let mut derivatives = Vec::with_capacity(nodes.len());
+    for (i, xi) in nodes.iter().enumerate() {
+        let li = nodes
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .fold(1.0, |li, (_, xj)| li * (x - xj) / (xi - xj));
+        weights.push(li);
+
+        let dli = nodes
+            .iter()
+            .enumerate()
+            .filter(|(k, _)| *k != i)
+            .map(|(k, xk)| {
+                nodes
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i && *j != k)
+                    .fold(1.0 / (xi - xk), |prod, (_, xj)| prod * (x - xj) / (xi - xj))
+            })
+            .sum::<f64>();
+        derivatives.push(dli);
+    }
+    (weights, derivatives)
+}
+
+fn weighted_sum(weights: &[f64], values: &[f64]) -> f64 {
+    weights.iter().zip(values.iter()).map(|(w, v)| w * v).sum()
+}
+
+fn weighted_vector(weights: &[f64], values: &[Vector3D]) -> Vector3D {
+    values
+        .iter()
+        .zip(weights.iter())
+        .fold((0.0, 0.0, 0.0), |acc, ((x, y, z), w)| {
+            (acc.0 + w * x, acc.1 + w * y, acc.2 + w * z)
+        })
+}
+
+fn distance(a: Vector3D, b: Vector3D) -> f64 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2) + (a.2 - b.2).powi(2)).sqrt()
+}
+
+fn scale(v: Vector3D, k: f64) -> Vector3D {
+    (v.0 * k, v.1 * k, v.2 * k)
+}
+
+/*
+ * Cubic Hermite value and derivative, plus deviation to both
+ * quadratics that drop one of the derivative constraints.
+ * tau: time since first sample, h: time between both samples.
+ */
+fn hermite(p0: f64, v0: f64, p1: f64, v1: f64, tau: f64, h: f64) -> (f64, f64, f64, f64) {
+    let s = tau / h;
+    let (s2, s3) = (s * s, s * s * s);
+
+    let h00 = 2.0 * s3 - 3.0 * s2 + 1.0;
+    let h10 = s3 - 2.0 * s2 + s;
+    let h01 = -2.0 * s3 + 3.0 * s2;
+    let h11 = s3 - s2;
+    let p = h00 * p0 + h10 * h * v0 + h01 * p1 + h11 * h * v1;
+
+    let dh00 = 6.0 * s2 - 6.0 * s;
+    let dh10 = 3.0 * s2 - 4.0 * s + 1.0;
+    let dh01 = -6.0 * s2 + 6.0 * s;
+    let dh11 = 3.0 * s2 - 2.0 * s;
+    let v = (dh00 * p0 + dh10 * h * v0 + dh01 * p1 + dh11 * h * v1) / h;
+
+    // quadratic through (p0, v0, p1)
+    let ca = (p1 - p0 - v0 * h) / h / h;
+    let qa = p0 + v0 * tau + ca * tau * tau;
+    let dqa = v0 + 2.0 * ca * tau;
+
+    // quadratic through (p0, p1, v1)
+    let cb = (p0 - p1 + v1 * h) / h / h;
+    let qb = p1 + v1 * (tau - h) + cb * (tau - h) * (tau - h);
+    let dqb = v1 + 2.0 * cb * (tau - h);
+
+    let (p_err, v_err) = if (p - qa).abs() > (p - qb).abs() {
+        (p - qa, v - dqa)
+    } else {
+        (p - qb, v - dqb)
+    };
+    (p, v, p_err, v_err)
+}
+
+impl Interpolator {
+    /// Builds new [Interpolator] from [SP3] data, using desired method.
+    pub fn new(sp3: &SP3, method: InterpolationMethod) -> Self {
+        let mut series = HashMap::<SV, Vec<(Epoch, SP3Entry)>>::new();
+        for (key, entry) in sp3.data.iter() {
+            if entry.position == (0.0, 0.0, 0.0) {
+                continue; // velocity only
+            }
+            series
+                .entry(key.sv)
+                .or_default()
+                .push((key.epoch, entry.clone()));
+        }
+        for samples in series.values_mut() {
+            samples.sort_by(|(a, _), (b, _)| a.cmp(b));
+        }
+        Self {
+            method,
+            series,
+            interval: sp3.epoch_interval,
+        }
+    }
+    /// Returns [InterpolationMethod] in use
+    pub fn method(&self) -> InterpolationMethod {
+        self.method
+    }
+    /// Interpolates [SV] state at desired instant `t`. Returns None when
+    /// the time window cannot be designed (too close to either end of the dataset,
+    /// or data gap), when this [SV] is maneuvering within the time window,
+    /// or when Hermite interpolation is requested without velocities.
+    /// For Lagrange interpolation, the time window spans (order +1) samples and
+    /// ((order +1)/2) samples must precede `t`.
+    pub fn interpolate(&self, sv: SV, t: Epoch) -> Option<Interpolated> {
+        let samples = self.series.get(&sv)?;
+        match self.method {
+            InterpolationMethod::Lagrange(order) => self.lagrange(samples, t, order),
+            InterpolationMethod::Hermite => self.hermite(samples, t),
+        }
+    }
+    /*
+     * Verifies the time window is valid (no data gap, no maneuver)
+     */
+    fn valid_window(&self, window: &[(Epoch, SP3Entry)], t: Epoch) -> bool {
+        let closest = window.iter().any(|(e, _)| (*e - t).abs() < self.interval);
+        let maneuver = window.iter().any(|(_, entry)| entry.maneuver);
+        closest && !maneuver
+    }
+    fn lagrange(
+        &self,
+        samples: &[(Epoch, SP3Entry)],
+        t: Epoch,
+        order: usize,
+    ) -> Option<Interpolated> {
+        if order == 0 {
+            return None;
+        }
+        let size = order + 1;
+        let before = samples.partition_point(|(e, _)| *e < t);
+        let start = before.checked_sub(size / 2)?;
+        if start + size > samples.len() {
+            return None;
+        }
+
+        let window = &samples[start..start + size];
+        if !self.valid_window(window, t) {
+            return None;
+        }
+
+        let nodes = window
+            .iter()
+            .map(|(e, _)| (*e - t).to_seconds())
+            .collect::<Vec<_>>();
+
+        // lower order: drop furthest sample
+        let (low_start, low_end) = if nodes[0].abs() > nodes[size - 1].abs() {
+            (1, size)
+        } else {
+            (0, size - 1)
+        };
+
+        let (w, dw) = lagrange_weights(&nodes, 0.0);
+        let (lw, ldw) = lagrange_weights(&nodes[low_start..low_end], 0.0);
+
+        let positions = window.iter().map(|(_, e)| e.position).collect::<Vec<_>>();
+        let position = weighted_vector(&w, &positions);
+        let position_low = weighted_vector(&lw, &positions[low_start..low_end]);
+
+        let velocities = window
+            .iter()
+            .filter_map(|(_, e)| e.velocity)
+            .collect::<Vec<_>>();
+
+        let (velocity, velocity_low) = if velocities.len() == size {
+            (
+                weighted_vector(&w, &velocities),
+                weighted_vector(&lw, &velocities[low_start..low_end]),
+            )
+        } else {
+            (
+                scale(weighted_vector(&dw, &positions), 1.0 / DM_S_TO_KM_S),
+                scale(
+                    weighted_vector(&ldw, &positions[low_start..low_end]),
+                    1.0 / DM_S_TO_KM_S,
+                ),
+            )
+        };
+
+        let clocks = window
+            .iter()
+            .filter_map(|(_, e)| e.clock)
+            .collect::<Vec<_>>();
+
+        let (clock, clock_error) = if clocks.len() == size {
+            let clock = weighted_sum(&w, &clocks);
+            let clock_low = weighted_sum(&lw, &clocks[low_start..low_end]);
+            (Some(clock), Some((clock - clock_low).abs()))
+        } else {
+            (None, None)
+        };
+
+        Some(Interpolated {
+            position,
+            position_error: distance(position, position_low),
+            velocity,
+            velocity_error: distance(velocity, velocity_low),
+            clock,
+            clock_error,
+        })
+    }
+    fn hermite(&self, samples: &[(Epoch, SP3Entry)], t: Epoch) -> Option<Interpolated> {
+        let before = samples.partition_point(|(e, _)| *e < t);
+        let start = if before == 0 {
+            // t may match first sample
+            0
+        } else {
+            before - 1
+        };
+        if start + 2 > samples.len() {
+            return None;
+        }
+
+        let window = &samples[start..start + 2];
+        if t < window[0].0 || t > window[1].0 || !self.valid_window(window, t) {
+            return None;
+        }
+
+        let (t0, s0) = &window[0];
+        let (t1, s1) = &window[1];
+
+        let v0 = scale(s0.velocity?, DM_S_TO_KM_S);
+        let v1 = scale(s1.velocity?, DM_S_TO_KM_S);
+
+        let h = (*t1 - *t0).to_seconds();
+        let tau = (t - *t0).to_seconds();
+
+        let x = hermite(s0.position.0, v0.0, s1.position.0, v1.0, tau, h);
+        let y = hermite(s0.position.1, v0.1, s1.position.1, v1.1, tau, h);
+        let z = hermite(s0.position.2, v0.2, s1.position.2, v1.2, tau, h);
+
+        let position = (x.0, y.0, z.0);
+        let velocity = scale((x.1, y.1, z.1), 1.0 / DM_S_TO_KM_S);
+        let position_error = (x.2.powi(2) + y.2.powi(2) + z.2.powi(2)).sqrt();
+        let velocity_error = (x.3.powi(2) + y.3.powi(2) + z.3.powi(2)).sqrt() / DM_S_TO_KM_S;
+
+        let (clock, clock_error) = match (s0.clock, s1.clock) {
+            (Some(c0), Some(c1)) => match (s0.clock_rate, s1.clock_rate) {
+                (Some(r0), Some(r1)) => {
+                    let (r0, r1) = (r0 * CLOCK_RATE_TO_S_S, r1 * CLOCK_RATE_TO_S_S);
+                    let (clock, _, err, _) = hermite(c0, r0, c1, r1, tau, h);
+                    (Some(clock), Some(err.abs()))
+                },
+                _ => {
+                    // linear interpolation: no error estimate
+                    let clock = c0 + (c1 - c0) * tau / h;
+                    (Some(clock), None)
+                },
+            },
+            _ => (None, None),
+        };
+
+        Some(Interpolated {
+            position,
+            position_error,
+            velocity,
+            velocity_error,
+            clock,
+            clock_error,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{hermite, lagrange_weights};
+    #[test]
+    fn lagrange() {
+        // exact for polynomials of degree <= order
+        let nodes = [-2.0, -1.0, 0.5, 1.0, 3.0];
+        let values = nodes
+            .iter()
+            .map(|x| x * x * x - 2.0 * x)
+            .collect::<Vec<_>>();
+        let (w, dw) = lagrange_weights(&nodes, 0.25);
+        let value: f64 = w.iter().zip(values.iter()).map(|(w, v)| w * v).sum();
+        let derivative: f64 = dw.iter().zip(values.iter()).map(|(w, v)| w * v).sum();
+        assert!((value - (0.25_f64.powi(3) - 0.5)).abs() < 1.0E-12);
+        assert!((derivative - (3.0 * 0.25 * 0.25 - 2.0)).abs() < 1.0E-12);
+        // derivative at a node
+        let (_, dw) = lagrange_weights(&nodes, 1.0);
+        let derivative: f64 = dw.iter().zip(values.iter()).map(|(w, v)| w * v).sum();
+        assert!((derivative - 1.0).abs() < 1.0E-12);
+    }
+    #[test]
+    fn cubic_hermite() {
+        // exact for cubics, quadratics are error free
+        let f = |x: f64| 2.0 * x * x * x - x * x + 3.0;
+        let df = |x: f64| 6.0 * x * x - 2.0 * x;
+        let (p, v, _, _) = hermite(f(1.0), df(1.0), f(3.0), df(3.0), 0.5, 2.0);
+        assert!((p - f(1.5)).abs() < 1.0E-12);
+        assert!((v - df(1.5)).abs() < 1.0E-12);
+
+        let f = |x: f64| x * x - 4.0 * x;
+        let df = |x: f64| 2.0 * x - 4.0;
+        let (p, v, p_err, v_err) = hermite(f(0.0), df(0.0), f(1.0), df(1.0), 0.3, 1.0);
+        assert!((p - f(0.3)).abs() < 1.0E-12);
+        assert!((v - df(0.3)).abs() < 1.0E-12);
+        assert!(p_err.abs() < 1.0E-12);
+        assert!(v_err.abs() < 1.0E-12);
+    }
+}
@@ -19,13 +19,16 @@ use thiserror::Error;
 #[cfg(feature = "processing")]
 use qc_traits::processing::{
     Decimate, DecimationFilter, DecimationFilterType, FilterItem, MaskFilter, MaskOperand, Masking,
-    Preprocessing,
+    Preprocessing, Split, SplitError,
 };
 
 #[cfg(test)]
 mod tests;
 
+mod comparison;
+mod correlation;
 mod header;
+mod interpolation;
 mod position;
 mod reader;
 mod velocity;
@@ -39,11 +42,15 @@ use header::{
     line2::{is_header_line2, Line2},
 };
 
+use comparison::Comparison;
+use correlation::{position_correlation_entry, velocity_correlation_entry, Correlation};
+use interpolation::{InterpolationMethod, Interpolator};
 use position::{position_entry, PositionEntry};
 use velocity::{velocity_entry, VelocityEntry};
 
-use reader::BufferedReader;
-use std::io::BufRead;
+use reader::{BufferedReader, SniffedReader};
+use std::fs::File;
+use std::io::{BufRead, BufWriter, Write};
 use version::Version;
 
 #[cfg(feature = "serde")]
@@ -57,7 +64,13 @@ use std::path::Path;
 type Vector3D = (f64, f64, f64);
 
 pub mod prelude {
-    pub use crate::{version::Version, DataType, Error, OrbitType, SP3};
+    pub use crate::{
+        comparison::{Comparison, Helmert, Residual, ResidualRms},
+        correlation::Correlation,
+        interpolation::{Interpolated, InterpolationMethod, Interpolator},
+        version::Version,
+        DataType, Error, OrbitType, SP3Entry, SP3Key, SP3,
+    };
     // Pub re-export
     pub use gnss::prelude::{Constellation, SV};
     pub use hifitime::{Duration, Epoch, TimeScale};
@@ -67,6 +80,22 @@ fn file_descriptor(content: &str) -> bool {
     content.starts_with("%c")
 }
 
+fn floating_point_descriptor(content: &str) -> bool {
+    content.starts_with("%f")
+}
+
+fn int_descriptor(content: &str) -> bool {
+    content.starts_with("%i")
+}
+
+fn sv_descriptor(content: &str) -> bool {
+    content.starts_with('+') && !content.starts_with("++")
+}
+
+fn sv_accuracy_descriptor(content: &str) -> bool {
+    content.starts_with("++")
+}
+
 fn sp3_comment(content: &str) -> bool {
     content.starts_with("/*")
 }
@@ -182,6 +211,26 @@ pub struct SP3Entry {
     /// Possible velocity vector (rarely present) expressed in [km/s] in the same
     /// reference frame.
     pub velocity: Option<Vector3D>,
+    /// Possible (x, y, z) position standard deviations, in mm.
+    pub position_sdev: Option<Vector3D>,
+    /// Possible clock offset standard deviation, in ps.
+    pub clock_sdev: Option<f64>,
+    /// Possible (x, y, z) velocity standard deviations, in 10^-4 mm/s.
+    pub velocity_sdev: Option<Vector3D>,
+    /// Possible clock rate standard deviation, in 10^-4 ps/s.
+    pub clock_rate_sdev: Option<f64>,
+    /// Clock event flag: discontinuity of the clock offset at this [Epoch].
+    pub clock_event: bool,
+    /// Clock offset is predicted, not estimated.
+    pub clock_prediction: bool,
+    /// Maneuver flag: this [SV] is maneuvering at this [Epoch].
+    pub maneuver: bool,
+    /// Orbital state is predicted, not estimated.
+    pub orbit_prediction: bool,
+    /// Possible position and clock correlation (EP) record.
+    pub position_correlation: Option<Correlation>,
+    /// Possible velocity and clock rate correlation (EV) record.
+    pub velocity_correlation: Option<Correlation>,
 }
 
 impl SP3Entry {
@@ -193,6 +242,16 @@ impl SP3Entry {
             clock: None,
             velocity: None,
             clock_rate: None,
+            position_sdev: None,
+            clock_sdev: None,
+            velocity_sdev: None,
+            clock_rate_sdev: None,
+            clock_event: false,
+            clock_prediction: false,
+            maneuver: false,
+            orbit_prediction: false,
+            position_correlation: None,
+            velocity_correlation: None,
         }
     }
     /// Builds new [SP3Entry] with given position and velocity vector,
@@ -203,6 +262,16 @@ impl SP3Entry {
             clock: None,
             clock_rate: None,
             velocity: Some(velocity),
+            position_sdev: None,
+            clock_sdev: None,
+            velocity_sdev: None,
+            clock_rate_sdev: None,
+            clock_event: false,
+            clock_prediction: false,
+            maneuver: false,
+            orbit_prediction: false,
+            position_correlation: None,
+            velocity_correlation: None,
         }
     }
     /// Copies and returns [Self] with given position vector
@@ -262,6 +331,20 @@ pub struct SP3 {
     pub epoch_interval: Duration,
     /// Satellite Vehicles
     pub sv: Vec<SV>,
+    /// [SV] accuracy in mm, as described in the header section.
+    /// Vehicles with unknown accuracy are not listed.
+    pub sv_accuracy: BTreeMap<SV, f64>,
+    /// Floating point base used in position (mm) and velocity (10^-4 mm/s)
+    /// standard deviation exponents.
+    pub position_base: f64,
+    /// Floating point base used in clock (ps) and clock rate (10^-4 ps/s)
+    /// standard deviation exponents.
+    pub clock_base: f64,
+    /// %c, %f and %i header lines, stored as is.
+    /// Apart from [Self::constellation], [Self::time_scale], [Self::position_base]
+    /// and [Self::clock_base], their content is not interpreted, but it is preserved
+    /// when formatting.
+    pub descriptors: Vec<String>,
     /// File content are [SP3Entry]s sorted per [SP3Key]
     pub data: BTreeMap<SP3Key, SP3Entry>,
     /// File header comments, stored as is.
@@ -330,6 +413,10 @@ pub enum ParsingError {
     Coordinates(String),
     #[error("failed to parse clock data from \"{0}\"")]
     Clock(String),
+    #[error("failed to parse correlation data from \"{0}\"")]
+    Correlation(String),
+    #[error("malformed %f line \"{0}\"")]
+    MalformedFloatingPointDescriptor(String),
 }
 
 /*
@@ -365,6 +452,49 @@ fn parse_epoch(content: &str, time_scale: TimeScale) -> Result<Epoch, ParsingErr
     .or(Err(ParsingError::Epoch))
 }
 
+/*
+ * Formats hifitime::Epoch to standard format, expressed in given TimeScale
+ */
+fn format_epoch(epoch: Epoch, time_scale: TimeScale) -> String {
+    let isofmt = epoch.to_gregorian_str(time_scale);
+    let mut datetime = isofmt.split('T');
+    let date = datetime.next().unwrap_or_default();
+    let time = datetime
+        .next()
+        .unwrap_or_default()
+        .split(' ')
+        .next()
+        .unwrap_or_default();
+
+    let mut date = date.split('-');
+    let y = date.next().unwrap_or_default();
+    let m = date.next().unwrap_or_default().parse::<u8>().unwrap_or(0);
+    let d = date.next().unwrap_or_default().parse::<u8>().unwrap_or(0);
+
+    let mut time = time.split(':');
+    let hh = time.next().unwrap_or_default().parse::<u8>().unwrap_or(0);
+    let mm = time.next().unwrap_or_default().parse::<u8>().unwrap_or(0);
+    let ss = f64::from_str(time.next().unwrap_or_default()).unwrap_or(0.0);
+
+    format!("{:>4} {:>2} {:>2} {:>2} {:>2} {:11.8}", y, m, d, hh, mm, ss)
+}
+
+/*
+ * Converts standard deviation to exponent of given base,
+ * as encoded in SP3 files
+ */
+fn sdev_exponent(sdev: f64, base: f64) -> Option<u16> {
+    if base <= 1.0 || sdev <= 0.0 {
+        return None;
+    }
+    let exp = (sdev.ln() / base.ln()).round();
+    if exp < 0.0 {
+        None
+    } else {
+        Some(exp as u16)
+    }
+}
+
 impl SP3 {
     /// Parses given SP3 file, with possible seamless
     /// .gz decompression, if compiled with the "flate2" feature.
@@ -375,6 +505,14 @@ impl SP3 {
     /// See [Self::from_path]
     pub fn from_file(path: &str) -> Result<Self, Error> {
         let reader = BufferedReader::new(path)?;
+        Self::from_reader(reader)
+    }
+    /// Parses SP3 from any [BufRead] data source,
+    /// for example an in-memory buffer or a network stream.
+    /// gzip (requires "flate2" feature) and .Z (requires "lzw" feature)
+    /// compressions are identified by content sniffing.
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Self, Error> {
+        let reader = SniffedReader::new(reader)?;
 
         let mut version = Version::default();
         let mut data_type = DataType::default();
@@ -382,6 +520,16 @@ impl SP3 {
         let mut time_scale = TimeScale::default();
         let mut constellation = Constellation::default();
         let mut pc_count = 0_u8;
+        let mut pf_count = 0_u8;
+        let mut position_base = 0.0_f64;
+        let mut clock_base = 0.0_f64;
+
+        // header (+ ++) SV accuracy table
+        let mut header_sv: Vec<Option<SV>> = Vec::new();
+        let mut accuracy_exp: Vec<u8> = Vec::new();
+
+        // EP/EV records refer to the previous state vector
+        let mut last_sv: Option<SV> = None;
 
         let mut coord_system = String::from("Unknown");
         let mut orbit_type = OrbitType::default();
@@ -392,13 +540,14 @@ impl SP3 {
 
         let mut vehicles: Vec<SV> = Vec::new();
         let mut comments = Comments::new();
+        let mut descriptors = Vec::<String>::with_capacity(6);
         let mut data = BTreeMap::<SP3Key, SP3Entry>::new();
 
         let mut epoch = Epoch::default();
         let mut epochs: Vec<Epoch> = Vec::new();
 
         for line in reader.lines() {
-            let line = line.unwrap();
+            let line = line?;
             let line = line.trim();
             if sp3_comment(line) {
                 if line.len() > 4 {
@@ -417,6 +566,9 @@ impl SP3 {
                 let l2 = Line2::from_str(line)?;
                 (week_counter, epoch_interval, mjd_start) = l2.to_parts();
             }
+            if file_descriptor(line) || floating_point_descriptor(line) || int_descriptor(line) {
+                descriptors.push(line.to_string());
+            }
             if file_descriptor(line) {
                 if line.len() < 60 {
                     return Err(Error::ParsingError(ParsingError::MalformedDescriptor(
@@ -431,6 +583,34 @@ impl SP3 {
 
                 pc_count += 1;
             }
+            if floating_point_descriptor(line) {
+                if pf_count == 0 {
+                    let malformed = || {
+                        Error::ParsingError(ParsingError::MalformedFloatingPointDescriptor(
+                            line.to_string(),
+                        ))
+                    };
+                    position_base = f64::from_str(line.get(3..13).ok_or_else(malformed)?.trim())
+                        .map_err(|_| malformed())?;
+                    clock_base = f64::from_str(line.get(14..26).ok_or_else(malformed)?.trim())
+                        .map_err(|_| malformed())?;
+                }
+                pf_count += 1;
+            }
+            if sv_descriptor(line) {
+                let mut offset = 9;
+                while let Some(item) = line.get(offset..offset + 3) {
+                    header_sv.push(SV::from_str(item.trim()).ok());
+                    offset += 3;
+                }
+            }
+            if sv_accuracy_descriptor(line) {
+                let mut offset = 9;
+                while let Some(item) = line.get(offset..offset + 3) {
+                    accuracy_exp.push(u8::from_str(item.trim()).unwrap_or(0));
+                    offset += 3;
+                }
+            }
             if new_epoch(line) {
                 epoch = parse_epoch(&line[3..], time_scale)?;
                 epochs.push(epoch);
@@ -446,6 +626,7 @@ impl SP3 {
                 if !vehicles.contains(&sv) {
                     vehicles.push(sv);
                 }
+                last_sv = Some(sv);
                 // verify entry validity
                 if x_km != 0.0_f64 && y_km != 0.0_f64 && z_km != 0.0_f64 {
                     let key = SP3Key { epoch, sv };
@@ -454,13 +635,49 @@ impl SP3 {
                     } else {
                         if let Some(clk) = clk {
                             data.insert(
-                                key,
+                                key.clone(),
                                 SP3Entry::from_position((x_km, y_km, z_km)).with_clock_offset(clk),
                             );
                         } else {
-                            data.insert(key, SP3Entry::from_position((x_km, y_km, z_km)));
+                            data.insert(key.clone(), SP3Entry::from_position((x_km, y_km, z_km)));
                         }
                     }
+                    if let Some(e) = data.get_mut(&key) {
+                        if let Some((x, y, z)) = entry.position_exp {
+                            if position_base > 0.0 {
+                                e.position_sdev = Some((
+                                    position_base.powi(x as i32),
+                                    position_base.powi(y as i32),
+                                    position_base.powi(z as i32),
+                                ));
+                            }
+                        }
+                        if let Some(exp) = entry.clock_exp {
+                            if clock_base > 0.0 {
+                                e.clock_sdev = Some(clock_base.powi(exp as i32));
+                            }
+                        }
+                        e.clock_event = entry.clock_event;
+                        e.clock_prediction = entry.clock_prediction;
+                        e.maneuver = entry.maneuver;
+                        e.orbit_prediction = entry.orbit_prediction;
+                    }
+                }
+            }
+            if position_correlation_entry(line) {
+                if let Some(sv) = last_sv {
+                    let correlation = Correlation::from_str(line)?;
+                    if let Some(e) = data.get_mut(&SP3Key { epoch, sv }) {
+                        e.position_correlation = Some(correlation);
+                    }
+                }
+            }
+            if velocity_correlation_entry(line) {
+                if let Some(sv) = last_sv {
+                    let correlation = Correlation::from_str(line)?;
+                    if let Some(e) = data.get_mut(&SP3Key { epoch, sv }) {
+                        e.velocity_correlation = Some(correlation);
+                    }
                 }
             }
             if velocity_entry(line) {
@@ -469,6 +686,7 @@ impl SP3 {
                 }
                 let entry = VelocityEntry::from_str(line)?;
                 let (sv, (vel_x, vel_y, vel_z), clk) = entry.to_parts();
+                last_sv = Some(sv);
 
                 //TODO : move this into %c config frame
                 if !vehicles.contains(&sv) {
@@ -485,16 +703,46 @@ impl SP3 {
                     } else {
                         if let Some(clk) = clk {
                             data.insert(
-                                key,
+                                key.clone(),
                                 SP3Entry::from_position((0.0, 0.0, 0.0)).with_clock_rate(clk),
                             );
                         } else {
-                            data.insert(key, SP3Entry::from_position((0.0, 0.0, 0.0)));
+                            data.insert(key.clone(), SP3Entry::from_position((0.0, 0.0, 0.0)));
+                        }
+                    }
+                    if let Some(e) = data.get_mut(&key) {
+                        if let Some((x, y, z)) = entry.velocity_exp {
+                            if position_base > 0.0 {
+                                e.velocity_sdev = Some((
+                                    position_base.powi(x as i32),
+                                    position_base.powi(y as i32),
+                                    position_base.powi(z as i32),
+                                ));
+                            }
+                        }
+                        if let Some(exp) = entry.clock_exp {
+                            if clock_base > 0.0 {
+                                e.clock_rate_sdev = Some(clock_base.powi(exp as i32));
+                            }
                         }
                     }
                 }
             }
         }
+
+        let sv_accuracy = header_sv
+            .iter()
+            .zip(accuracy_exp.iter())
+            .filter_map(|(sv, exp)| {
+                let sv = (*sv)?;
+                if *exp > 0 {
+                    Some((sv, 2.0_f64.powi(*exp as i32)))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
         Ok(Self {
             version,
             data_type,
@@ -508,10 +756,222 @@ impl SP3 {
             epoch_interval,
             mjd_start,
             sv: vehicles,
+            sv_accuracy,
+            position_base,
+            clock_base,
+            descriptors,
             data,
             comments,
         })
     }
+    /// Writes [SP3] into given file, following SP3-c or SP3-d
+    /// specifications, depending on [Self::version].
+    /// ```
+    /// use sp3::prelude::*;
+    /// let sp3 = SP3::from_file("../test_resources/SP3/co108870.sp3")
+    ///     .unwrap();
+    /// let path = std::env::temp_dir().join("co108870.sp3");
+    /// let path = path.to_string_lossy();
+    /// assert!(sp3.to_file(&path).is_ok());
+    /// # std::fs::remove_file(path.as_ref()).unwrap();
+    /// ```
+    pub fn to_file(&self, path: &str) -> Result<(), Error> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.to_writer(&mut writer)
+    }
+    /// Writes [SP3] into any [Write]able sink, like stdout,
+    /// a socket or an in-memory buffer.
+    /// [SV]s that are not described at a given epoch are omitted.
+    /// ```
+    /// use sp3::prelude::*;
+    /// let sp3 = SP3::from_file("../test_resources/SP3/co108870.sp3")
+    ///     .unwrap();
+    /// let mut buf = Vec::<u8>::new();
+    /// assert!(sp3.to_writer(&mut buf).is_ok());
+    /// let copy = SP3::from_reader(buf.as_slice())
+    ///     .unwrap();
+    /// assert_eq!(copy.epoch, sp3.epoch);
+    /// assert_eq!(copy.sv, sp3.sv);
+    /// ```
+    pub fn to_writer<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        let mut writer = BufWriter::new(writer);
+        self.write_header(&mut writer)?;
+        for epoch in self.epoch() {
+            writeln!(writer, "*  {}", format_epoch(epoch, self.time_scale))?;
+            for sv in self.sv() {
+                let entry = match self.data.get(&SP3Key { epoch, sv }) {
+                    Some(entry) => entry,
+                    None => continue,
+                };
+                let mut position = PositionEntry::new(sv, entry.position, entry.clock);
+                if let Some((x, y, z)) = entry.position_sdev {
+                    let x = sdev_exponent(x, self.position_base);
+                    let y = sdev_exponent(y, self.position_base);
+                    let z = sdev_exponent(z, self.position_base);
+                    if let (Some(x), Some(y), Some(z)) = (x, y, z) {
+                        position.position_exp = Some((x as u8, y as u8, z as u8));
+                    }
+                }
+                if let Some(clk) = entry.clock_sdev {
+                    position.clock_exp = sdev_exponent(clk, self.clock_base);
+                }
+                position.clock_event = entry.clock_event;
+                position.clock_prediction = entry.clock_prediction;
+                position.maneuver = entry.maneuver;
+                position.orbit_prediction = entry.orbit_prediction;
+                writeln!(writer, "{}", position)?;
+
+                if let Some(correlation) = entry.position_correlation {
+                    writeln!(writer, "{}", correlation.format(false))?;
+                }
+                if let Some(velocity) = entry.velocity {
+                    let mut velocity = VelocityEntry::new(sv, velocity, entry.clock_rate);
+                    if let Some((x, y, z)) = entry.velocity_sdev {
+                        let x = sdev_exponent(x, self.position_base);
+                        let y = sdev_exponent(y, self.position_base);
+                        let z = sdev_exponent(z, self.position_base);
+                        if let (Some(x), Some(y), Some(z)) = (x, y, z) {
+                            velocity.velocity_exp = Some((x as u8, y as u8, z as u8));
+                        }
+                    }
+                    if let Some(rate) = entry.clock_rate_sdev {
+                        velocity.clock_exp = sdev_exponent(rate, self.clock_base);
+                    }
+                    writeln!(writer, "{}", velocity)?;
+                    if let Some(correlation) = entry.velocity_correlation {
+                        writeln!(writer, "{}", correlation.format(true))?;
+                    }
+                }
+            }
+        }
+        writeln!(writer, "EOF")?;
+        writer.flush()?;
+        Ok(())
+    }
+    /*
+     * Writes header section: lines #1 & #2, SV and accuracy blocks,
+     * %c %f %i descriptors and comments
+     */
+    fn write_header<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        let first_epoch = self.first_epoch().unwrap_or_default();
+        writeln!(
+            writer,
+            "#{}{}{} {:7} ORBIT {:>5} {:>3} {:>4}",
+            self.version,
+            self.data_type,
+            format_epoch(first_epoch, self.time_scale),
+            self.nb_epochs(),
+            self.coord_system,
+            self.orbit_type,
+            self.agency,
+        )?;
+
+        let line2 = Line2 {
+            week_counter: self.week_counter,
+            epoch_interval: self.epoch_interval,
+            mjd: self.mjd_start,
+        };
+        writeln!(writer, "{}", line2)?;
+
+        // SP3-c is limited to 85 vehicles (5 lines),
+        // SP3-d has at least 5 lines but may expand to describe more vehicles
+        let nb_lines = match self.version {
+            Version::D => std::cmp::max(5, self.sv.len().div_ceil(17)),
+            _ => 5,
+        };
+        for i in 0..nb_lines {
+            if i == 0 {
+                write!(writer, "+  {:3}   ", self.sv.len())?;
+            } else {
+                write!(writer, "+        ")?;
+            }
+            for j in 0..17 {
+                match self.sv.get(i * 17 + j) {
+                    Some(sv) => write!(writer, "{:x}", sv)?,
+                    None => write!(writer, "  0")?,
+                }
+            }
+            writeln!(writer)?;
+        }
+        for i in 0..nb_lines {
+            write!(writer, "++       ")?;
+            for j in 0..17 {
+                let exp = match self.sv.get(i * 17 + j) {
+                    Some(sv) => match self.sv_accuracy.get(sv) {
+                        Some(accuracy) => sdev_exponent(*accuracy, 2.0).unwrap_or(0),
+                        None => 0,
+                    },
+                    None => 0,
+                };
+                write!(writer, "{:3}", exp)?;
+            }
+            writeln!(writer)?;
+        }
+
+        // interpreted fields are refreshed,
+        // the rest of the descriptors is preserved
+        let stored = |prefix: &'static str| {
+            self.descriptors
+                .iter()
+                .filter(move |line| line.starts_with(prefix))
+        };
+
+        let mut c_lines = stored("%c");
+        writeln!(
+            writer,
+            "%c {:<2} cc {:<3}{}",
+            format!("{:x}", self.constellation),
+            format!("{:x}", self.time_scale),
+            c_lines
+                .next()
+                .and_then(|line| line.get(12..))
+                .unwrap_or(" ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc"),
+        )?;
+        writeln!(
+            writer,
+            "{}",
+            c_lines
+                .next()
+                .map(|line| line.as_str())
+                .unwrap_or("%c cc cc ccc ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc")
+        )?;
+
+        let mut f_lines = stored("%f");
+        writeln!(
+            writer,
+            "%f {:10.7} {:12.9}{}",
+            self.position_base,
+            self.clock_base,
+            f_lines
+                .next()
+                .and_then(|line| line.get(26..))
+                .unwrap_or("  0.00000000000  0.000000000000000"),
+        )?;
+        writeln!(
+            writer,
+            "{}",
+            f_lines
+                .next()
+                .map(|line| line.as_str())
+                .unwrap_or("%f  0.0000000  0.000000000  0.00000000000  0.000000000000000")
+        )?;
+
+        let mut i_lines = stored("%i");
+        for _ in 0..2 {
+            writeln!(
+                writer,
+                "{}",
+                i_lines
+                    .next()
+                    .map(|line| line.as_str())
+                    .unwrap_or("%i    0    0    0    0      0      0      0      0         0")
+            )?;
+        }
+        for comment in self.comments() {
+            writeln!(writer, "/* {}", comment)?;
+        }
+        Ok(())
+    }
     /// Returns a unique Epoch iterator where either
     /// Position or Clock data is provided.
     pub fn epoch(&self) -> impl Iterator<Item = Epoch> + '_ {
@@ -529,6 +989,77 @@ impl SP3 {
     pub fn last_epoch(&self) -> Option<Epoch> {
         self.epoch.last().copied()
     }
+    /// Returns dominant sampling interval, which should match
+    /// [Self::epoch_interval] for most files.
+    pub fn dominant_sample_rate(&self) -> Option<Duration> {
+        self.sampling_histogram()
+            .max_by(|(_, pop_i), (_, pop_j)| pop_i.cmp(pop_j))
+            .map(|dominant| dominant.0)
+    }
+    /// Histogram analysis on [Epoch] interval.
+    /// ```
+    /// use sp3::prelude::*;
+    /// let sp3 = SP3::from_file("../test_resources/SP3/co108870.sp3")
+    ///     .unwrap();
+    /// assert!(sp3.sampling_histogram().eq(vec![
+    ///     (Duration::from_seconds(900.0), 95),
+    /// ]));
+    /// ```
+    pub fn sampling_histogram(&self) -> Box<dyn Iterator<Item = (Duration, usize)> + '_> {
+        // compute dt = |e_k+1 - e_k| : instantaneous epoch delta
+        //              then compute an histogram on these intervals
+        Box::new(
+            self.epoch()
+                .zip(self.epoch().skip(1))
+                .map(|(ek, ekp1)| ekp1 - ek)
+                .fold(vec![], |mut list, dt| {
+                    let mut found = false;
+                    for (delta, pop) in list.iter_mut() {
+                        if *delta == dt {
+                            *pop += 1;
+                            found = true;
+                            break;
+                        }
+                    }
+                    if !found {
+                        list.push((dt, 1));
+                    }
+                    list
+                })
+                .into_iter(),
+        )
+    }
+    /// Returns True if Self has a steady sampling, ie., all epoch interval
+    /// are evenly spaced
+    pub fn steady_sampling(&self) -> bool {
+        self.sampling_histogram().count() == 1
+    }
+    /// Returns an iterator over unexpected data gaps,
+    /// in the form ([`Epoch`], [`Duration`]), where
+    /// epoch is the starting datetime, and its related duration.
+    /// When tolerance is set to None, the reference sample rate is
+    /// [Self::dominant_sample_rate], or [Self::epoch_interval].
+    pub fn data_gaps(
+        &self,
+        tolerance: Option<Duration>,
+    ) -> Box<dyn Iterator<Item = (Epoch, Duration)> + '_> {
+        let sample_rate = match tolerance {
+            Some(dt) => dt,
+            None => self.dominant_sample_rate().unwrap_or(self.epoch_interval),
+        };
+        Box::new(
+            self.epoch()
+                .zip(self.epoch().skip(1))
+                .filter_map(move |(ek, ekp1)| {
+                    let dt = ekp1 - ek;
+                    if dt > sample_rate {
+                        Some((ek, dt))
+                    } else {
+                        None
+                    }
+                }),
+        )
+    }
     /// Returns a unique [Constellation] iterator
     pub fn constellation(&self) -> impl Iterator<Item = Constellation> + '_ {
         self.sv().map(|sv| sv.constellation).unique()
@@ -584,11 +1115,36 @@ impl SP3 {
             Some((k.epoch, k.sv, rate))
         })
     }
+    /// Returns an Iterator over [Epoch]s where [SV] is flagged as maneuvering.
+    pub fn sv_maneuver(&self) -> impl Iterator<Item = (Epoch, SV)> + '_ {
+        self.data.iter().filter_map(|(k, v)| {
+            if v.maneuver {
+                Some((k.epoch, k.sv))
+            } else {
+                None
+            }
+        })
+    }
+    /// Returns an Iterator over SV position standard deviations, in mm.
+    pub fn sv_position_sdev(&self) -> impl Iterator<Item = (Epoch, SV, Vector3D)> + '_ {
+        self.data.iter().filter_map(|(k, v)| {
+            let sdev = v.position_sdev?;
+            Some((k.epoch, k.sv, sdev))
+        })
+    }
+    /// Returns an Iterator over SV clock offset standard deviations, in ps.
+    pub fn sv_clock_sdev(&self) -> impl Iterator<Item = (Epoch, SV, f64)> + '_ {
+        self.data.iter().filter_map(|(k, v)| {
+            let sdev = v.clock_sdev?;
+            Some((k.epoch, k.sv, sdev))
+        })
+    }
     /// Interpolate Clock (offset) at desired "t" expressed in the timescale you want.
     /// SP3 files usually have a 15' sampling interval which makes this operation
     /// most likely incorrect. You should either use higher sample rate to reduce
     /// the error generated by interpolation, or use different products like
     /// high precision Clock RINEX files.
+    /// See [Self::interpolator] for other methods and error estimates.
     pub fn sv_clock_interpolate(&self, t: Epoch, sv: SV) -> Option<f64> {
         let before = self
             .sv_clock()
@@ -628,13 +1184,17 @@ impl SP3 {
     /// contained in the interval ](N +1)/2 * τ;  T - (N +1)/2 * τ],
     /// where N is the interpolation order, τ the epoch interval (15 ' is the standard
     /// in SP3) and T the last Epoch in this file. See [Bibliography::Japhet2021].
+    /// Returns None when this [SV] is flagged as maneuvering within the time window.
+    /// See [Self::interpolator] to interpolate many [Epoch]s efficiently,
+    /// with error estimates.
     pub fn sv_position_interpolate(&self, sv: SV, t: Epoch, order: usize) -> Option<Vector3D> {
         let odd_order = order % 2 > 0;
         let sv_position: Vec<_> = self
-            .sv_position()
-            .filter_map(|(e, svnn, (x, y, z))| {
-                if sv == svnn {
-                    Some((e, (x, y, z)))
+            .data
+            .iter()
+            .filter_map(|(k, v)| {
+                if k.sv == sv {
+                    Some((k.epoch, v.position, v.maneuver))
                 } else {
                     None
                 }
@@ -645,7 +1205,7 @@ impl SP3 {
          */
         let center = match sv_position
             .iter()
-            .find(|(e, _)| (*e - t).abs() < self.epoch_interval)
+            .find(|(e, _, _)| (*e - t).abs() < self.epoch_interval)
         {
             Some(center) => center,
             None => {
@@ -657,7 +1217,7 @@ impl SP3 {
             },
         };
         // println!("CENTRAL EPOCH : {:?}", center); //DEBUG
-        let center_pos = match sv_position.iter().position(|(e, _)| *e == center.0) {
+        let center_pos = match sv_position.iter().position(|(e, _, _)| *e == center.0) {
             Some(center) => center,
             None => {
                 /* will never happen at this point*/
@@ -675,14 +1235,23 @@ impl SP3 {
             return None;
         }
 
-        let mut polynomials = Vector3D::default();
         let offset = center_pos - min_before;
 
+        if sv_position[offset..offset + order + 1]
+            .iter()
+            .any(|(_, _, maneuver)| *maneuver)
+        {
+            /* maneuvering within time window */
+            return None;
+        }
+
+        let mut polynomials = Vector3D::default();
+
         for i in 0..order + 1 {
             let mut li = 1.0_f64;
-            let (e_i, (x_i, y_i, z_i)) = sv_position[offset + i];
+            let (e_i, (x_i, y_i, z_i), _) = sv_position[offset + i];
             for j in 0..order + 1 {
-                let (e_j, _) = sv_position[offset + j];
+                let (e_j, _, _) = sv_position[offset + j];
                 if j != i {
                     li *= (t - e_j).to_seconds();
                     li /= (e_i - e_j).to_seconds();
@@ -695,11 +1264,44 @@ impl SP3 {
 
         Some(polynomials)
     }
+    /// Builds an [Interpolator] that indexes this [SP3] per [SV] once,
+    /// to interpolate position, velocity and clock states
+    /// with error estimates at many [Epoch]s efficiently.
+    pub fn interpolator(&self, method: InterpolationMethod) -> Interpolator {
+        Interpolator::new(self, method)
+    }
+    /// Compares [SP3] to a reference product (for example rapid versus final orbits),
+    /// for all [SV]s and [Epoch]s described by both products.
+    /// When `helmert` is true, a 7 parameter [Helmert](crate::prelude::Helmert)
+    /// transformation is estimated between both frames and applied prior forming the residuals.
+    /// ```
+    /// use sp3::prelude::*;
+    /// let sp3 = SP3::from_file("../test_resources/SP3/co108870.sp3")
+    ///     .unwrap();
+    /// let comparison = sp3.compare(&sp3, false);
+    /// for (_, rms) in comparison.rms() {
+    ///     assert_eq!(rms.position, 0.0);
+    /// }
+    /// ```
+    pub fn compare(&self, reference: &Self, helmert: bool) -> Comparison {
+        Comparison::new(self, reference, helmert)
+    }
 }
 
 #[cfg(feature = "qc")]
 use qc_traits::{Merge, MergeError};
 
+/*
+ * Merges rhs information into lhs. When rhs is less trustworthy,
+ * it only completes missing information.
+ */
+#[cfg(feature = "qc")]
+fn merge_field<T: Copy>(lhs: &mut Option<T>, rhs: Option<T>, rhs_downgrade: bool) {
+    if rhs.is_some() && (!rhs_downgrade || lhs.is_none()) {
+        *lhs = rhs;
+    }
+}
+
 #[cfg(feature = "qc")]
 impl Merge for SP3 {
     fn merge(&self, rhs: &Self) -> Result<Self, MergeError> {
@@ -747,21 +1349,88 @@ impl Merge for SP3 {
                 self.sv.push(*sv);
             }
         }
+        // update SV accuracy (pessimistic)
+        for (sv, accuracy) in &rhs.sv_accuracy {
+            let lhs_accuracy = self.sv_accuracy.entry(*sv).or_insert(*accuracy);
+            *lhs_accuracy = lhs_accuracy.max(*accuracy);
+        }
+        if self.position_base == 0.0 {
+            self.position_base = rhs.position_base;
+        }
+        if self.clock_base == 0.0 {
+            self.clock_base = rhs.clock_base;
+        }
         // update sampling interval (pessimistic)
         self.epoch_interval = std::cmp::max(self.epoch_interval, rhs.epoch_interval);
         // Merge new entries
         // and upgrade missing information (if possible)
         for (key, entry) in &rhs.data {
             if let Some(lhs_entry) = self.data.get_mut(key) {
-                if let Some(clock) = entry.clock {
-                    lhs_entry.clock = Some(clock);
-                }
-                if let Some(rate) = entry.clock_rate {
-                    lhs_entry.clock_rate = Some(rate);
+                // estimated states prevail over predicted ones:
+                // predictions only complete missing information
+                let orbit_upgrade = lhs_entry.orbit_prediction && !entry.orbit_prediction;
+                let orbit_downgrade = !lhs_entry.orbit_prediction && entry.orbit_prediction;
+                let clock_upgrade = lhs_entry.clock_prediction && !entry.clock_prediction;
+                let clock_downgrade = !lhs_entry.clock_prediction && entry.clock_prediction;
+
+                if orbit_upgrade {
+                    lhs_entry.position = entry.position;
+                    lhs_entry.velocity = entry.velocity;
+                    lhs_entry.position_sdev = entry.position_sdev;
+                    lhs_entry.velocity_sdev = entry.velocity_sdev;
+                    lhs_entry.position_correlation = entry.position_correlation;
+                    lhs_entry.velocity_correlation = entry.velocity_correlation;
+                    lhs_entry.orbit_prediction = false;
+                } else {
+                    merge_field(&mut lhs_entry.velocity, entry.velocity, orbit_downgrade);
+                    merge_field(
+                        &mut lhs_entry.position_sdev,
+                        entry.position_sdev,
+                        orbit_downgrade,
+                    );
+                    merge_field(
+                        &mut lhs_entry.velocity_sdev,
+                        entry.velocity_sdev,
+                        orbit_downgrade,
+                    );
+                    merge_field(
+                        &mut lhs_entry.position_correlation,
+                        entry.position_correlation,
+                        orbit_downgrade,
+                    );
+                    merge_field(
+                        &mut lhs_entry.velocity_correlation,
+                        entry.velocity_correlation,
+                        orbit_downgrade,
+                    );
                 }
-                if let Some(velocity) = entry.velocity {
-                    lhs_entry.velocity = Some(velocity);
+
+                if clock_upgrade {
+                    lhs_entry.clock = entry.clock;
+                    lhs_entry.clock_rate = entry.clock_rate;
+                    lhs_entry.clock_sdev = entry.clock_sdev;
+                    lhs_entry.clock_rate_sdev = entry.clock_rate_sdev;
+                    lhs_entry.clock_event = entry.clock_event;
+                    lhs_entry.clock_prediction = false;
+                } else {
+                    if clock_downgrade && lhs_entry.clock.is_none() && entry.clock.is_some() {
+                        // predicted clock offset is adopted
+                        lhs_entry.clock_prediction = true;
+                    }
+                    merge_field(&mut lhs_entry.clock, entry.clock, clock_downgrade);
+                    merge_field(&mut lhs_entry.clock_rate, entry.clock_rate, clock_downgrade);
+                    merge_field(&mut lhs_entry.clock_sdev, entry.clock_sdev, clock_downgrade);
+                    merge_field(
+                        &mut lhs_entry.clock_rate_sdev,
+                        entry.clock_rate_sdev,
+                        clock_downgrade,
+                    );
+                    if !clock_downgrade {
+                        lhs_entry.clock_event |= entry.clock_event;
+                    }
                 }
+
+                lhs_entry.maneuver |= entry.maneuver;
             } else {
                 if !self.epoch.contains(&key.epoch) {
                     self.epoch.push(key.epoch); // new epoch
@@ -943,3 +1612,82 @@ impl Decimate for SP3 {
         }
     }
 }
+
+#[cfg(feature = "processing")]
+impl SP3 {
+    /*
+     * Retains [Epoch]s that match the predicate, then updates the
+     * header fields that depend on the time frame
+     */
+    fn retain_epochs<F: Fn(Epoch) -> bool>(&mut self, f: F) {
+        let first_epoch = self.first_epoch();
+        self.epoch.retain(|t| f(*t));
+        self.data.retain(|k, _| f(k.epoch));
+
+        let sv = self.data.keys().map(|k| k.sv).unique().collect::<Vec<_>>();
+        self.sv.retain(|s| sv.contains(s));
+        self.sv_accuracy.retain(|s, _| sv.contains(s));
+
+        // header describes the first epoch
+        if let (Some(prev), Some(first)) = (first_epoch, self.first_epoch()) {
+            let dt = (first - prev).to_seconds();
+
+            let seconds = self.week_counter.1 + dt;
+            let weeks = (seconds / 604800.0).floor();
+            self.week_counter = (
+                self.week_counter.0 + weeks as u32,
+                seconds - weeks * 604800.0,
+            );
+
+            let days = self.mjd_start.1 + dt / 86400.0;
+            let mjd = days.floor();
+            self.mjd_start = (self.mjd_start.0 + mjd as u32, days - mjd);
+        }
+    }
+}
+
+#[cfg(feature = "processing")]
+impl Split for SP3 {
+    fn split(&self, epoch: Epoch) -> Result<(Self, Self), SplitError> {
+        let mut lhs = self.clone();
+        lhs.retain_epochs(|t| t < epoch);
+        let mut rhs = self.clone();
+        rhs.retain_epochs(|t| t >= epoch);
+        Ok((lhs, rhs))
+    }
+    fn split_dt(&self, dt: Duration) -> Result<Vec<Self>, SplitError> {
+        if dt <= Duration::ZERO {
+            return Err(SplitError::InvalidDuration);
+        }
+        // each window starts on the first epoch that is
+        // not covered by the previous window
+        let mut starts = Vec::<Epoch>::new();
+        for t in self.epoch() {
+            match starts.last() {
+                Some(start) => {
+                    if t - *start >= dt {
+                        starts.push(t);
+                    }
+                },
+                None => starts.push(t),
+            }
+        }
+        let ret = starts
+            .iter()
+            .enumerate()
+            .map(|(i, start)| {
+                let end = starts.get(i + 1).copied();
+                let mut s = self.clone();
+                s.retain_epochs(|t| {
+                    if let Some(end) = end {
+                        t >= *start && t < end
+                    } else {
+                        t >= *start
+                    }
+                });
+                s
+            })
+            .collect();
+        Ok(ret)
+    }
+}
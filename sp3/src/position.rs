@@ -10,6 +10,43 @@ pub struct PositionEntry {
     sv: SV,
     clock: Option<f64>,
     position: (f64, f64, f64),
+    /// (x, y, z) standard deviation exponents
+    pub(crate) position_exp: Option<(u8, u8, u8)>,
+    /// Clock standard deviation exponent
+    pub(crate) clock_exp: Option<u16>,
+    /// Clock event flag (E)
+    pub(crate) clock_event: bool,
+    /// Clock prediction flag (P)
+    pub(crate) clock_prediction: bool,
+    /// Maneuver flag (M)
+    pub(crate) maneuver: bool,
+    /// Orbit prediction flag (P)
+    pub(crate) orbit_prediction: bool,
+}
+
+/*
+ * Parses optional (blank) exponent field
+ */
+pub(crate) fn parse_exponent<T: std::str::FromStr>(
+    line: &str,
+    start: usize,
+    end: usize,
+) -> Option<T> {
+    let field = line.get(start..end)?.trim();
+    if field.is_empty() {
+        None
+    } else {
+        T::from_str(field).ok()
+    }
+}
+
+/*
+ * Tests flag presence at given position
+ */
+pub(crate) fn flag(line: &str, pos: usize, c: char) -> bool {
+    line.get(pos..pos + 1)
+        .map(|s| s.starts_with(c))
+        .unwrap_or(false)
 }
 
 impl std::str::FromStr for PositionEntry {
@@ -24,7 +61,7 @@ impl std::str::FromStr for PositionEntry {
             .or(Err(ParsingError::Coordinates(line[18..32].to_string())))?;
         let z = f64::from_str(line[32..46].trim())
             .or(Err(ParsingError::Coordinates(line[32..46].to_string())))?;
-        if !line[45..52].trim().eq("999999.") {
+        if !line[46..60].trim().starts_with("999999.") {
             /*
              * Clock data present
              */
@@ -32,16 +69,116 @@ impl std::str::FromStr for PositionEntry {
                 .or(Err(ParsingError::Clock(line[46..60].to_string())))?;
             clock = Some(clk_data * 1.0E-6);
         }
+
+        let x_exp = parse_exponent::<u8>(line, 61, 63);
+        let y_exp = parse_exponent::<u8>(line, 64, 66);
+        let z_exp = parse_exponent::<u8>(line, 67, 69);
+
+        let position_exp = match (x_exp, y_exp, z_exp) {
+            (Some(x), Some(y), Some(z)) => Some((x, y, z)),
+            _ => None,
+        };
+
         Ok(Self {
             sv,
             clock,
             position: (x, y, z),
+            position_exp,
+            clock_exp: parse_exponent::<u16>(line, 70, 73),
+            clock_event: flag(line, 74, 'E'),
+            clock_prediction: flag(line, 75, 'P'),
+            maneuver: flag(line, 78, 'M'),
+            orbit_prediction: flag(line, 79, 'P'),
         })
     }
 }
 
+impl std::fmt::Display for PositionEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let (x, y, z) = self.position;
+        write!(f, "P{:x}{:14.6}{:14.6}{:14.6}", self.sv, x, y, z)?;
+        match self.clock {
+            Some(clock) => write!(f, "{:14.6}", clock * 1.0E6)?,
+            None => write!(f, "{:14.6}", 999999.999999_f64)?,
+        }
+
+        let has_flags =
+            self.clock_event || self.clock_prediction || self.maneuver || self.orbit_prediction;
+
+        if self.position_exp.is_none() && self.clock_exp.is_none() && !has_flags {
+            return Ok(());
+        }
+
+        let mut extra = String::with_capacity(20);
+        match self.position_exp {
+            Some((x, y, z)) => extra.push_str(&format!(" {:2} {:2} {:2}", x, y, z)),
+            None => extra.push_str("         "),
+        }
+        match self.clock_exp {
+            Some(clk) => extra.push_str(&format!(" {:3}", clk)),
+            None => extra.push_str("    "),
+        }
+        extra.push(' ');
+        extra.push(if self.clock_event { 'E' } else { ' ' });
+        extra.push(if self.clock_prediction { 'P' } else { ' ' });
+        extra.push_str("  ");
+        extra.push(if self.maneuver { 'M' } else { ' ' });
+        extra.push(if self.orbit_prediction { 'P' } else { ' ' });
+
+        write!(f, "{}", extra.trim_end())
+    }
+}
+
 impl PositionEntry {
+    /// Builds new [PositionEntry], clock offset expressed in seconds
+    pub(crate) fn new(sv: SV, position: (f64, f64, f64), clock: Option<f64>) -> Self {
+        Self {
+            sv,
+            clock,
+            position,
+            position_exp: None,
+            clock_exp: None,
+            clock_event: false,
+            clock_prediction: false,
+            maneuver: false,
+            orbit_prediction: false,
+        }
+    }
     pub fn to_parts(&self) -> (SV, (f64, f64, f64), Option<f64>) {
         (self.sv, self.position, self.clock)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::PositionEntry;
+    use std::str::FromStr;
+    #[test]
+    fn accuracy_and_flags() {
+        let content =
+            "PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152 10  9 11 102 EP  MP";
+        let entry = PositionEntry::from_str(content).unwrap();
+        assert_eq!(entry.position_exp, Some((10, 9, 11)));
+        assert_eq!(entry.clock_exp, Some(102));
+        assert!(entry.clock_event);
+        assert!(entry.clock_prediction);
+        assert!(entry.maneuver);
+        assert!(entry.orbit_prediction);
+        assert_eq!(entry.to_string(), content);
+
+        let content = "PG01 -22335.782004 -14656.280389  -1218.238499   -176.397152";
+        let entry = PositionEntry::from_str(content).unwrap();
+        assert!(entry.position_exp.is_none());
+        assert!(entry.clock_exp.is_none());
+        assert!(!entry.maneuver);
+        assert_eq!(entry.to_string(), content);
+
+        let content =
+            "PG01 -22335.782004 -14656.280389  -1218.238499 999999.999999                  M";
+        let entry = PositionEntry::from_str(content).unwrap();
+        assert!(entry.to_parts().2.is_none());
+        assert!(entry.maneuver);
+        assert!(!entry.orbit_prediction);
+        assert_eq!(entry.to_string(), content);
+    }
+}
@@ -1,9 +1,14 @@
 //! Buffered Reader wrapper, for efficient data reading
-//! and integrated .gz decompression.
+//! and integrated .gz and .Z decompression.
 #[cfg(feature = "flate2")]
 use flate2::read::GzDecoder;
 use std::fs::File;
-use std::io::BufReader; // Seek, SeekFrom};
+use std::io::{BufRead, BufReader}; // Seek, SeekFrom};
+
+#[cfg(feature = "lzw")]
+pub use rinex_readers::LzwDecoder;
+
+pub use rinex_readers::SniffedReader;
 
 #[derive(Debug)]
 pub enum BufferedReader {
@@ -12,11 +17,14 @@ pub enum BufferedReader {
     /// gzip compressed filed
     #[cfg(feature = "flate2")]
     GzFile(BufReader<GzDecoder<File>>),
+    /// Unix compress (LZW) compressed file
+    #[cfg(feature = "lzw")]
+    LzwFile(LzwDecoder<BufReader<File>>),
 }
 
 impl BufferedReader {
     /// Builds a new BufferedReader for efficient file interation,
-    /// with possible .gz decompression
+    /// with possible .gz or .Z decompression
     pub fn new(path: &str) -> std::io::Result<Self> {
         let f = File::open(path)?;
         if path.ends_with(".gz") {
@@ -30,7 +38,18 @@ impl BufferedReader {
                 panic!(".gz data requires --flate2 feature")
             }
         } else if path.ends_with(".Z") {
-            panic!(".z decompresion is not supported: uncompress manually")
+            // --> unix compress (LZW) encoded
+            #[cfg(feature = "lzw")]
+            {
+                Ok(Self::LzwFile(LzwDecoder::new(BufReader::new(f))))
+            }
+            #[cfg(not(feature = "lzw"))]
+            {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::Unsupported,
+                    ".Z decompression requires --lzw feature",
+                ))
+            }
         } else {
             // Assumes no extra compression
             Ok(Self::PlainFile(BufReader::new(f)))
@@ -44,16 +63,20 @@ impl std::io::Read for BufferedReader {
             Self::PlainFile(ref mut h) => h.read(buf),
             #[cfg(feature = "flate2")]
             Self::GzFile(ref mut h) => h.read(buf),
+            #[cfg(feature = "lzw")]
+            Self::LzwFile(ref mut h) => h.read(buf),
         }
     }
 }
 
-impl std::io::BufRead for BufferedReader {
+impl BufRead for BufferedReader {
     fn fill_buf(&mut self) -> Result<&[u8], std::io::Error> {
         match self {
             Self::PlainFile(ref mut bufreader) => bufreader.fill_buf(),
             #[cfg(feature = "flate2")]
             Self::GzFile(ref mut bufreader) => bufreader.fill_buf(),
+            #[cfg(feature = "lzw")]
+            Self::LzwFile(ref mut bufreader) => bufreader.fill_buf(),
         }
     }
     fn consume(&mut self, s: usize) {
@@ -61,6 +84,8 @@ impl std::io::BufRead for BufferedReader {
             Self::PlainFile(ref mut bufreader) => bufreader.consume(s),
             #[cfg(feature = "flate2")]
             Self::GzFile(ref mut bufreader) => bufreader.consume(s),
+            #[cfg(feature = "lzw")]
+            Self::LzwFile(ref mut bufreader) => bufreader.consume(s),
         }
     }
 }
@@ -0,0 +1,93 @@
+//! SP3 comparison tests
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use std::path::PathBuf;
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn ultra_rapid_vs_rapid() {
+        let test_pool = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("SP3");
+
+        let path = test_pool.join("EMR0OPSULT_20232391800_02D_15M_ORB.SP3.gz");
+        let ultra = SP3::from_file(&path.to_string_lossy()).unwrap();
+
+        let path = test_pool.join("ESA0OPSRAP_20232390000_01D_15M_ORB.SP3.gz");
+        let rapid = SP3::from_file(&path.to_string_lossy()).unwrap();
+
+        // 6h overlap
+        let comparison = ultra.compare(&rapid, false);
+        assert!(comparison.helmert.is_none());
+        assert_eq!(comparison.residuals.len(), 1272);
+
+        let rms = comparison.rms();
+        assert_eq!(rms.len(), 53);
+        for (sv, rms) in rms.iter() {
+            assert_eq!(rms.samples, 24);
+            assert!(rms.position < 0.2, "{} rms={}", sv, rms.position);
+            let clock = rms.clock.expect("missing clock rms");
+            if sv.constellation == Constellation::GPS {
+                assert!(clock < 1.0E-9, "{} clock rms={}", sv, clock);
+            }
+        }
+
+        // both products are expressed in IGS20: Helmert is null
+        let comparison = ultra.compare(&rapid, true);
+        let helmert = comparison.helmert.expect("helmert estimate failed");
+        assert!(helmert.translation.0.abs() < 0.01);
+        assert!(helmert.translation.1.abs() < 0.01);
+        assert!(helmert.translation.2.abs() < 0.01);
+        assert!(helmert.scale.abs() < 0.5);
+        assert!(helmert.rotation.0.abs() < 0.5);
+        assert!(helmert.rotation.1.abs() < 0.5);
+        assert!(helmert.rotation.2.abs() < 0.5);
+    }
+    #[test]
+    fn helmert() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("SP3")
+            .join("co108870.sp3");
+        let reference = SP3::from_file(&path.to_string_lossy()).unwrap();
+
+        let helmert = Helmert {
+            translation: (1.0, -2.0, 0.5),
+            scale: 2.0,
+            rotation: (0.3, -0.2, 0.1),
+        };
+
+        // express in another frame, with a clock offset
+        let mut sp3 = reference.clone();
+        for entry in sp3.data.values_mut() {
+            entry.position = helmert.apply(entry.position);
+            entry.clock = entry.clock.map(|clock| clock + 1.0E-6);
+        }
+
+        let comparison = sp3.compare(&reference, false);
+        for (sv, rms) in comparison.rms() {
+            assert!(rms.position > 1.0, "{} rms={}", sv, rms.position);
+            if let Some(clock) = rms.clock {
+                assert!(clock < 1.0E-12, "{} clock rms={}", sv, clock);
+            }
+        }
+
+        let comparison = sp3.compare(&reference, true);
+        let estimate = comparison.helmert.expect("helmert estimate failed");
+        assert!((estimate.translation.0 + 1.0).abs() < 1.0E-3);
+        assert!((estimate.translation.1 - 2.0).abs() < 1.0E-3);
+        assert!((estimate.translation.2 + 0.5).abs() < 1.0E-3);
+        assert!((estimate.scale + 2.0).abs() < 1.0E-3);
+        assert!((estimate.rotation.0 + 0.3).abs() < 1.0E-3);
+        assert!((estimate.rotation.1 - 0.2).abs() < 1.0E-3);
+        assert!((estimate.rotation.2 + 0.1).abs() < 1.0E-3);
+
+        for (sv, rms) in comparison.rms() {
+            assert!(rms.position < 1.0E-3, "{} rms={}", sv, rms.position);
+        }
+    }
+}
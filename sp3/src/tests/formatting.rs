@@ -0,0 +1,200 @@
+//! SP3 formatting tests
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use std::path::PathBuf;
+    /*
+     * Parses, dumps and parses [SP3] again, then compares both
+     */
+    fn run_roundtrip(file: &str) {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("SP3")
+            .join(file);
+
+        let sp3 = SP3::from_path(&path).unwrap();
+
+        let mut buf = Vec::<u8>::new();
+        sp3.to_writer(&mut buf)
+            .unwrap_or_else(|e| panic!("failed to format {}: {:?}", file, e));
+
+        let dumped = SP3::from_reader(buf.as_slice())
+            .unwrap_or_else(|e| panic!("failed to parse dumped {}: {:?}", file, e));
+
+        assert_eq!(dumped.version, sp3.version, "{}: bad version", file);
+        assert_eq!(dumped.data_type, sp3.data_type, "{}: bad data type", file);
+        assert_eq!(dumped.coord_system, sp3.coord_system, "{}", file);
+        assert_eq!(dumped.orbit_type, sp3.orbit_type, "{}", file);
+        assert_eq!(dumped.agency, sp3.agency, "{}", file);
+        assert_eq!(dumped.constellation, sp3.constellation, "{}", file);
+        assert_eq!(dumped.time_scale, sp3.time_scale, "{}", file);
+        assert_eq!(dumped.week_counter, sp3.week_counter, "{}", file);
+        assert_eq!(dumped.mjd_start, sp3.mjd_start, "{}", file);
+        assert_eq!(dumped.epoch_interval, sp3.epoch_interval, "{}", file);
+        assert_eq!(dumped.epoch, sp3.epoch, "{}: bad epochs", file);
+        assert_eq!(dumped.sv, sp3.sv, "{}: bad vehicles", file);
+        assert_eq!(dumped.comments, sp3.comments, "{}: bad comments", file);
+        assert_eq!(dumped.data.len(), sp3.data.len(), "{}: bad data", file);
+        assert_eq!(dumped.position_base, sp3.position_base, "{}", file);
+        assert_eq!(dumped.clock_base, sp3.clock_base, "{}", file);
+
+        // non interpreted descriptors are preserved
+        assert_eq!(dumped.descriptors.len(), sp3.descriptors.len(), "{}", file);
+        for (nth, (dumped, descriptor)) in dumped
+            .descriptors
+            .iter()
+            .zip(sp3.descriptors.iter())
+            .enumerate()
+        {
+            match nth {
+                0 => assert_eq!(dumped.get(12..), descriptor.get(12..), "{}", file),
+                2 => assert_eq!(dumped.get(26..), descriptor.get(26..), "{}", file),
+                _ => assert_eq!(dumped, descriptor, "{}: bad descriptor", file),
+            }
+        }
+
+        for sv in sp3.sv() {
+            assert_eq!(
+                dumped.sv_accuracy.get(&sv),
+                sp3.sv_accuracy.get(&sv),
+                "{}: bad {} accuracy",
+                file,
+                sv
+            );
+        }
+
+        for ((k, v), (dumped_k, dumped_v)) in sp3.data.iter().zip(dumped.data.iter()) {
+            assert_eq!(k, dumped_k, "{}: bad entry", file);
+            let (x, y, z) = v.position;
+            let (dumped_x, dumped_y, dumped_z) = dumped_v.position;
+            assert!((x - dumped_x).abs() < 1.0E-6, "{}: bad position", file);
+            assert!((y - dumped_y).abs() < 1.0E-6, "{}: bad position", file);
+            assert!((z - dumped_z).abs() < 1.0E-6, "{}: bad position", file);
+            match (v.clock, dumped_v.clock) {
+                (Some(clk), Some(dumped_clk)) => {
+                    assert!((clk - dumped_clk).abs() < 1.0E-12, "{}: bad clock", file)
+                },
+                (None, None) => {},
+                _ => panic!("{}: bad clock at {:?}", file, k),
+            }
+            assert_eq!(v.velocity.is_some(), dumped_v.velocity.is_some());
+            if let (Some(vel), Some(dumped_vel)) = (v.velocity, dumped_v.velocity) {
+                assert!(
+                    (vel.0 - dumped_vel.0).abs() < 1.0E-6,
+                    "{}: bad velocity",
+                    file
+                );
+                assert!(
+                    (vel.1 - dumped_vel.1).abs() < 1.0E-6,
+                    "{}: bad velocity",
+                    file
+                );
+                assert!(
+                    (vel.2 - dumped_vel.2).abs() < 1.0E-6,
+                    "{}: bad velocity",
+                    file
+                );
+            }
+            assert_eq!(v.clock_rate.is_some(), dumped_v.clock_rate.is_some());
+            assert_eq!(v.clock_event, dumped_v.clock_event, "{}: bad flags", file);
+            assert_eq!(v.clock_prediction, dumped_v.clock_prediction);
+            assert_eq!(v.maneuver, dumped_v.maneuver, "{}: bad flags", file);
+            assert_eq!(v.orbit_prediction, dumped_v.orbit_prediction);
+            assert_eq!(v.position_correlation, dumped_v.position_correlation);
+            assert_eq!(v.velocity_correlation, dumped_v.velocity_correlation);
+            match (v.position_sdev, dumped_v.position_sdev) {
+                (Some(sdev), Some(dumped_sdev)) => {
+                    assert!(
+                        (sdev.0 - dumped_sdev.0).abs() < 1.0E-6,
+                        "{}: bad sdev",
+                        file
+                    );
+                    assert!(
+                        (sdev.1 - dumped_sdev.1).abs() < 1.0E-6,
+                        "{}: bad sdev",
+                        file
+                    );
+                    assert!(
+                        (sdev.2 - dumped_sdev.2).abs() < 1.0E-6,
+                        "{}: bad sdev",
+                        file
+                    );
+                },
+                (None, None) => {},
+                _ => panic!("{}: bad position sdev at {:?}", file, k),
+            }
+            assert_eq!(
+                v.velocity_sdev, dumped_v.velocity_sdev,
+                "{}: bad sdev",
+                file
+            );
+            assert_eq!(v.clock_rate_sdev, dumped_v.clock_rate_sdev, "{}", file);
+            match (v.clock_sdev, dumped_v.clock_sdev) {
+                (Some(sdev), Some(dumped_sdev)) => {
+                    assert!((sdev - dumped_sdev).abs() < 1.0E-6, "{}: bad sdev", file)
+                },
+                (None, None) => {},
+                _ => panic!("{}: bad clock sdev at {:?}", file, k),
+            }
+        }
+    }
+    #[test]
+    fn plain_roundtrip() {
+        for file in ["co108870.sp3", "em108871.sp3", "sp3d.txt"] {
+            run_roundtrip(file);
+        }
+    }
+    #[test]
+    fn missing_vehicles() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("SP3")
+            .join("co108870.sp3");
+
+        let mut sp3 = SP3::from_path(&path).unwrap();
+
+        // remove one vehicle from first epoch
+        let key = sp3.data.keys().next().cloned().unwrap();
+        sp3.data.remove(&key);
+
+        let mut buf = Vec::<u8>::new();
+        sp3.to_writer(&mut buf).unwrap();
+
+        // vehicle is omitted, not described as null
+        let content = String::from_utf8(buf.clone()).unwrap();
+        let first_epoch = content
+            .lines()
+            .skip_while(|line| !line.starts_with('*'))
+            .skip(1)
+            .take_while(|line| !line.starts_with('*'))
+            .collect::<Vec<_>>();
+        assert_eq!(first_epoch.len(), sp3.sv.len() - 1);
+        assert!(first_epoch
+            .iter()
+            .all(|line| !line.starts_with(&format!("P{:x}", key.sv))));
+
+        let dumped = SP3::from_reader(buf.as_slice()).unwrap();
+        assert_eq!(dumped.sv, sp3.sv);
+        assert_eq!(dumped.data.len(), sp3.data.len());
+        assert!(dumped.data.get(&key).is_none());
+    }
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn gzip_roundtrip() {
+        for file in [
+            "EMR0OPSULT_20232391800_02D_15M_ORB.SP3.gz",
+            "ESA0OPSULT_20232320600_02D_15M_ORB.SP3.gz",
+            "COD0MGXFIN_20230500000_01D_05M_ORB.SP3.gz",
+            "ESA0OPSRAP_20232390000_01D_15M_ORB.SP3.gz",
+            "GRG0MGXFIN_20201760000_01D_15M_ORB.SP3.gz",
+            "GRG0MGXFIN_20201770000_01D_15M_ORB.SP3.gz",
+            "Sta21114.sp3.gz",
+        ] {
+            run_roundtrip(file);
+        }
+    }
+}
@@ -109,4 +109,152 @@ mod test {
             }
         }
     }
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn interpolator() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("SP3")
+            .join("EMR0OPSULT_20232391800_02D_15M_ORB.SP3.gz");
+
+        let sp3 = SP3::from_file(&path.to_string_lossy()).unwrap();
+        let dt = sp3.epoch_interval;
+
+        // decimate by 2 and verify interpolated states
+        // against the discarded epochs
+        let mut decimated = sp3.clone();
+        let discarded = sp3.epoch().skip(1).step_by(2).collect::<Vec<_>>();
+        decimated.data.retain(|k, _| !discarded.contains(&k.epoch));
+        decimated.epoch_interval = dt * 2.0_f64;
+
+        let interpolator = decimated.interpolator(InterpolationMethod::Lagrange(9));
+        let mut tested = 0;
+
+        for (t, sv, position) in sp3.sv_position() {
+            if !discarded.contains(&t) {
+                continue;
+            }
+            let interpolated = match interpolator.interpolate(sv, t) {
+                Some(interpolated) => interpolated,
+                None => continue,
+            };
+            tested += 1;
+            let err = ((interpolated.position.0 - position.0).powi(2)
+                + (interpolated.position.1 - position.1).powi(2)
+                + (interpolated.position.2 - position.2).powi(2))
+            .sqrt();
+            assert!(err < 1.0E-3, "{}({}) error too large: {} km", sv, t, err);
+            assert!(
+                err < 2.0 * interpolated.position_error,
+                "{}({}) error {} km not covered by estimate {} km",
+                sv,
+                t,
+                err,
+                interpolated.position_error
+            );
+            let (vx, vy, vz) = interpolated.velocity;
+            let speed = (vx.powi(2) + vy.powi(2) + vz.powi(2)).sqrt();
+            assert!(
+                speed > 1.0E4 && speed < 5.0E4,
+                "bad velocity {} dm/s",
+                speed
+            );
+            assert_eq!(
+                interpolated.clock.is_some(),
+                interpolated.clock_error.is_some()
+            );
+
+            // legacy interpolation designs its own time window
+            if let Some(legacy) = decimated.sv_position_interpolate(sv, t, 9) {
+                let err = ((legacy.0 - position.0).powi(2)
+                    + (legacy.1 - position.1).powi(2)
+                    + (legacy.2 - position.2).powi(2))
+                .sqrt();
+                assert!(
+                    err < 1.0E-3,
+                    "{}({}) legacy error too large: {} km",
+                    sv,
+                    t,
+                    err
+                );
+            }
+        }
+        assert!(tested > 4000, "only {} epochs were tested", tested);
+
+        // Hermite: inject velocities in the dataset
+        let full = sp3.interpolator(InterpolationMethod::Lagrange(9));
+        let mut with_velocities = decimated.clone();
+        for (k, entry) in with_velocities.data.iter_mut() {
+            if let Some(interpolated) = full.interpolate(k.sv, k.epoch) {
+                entry.velocity = Some(interpolated.velocity);
+            }
+        }
+        let hermite = with_velocities.interpolator(InterpolationMethod::Hermite);
+        let no_velocity = decimated.interpolator(InterpolationMethod::Hermite);
+        let mut tested = 0;
+
+        for (t, sv, position) in sp3.sv_position() {
+            if !discarded.contains(&t) {
+                continue;
+            }
+            assert!(no_velocity.interpolate(sv, t).is_none());
+            let interpolated = match hermite.interpolate(sv, t) {
+                Some(interpolated) => interpolated,
+                None => continue,
+            };
+            tested += 1;
+            let err = ((interpolated.position.0 - position.0).powi(2)
+                + (interpolated.position.1 - position.1).powi(2)
+                + (interpolated.position.2 - position.2).powi(2))
+            .sqrt();
+            assert!(
+                err < 1.0,
+                "{}({}) hermite error too large: {} km",
+                sv,
+                t,
+                err
+            );
+            assert!(err < interpolated.position_error);
+        }
+        assert!(tested > 4000, "only {} epochs were tested", tested);
+    }
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn interpolation_maneuver() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("SP3")
+            .join("EMR0OPSULT_20232391800_02D_15M_ORB.SP3.gz");
+
+        let mut sp3 = SP3::from_file(&path.to_string_lossy()).unwrap();
+        let dt = sp3.epoch_interval;
+
+        let (t, sv) = sp3
+            .sv_position()
+            .find_map(|(t, sv, _)| {
+                let later = sp3.sv_position_interpolate(sv, t + dt * 20.0_f64, 9);
+                if sp3.sv_position_interpolate(sv, t, 9).is_some() && later.is_some() {
+                    Some((t, sv))
+                } else {
+                    None
+                }
+            })
+            .unwrap();
+
+        // flag one epoch of the time window
+        let key = SP3Key { epoch: t - dt, sv };
+        sp3.data.get_mut(&key).unwrap().maneuver = true;
+
+        assert!(sp3.sv_position_interpolate(sv, t, 9).is_none());
+        assert!(sp3.sv_position_interpolate(sv, t - dt, 9).is_none());
+
+        // flag is outside the time window
+        assert!(sp3
+            .sv_position_interpolate(sv, t + dt * 20.0_f64, 9)
+            .is_some());
+    }
 }
@@ -88,4 +88,50 @@ mod test {
             Some(Epoch::from_str("2023-08-27T23:45:00 GPST").unwrap())
         );
     }
+    #[test]
+    fn merge_predictions() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("SP3")
+            .join("co108870.sp3");
+
+        let estimated = SP3::from_path(&path).unwrap();
+        let key = estimated.data.keys().next().cloned().unwrap();
+        let entry = estimated.data.get(&key).cloned().unwrap();
+
+        let mut predicted = estimated.clone();
+        let predicted_entry = predicted.data.get_mut(&key).unwrap();
+        predicted_entry.position = (1.0, 2.0, 3.0);
+        predicted_entry.clock = Some(1.0E-3);
+        predicted_entry.orbit_prediction = true;
+        predicted_entry.clock_prediction = true;
+
+        // estimates prevail
+        let merged = predicted.merge(&estimated).unwrap();
+        let merged = merged.data.get(&key).unwrap();
+        assert_eq!(merged.position, entry.position);
+        assert_eq!(merged.clock, entry.clock);
+        assert!(!merged.orbit_prediction);
+        assert!(!merged.clock_prediction);
+
+        // predictions do not override estimates
+        let merged = estimated.merge(&predicted).unwrap();
+        let merged = merged.data.get(&key).unwrap();
+        assert_eq!(merged.position, entry.position);
+        assert_eq!(merged.clock, entry.clock);
+        assert!(!merged.orbit_prediction);
+        assert!(!merged.clock_prediction);
+
+        // predictions complete missing information
+        let mut incomplete = estimated.clone();
+        incomplete.data.get_mut(&key).unwrap().clock = None;
+        let merged = incomplete.merge(&predicted).unwrap();
+        let merged = merged.data.get(&key).unwrap();
+        assert_eq!(merged.position, entry.position);
+        assert_eq!(merged.clock, Some(1.0E-3));
+        assert!(merged.clock_prediction);
+        assert!(!merged.orbit_prediction);
+    }
 }
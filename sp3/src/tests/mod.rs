@@ -1,6 +1,9 @@
+mod comparison;
+mod formatting;
 mod interpolation;
 mod parser_3c;
 mod parser_3d;
+mod split;
 mod test_pool;
 
 #[cfg(feature = "qc")]
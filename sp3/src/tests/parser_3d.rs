@@ -95,6 +95,48 @@ mod test {
                 panic!("identified wrong sv");
             }
         }
+        /*
+         * Test accuracy & flags
+         */
+        assert_eq!(
+            sp3.sv_accuracy.len(),
+            93,
+            "bad number of accuracy exponents"
+        );
+        assert_eq!(sp3.sv_accuracy.get(&sv!("C01")), Some(&1024.0));
+        assert_eq!(sp3.sv_accuracy.get(&sv!("G01")), Some(&64.0));
+        assert_eq!(sp3.sv_accuracy.get(&sv!("E01")), Some(&64.0));
+        assert_eq!(sp3.position_base, 1.25);
+        assert_eq!(sp3.clock_base, 1.025);
+
+        for (epoch, sv, (x, y, z)) in sp3.sv_position_sdev() {
+            assert_eq!(epoch, Epoch::from_str("2019-10-27T00:00:00 GPST").unwrap());
+            assert_eq!(sv, sv!("G01"), "bad position sdev");
+            assert!((x - 1.25_f64.powi(10)).abs() < 1E-9, "bad position sdev");
+            assert!((y - 1.25_f64.powi(9)).abs() < 1E-9, "bad position sdev");
+            assert!((z - 1.25_f64.powi(11)).abs() < 1E-9, "bad position sdev");
+        }
+        assert_eq!(sp3.sv_position_sdev().count(), 1);
+
+        let clock_sdev = sp3.sv_clock_sdev().collect::<Vec<_>>();
+        assert_eq!(clock_sdev.len(), 1);
+        assert!((clock_sdev[0].2 - 1.025_f64.powi(102)).abs() < 1E-9);
+
+        assert_eq!(
+            sp3.sv_maneuver().collect::<Vec<_>>(),
+            vec![(
+                Epoch::from_str("2019-10-27T00:00:00 GPST").unwrap(),
+                sv!("G01")
+            )],
+        );
+        for (key, entry) in &sp3.data {
+            let flagged = key.sv == sv!("G01");
+            assert_eq!(entry.clock_event, flagged);
+            assert_eq!(entry.clock_prediction, flagged);
+            assert_eq!(entry.orbit_prediction, flagged);
+            assert!(entry.position_correlation.is_none());
+        }
+
         /*
          * Test file comments
          */
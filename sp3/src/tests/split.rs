@@ -0,0 +1,100 @@
+//! SP3 split and sampling operations
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    #[cfg(feature = "processing")]
+    use crate::Split;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+    #[test]
+    fn sampling() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("SP3")
+            .join("co108870.sp3");
+        let sp3 = SP3::from_file(&path.to_string_lossy()).unwrap();
+
+        let dt = Duration::from_seconds(900.0);
+        assert!(sp3.steady_sampling());
+        assert_eq!(sp3.dominant_sample_rate(), Some(dt));
+        assert_eq!(sp3.data_gaps(None).count(), 0);
+
+        // introduce a 1h gap
+        let t0 = Epoch::from_str("1997-01-05T06:00:00 GPST").unwrap();
+        let t1 = Epoch::from_str("1997-01-05T07:00:00 GPST").unwrap();
+        let mut gapped = sp3.clone();
+        gapped.epoch.retain(|t| *t < t0 || *t >= t1);
+
+        assert!(!gapped.steady_sampling());
+        assert_eq!(gapped.dominant_sample_rate(), Some(dt));
+
+        let t = Epoch::from_str("1997-01-05T05:45:00 GPST").unwrap();
+        let gaps = gapped.data_gaps(None).collect::<Vec<_>>();
+        assert_eq!(gaps, vec![(t, Duration::from_seconds(4500.0))]);
+
+        let gaps = gapped.data_gaps(Some(Duration::from_seconds(2.0 * 3600.0)));
+        assert_eq!(gaps.count(), 0);
+    }
+    #[cfg(feature = "processing")]
+    #[test]
+    fn split() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("SP3")
+            .join("co108870.sp3");
+        let sp3 = SP3::from_file(&path.to_string_lossy()).unwrap();
+
+        let t = Epoch::from_str("1997-01-05T06:00:00 GPST").unwrap();
+        let (lhs, rhs) = sp3.split(t).unwrap();
+        assert_eq!(lhs.nb_epochs(), 24);
+        assert_eq!(rhs.nb_epochs(), 72);
+        assert!(lhs.epoch().all(|e| e < t));
+        assert!(rhs.epoch().all(|e| e >= t));
+        assert_eq!(lhs.data.len() + rhs.data.len(), sp3.data.len());
+
+        assert_eq!(lhs.week_counter, sp3.week_counter);
+        assert_eq!(lhs.mjd_start, sp3.mjd_start);
+        assert_eq!(rhs.week_counter, (887, 21600.0));
+        assert_eq!(rhs.mjd_start, (50453, 0.25));
+
+        assert!(sp3.split_dt(Duration::ZERO).is_err());
+    }
+    #[cfg(all(feature = "flate2", feature = "processing"))]
+    #[test]
+    fn split_dt() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("SP3")
+            .join("EMR0OPSULT_20232391800_02D_15M_ORB.SP3.gz");
+        let sp3 = SP3::from_file(&path.to_string_lossy()).unwrap();
+
+        // 2-day ultra rapid into 6h windows
+        let files = sp3.split_dt(Duration::from_seconds(6.0 * 3600.0)).unwrap();
+        assert_eq!(files.len(), 8);
+        assert_eq!(
+            files.iter().map(|f| f.data.len()).sum::<usize>(),
+            sp3.data.len()
+        );
+
+        let mut start = Epoch::from_str("2023-08-27T18:00:00 GPST").unwrap();
+        for (i, file) in files.iter().enumerate() {
+            assert_eq!(file.nb_epochs(), 24);
+            assert_eq!(file.first_epoch(), Some(start));
+            assert!(file.steady_sampling());
+            assert_eq!(file.data_gaps(None).count(), 0);
+
+            let seconds = 64800.0 + i as f64 * 21600.0;
+            assert_eq!(file.week_counter, (2277, seconds));
+            let days = 60183.75 + i as f64 * 0.25;
+            assert_eq!(file.mjd_start, (days.floor() as u32, days.fract()));
+            start += Duration::from_seconds(6.0 * 3600.0);
+        }
+    }
+}
@@ -28,6 +28,52 @@ mod test {
             );
         }
     }
+    #[cfg(feature = "lzw")]
+    #[test]
+    fn lzw_data() {
+        let prefix = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("SP3");
+
+        let plain = SP3::from_path(&prefix.join("co108870.sp3")).unwrap();
+        let sp3 = SP3::from_path(&prefix.join("co108870.sp3.Z"));
+        assert!(
+            sp3.is_ok(),
+            "failed to parse data/co108870.sp3.Z, error: {:?}",
+            sp3.err()
+        );
+        let sp3 = sp3.unwrap();
+        assert_eq!(sp3.epoch, plain.epoch);
+        assert_eq!(sp3.sv, plain.sv);
+        assert_eq!(sp3.comments, plain.comments);
+        assert!(sp3.sv_position().eq(plain.sv_position()));
+    }
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn from_reader() {
+        let path = PathBuf::new()
+            .join(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("test_resources")
+            .join("SP3")
+            .join("ESA0OPSRAP_20232390000_01D_15M_ORB.SP3.gz");
+
+        // gzip compression is identified by content sniffing
+        let content = std::fs::read(&path).unwrap();
+        let sp3 = SP3::from_reader(content.as_slice());
+        assert!(
+            sp3.is_ok(),
+            "failed to parse in-memory content, error: {:?}",
+            sp3.err()
+        );
+        let sp3 = sp3.unwrap();
+        let model = SP3::from_path(&path).unwrap();
+        assert_eq!(sp3.epoch, model.epoch);
+        assert_eq!(sp3.sv, model.sv);
+        assert!(sp3.sv_position().eq(model.sv_position()));
+    }
     #[test]
     fn data_folder() {
         let prefix = PathBuf::new()
@@ -1,4 +1,5 @@
 //! Velocity entry parsing
+use crate::position::parse_exponent;
 use crate::ParsingError;
 use crate::SV;
 
@@ -6,10 +7,17 @@ pub fn velocity_entry(content: &str) -> bool {
     content.starts_with('V')
 }
 
+/// Velocity (V) record. Unlike position records,
+/// SP3-c and SP3-d do not define any flag for velocity records:
+/// clock event, prediction and maneuver flags are described by the position record.
 pub struct VelocityEntry {
     sv: SV,
     velocity: (f64, f64, f64),
     clock: Option<f64>,
+    /// (x, y, z) velocity standard deviation exponents
+    pub(crate) velocity_exp: Option<(u8, u8, u8)>,
+    /// Clock rate standard deviation exponent
+    pub(crate) clock_exp: Option<u16>,
 }
 
 impl std::str::FromStr for VelocityEntry {
@@ -25,7 +33,7 @@ impl std::str::FromStr for VelocityEntry {
         let z = f64::from_str(line[32..46].trim())
             .or(Err(ParsingError::Coordinates(line[32..46].to_string())))?;
 
-        if !line[45..52].trim().eq("999999.") {
+        if !line[46..60].trim().starts_with("999999.") {
             /*
              * Clock data present
              */
@@ -33,16 +41,85 @@ impl std::str::FromStr for VelocityEntry {
                 .or(Err(ParsingError::Clock(line[46..60].to_string())))?;
             clock = Some(clk_data);
         }
+
+        let x_exp = parse_exponent::<u8>(line, 61, 63);
+        let y_exp = parse_exponent::<u8>(line, 64, 66);
+        let z_exp = parse_exponent::<u8>(line, 67, 69);
+
+        let velocity_exp = match (x_exp, y_exp, z_exp) {
+            (Some(x), Some(y), Some(z)) => Some((x, y, z)),
+            _ => None,
+        };
+
         Ok(Self {
             sv,
             velocity: (x, y, z),
             clock,
+            velocity_exp,
+            clock_exp: parse_exponent::<u16>(line, 70, 73),
         })
     }
 }
 
+impl std::fmt::Display for VelocityEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let (x, y, z) = self.velocity;
+        write!(f, "V{:x}{:14.6}{:14.6}{:14.6}", self.sv, x, y, z)?;
+        match self.clock {
+            Some(clock) => write!(f, "{:14.6}", clock)?,
+            None => write!(f, "{:14.6}", 999999.999999_f64)?,
+        }
+
+        if self.velocity_exp.is_none() && self.clock_exp.is_none() {
+            return Ok(());
+        }
+
+        let mut extra = String::with_capacity(13);
+        match self.velocity_exp {
+            Some((x, y, z)) => extra.push_str(&format!(" {:2} {:2} {:2}", x, y, z)),
+            None => extra.push_str("         "),
+        }
+        if let Some(clk) = self.clock_exp {
+            extra.push_str(&format!(" {:3}", clk));
+        }
+
+        write!(f, "{}", extra.trim_end())
+    }
+}
+
 impl VelocityEntry {
+    /// Builds new [VelocityEntry]
+    pub(crate) fn new(sv: SV, velocity: (f64, f64, f64), clock: Option<f64>) -> Self {
+        Self {
+            sv,
+            velocity,
+            clock,
+            velocity_exp: None,
+            clock_exp: None,
+        }
+    }
     pub fn to_parts(&self) -> (SV, (f64, f64, f64), Option<f64>) {
         (self.sv, self.velocity, self.clock)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::VelocityEntry;
+    use std::str::FromStr;
+    #[test]
+    fn accuracy() {
+        let content = "VG01  -4577.223130  -3318.924565  27052.837213      4.396563  8  7  9 120";
+        let entry = VelocityEntry::from_str(content).unwrap();
+        assert_eq!(entry.velocity_exp, Some((8, 7, 9)));
+        assert_eq!(entry.clock_exp, Some(120));
+        assert_eq!(entry.to_string(), content);
+
+        let content = "VG01  -4577.223130  -3318.924565  27052.837213 999999.999999";
+        let entry = VelocityEntry::from_str(content).unwrap();
+        assert!(entry.velocity_exp.is_none());
+        assert!(entry.clock_exp.is_none());
+        assert!(entry.to_parts().2.is_none());
+        assert_eq!(entry.to_string(), content);
+    }
+}